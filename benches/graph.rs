@@ -0,0 +1,157 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use graph_algos::{algos, frozen::FrozenGraph, generators, Edge, Graph, IndexedGraph};
+use rand::{rngs::StdRng, SeedableRng};
+
+/// The numbers of edges in the generated graphs
+const SIZES: [usize; 3] = [10_000, 100_000, 1_000_000];
+
+/// Builds a random graph with an average out degree of 10,
+/// using a fixed seed so every run benchmarks the same graph
+fn random_graph(edges: usize) -> Graph<usize> {
+    let mut rng = StdRng::seed_from_u64(0xdead_beef);
+    generators::gnm_random(edges / 10, edges, 100, &mut rng)
+}
+
+fn bench_add_edge(c: &mut Criterion) {
+    let mut group = c.benchmark_group("add_edge");
+    group.sample_size(10);
+
+    for &size in SIZES.iter() {
+        let edges: Vec<(usize, Edge<usize>)> = random_graph(size)
+            .edges()
+            .map(|(u, edge)| (*u, edge.clone()))
+            .collect();
+
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &edges, |b, edges| {
+            b.iter_batched(
+                || edges.clone(),
+                |edges| {
+                    let mut graph = Graph::empty();
+                    for (u, edge) in edges {
+                        graph.add_edge(u, edge);
+                    }
+                    graph
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    group.sample_size(10);
+
+    for &size in SIZES.iter() {
+        let input = random_graph(size).to_string_unstable();
+
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &input, |b, input| {
+            b.iter(|| input.parse::<Graph<usize>>().unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_dijkstra(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dijkstra");
+    group.sample_size(10);
+
+    for &size in SIZES.iter() {
+        let graph = random_graph(size);
+
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &graph, |b, graph| {
+            b.iter(|| algos::dijkstra(graph, &0))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_bfs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bfs");
+    group.sample_size(10);
+
+    for &size in SIZES.iter() {
+        let graph = random_graph(size);
+
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &graph, |b, graph| {
+            b.iter(|| algos::bfs_shortest_paths(graph, &0))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_bellman_ford(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bellman_ford");
+    group.sample_size(10);
+
+    // the map based version is too slow to be worth running on the smallest graph
+    for &size in SIZES.iter().filter(|&&size| size >= 100_000) {
+        let graph = random_graph(size);
+        let indexed = IndexedGraph::from(&graph);
+        let frozen = FrozenGraph::from(&indexed);
+        let source = indexed.id(&0).unwrap();
+
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("graph", size), &graph, |b, graph| {
+            b.iter(|| algos::bellman_ford(graph, &0))
+        });
+        group.bench_with_input(BenchmarkId::new("frozen", size), &frozen, |b, frozen| {
+            b.iter(|| frozen.bellman_ford(source))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_scc(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scc");
+    group.sample_size(10);
+
+    for &size in SIZES.iter() {
+        let graph = random_graph(size);
+
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &graph, |b, graph| {
+            b.iter(|| algos::strongly_connected_components(graph))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_add_edge,
+    bench_parse,
+    bench_dijkstra,
+    bench_bfs,
+    bench_bellman_ford,
+    bench_scc
+);
+criterion_main!(benches);
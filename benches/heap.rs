@@ -0,0 +1,227 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Compares the priority queues when used by Dijkstra's and Prim's algorithms.
+//!
+//! The graphs are converted to adjacency vectors first so only the cost of the queues differs.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use graph_algos::{generators, heap::PairingHeap, util::IndexedHeap, EdgeWeight};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+/// The numbers of edges in the generated graphs
+const SIZES: [usize; 3] = [10_000, 100_000, 1_000_000];
+
+/// A graph as the weighted successors of each node
+type Adjacency = Vec<Vec<(usize, i64)>>;
+
+/// Builds a random graph with an average out degree of 10,
+/// using a fixed seed so every run benchmarks the same graph
+fn random_adjacency(edges: usize, undirected: bool) -> Adjacency {
+    let mut rng = StdRng::seed_from_u64(0xdead_beef);
+    let graph = generators::gnm_random(edges / 10, edges, 100, &mut rng);
+
+    let mut adjacency = vec![Vec::new(); graph.len()];
+    for (u, edge) in graph.edges() {
+        let w = match edge.weight() {
+            Some(EdgeWeight::Weight(w)) => w,
+            _ => unreachable!("generated edges have finite weights"),
+        };
+        adjacency[*u].push((*edge.destination(), w));
+        if undirected {
+            adjacency[*edge.destination()].push((*u, w));
+        }
+    }
+
+    adjacency
+}
+
+fn dijkstra_binary_heap(graph: &Adjacency) -> Vec<i64> {
+    let mut dists = vec![i64::MAX; graph.len()];
+    let mut queue = BinaryHeap::new();
+    dists[0] = 0;
+    queue.push(Reverse((0, 0)));
+
+    while let Some(Reverse((d, u))) = queue.pop() {
+        if d > dists[u] {
+            continue;
+        }
+        for &(v, w) in &graph[u] {
+            if d + w < dists[v] {
+                dists[v] = d + w;
+                queue.push(Reverse((d + w, v)));
+            }
+        }
+    }
+
+    dists
+}
+
+fn dijkstra_indexed_heap(graph: &Adjacency) -> Vec<i64> {
+    let mut dists = vec![i64::MAX; graph.len()];
+    let mut queue = IndexedHeap::with_capacity(graph.len());
+    dists[0] = 0;
+    queue.push(0, 0);
+
+    while let Some((u, d)) = queue.pop() {
+        for &(v, w) in &graph[u] {
+            if d + w < dists[v] {
+                dists[v] = d + w;
+                queue.push(v, d + w);
+            }
+        }
+    }
+
+    dists
+}
+
+fn dijkstra_pairing_heap(graph: &Adjacency) -> Vec<i64> {
+    let mut dists = vec![i64::MAX; graph.len()];
+    let mut handles = vec![None; graph.len()];
+    let mut queue = PairingHeap::new();
+    dists[0] = 0;
+    queue.push(0, 0);
+
+    while let Some((u, d)) = queue.pop() {
+        for &(v, w) in &graph[u] {
+            if d + w < dists[v] {
+                dists[v] = d + w;
+                match &handles[v] {
+                    Some(handle) if queue.decrease_key(handle, d + w) => {}
+                    _ => handles[v] = Some(queue.push(v, d + w)),
+                }
+            }
+        }
+    }
+
+    dists
+}
+
+fn prim_binary_heap(graph: &Adjacency) -> i64 {
+    let mut in_tree = vec![false; graph.len()];
+    let mut queue = BinaryHeap::new();
+    let mut total = 0;
+    queue.push(Reverse((0, 0)));
+
+    while let Some(Reverse((w, u))) = queue.pop() {
+        if in_tree[u] {
+            continue;
+        }
+        in_tree[u] = true;
+        total += w;
+        for &(v, w) in &graph[u] {
+            if !in_tree[v] {
+                queue.push(Reverse((w, v)));
+            }
+        }
+    }
+
+    total
+}
+
+fn prim_indexed_heap(graph: &Adjacency) -> i64 {
+    let mut in_tree = vec![false; graph.len()];
+    let mut queue = IndexedHeap::with_capacity(graph.len());
+    let mut total = 0;
+    queue.push(0, 0);
+
+    while let Some((u, w)) = queue.pop() {
+        in_tree[u] = true;
+        total += w;
+        for &(v, w) in &graph[u] {
+            if !in_tree[v] && !matches!(queue.priority(&v), Some(&p) if p <= w) {
+                queue.push(v, w);
+            }
+        }
+    }
+
+    total
+}
+
+fn prim_pairing_heap(graph: &Adjacency) -> i64 {
+    let mut in_tree = vec![false; graph.len()];
+    let mut handles = vec![None; graph.len()];
+    let mut queue = PairingHeap::new();
+    let mut total = 0;
+    queue.push(0, 0);
+
+    while let Some((u, w)) = queue.pop() {
+        in_tree[u] = true;
+        total += w;
+        for &(v, w) in &graph[u] {
+            if in_tree[v] {
+                continue;
+            }
+            match &handles[v] {
+                Some(handle) if queue.priority(handle).is_some() => {
+                    queue.decrease_key(handle, w);
+                }
+                _ => handles[v] = Some(queue.push(v, w)),
+            }
+        }
+    }
+
+    total
+}
+
+fn bench_dijkstra(c: &mut Criterion) {
+    let mut group = c.benchmark_group("heap_dijkstra");
+    group.sample_size(10);
+
+    for &size in SIZES.iter() {
+        let graph = random_adjacency(size, false);
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("binary", size), &graph, |b, graph| {
+            b.iter(|| dijkstra_binary_heap(graph))
+        });
+        group.bench_with_input(BenchmarkId::new("indexed", size), &graph, |b, graph| {
+            b.iter(|| dijkstra_indexed_heap(graph))
+        });
+        group.bench_with_input(BenchmarkId::new("pairing", size), &graph, |b, graph| {
+            b.iter(|| dijkstra_pairing_heap(graph))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_prim(c: &mut Criterion) {
+    let mut group = c.benchmark_group("heap_prim");
+    group.sample_size(10);
+
+    for &size in SIZES.iter() {
+        let graph = random_adjacency(size, true);
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("binary", size), &graph, |b, graph| {
+            b.iter(|| prim_binary_heap(graph))
+        });
+        group.bench_with_input(BenchmarkId::new("indexed", size), &graph, |b, graph| {
+            b.iter(|| prim_indexed_heap(graph))
+        });
+        group.bench_with_input(BenchmarkId::new("pairing", size), &graph, |b, graph| {
+            b.iter(|| prim_pairing_heap(graph))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_dijkstra, bench_prim);
+criterion_main!(benches);
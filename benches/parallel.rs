@@ -0,0 +1,118 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use graph_algos::{algos, frozen::FrozenGraph, generators, parallel, Graph, IndexedGraph};
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Builds a random graph with `nodes` nodes and `edges` weighted edges,
+/// using a fixed seed so every run benchmarks the same graph
+fn random_graph(nodes: usize, edges: usize) -> Graph<usize> {
+    let mut rng = StdRng::seed_from_u64(0xdead_beef);
+    generators::gnm_random(nodes, edges, 100, &mut rng)
+}
+
+/// Runs `f` on a rayon thread pool with the given number of threads
+fn with_threads<T: Send>(threads: usize, f: impl FnOnce() -> T + Send) -> T {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .unwrap()
+        .install(f)
+}
+
+const THREADS: [usize; 4] = [1, 2, 4, 8];
+
+fn bench_bfs(c: &mut Criterion) {
+    let graph = random_graph(100_000, 1_000_000);
+    let mut group = c.benchmark_group("bfs");
+
+    group.bench_function("sequential", |b| {
+        b.iter(|| algos::bfs_shortest_paths(&graph, &0))
+    });
+
+    for threads in THREADS.iter() {
+        group.bench_with_input(BenchmarkId::new("parallel", threads), threads, |b, &t| {
+            b.iter(|| with_threads(t, || parallel::par_bfs_shortest_paths(&graph, &0)))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_bellman_ford(c: &mut Criterion) {
+    let graph = random_graph(10_000, 100_000);
+    let mut group = c.benchmark_group("bellman_ford");
+    group.sample_size(10);
+
+    let indexed = IndexedGraph::from(&graph);
+    let frozen = FrozenGraph::from(&indexed);
+    let source = indexed.id(&0).unwrap();
+
+    group.bench_function("sequential", |b| b.iter(|| algos::bellman_ford(&graph, &0)));
+    group.bench_function("frozen", |b| b.iter(|| frozen.bellman_ford(source)));
+
+    for threads in THREADS.iter() {
+        group.bench_with_input(BenchmarkId::new("parallel", threads), threads, |b, &t| {
+            b.iter(|| with_threads(t, || parallel::par_bellman_ford(&graph, &0)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("frozen_parallel", threads),
+            threads,
+            |b, &t| b.iter(|| with_threads(t, || frozen.par_bellman_ford(source))),
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_connected_components(c: &mut Criterion) {
+    let graph = random_graph(100_000, 200_000);
+    let mut group = c.benchmark_group("connected_components");
+    group.sample_size(10);
+
+    for threads in THREADS.iter() {
+        group.bench_with_input(BenchmarkId::new("parallel", threads), threads, |b, &t| {
+            b.iter(|| with_threads(t, || parallel::par_connected_components(&graph)))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_pagerank(c: &mut Criterion) {
+    let graph = random_graph(100_000, 1_000_000);
+    let mut group = c.benchmark_group("pagerank");
+    group.sample_size(10);
+
+    for threads in THREADS.iter() {
+        group.bench_with_input(BenchmarkId::new("parallel", threads), threads, |b, &t| {
+            b.iter(|| with_threads(t, || parallel::par_pagerank(&graph, 0.85, 20, 0.0)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_bfs,
+    bench_bellman_ford,
+    bench_connected_components,
+    bench_pagerank
+);
+criterion_main!(benches);
@@ -0,0 +1,15 @@
+//! Reads arbitrary bytes as a DOT file, checking that anything which reads
+//! can be written out and read back in as the same graph.
+
+#![no_main]
+
+use graph_algos::formats::dot;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(graph) = dot::read(data) {
+        let mut output = Vec::new();
+        dot::write(&graph, &mut output).unwrap();
+        assert_eq!(dot::read(output.as_slice()).unwrap(), graph);
+    }
+});
@@ -0,0 +1,15 @@
+//! Reads arbitrary bytes as a GraphML file, checking that anything which reads
+//! can be written out and read back in as the same graph.
+
+#![no_main]
+
+use graph_algos::formats::graphml;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(graph) = graphml::read(data) {
+        let mut output = Vec::new();
+        graphml::write(&graph, &mut output).unwrap();
+        assert_eq!(graphml::read(output.as_slice()).unwrap(), graph);
+    }
+});
@@ -0,0 +1,22 @@
+//! Parses arbitrary text in the format accepted by `str::parse`,
+//! checking that the lenient parser never panics, agrees with the strict one
+//! whenever the strict one succeeds, and reports errors exactly when the strict one fails.
+
+#![no_main]
+
+use graph_algos::Graph;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let (lenient, errors) = Graph::<String>::parse_report(input);
+    match input.parse::<Graph<String>>() {
+        Ok(strict) => assert!(errors.is_empty() && strict == lenient),
+        Err(_) => assert!(!errors.is_empty()),
+    }
+
+    let (lenient, errors) = Graph::<u32>::parse_report(input);
+    match input.parse::<Graph<u32>>() {
+        Ok(strict) => assert!(errors.is_empty() && strict == lenient),
+        Err(_) => assert!(!errors.is_empty()),
+    }
+});
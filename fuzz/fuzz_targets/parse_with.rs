@@ -0,0 +1,32 @@
+//! Parses arbitrary text with a dialect of the plain text format picked by the first byte of the input.
+
+#![no_main]
+
+use graph_algos::{Graph, GraphTextFormat};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (u8, &str)| {
+    let (flags, text) = input;
+
+    let mut format = if flags & 1 == 0 {
+        GraphTextFormat::adjacency_list()
+    } else {
+        GraphTextFormat::edge_list()
+    };
+    if flags & 2 != 0 {
+        format = format.whitespace_separated();
+    }
+    if flags & 4 != 0 {
+        format = format.header();
+    }
+    if flags & 8 != 0 {
+        format = format.comment_prefix("#");
+    }
+    if flags & 16 != 0 {
+        format = format.zero_indexed();
+    } else if flags & 32 != 0 {
+        format = format.one_indexed();
+    }
+
+    let _ = Graph::<usize>::parse_with(text, &format);
+});
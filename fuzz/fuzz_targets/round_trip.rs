@@ -0,0 +1,43 @@
+//! Builds arbitrary graphs and checks that writing them out in the text, DOT and GraphML formats
+//! and reading them back in gives the same graph.
+
+#![no_main]
+
+use graph_algos::{
+    formats::{dot, graphml},
+    Edge, FormatStyle, Graph,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|graph: Graph<u32>| {
+    // the adjacency list only has lines for nodes with outbound edges
+    let text = graph
+        .formatter()
+        .style(FormatStyle::AdjacencyList)
+        .to_string();
+    let mut connected = graph.clone();
+    connected.prune_isolated();
+    assert_eq!(text.parse::<Graph<u32>>().unwrap(), connected);
+
+    // the file formats name their nodes with strings
+    let mut named: Graph<String> = Graph::with_capacity(graph.len());
+    for u in graph.nodes() {
+        named.add_node(u.to_string());
+    }
+    for (u, edge) in graph.edges() {
+        let v = edge.destination().to_string();
+        let edge = match edge.weight() {
+            Some(weight) => Edge::new_with_weight(v, weight),
+            None => Edge::new(v),
+        };
+        named.add_edge(u.to_string(), edge);
+    }
+
+    let mut output = Vec::new();
+    dot::write(&graph, &mut output).unwrap();
+    assert_eq!(dot::read(output.as_slice()).unwrap(), named);
+
+    let mut output = Vec::new();
+    graphml::write(&graph, &mut output).unwrap();
+    assert_eq!(graphml::read(output.as_slice()).unwrap(), named);
+});
@@ -0,0 +1,269 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::{
+    error::Cancelled,
+    util::index_nodes,
+    visit::{EdgeWeighted, IntoNodes},
+    AlgoControl, EdgeWeight, NodeBounds,
+};
+use std::{collections::HashMap, fmt, io};
+
+/// The shortest path distances between every pair of nodes in a graph
+///
+/// The rows and columns are in the order of [`DistMatrix::nodes`].
+/// Pairs with no path between them are [`EdgeWeight::infinity`] apart.
+#[derive(Debug, Clone)]
+pub struct DistMatrix<'a, N: NodeBounds> {
+    nodes: Vec<&'a N>,
+    indices: HashMap<&'a N, usize>,
+    dists: Vec<EdgeWeight>,
+}
+
+impl<'a, N: NodeBounds> DistMatrix<'a, N> {
+    /// Returns the number of nodes in the matrix
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns whether the matrix has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the nodes in the order of the rows and columns of the matrix
+    pub fn nodes(&self) -> &[&'a N] {
+        &self.nodes
+    }
+
+    /// Returns the row and column of `u` in the matrix
+    pub fn index_of(&self, u: &N) -> Option<usize> {
+        self.indices.get(u).copied()
+    }
+
+    /// Returns the distance between the nodes at rows `i` and `j`
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn distance_index(&self, i: usize, j: usize) -> EdgeWeight {
+        assert!(i < self.len() && j < self.len(), "index out of bounds");
+        self.dists[i * self.len() + j]
+    }
+
+    /// Returns the distance from `u` to `v`, or `None` if either isn't in the matrix
+    pub fn distance(&self, u: &N, v: &N) -> Option<EdgeWeight> {
+        Some(self.distance_index(self.index_of(u)?, self.index_of(v)?))
+    }
+
+    /// Returns whether the graph contained a negative cycle,
+    /// in which case the distances of paths touching it are meaningless
+    pub fn has_negative_cycle(&self) -> bool {
+        (0..self.len()).any(|i| self.distance_index(i, i) < 0.into())
+    }
+
+    /// Writes the matrix as CSV, with a header row and a first column of node labels
+    ///
+    /// Unreachable pairs are written as `+inf`.
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    /// use graph_algos::algos::floyd_warshall;
+    ///
+    /// let graph: Graph<&str> = graph! {
+    ///     "a" => ["b" => 2],
+    ///     "b" => ["c" => 3],
+    /// };
+    ///
+    /// let matrix = floyd_warshall(&graph).sorted();
+    /// let mut csv = Vec::new();
+    /// matrix.to_csv(&mut csv).unwrap();
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(csv).unwrap(),
+    ///     ",a,b,c\na,0,2,5\nb,+inf,0,3\nc,+inf,+inf,0\n",
+    /// );
+    /// ```
+    pub fn to_csv<W: io::Write>(&self, mut writer: W) -> io::Result<()>
+    where
+        N: fmt::Display,
+    {
+        for node in &self.nodes {
+            write!(writer, ",{}", csv_field(node))?;
+        }
+        writeln!(writer)?;
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            write!(writer, "{}", csv_field(node))?;
+            for j in 0..self.len() {
+                write!(writer, ",{}", self.distance_index(i, j))?;
+            }
+            writeln!(writer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the matrix with its rows and columns in ascending order of the nodes
+    pub fn sorted(self) -> Self
+    where
+        N: Ord,
+    {
+        let mut order: Vec<usize> = (0..self.len()).collect();
+        order.sort_by_key(|&i| self.nodes[i]);
+
+        let nodes: Vec<&'a N> = order.iter().map(|&i| self.nodes[i]).collect();
+        let indices = nodes.iter().enumerate().map(|(i, &u)| (u, i)).collect();
+        let dists = order
+            .iter()
+            .flat_map(|&i| order.iter().map(move |&j| (i, j)))
+            .map(|(i, j)| self.distance_index(i, j))
+            .collect();
+
+        Self {
+            nodes,
+            indices,
+            dists,
+        }
+    }
+
+    /// Converts the matrix into an `ndarray` array of `f64`s
+    ///
+    /// Unreachable pairs become `f64::INFINITY`.
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    /// use graph_algos::algos::floyd_warshall;
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2 => 4],
+    ///     2 => [1 => 1],
+    /// };
+    ///
+    /// let array = floyd_warshall(&graph).sorted().to_array();
+    ///
+    /// assert_eq!(array.shape(), &[2, 2]);
+    /// assert_eq!(array[[0, 1]], 4.0);
+    /// assert_eq!(array[[1, 0]], 1.0);
+    /// ```
+    #[cfg(feature = "ndarray")]
+    pub fn to_array(&self) -> ndarray::Array2<f64> {
+        ndarray::Array2::from_shape_fn((self.len(), self.len()), |(i, j)| {
+            match self.distance_index(i, j) {
+                EdgeWeight::Weight(w) => w as f64,
+                EdgeWeight::PosInfinity => f64::INFINITY,
+                EdgeWeight::NegInfinity => f64::NEG_INFINITY,
+            }
+        })
+    }
+}
+
+/// Quotes a CSV field if it contains a separator, quote or newline
+fn csv_field(value: impl fmt::Display) -> String {
+    let value = value.to_string();
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// Finds the shortest distances between every pair of nodes using the Floyd-Warshall algorithm
+///
+/// Negative edge weights are allowed, see [`DistMatrix::has_negative_cycle`].
+/// Panics if the graph contains an edge without a weight.
+/// ```
+/// use graph_algos::{graph, Graph, EdgeWeight};
+/// use graph_algos::algos::floyd_warshall;
+///
+/// let graph: Graph<&str> = graph! {
+///     "a" => ["b" => 4, "c" => 2],
+///     "b" => ["d" => 1],
+///     "c" => ["b" => -3],
+/// };
+///
+/// let matrix = floyd_warshall(&graph);
+///
+/// assert_eq!(matrix.distance(&"a", &"d"), Some(0.into()));
+/// assert_eq!(matrix.distance(&"d", &"a"), Some(EdgeWeight::infinity()));
+/// assert_eq!(matrix.distance(&"a", &"e"), None);
+/// assert!(!matrix.has_negative_cycle());
+/// ```
+pub fn floyd_warshall<'a, G>(graph: G) -> DistMatrix<'a, G::Node>
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a>,
+{
+    floyd_warshall_with_control(graph, &AlgoControl::new())
+        .expect("a fresh AlgoControl is never cancelled")
+}
+
+/// Finds the shortest distances between every pair of nodes using the Floyd-Warshall algorithm,
+/// reporting its progress to `control` and stopping early if it is cancelled
+///
+/// Progress is counted in the intermediate nodes processed, out of every node in the graph.
+/// See [`floyd_warshall`] and [`AlgoControl`].
+pub fn floyd_warshall_with_control<'a, G>(
+    graph: G,
+    control: &AlgoControl,
+) -> Result<DistMatrix<'a, G::Node>, Cancelled>
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a>,
+{
+    let (nodes, indices) = index_nodes(graph);
+    let n = nodes.len();
+
+    let mut dists = vec![EdgeWeight::infinity(); n * n];
+    for i in 0..n {
+        dists[i * n + i] = 0.into();
+    }
+
+    for (i, &u) in nodes.iter().enumerate() {
+        for (v, weight) in graph.neighbors_weighted(u) {
+            let w = weight.expect("Floyd-Warshall requires edges to have weights - panicking.");
+            let j = indices[v];
+            // keep the lightest of any parallel edges
+            if w < dists[i * n + j] {
+                dists[i * n + j] = w;
+            }
+        }
+    }
+
+    for k in 0..n {
+        control.step(k, n)?;
+
+        for i in 0..n {
+            let ik = dists[i * n + k];
+            if ik == EdgeWeight::infinity() {
+                continue;
+            }
+
+            for j in 0..n {
+                let kj = dists[k * n + j];
+                if kj == EdgeWeight::infinity() {
+                    continue;
+                }
+
+                if ik + kj < dists[i * n + j] {
+                    dists[i * n + j] = ik + kj;
+                }
+            }
+        }
+    }
+
+    control.step(n, n)?;
+
+    Ok(DistMatrix {
+        nodes,
+        indices,
+        dists,
+    })
+}
@@ -0,0 +1,164 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{
+    util::index_graph,
+    visit::{IntoNeighbors, IntoNodes},
+    NodeBounds,
+};
+
+/// An iterator over every topological ordering of a graph, created by [`all_topo_sorts`]
+#[derive(Debug, Clone)]
+pub struct AllTopoSorts<'a, N: NodeBounds> {
+    nodes: Vec<&'a N>,
+    adjacency: Vec<Vec<usize>>,
+    in_degrees: Vec<usize>,
+    placed: Vec<bool>,
+    order: Vec<usize>,
+    /// the next candidate to try at each depth of the search
+    choices: Vec<usize>,
+    started: bool,
+    done: bool,
+}
+
+/// Lazily enumerates every topological ordering of the graph with a backtracking search
+///
+/// There can be exponentially many orderings, use [`Iterator::take`] to stop after a limit.
+/// Yields nothing if the graph has a cycle.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::all_topo_sorts;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [3],
+///     2 => [3],
+///     3 => [4, 5],
+/// };
+///
+/// let mut orders: Vec<Vec<&u32>> = all_topo_sorts(&graph).collect();
+/// orders.sort();
+///
+/// assert_eq!(orders, vec![
+///     vec![&1, &2, &3, &4, &5],
+///     vec![&1, &2, &3, &5, &4],
+///     vec![&2, &1, &3, &4, &5],
+///     vec![&2, &1, &3, &5, &4],
+/// ]);
+///
+/// // stop early on graphs with too many orderings to list
+/// let wide: Graph<u32> = (0..20).map(|i| (i, graph_algos::Edge::new(100))).collect();
+/// assert_eq!(all_topo_sorts(&wide).take(3).count(), 3);
+///
+/// let cyclic: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [1],
+/// };
+/// assert_eq!(all_topo_sorts(&cyclic).count(), 0);
+/// ```
+pub fn all_topo_sorts<'a, G>(graph: G) -> AllTopoSorts<'a, G::Node>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let (nodes, adjacency) = index_graph(graph);
+
+    let mut in_degrees = vec![0; nodes.len()];
+    for &v in adjacency.iter().flatten() {
+        in_degrees[v] += 1;
+    }
+
+    AllTopoSorts {
+        placed: vec![false; nodes.len()],
+        order: Vec::with_capacity(nodes.len()),
+        choices: Vec::with_capacity(nodes.len()),
+        nodes,
+        adjacency,
+        in_degrees,
+        started: false,
+        done: false,
+    }
+}
+
+impl<'a, N: NodeBounds> AllTopoSorts<'a, N> {
+    /// appends `u` to the ordering
+    fn place(&mut self, u: usize) {
+        self.placed[u] = true;
+        self.order.push(u);
+        for &v in &self.adjacency[u] {
+            self.in_degrees[v] -= 1;
+        }
+    }
+
+    /// removes the last node from the ordering, returns false if the ordering was empty
+    fn backtrack(&mut self) -> bool {
+        match self.order.pop() {
+            Some(u) => {
+                self.placed[u] = false;
+                for &v in &self.adjacency[u] {
+                    self.in_degrees[v] += 1;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<'a, N: NodeBounds> Iterator for AllTopoSorts<'a, N> {
+    type Item = Vec<&'a N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        // undo the last choice of the previous ordering to move on to the next one
+        if self.started && !self.backtrack() {
+            self.done = true;
+            return None;
+        }
+        self.started = true;
+
+        let n = self.nodes.len();
+        loop {
+            let depth = self.order.len();
+            if depth == n {
+                return Some(self.order.iter().map(|&u| self.nodes[u]).collect());
+            }
+
+            if self.choices.len() == depth {
+                self.choices.push(0);
+            }
+
+            let candidate =
+                (self.choices[depth]..n).find(|&u| !self.placed[u] && self.in_degrees[u] == 0);
+
+            match candidate {
+                Some(u) => {
+                    self.choices[depth] = u + 1;
+                    self.place(u);
+                }
+                None => {
+                    self.choices.pop();
+                    if !self.backtrack() {
+                        self.done = true;
+                        return None;
+                    }
+                }
+            }
+        }
+    }
+}
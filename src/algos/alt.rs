@@ -0,0 +1,343 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Landmark lower bounds for A*, known as ALT (A*, landmarks and the triangle inequality).
+//!
+//! A [`LandmarkHeuristic`] picks a few landmark nodes and stores the distance from every node
+//! to and from each of them.
+//! By the triangle inequality the distance between two nodes is at least the difference
+//! of their distances to any landmark, so the best of these differences is a heuristic for [`astar`]
+//! which never overestimates, and which guides the search far better than a geometric bound
+//! on graphs like road networks where the edge weights are travel times rather than lengths.
+//!
+//! ```
+//! use graph_algos::Graph;
+//! use graph_algos::algos::{astar, dijkstra};
+//! use graph_algos::algos::alt::{LandmarkHeuristic, LandmarkStrategy};
+//!
+//! let grid = Graph::grid(20, 20, false);
+//! let landmarks = LandmarkHeuristic::new(&grid, 4, LandmarkStrategy::Farthest);
+//! assert_eq!(landmarks.landmarks().len(), 4);
+//!
+//! let (start, goal) = ((0, 3), (17, 12));
+//! let (path, cost) = astar(&grid, &start, &goal, landmarks.heuristic(&goal)).unwrap();
+//!
+//! let (_, distances) = dijkstra(&grid, &start);
+//! assert_eq!(cost, distances[&goal]);
+//! assert_eq!(path.to_string().matches("->").count(), 26);
+//! ```
+//!
+//! [`astar`]: crate::algos::astar
+
+use super::dijkstra;
+use crate::{
+    error::Cancelled,
+    util::index_nodes,
+    visit::{EdgeWeighted, IntoNodes, Reversed},
+    AlgoControl, Edge, EdgeWeight, Graph, NodeBounds,
+};
+use rand::{rngs::StdRng, seq::index, SeedableRng};
+use std::{cmp::Reverse, collections::HashMap};
+
+/// How [`LandmarkHeuristic::new`] chooses its landmarks
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum LandmarkStrategy {
+    /// Each landmark is the node furthest from the landmarks chosen so far,
+    /// which spreads them around the edges of the graph where they give the best bounds
+    #[default]
+    Farthest,
+
+    /// The nodes with the most edges, which is quick but can bunch the landmarks together
+    HighestDegree,
+
+    /// Nodes chosen uniformly at random by a generator seeded with the given seed
+    Random(u64),
+}
+
+/// A lower bound on the distance between any two nodes of a graph,
+/// from precomputed distances to and from a few landmarks
+///
+/// See the [module documentation](self) for how it is used with [`astar`](crate::algos::astar).
+#[derive(Debug, Clone)]
+pub struct LandmarkHeuristic<'a, N: NodeBounds> {
+    indices: HashMap<&'a N, usize>,
+    landmarks: Vec<&'a N>,
+    /// The distance from each landmark to each node, `landmarks.len()` entries per node
+    from_landmarks: Vec<Option<i64>>,
+    /// The distance from each node to each landmark, `landmarks.len()` entries per node
+    to_landmarks: Vec<Option<i64>>,
+}
+
+impl<'a, N: NodeBounds> LandmarkHeuristic<'a, N> {
+    /// Chooses `k` landmarks with `strategy` and finds the distances to and from each of them,
+    /// with two runs of Dijkstra's algorithm per landmark
+    ///
+    /// Graphs with fewer than `k` nodes use every node as a landmark.
+    /// Unweighted edges count as having a weight of 1.
+    ///
+    /// Panics if any edge has a negative or infinite weight.
+    pub fn new<G>(graph: G, k: usize, strategy: LandmarkStrategy) -> Self
+    where
+        G: IntoNodes<'a, Node = N> + EdgeWeighted<'a>,
+    {
+        Self::new_with_control(graph, k, strategy, &AlgoControl::new())
+            .expect("a fresh AlgoControl is never cancelled")
+    }
+
+    /// Chooses `k` landmarks with `strategy` and finds the distances to and from each of them,
+    /// reporting progress to `control` and stopping early if it is cancelled
+    ///
+    /// Progress is counted in the landmarks whose distances have been found, out of every landmark.
+    /// [`LandmarkStrategy::Farthest`] also searches from each landmark while choosing them,
+    /// and stops between those searches if it is cancelled.
+    /// See [`LandmarkHeuristic::new`] and [`AlgoControl`].
+    /// ```
+    /// use graph_algos::{AlgoControl, Graph};
+    /// use graph_algos::algos::alt::{LandmarkHeuristic, LandmarkStrategy};
+    /// use graph_algos::error::Cancelled;
+    ///
+    /// let grid = Graph::grid(20, 20, false);
+    ///
+    /// let control = AlgoControl::new();
+    /// control.cancel();
+    /// let landmarks =
+    ///     LandmarkHeuristic::new_with_control(&grid, 4, LandmarkStrategy::Farthest, &control);
+    /// assert!(matches!(landmarks, Err(Cancelled)));
+    /// ```
+    pub fn new_with_control<G>(
+        graph: G,
+        k: usize,
+        strategy: LandmarkStrategy,
+        control: &AlgoControl,
+    ) -> Result<Self, Cancelled>
+    where
+        G: IntoNodes<'a, Node = N> + EdgeWeighted<'a>,
+    {
+        let (nodes, indices) = index_nodes(graph);
+
+        // the graph over node indices with every edge weighted, searched forwards and backwards
+        let mut forward: Graph<usize> = Graph::with_capacity(nodes.len());
+        let mut degrees = vec![0; nodes.len()];
+        for i in 0..nodes.len() {
+            forward.add_node(i);
+        }
+        for (i, &u) in nodes.iter().enumerate() {
+            for (v, weight) in graph.neighbors_weighted(u) {
+                let w = match weight.unwrap_or_else(|| 1.into()) {
+                    EdgeWeight::Weight(w) if w >= 0 => w,
+                    w => panic!(
+                        "LandmarkHeuristic needs non-negative finite weights, found {}",
+                        w
+                    ),
+                };
+                let j = indices[v];
+                forward.add_edge(i, Edge::new_with_weight(j, w));
+                degrees[i] += 1;
+                degrees[j] += 1;
+            }
+        }
+        let backward = Reversed::new(&forward);
+
+        let k = k.min(nodes.len());
+        let chosen: Vec<usize> = match strategy {
+            LandmarkStrategy::Farthest => farthest_landmarks(&forward, &backward, k, control)?,
+            LandmarkStrategy::HighestDegree => {
+                let mut order: Vec<usize> = (0..nodes.len()).collect();
+                order.sort_unstable_by_key(|&i| Reverse(degrees[i]));
+                order.truncate(k);
+                order
+            }
+            LandmarkStrategy::Random(seed) => {
+                index::sample(&mut StdRng::seed_from_u64(seed), nodes.len(), k).into_vec()
+            }
+        };
+
+        let mut from_landmarks = vec![None; nodes.len() * k];
+        let mut to_landmarks = vec![None; nodes.len() * k];
+        for (l, landmark) in chosen.iter().enumerate() {
+            control.step(l, k)?;
+            let landmark = forward
+                .node_ref(landmark)
+                .expect("landmarks are node indices");
+            let from = distances(&forward, landmark);
+            let to = distances(&backward, landmark);
+            for (i, (from, to)) in from.into_iter().zip(to).enumerate() {
+                from_landmarks[i * k + l] = from;
+                to_landmarks[i * k + l] = to;
+            }
+        }
+
+        control.step(k, k)?;
+
+        Ok(Self {
+            indices,
+            landmarks: chosen.into_iter().map(|i| nodes[i]).collect(),
+            from_landmarks,
+            to_landmarks,
+        })
+    }
+
+    /// Returns the landmarks, in the order they were chosen
+    pub fn landmarks(&self) -> &[&'a N] {
+        &self.landmarks
+    }
+
+    /// Returns a lower bound on the distance from `u` to `v`
+    ///
+    /// The bound is infinite if a landmark shows there is no path from `u` to `v`,
+    /// and 0 if either node isn't in the graph or no landmark gives a better one.
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    /// use graph_algos::algos::alt::{LandmarkHeuristic, LandmarkStrategy};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2 => 3],
+    ///     2 => [3 => 4],
+    ///     3 => [4 => 5],
+    /// };
+    ///
+    /// let landmarks = LandmarkHeuristic::new(&graph, 1, LandmarkStrategy::Random(7));
+    /// assert!(landmarks.lower_bound(&1, &4) <= 12.into());
+    /// assert!(landmarks.lower_bound(&2, &3) <= 4.into());
+    ///
+    /// // with every node as a landmark the bounds are exact
+    /// let landmarks = LandmarkHeuristic::new(&graph, 4, LandmarkStrategy::Farthest);
+    /// assert_eq!(landmarks.lower_bound(&1, &4), 12.into());
+    /// assert_eq!(landmarks.lower_bound(&2, &3), 4.into());
+    /// assert_eq!(landmarks.lower_bound(&1, &5), 0.into());
+    /// ```
+    pub fn lower_bound(&self, u: &N, v: &N) -> EdgeWeight {
+        match (self.indices.get(u), self.indices.get(v)) {
+            (Some(&i), Some(&j)) => self.bound(i, j),
+            _ => 0.into(),
+        }
+    }
+
+    /// Returns the heuristic for an A* search towards `goal`,
+    /// the [lower bound](Self::lower_bound) on the distance from each node to it
+    pub fn heuristic(&self, goal: &N) -> impl FnMut(&N) -> EdgeWeight + '_ {
+        let goal = self.indices.get(goal).copied();
+        move |u| match (self.indices.get(u), goal) {
+            (Some(&i), Some(j)) => self.bound(i, j),
+            _ => 0.into(),
+        }
+    }
+
+    /// Returns the best lower bound on the distance from node `i` to node `j` given by any landmark,
+    /// which is infinite if a landmark shows there is no path
+    fn bound(&self, i: usize, j: usize) -> EdgeWeight {
+        let k = self.landmarks.len();
+        let (from_i, from_j) = (
+            &self.from_landmarks[i * k..][..k],
+            &self.from_landmarks[j * k..][..k],
+        );
+        let (to_i, to_j) = (
+            &self.to_landmarks[i * k..][..k],
+            &self.to_landmarks[j * k..][..k],
+        );
+
+        let mut best = 0;
+        for l in 0..k {
+            // d(i, j) >= d(l, j) - d(l, i), and if l reaches i but not j then i can't reach j
+            match (from_i[l], from_j[l]) {
+                (Some(li), Some(lj)) => best = best.max(lj - li),
+                (Some(_), None) => return EdgeWeight::PosInfinity,
+                _ => {}
+            }
+            // d(i, j) >= d(i, l) - d(j, l), and if j reaches l but i doesn't then i can't reach j
+            match (to_i[l], to_j[l]) {
+                (Some(il), Some(jl)) => best = best.max(il - jl),
+                (None, Some(_)) => return EdgeWeight::PosInfinity,
+                _ => {}
+            }
+        }
+
+        best.into()
+    }
+}
+
+/// Chooses `k` landmarks, each as far as possible in either direction from the ones before it,
+/// starting from the node furthest from an arbitrary node
+fn farthest_landmarks<'a>(
+    forward: &'a Graph<usize>,
+    backward: &Reversed<'a, &'a Graph<usize>>,
+    k: usize,
+    control: &AlgoControl,
+) -> Result<Vec<usize>, Cancelled> {
+    let mut chosen: Vec<usize> = Vec::with_capacity(k);
+    if k == 0 {
+        return Ok(chosen);
+    }
+
+    let closeness = |landmark| -> Vec<Option<i64>> {
+        let landmark = forward
+            .node_ref(&landmark)
+            .expect("landmarks are node indices");
+        let from = distances(forward, landmark);
+        let to = distances(backward, landmark);
+        from.into_iter()
+            .zip(to)
+            .map(|(a, b)| nearer(a, b))
+            .collect()
+    };
+
+    // the distance of each node from the nearest landmark, or `None` if it is cut off from all of them
+    control.check()?;
+    let mut nearest = closeness(0);
+    while chosen.len() < k {
+        control.check()?;
+        let next = (0..forward.len())
+            .filter(|i| !chosen.contains(i))
+            .max_by_key(|&i| nearest[i].unwrap_or(i64::MAX))
+            .expect("there are at least k nodes");
+
+        let reach = closeness(next);
+        if chosen.is_empty() {
+            nearest = reach;
+        } else {
+            for (near, d) in nearest.iter_mut().zip(reach) {
+                *near = nearer(*near, d);
+            }
+        }
+        chosen.push(next);
+    }
+
+    Ok(chosen)
+}
+
+/// Returns the smaller of two distances, where `None` is infinitely far
+fn nearer(a: Option<i64>, b: Option<i64>) -> Option<i64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Finds the distance of every node from `source` with Dijkstra's algorithm,
+/// indexed by node, with `None` for the nodes which can't be reached
+fn distances<'a, G>(graph: G, source: &'a usize) -> Vec<Option<i64>>
+where
+    G: IntoNodes<'a, Node = usize> + EdgeWeighted<'a>,
+{
+    let mut distances = vec![None; graph.node_count()];
+    for (&v, distance) in dijkstra(graph, source).1 {
+        if let EdgeWeight::Weight(d) = distance {
+            distances[v] = Some(d);
+        }
+    }
+
+    distances
+}
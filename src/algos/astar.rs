@@ -0,0 +1,66 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use super::Search;
+use crate::{visit::EdgeWeighted, EdgeWeight, Path};
+
+/// Finds a shortest path from `start` to `goal` with the A* search algorithm
+///
+/// The `heuristic` estimates the distance from a node to the goal, and guides the search
+/// towards it so fewer nodes are explored than with [`crate::algos::dijkstra`].
+/// The path found is only guaranteed to be a shortest one if the heuristic never overestimates.
+/// Returns the path and its total weight, or `None` if the goal can't be reached.
+///
+/// Panics if the search reaches an edge without a weight.
+/// ```
+/// use graph_algos::{graph, Graph, EdgeWeight};
+/// use graph_algos::algos::astar;
+///
+/// // nodes are positions on a number line and edges cost at least the distance travelled
+/// let graph: Graph<i64> = graph! {
+///     0 => [5 => 5, -1 => 1],
+///     -1 => [10 => 20],
+///     5 => [10 => 6],
+/// };
+///
+/// let (path, cost) = astar(&graph, &0, &10, |&n| EdgeWeight::new((10 - n).abs())).unwrap();
+/// assert_eq!(path.to_string(), "0 --(5)-> 5 --(6)-> 10");
+/// assert_eq!(cost, 11.into());
+///
+/// assert!(astar(&graph, &10, &0, |_| 0.into()).is_none());
+/// ```
+pub fn astar<'a, G, H>(
+    graph: G,
+    start: &'a G::Node,
+    goal: &G::Node,
+    mut heuristic: H,
+) -> Option<(Path<'a, G::Node>, EdgeWeight)>
+where
+    G: EdgeWeighted<'a>,
+    H: FnMut(&G::Node) -> EdgeWeight,
+{
+    let mut search = Search::new(start, 0.into());
+    while let Some((node, distance)) = search.pop() {
+        if node == goal {
+            return Some((search.path(node), distance));
+        }
+
+        // nodes can be reopened if the heuristic isn't consistent
+        search.expand(graph, node, distance, &mut heuristic);
+    }
+
+    None
+}
@@ -0,0 +1,135 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{
+    visit::{IntoNeighbors, IntoNodes},
+    Edge, NodeBounds, Path,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Checks whether the graph is bipartite by two-colouring it with a breadth first search,
+/// treating every edge as undirected
+///
+/// Returns the colour of every node when the graph is bipartite,
+/// so no edge joins two nodes of the same colour.
+/// Otherwise returns an odd length cycle as a witness,
+/// which may follow edges in either direction and so carries no weights.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::is_bipartite;
+///
+/// let square: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [3],
+///     4 => [3, 1],
+/// };
+///
+/// let colours = is_bipartite(&square).unwrap();
+/// assert_eq!(colours[&1], colours[&3]);
+/// assert_ne!(colours[&1], colours[&2]);
+///
+/// let triangle: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [3],
+///     1 => [3],
+/// };
+///
+/// let cycle = is_bipartite(&triangle).unwrap_err();
+/// assert_eq!(cycle.edges().len(), 3);
+/// assert_eq!(cycle.edges().last().unwrap().destination(), &cycle.head());
+/// ```
+pub fn is_bipartite<'a, G>(graph: G) -> Result<HashMap<&'a G::Node, bool>, Path<'a, G::Node>>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let mut undirected: HashMap<&G::Node, Vec<&G::Node>> = HashMap::new();
+    for u in graph.nodes() {
+        for v in graph.neighbors(u) {
+            undirected.entry(u).or_default().push(v);
+            undirected.entry(v).or_default().push(u);
+        }
+    }
+
+    let mut colours: HashMap<&G::Node, bool> = HashMap::with_capacity(graph.node_count());
+    let mut parents: HashMap<&G::Node, &G::Node> = HashMap::new();
+
+    for root in graph.nodes() {
+        if colours.contains_key(root) {
+            continue;
+        }
+
+        colours.insert(root, false);
+        let mut queue: VecDeque<&G::Node> = vec![root].into();
+
+        while let Some(u) = queue.pop_front() {
+            let colour = colours[u];
+
+            for &v in undirected.get(u).into_iter().flatten() {
+                match colours.get(v) {
+                    None => {
+                        colours.insert(v, !colour);
+                        parents.insert(v, u);
+                        queue.push_back(v);
+                    }
+                    Some(&c) if c == colour => return Err(odd_cycle(&parents, u, v)),
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+
+    Ok(colours)
+}
+
+/// Builds the odd cycle formed by the search tree paths to `u` and `v`
+/// and the edge between them, which joins two nodes of the same colour
+fn odd_cycle<'a, N: NodeBounds>(
+    parents: &HashMap<&'a N, &'a N>,
+    u: &'a N,
+    v: &'a N,
+) -> Path<'a, N> {
+    let ancestors = |mut node: &'a N| {
+        let mut path = vec![node];
+        while let Some(&parent) = parents.get(node) {
+            path.push(parent);
+            node = parent;
+        }
+        path
+    };
+
+    let to_u = ancestors(u);
+    let to_v = ancestors(v);
+
+    // find the lowest common ancestor of u and v in the search tree
+    let u_ancestors: HashSet<&N> = to_u.iter().copied().collect();
+    let lca_pos_v = to_v
+        .iter()
+        .position(|node| u_ancestors.contains(node))
+        .expect("u and v are in the same search tree");
+    let lca = to_v[lca_pos_v];
+    let lca_pos_u = to_u.iter().position(|&node| node == lca).unwrap();
+
+    // lca -> ... -> u -> v -> ... -> lca
+    let edges = to_u[..lca_pos_u]
+        .iter()
+        .rev()
+        .chain(to_v[..=lca_pos_v].iter())
+        .map(|&node| Edge::new(node))
+        .collect();
+
+    Path::from_parts(lca, edges)
+}
@@ -0,0 +1,176 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use super::Search;
+use crate::{
+    visit::{EdgeWeighted, IntoNeighbors},
+    Edge, EdgeWeight, NodeBounds, PredMap,
+};
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+/// The nodes reached by a search which stopped at a budget,
+/// created by [`dijkstra_bounded`] and [`bfs_bounded`]
+///
+/// `D` is the type of the distances, an [`EdgeWeight`] for the total weight of a path
+/// or a `usize` for the number of edges on it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundedSearch<'a, N: NodeBounds, D> {
+    /// The predecessor of every reached node on a shortest path to it, with the start its own predecessor
+    pub pred_map: PredMap<'a, N>,
+    /// The distance of every reached node from the start, all within the budget
+    pub distances: HashMap<&'a N, D>,
+    /// The reached nodes with an edge to a node beyond the budget, in the order they were reached
+    pub frontier: Vec<&'a N>,
+}
+
+impl<'a, N: NodeBounds, D> BoundedSearch<'a, N, D> {
+    /// Returns whether `u` can be reached within the budget
+    pub fn contains(&self, u: &N) -> bool {
+        self.distances.contains_key(u)
+    }
+
+    /// Returns the number of nodes reached, including the start
+    pub fn len(&self) -> usize {
+        self.distances.len()
+    }
+
+    /// Returns whether no nodes were reached, which only happens when the budget is negative
+    pub fn is_empty(&self) -> bool {
+        self.distances.is_empty()
+    }
+}
+
+/// Finds the shortest paths from `s` to every node within `max_cost` of it using Dijkstra's algorithm,
+/// never expanding a node beyond the budget
+///
+/// The search only does as much work as the region within the budget needs,
+/// however big the rest of the graph is, answering queries like "what is within 30 minutes".
+/// Nothing is reached if `max_cost` is negative.
+///
+/// Panics if an edge without a weight is reached.
+/// ```
+/// use graph_algos::{graph, Graph, Path};
+/// use graph_algos::algos::dijkstra_bounded;
+///
+/// // travel times in minutes
+/// let roads: Graph<&str> = graph! {
+///     "home" => ["shop" => 10, "park" => 25],
+///     "shop" => ["station" => 15, "home" => 10],
+///     "park" => ["lake" => 20],
+///     "station" => ["airport" => 40],
+/// };
+///
+/// let within = dijkstra_bounded(&roads, &"home", 30.into());
+/// assert!(within.contains(&"station") && within.contains(&"park"));
+/// assert!(!within.contains(&"lake") && !within.contains(&"airport"));
+/// assert_eq!(within.distances[&"station"], 25.into());
+///
+/// let path = Path::new_path_to(&within.pred_map, &"station").unwrap();
+/// assert_eq!(path.to_string(), r#""home" --(10)-> "shop" --(15)-> "station""#);
+///
+/// // the edges out of the region leave from these
+/// let mut frontier = within.frontier.clone();
+/// frontier.sort();
+/// assert_eq!(frontier, vec![&"park", &"station"]);
+/// ```
+pub fn dijkstra_bounded<'a, G: EdgeWeighted<'a>>(
+    graph: G,
+    s: &'a G::Node,
+    max_cost: EdgeWeight,
+) -> BoundedSearch<'a, G::Node, EdgeWeight> {
+    // states beyond the budget are never queued, so everything queued is within it
+    let mut search = Search::new(s, 0.into()).with_budget(max_cost);
+    let mut order = Vec::new();
+    while let Some((node, distance)) = search.pop() {
+        order.push(node);
+        search.expand(graph, node, distance, |_| 0.into());
+    }
+
+    let pred_map = search.pred_map();
+    let distances = search.costs;
+    let frontier = frontier(graph, &order, &distances);
+    BoundedSearch {
+        pred_map,
+        distances,
+        frontier,
+    }
+}
+
+/// Finds the paths with the fewest edges from `s` to every node at most `max_depth` edges away from it
+/// using a breadth first search, never expanding a node beyond the budget
+/// ```
+/// use graph_algos::Graph;
+/// use graph_algos::algos::bfs_bounded;
+///
+/// let grid = Graph::grid(10, 10, false);
+///
+/// // the diamond of cells at most 2 steps from the middle
+/// let within = bfs_bounded(&grid, &(5, 5), 2);
+/// assert_eq!(within.len(), 13);
+/// assert_eq!(within.distances[&(5, 7)], 2);
+/// assert_eq!(within.frontier.len(), 8);
+/// ```
+pub fn bfs_bounded<'a, G: IntoNeighbors<'a>>(
+    graph: G,
+    s: &'a G::Node,
+    max_depth: usize,
+) -> BoundedSearch<'a, G::Node, usize> {
+    let mut pred_map: PredMap<'a, G::Node> = HashMap::new();
+    let mut distances: HashMap<&'a G::Node, usize> = HashMap::new();
+    let mut order = Vec::new();
+    let mut discovered: VecDeque<&G::Node> = vec![s].into();
+
+    pred_map.insert(s, Edge::new(s));
+    distances.insert(s, 0);
+
+    while let Some(u) = discovered.pop_front() {
+        order.push(u);
+
+        let depth = distances[u];
+        if depth == max_depth {
+            continue;
+        }
+
+        for v in graph.neighbors(u) {
+            // the first time we see a node is along a shortest path
+            if let Entry::Vacant(entry) = distances.entry(v) {
+                entry.insert(depth + 1);
+                pred_map.insert(v, Edge::new(u));
+                discovered.push_back(v);
+            }
+        }
+    }
+
+    let frontier = frontier(graph, &order, &distances);
+    BoundedSearch {
+        pred_map,
+        distances,
+        frontier,
+    }
+}
+
+/// Returns the reached nodes, in the order given, with an edge to a node which wasn't reached
+fn frontier<'a, G: IntoNeighbors<'a>, D>(
+    graph: G,
+    order: &[&'a G::Node],
+    distances: &HashMap<&'a G::Node, D>,
+) -> Vec<&'a G::Node> {
+    order
+        .iter()
+        .copied()
+        .filter(|&u| graph.neighbors(u).any(|v| !distances.contains_key(v)))
+        .collect()
+}
@@ -0,0 +1,144 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::visit::IntoNeighbors;
+use std::collections::{HashMap, HashSet};
+
+/// The edges reachable from the start of a depth first search, sorted by how the search met them,
+/// created by [`dfs_classify`]
+///
+/// Every edge is stored as a `(source, destination)` pair in exactly one of the four sets.
+/// The timestamps come from a single clock which ticks once each time
+/// a node is discovered or finished.
+#[derive(Debug, Clone)]
+pub struct DfsClassification<'a, N> {
+    /// Edges along which a node was discovered for the first time
+    pub tree: HashSet<(&'a N, &'a N)>,
+    /// Edges to an ancestor which is still being explored, including self loops
+    pub back: HashSet<(&'a N, &'a N)>,
+    /// Edges to an already finished descendant
+    pub forward: HashSet<(&'a N, &'a N)>,
+    /// Edges to an already finished node which is neither an ancestor nor a descendant
+    pub cross: HashSet<(&'a N, &'a N)>,
+    /// The time at which each node was first reached
+    pub discovered: HashMap<&'a N, usize>,
+    /// The time at which all of the outbound edges of each node had been explored
+    pub finished: HashMap<&'a N, usize>,
+}
+
+impl<N> DfsClassification<'_, N> {
+    /// Returns whether the search found a cycle, which is the case exactly when there is a back edge
+    pub fn has_cycle(&self) -> bool {
+        !self.back.is_empty()
+    }
+}
+
+/// Classifies the edges reachable from `start` as tree, back, forward or cross edges
+/// of a depth first search, returning `None` if `start` isn't in the graph
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::dfs_classify;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [3],
+///     3 => [1],
+/// };
+///
+/// let dfs = dfs_classify(&graph, &1).unwrap();
+///
+/// assert_eq!(dfs.tree.len(), 2);
+/// assert!(dfs.back.contains(&(&3, &1)));
+/// assert!(dfs.has_cycle());
+/// assert_eq!(dfs.discovered[&1], 0);
+/// assert_eq!(dfs.finished[&1], 5);
+/// ```
+///
+/// Whether an edge is a forward or a cross edge depends on the order the successors are visited in:
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::dfs_classify;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2, 3],
+///     2 => [3],
+/// };
+/// let dfs = dfs_classify(&graph, &1).unwrap();
+/// assert!(dfs.forward.contains(&(&1, &3)));
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [3, 2],
+///     2 => [3],
+/// };
+/// let dfs = dfs_classify(&graph, &1).unwrap();
+/// assert!(dfs.cross.contains(&(&2, &3)));
+///
+/// assert!(dfs_classify(&graph, &4).is_none());
+/// ```
+pub fn dfs_classify<'a, G>(graph: G, start: &'a G::Node) -> Option<DfsClassification<'a, G::Node>>
+where
+    G: IntoNeighbors<'a>,
+{
+    if !graph.contains_node(start) {
+        return None;
+    }
+
+    let mut result = DfsClassification {
+        tree: HashSet::new(),
+        back: HashSet::new(),
+        forward: HashSet::new(),
+        cross: HashSet::new(),
+        discovered: HashMap::new(),
+        finished: HashMap::new(),
+    };
+    let mut clock = 0;
+
+    result.discovered.insert(start, clock);
+    clock += 1;
+
+    // the recursion is kept on an explicit stack so deep graphs can't overflow it
+    let mut stack = vec![(start, graph.neighbors(start))];
+    while let Some((u, succs)) = stack.last_mut() {
+        let u = *u;
+        match succs.next() {
+            Some(v) => match (result.discovered.get(v), result.finished.contains_key(v)) {
+                (None, _) => {
+                    result.tree.insert((u, v));
+                    result.discovered.insert(v, clock);
+                    clock += 1;
+                    stack.push((v, graph.neighbors(v)));
+                }
+                (Some(_), false) => {
+                    result.back.insert((u, v));
+                }
+                (Some(&v_time), true) => {
+                    if result.discovered[u] < v_time {
+                        result.forward.insert((u, v));
+                    } else {
+                        result.cross.insert((u, v));
+                    }
+                }
+            },
+            None => {
+                result.finished.insert(u, clock);
+                clock += 1;
+                stack.pop();
+            }
+        }
+    }
+
+    Some(result)
+}
@@ -0,0 +1,669 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::{
+    util::index_graph,
+    visit::{IntoNeighbors, IntoNodes},
+    NodeBounds,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+/// How [`layering_with`] assigns nodes to layers
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum LayeringMethod {
+    /// Each node goes one layer below its lowest predecessor,
+    /// which is fast and uses as few layers as possible but can leave some edges very long
+    LongestPath,
+
+    /// The network simplex method of Gansner et al., which starts from the longest path layering
+    /// and moves nodes between layers to minimise the total length of the edges
+    #[default]
+    NetworkSimplex,
+}
+
+/// An assignment of the nodes of a graph to layers, created by [`layering`] and [`layering_with`]
+///
+/// Every edge of [`Layering::edges`] points from a lower numbered layer to a higher numbered one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layering<'a, N: NodeBounds> {
+    /// The layer of each node, counting from 0
+    pub layer_of: HashMap<&'a N, usize>,
+    /// The nodes in each layer
+    pub layers: Vec<Vec<&'a N>>,
+    /// The edges of the graph with self loops and parallel edges dropped,
+    /// and with the edges in [`Layering::reversed`] turned around so that the graph is acyclic
+    pub edges: Vec<(&'a N, &'a N)>,
+    /// The edges which were reversed to break cycles, in the direction they have in [`Layering::edges`]
+    pub reversed: Vec<(&'a N, &'a N)>,
+}
+
+impl<'a, N: NodeBounds> Layering<'a, N> {
+    /// Returns the number of layers
+    pub fn height(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Returns the total length of the edges, the number of layers each one spans added up
+    pub fn total_edge_length(&self) -> usize {
+        self.edges
+            .iter()
+            .map(|(u, v)| self.layer_of[v] - self.layer_of[u])
+            .sum()
+    }
+
+    /// Splits every edge spanning more than one layer into a chain of edges through dummy nodes,
+    /// one in each layer it passes through, so that every edge joins neighbouring layers
+    ///
+    /// This is the form the later stages of a Sugiyama style layout, ordering the nodes within
+    /// each layer and placing them, expect, and the dummy nodes give the bends of the long edges.
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    /// use graph_algos::algos::{layering, LayerNode};
+    ///
+    /// let graph: Graph<&str> = graph! {
+    ///     "a" => ["b", "c"],
+    ///     "b" => ["c"],
+    /// };
+    ///
+    /// let proper = layering(&graph).with_dummies();
+    ///
+    /// let widths: Vec<usize> = proper.layers.iter().map(Vec::len).collect();
+    /// assert_eq!(widths, vec![1, 2, 1]);
+    /// assert!(proper.layers[1].contains(&LayerNode::Dummy {
+    ///     source: &"a",
+    ///     destination: &"c",
+    ///     layer: 1,
+    /// }));
+    /// assert_eq!(proper.edges.len(), 4);
+    /// assert!(proper
+    ///     .edges
+    ///     .iter()
+    ///     .all(|&(u, v)| proper.layer_of(u) + 1 == proper.layer_of(v)));
+    /// ```
+    pub fn with_dummies(&self) -> ProperLayering<'a, N> {
+        let mut layers: Vec<Vec<LayerNode<'a, N>>> = self
+            .layers
+            .iter()
+            .map(|layer| layer.iter().map(|&u| LayerNode::Node(u)).collect())
+            .collect();
+        let mut edges = Vec::with_capacity(self.edges.len());
+
+        for &(source, destination) in &self.edges {
+            let (top, bottom) = (self.layer_of[source], self.layer_of[destination]);
+
+            let mut previous = LayerNode::Node(source);
+            for (layer, nodes) in layers.iter_mut().enumerate().take(bottom).skip(top + 1) {
+                let dummy = LayerNode::Dummy {
+                    source,
+                    destination,
+                    layer,
+                };
+                nodes.push(dummy);
+                edges.push((previous, dummy));
+                previous = dummy;
+            }
+            edges.push((previous, LayerNode::Node(destination)));
+        }
+
+        ProperLayering {
+            layer_of: self.layer_of.clone(),
+            layers,
+            edges,
+        }
+    }
+}
+
+/// A node of a [`ProperLayering`], either a node of the graph
+/// or a dummy node standing in for a long edge as it passes through a layer
+pub enum LayerNode<'a, N> {
+    /// A node of the graph
+    Node(&'a N),
+
+    /// The point where the edge from `source` to `destination` crosses layer `layer`
+    Dummy {
+        /// the source of the long edge
+        source: &'a N,
+        /// the destination of the long edge
+        destination: &'a N,
+        /// the layer the dummy node is in
+        layer: usize,
+    },
+}
+
+impl<'a, N: NodeBounds> LayerNode<'a, N> {
+    /// Returns the node of the graph, or `None` for a dummy node
+    pub fn node(&self) -> Option<&'a N> {
+        match *self {
+            Self::Node(u) => Some(u),
+            Self::Dummy { .. } => None,
+        }
+    }
+
+    /// Returns whether this is a dummy node
+    pub fn is_dummy(&self) -> bool {
+        matches!(self, Self::Dummy { .. })
+    }
+}
+
+impl<N> Clone for LayerNode<'_, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<N> Copy for LayerNode<'_, N> {}
+
+impl<N: PartialEq> PartialEq for LayerNode<'_, N> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Node(u), Self::Node(v)) => u == v,
+            (
+                Self::Dummy {
+                    source: s1,
+                    destination: d1,
+                    layer: l1,
+                },
+                Self::Dummy {
+                    source: s2,
+                    destination: d2,
+                    layer: l2,
+                },
+            ) => s1 == s2 && d1 == d2 && l1 == l2,
+            _ => false,
+        }
+    }
+}
+
+impl<N: Eq> Eq for LayerNode<'_, N> {}
+
+impl<N: Hash> Hash for LayerNode<'_, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Self::Node(u) => (0u8, u).hash(state),
+            Self::Dummy {
+                source,
+                destination,
+                layer,
+            } => (1u8, source, destination, layer).hash(state),
+        }
+    }
+}
+
+impl<N: fmt::Debug> fmt::Debug for LayerNode<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Node(u) => f.debug_tuple("Node").field(u).finish(),
+            Self::Dummy {
+                source,
+                destination,
+                layer,
+            } => f
+                .debug_struct("Dummy")
+                .field("source", source)
+                .field("destination", destination)
+                .field("layer", layer)
+                .finish(),
+        }
+    }
+}
+
+/// A layering where every edge joins neighbouring layers, created by [`Layering::with_dummies`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProperLayering<'a, N: NodeBounds> {
+    layer_of: HashMap<&'a N, usize>,
+    /// The nodes and dummy nodes in each layer
+    pub layers: Vec<Vec<LayerNode<'a, N>>>,
+    /// The edges, each from a node in one layer to a node in the next
+    pub edges: Vec<(LayerNode<'a, N>, LayerNode<'a, N>)>,
+}
+
+impl<'a, N: NodeBounds> ProperLayering<'a, N> {
+    /// Returns the layer a node or dummy node is in
+    ///
+    /// Panics if the node isn't part of the layering.
+    pub fn layer_of(&self, node: LayerNode<'a, N>) -> usize {
+        match node {
+            LayerNode::Node(u) => self.layer_of[u],
+            LayerNode::Dummy { layer, .. } => layer,
+        }
+    }
+}
+
+/// Assigns the nodes of the graph to layers with the network simplex method,
+/// the first stage of drawing a graph as a hierarchy, see [`layering_with`]
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::{layering, layering_with, LayeringMethod};
+///
+/// let graph: Graph<&str> = graph! {
+///     "a" => ["b"],
+///     "b" => ["c"],
+///     "c" => ["d"],
+///     "e" => ["d"],
+/// };
+///
+/// // the longest path layering puts every source in the top layer
+/// let longest = layering_with(&graph, LayeringMethod::LongestPath);
+/// assert_eq!(longest.layer_of[&"e"], 0);
+/// assert_eq!(longest.total_edge_length(), 6);
+///
+/// // while network simplex moves "e" down next to "d"
+/// let simplex = layering(&graph);
+/// assert_eq!(simplex.layer_of[&"e"], 2);
+/// assert_eq!(simplex.total_edge_length(), 4);
+/// assert_eq!(simplex.height(), 4);
+/// ```
+pub fn layering<'a, G>(graph: G) -> Layering<'a, G::Node>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    layering_with(graph, LayeringMethod::NetworkSimplex)
+}
+
+/// Assigns the nodes of the graph to layers so that every edge points downwards,
+/// the first stage of drawing a graph as a hierarchy in the style of Sugiyama
+///
+/// Cycles are broken first by reversing the edges which close them in a depth first search,
+/// and self loops and parallel edges are dropped.
+/// Each weakly connected part of the graph starts from the top layer.
+/// Each step of the network simplex method takes time linear in the size of the graph,
+/// which suits graphs small enough to draw, and it stops after a bounded number of steps
+/// on graphs where it would otherwise cycle, in which case the layering is still valid
+/// but may not be the shortest.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::{layering_with, LayeringMethod};
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [3],
+///     3 => [1],
+/// };
+///
+/// let layering = layering_with(&graph, LayeringMethod::LongestPath);
+/// assert_eq!(layering.height(), 3);
+/// assert_eq!(layering.reversed.len(), 1);
+/// assert!(layering
+///     .edges
+///     .iter()
+///     .all(|(u, v)| layering.layer_of[u] < layering.layer_of[v]));
+/// ```
+pub fn layering_with<'a, G>(graph: G, method: LayeringMethod) -> Layering<'a, G::Node>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let (nodes, succs) = index_graph(graph);
+
+    // parallel edges, including ones made by reversing an edge, become a single heavier edge
+    let mut weights: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut reversed = HashSet::new();
+    for (u, v, was_reversed) in acyclic_edges(&succs) {
+        *weights.entry((u, v)).or_default() += 1;
+        if was_reversed {
+            reversed.insert((u, v));
+        }
+    }
+    let mut edges: Vec<(usize, usize, usize)> =
+        weights.into_iter().map(|((u, v), w)| (u, v, w)).collect();
+    edges.sort_unstable();
+
+    let plain: Vec<(usize, usize)> = edges.iter().map(|&(u, v, _)| (u, v)).collect();
+    let mut layer_of = longest_path_layers(nodes.len(), &plain);
+    if method == LayeringMethod::NetworkSimplex {
+        layer_of = network_simplex(nodes.len(), &edges, &layer_of);
+    }
+
+    let height = layer_of.iter().map(|&l| l + 1).max().unwrap_or(0);
+    let mut layers = vec![Vec::new(); height];
+    for (u, &layer) in layer_of.iter().enumerate() {
+        layers[layer].push(nodes[u]);
+    }
+
+    Layering {
+        layer_of: nodes.iter().copied().zip(layer_of).collect(),
+        layers,
+        reversed: plain
+            .iter()
+            .filter(|edge| reversed.contains(edge))
+            .map(|&(u, v)| (nodes[u], nodes[v]))
+            .collect(),
+        edges: plain.iter().map(|&(u, v)| (nodes[u], nodes[v])).collect(),
+    }
+}
+
+/// Returns the edges of the graph with self loops dropped
+/// and the edges closing cycles reversed, so that the result is acyclic,
+/// along with whether each edge was reversed
+pub(crate) fn acyclic_edges(succs: &[Vec<usize>]) -> Vec<(usize, usize, bool)> {
+    let n = succs.len();
+    let (mut visited, mut on_stack) = (vec![false; n], vec![false; n]);
+    let mut edges = Vec::new();
+
+    for start in 0..n {
+        if visited[start] {
+            continue;
+        }
+
+        visited[start] = true;
+        on_stack[start] = true;
+        let mut stack = vec![(start, 0)];
+
+        while let Some((u, next)) = stack.last_mut() {
+            let u = *u;
+            match succs[u].get(*next) {
+                Some(&v) => {
+                    *next += 1;
+                    if u == v {
+                        continue;
+                    }
+
+                    if on_stack[v] {
+                        edges.push((v, u, true));
+                    } else {
+                        edges.push((u, v, false));
+                        if !visited[v] {
+                            visited[v] = true;
+                            on_stack[v] = true;
+                            stack.push((v, 0));
+                        }
+                    }
+                }
+                None => {
+                    on_stack[u] = false;
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    edges
+}
+
+/// Places each node one layer below its lowest predecessor
+pub(crate) fn longest_path_layers(n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut succs = vec![Vec::new(); n];
+    let mut in_degree = vec![0; n];
+    for &(u, v) in edges {
+        succs[u].push(v);
+        in_degree[v] += 1;
+    }
+
+    let mut layer = vec![0; n];
+    let mut ready: Vec<usize> = (0..n).filter(|&u| in_degree[u] == 0).collect();
+    while let Some(u) = ready.pop() {
+        for &v in &succs[u] {
+            layer[v] = layer[v].max(layer[u] + 1);
+            in_degree[v] -= 1;
+            if in_degree[v] == 0 {
+                ready.push(v);
+            }
+        }
+    }
+
+    layer
+}
+
+/// The most pivots network simplex makes for each edge before giving up on finding the optimum
+const PIVOTS_PER_EDGE: usize = 20;
+
+/// Improves a feasible layering of a DAG with weighted edges `(u, v, weight)`
+/// to minimise the total weighted edge length, with the network simplex method
+///
+/// The layering is kept as a spanning forest of tight edges, those spanning exactly one layer.
+/// Each step finds a tree edge with a negative cut value, whose removal would let the two halves
+/// of its tree move apart to shorten the edges between them,
+/// and swaps it for the non-tree edge between the halves which is closest to being tight.
+fn network_simplex(n: usize, edges: &[(usize, usize, usize)], initial: &[usize]) -> Vec<usize> {
+    let mut rank: Vec<i64> = initial.iter().map(|&l| l as i64).collect();
+    let slack = |rank: &[i64], e: usize| {
+        let (u, v, _) = edges[e];
+        rank[v] - rank[u] - 1
+    };
+
+    let mut incident = vec![Vec::new(); n];
+    for (e, &(u, v, _)) in edges.iter().enumerate() {
+        incident[u].push(e);
+        incident[v].push(e);
+    }
+    let other = |e: usize, x: usize| {
+        let (u, v, _) = edges[e];
+        if u == x {
+            v
+        } else {
+            u
+        }
+    };
+
+    // grow a tight spanning tree of each weakly connected part,
+    // shifting the tree to make the closest edge out of it tight whenever it gets stuck
+    let mut in_tree = vec![false; edges.len()];
+    let mut reached = vec![false; n];
+    for root in 0..n {
+        if reached[root] {
+            continue;
+        }
+
+        reached[root] = true;
+        let mut part = vec![root];
+        loop {
+            let mut stack = part.clone();
+            while let Some(x) = stack.pop() {
+                for &e in &incident[x] {
+                    let y = other(e, x);
+                    if !reached[y] && slack(&rank, e) == 0 {
+                        reached[y] = true;
+                        in_tree[e] = true;
+                        part.push(y);
+                        stack.push(y);
+                    }
+                }
+            }
+
+            let closest = part
+                .iter()
+                .flat_map(|&x| incident[x].iter().map(move |&e| (x, e)))
+                .filter(|&(x, e)| !reached[other(e, x)])
+                .min_by_key(|&(_, e)| slack(&rank, e));
+            let (x, e) = match closest {
+                Some(found) => found,
+                None => break,
+            };
+
+            // move the tree towards the edge, which the other edges out of it have room for
+            let delta = if edges[e].1 == x {
+                -slack(&rank, e)
+            } else {
+                slack(&rank, e)
+            };
+            for &y in &part {
+                rank[y] += delta;
+            }
+        }
+    }
+
+    let mut cursor = 0;
+    let mut pivots = edges.len().saturating_mul(PIVOTS_PER_EDGE);
+    let tree = loop {
+        let tree = Forest::new(n, edges, &in_tree, &incident);
+        tree.rerank(edges, &mut rank);
+        if pivots == 0 {
+            break tree;
+        }
+        pivots -= 1;
+
+        // look for a negative cut value starting after the last edge which left the tree,
+        // which stops the search from favouring the same few edges
+        let leaving = (0..edges.len())
+            .map(|i| (cursor + i) % edges.len())
+            .find(|&e| in_tree[e] && tree.cut_value(e) < 0);
+        let leaving = match leaving {
+            Some(e) => e,
+            None => break tree,
+        };
+        cursor = leaving + 1;
+
+        // the replacement has to cross from the head side of the leaving edge to its tail side
+        let child = tree.child[leaving];
+        let tail_below = edges[leaving].0 == child;
+        let entering = (0..edges.len())
+            .filter(|&e| !in_tree[e])
+            .filter(|&e| {
+                let (u, v, _) = edges[e];
+                let (u_below, v_below) = (tree.contains(child, u), tree.contains(child, v));
+                if tail_below {
+                    !u_below && v_below
+                } else {
+                    u_below && !v_below
+                }
+            })
+            .min_by_key(|&e| slack(&rank, e));
+        let entering = match entering {
+            Some(e) => e,
+            None => break tree,
+        };
+
+        in_tree[leaving] = false;
+        in_tree[entering] = true;
+    };
+
+    // start every part from the top layer
+    let mut lowest: HashMap<usize, i64> = HashMap::new();
+    for (&root, &r) in tree.root.iter().zip(&rank) {
+        let top = lowest.entry(root).or_insert(r);
+        *top = (*top).min(r);
+    }
+
+    tree.root
+        .iter()
+        .zip(&rank)
+        .map(|(root, r)| (r - lowest[root]) as usize)
+        .collect()
+}
+
+/// A rooted spanning forest of tight edges used by [`network_simplex`]
+struct Forest {
+    /// the root of the tree each node is in
+    root: Vec<usize>,
+    /// the tree edge joining each node to its parent, `None` for the roots
+    parent_edge: Vec<Option<usize>>,
+    /// the nodes in the order they were reached from the roots
+    preorder: Vec<usize>,
+    /// the position of each node in a postorder walk of its tree
+    lim: Vec<usize>,
+    /// the smallest `lim` of any node in the subtree of each node
+    low: Vec<usize>,
+    /// the cut value of the tree edge above each node
+    cut: Vec<i64>,
+    /// the endpoint of each tree edge which is further from the root
+    child: Vec<usize>,
+}
+
+impl Forest {
+    /// roots the trees of the forest made up of the edges in `in_tree`, and works out the cut values
+    fn new(
+        n: usize,
+        edges: &[(usize, usize, usize)],
+        in_tree: &[bool],
+        incident: &[Vec<usize>],
+    ) -> Self {
+        let mut forest = Self {
+            root: vec![usize::MAX; n],
+            parent_edge: vec![None; n],
+            preorder: Vec::with_capacity(n),
+            lim: vec![0; n],
+            low: vec![0; n],
+            cut: vec![0; n],
+            child: vec![usize::MAX; edges.len()],
+        };
+
+        let mut next_lim = 0;
+        for root in 0..n {
+            if forest.root[root] != usize::MAX {
+                continue;
+            }
+
+            forest.root[root] = root;
+            forest.preorder.push(root);
+            forest.low[root] = next_lim;
+            let mut stack = vec![(root, 0)];
+            while let Some((x, next)) = stack.last_mut() {
+                let x = *x;
+                match incident[x].get(*next) {
+                    Some(&e) => {
+                        *next += 1;
+                        let (u, v, _) = edges[e];
+                        let y = if u == x { v } else { u };
+                        if in_tree[e] && forest.root[y] == usize::MAX {
+                            forest.root[y] = root;
+                            forest.parent_edge[y] = Some(e);
+                            forest.child[e] = y;
+                            forest.preorder.push(y);
+                            forest.low[y] = next_lim;
+                            stack.push((y, 0));
+                        }
+                    }
+                    None => {
+                        forest.lim[x] = next_lim;
+                        next_lim += 1;
+                        stack.pop();
+                    }
+                }
+            }
+        }
+
+        // the cut value of the edge above `x` is the weight of the edges leaving the subtree of `x`
+        // minus the weight of those entering it, negated if the tree edge points into the subtree
+        let mut balance = vec![0i64; n];
+        for &(u, v, w) in edges {
+            balance[u] += w as i64;
+            balance[v] -= w as i64;
+        }
+        for &x in forest.preorder.iter().rev() {
+            if let Some(e) = forest.parent_edge[x] {
+                let (u, v, _) = edges[e];
+                let parent = if u == x { v } else { u };
+                balance[parent] += balance[x];
+                forest.cut[x] = if u == x { balance[x] } else { -balance[x] };
+            }
+        }
+
+        forest
+    }
+
+    /// returns the cut value of a tree edge
+    fn cut_value(&self, e: usize) -> i64 {
+        self.cut[self.child[e]]
+    }
+
+    /// returns whether `y` is in the subtree of `x`
+    fn contains(&self, x: usize, y: usize) -> bool {
+        self.root[x] == self.root[y] && self.low[x] <= self.lim[y] && self.lim[y] <= self.lim[x]
+    }
+
+    /// sets the ranks so that every tree edge is tight, keeping the rank of each root
+    fn rerank(&self, edges: &[(usize, usize, usize)], rank: &mut [i64]) {
+        for &x in &self.preorder {
+            if let Some(e) = self.parent_edge[x] {
+                let (u, v, _) = edges[e];
+                rank[x] = if u == x { rank[v] - 1 } else { rank[u] + 1 };
+            }
+        }
+    }
+}
@@ -0,0 +1,144 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::Search;
+use crate::{EdgeWeight, NodeBounds};
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+/// Finds a shortest path from `start` to any node for which `is_goal` returns true,
+/// in a graph defined by a function returning the successors of a node and the weights of the edges to them
+///
+/// Nodes are only generated as the search reaches them, so the graph never needs to be built.
+/// Returns the nodes on the path, starting with `start`, and its total weight,
+/// or `None` if no goal can be reached.
+/// ```
+/// use graph_algos::EdgeWeight;
+/// use graph_algos::algos::dijkstra_lazy;
+///
+/// // reach 10 from 1, where doubling costs 1 and adding one costs 2
+/// let (path, cost) = dijkstra_lazy(
+///     1_u32,
+///     |&n| vec![(n * 2, EdgeWeight::new(1)), (n + 1, EdgeWeight::new(2))]
+///         .into_iter()
+///         .filter(|&(m, _)| m <= 10),
+///     |&n| n == 10,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(path, vec![1, 2, 4, 5, 10]);
+/// assert_eq!(cost, 5.into());
+/// ```
+pub fn dijkstra_lazy<N, F, I, G>(
+    start: N,
+    mut successors: F,
+    mut is_goal: G,
+) -> Option<(Vec<N>, EdgeWeight)>
+where
+    N: NodeBounds,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, EdgeWeight)>,
+    G: FnMut(&N) -> bool,
+{
+    // nodes are stored once and searched by their index from then on
+    let mut nodes: Vec<N> = vec![start.clone()];
+    let mut indices: HashMap<N, usize> = [(start, 0)].iter().cloned().collect();
+    let mut search = Search::new(0, 0.into());
+
+    while let Some((u, distance)) = search.pop() {
+        if is_goal(&nodes[u]) {
+            let path = std::iter::once(0)
+                .chain(search.path_to(u).into_iter().map(|(v, _)| v))
+                .map(|i| nodes[i].clone())
+                .collect();
+            return Some((path, distance));
+        }
+
+        for (v, weight) in successors(&nodes[u]) {
+            let v = match indices.entry(v) {
+                Entry::Occupied(o) => *o.get(),
+                Entry::Vacant(e) => {
+                    nodes.push(e.key().clone());
+                    *e.insert(nodes.len() - 1)
+                }
+            };
+
+            search.relax(u, v, distance + weight, Some(weight), |d| d);
+        }
+    }
+
+    None
+}
+
+/// Finds a path with the fewest edges from `start` to any node for which `is_goal` returns true,
+/// in a graph defined by a function returning the successors of a node
+///
+/// Nodes are only generated as the search reaches them, so the graph never needs to be built.
+/// Returns the nodes on the path, starting with `start`, or `None` if no goal can be reached.
+/// ```
+/// use graph_algos::algos::bfs_lazy;
+///
+/// // a knight's shortest route across a chess board
+/// let moves = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+/// let path = bfs_lazy(
+///     (0_i32, 0_i32),
+///     |&(x, y)| {
+///         moves
+///             .iter()
+///             .map(move |(dx, dy)| (x + dx, y + dy))
+///             .filter(|(x, y)| (0..8).contains(x) && (0..8).contains(y))
+///     },
+///     |&square| square == (7, 7),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(path.len(), 7);
+/// ```
+pub fn bfs_lazy<N, F, I, G>(start: N, mut successors: F, mut is_goal: G) -> Option<Vec<N>>
+where
+    N: NodeBounds,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    G: FnMut(&N) -> bool,
+{
+    let mut nodes: Vec<N> = vec![start.clone()];
+    let mut indices: HashMap<N, usize> = [(start, 0)].iter().cloned().collect();
+    let mut preds: Vec<usize> = vec![0];
+    let mut queue: VecDeque<usize> = vec![0].into();
+
+    while let Some(u) = queue.pop_front() {
+        if is_goal(&nodes[u]) {
+            let mut path = vec![u];
+            while path[path.len() - 1] != 0 {
+                path.push(preds[path[path.len() - 1]]);
+            }
+
+            return Some(path.into_iter().rev().map(|i| nodes[i].clone()).collect());
+        }
+
+        for v in successors(&nodes[u]) {
+            if let Entry::Vacant(e) = indices.entry(v) {
+                let v = nodes.len();
+                nodes.push(e.key().clone());
+                e.insert(v);
+                preds.push(u);
+                queue.push_back(v);
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,204 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{
+    algos::strongly_connected_components,
+    visit::{EdgeWeighted, IntoNodes},
+    Edge, EdgeWeight, NodeBounds, Path,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Finds a longest simple path in the graph, the path visiting each node at most once
+/// with the largest total weight
+///
+/// Unweighted edges count as a weight of 1 and edges with an infinite weight are ignored.
+/// The strongly connected components are found first, when they are all single nodes without self loops
+/// the graph is a DAG and the longest path is found exactly in linear time.
+/// Otherwise the problem is NP-hard, so simple paths are searched exhaustively until
+/// `search_limit` edges have been explored and the longest path seen is returned.
+/// Returns the path and whether it is exact, which is when the graph is a DAG or the search finished within the limit,
+/// or `None` if the graph is empty.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::longest_simple_path;
+///
+/// let dag: Graph<&str> = graph! {
+///     "a" => ["b" => 1, "c" => 5],
+///     "b" => ["d" => 1],
+///     "c" => ["d" => 1],
+///     "d" => ["e" => 2],
+/// };
+///
+/// let (path, exact) = longest_simple_path(&dag, 1000).unwrap();
+/// assert_eq!(path.to_string(), "\"a\" --(5)-> \"c\" --(1)-> \"d\" --(2)-> \"e\"");
+/// assert!(exact);
+///
+/// let cyclic: Graph<u32> = graph! {
+///     1 => [2 => 1],
+///     2 => [3 => 1, 1 => 1],
+///     3 => [1 => 1],
+/// };
+///
+/// let (path, exact) = longest_simple_path(&cyclic, 1000).unwrap();
+/// assert_eq!(path.edges().len(), 2);
+/// assert!(exact);
+///
+/// // a search cut short by the limit may have missed a longer path
+/// let (path, exact) = longest_simple_path(&cyclic, 1).unwrap();
+/// assert_eq!(path.edges().len(), 1);
+/// assert!(!exact);
+/// ```
+pub fn longest_simple_path<'a, G>(
+    graph: G,
+    search_limit: usize,
+) -> Option<(Path<'a, G::Node>, bool)>
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a>,
+{
+    let components = strongly_connected_components(graph);
+
+    let is_dag = components.iter().all(|component| match component[..] {
+        [u] => graph.neighbors(u).all(|v| v != u),
+        _ => false,
+    });
+
+    if is_dag {
+        dag_longest_path(graph, &components).map(|path| (path, true))
+    } else {
+        search_longest_path(graph, search_limit)
+    }
+}
+
+/// the length an edge contributes to a path, `None` for edges which are ignored
+fn edge_length(weight: Option<EdgeWeight>) -> Option<i64> {
+    match weight {
+        None => Some(1),
+        Some(EdgeWeight::Weight(w)) => Some(w),
+        Some(_) => None,
+    }
+}
+
+/// finds the longest path in a DAG given its components in reverse topological order
+fn dag_longest_path<'a, G>(graph: G, components: &[Vec<&'a G::Node>]) -> Option<Path<'a, G::Node>>
+where
+    G: EdgeWeighted<'a>,
+{
+    // the length of the longest path starting at each node and the first edge along it
+    let mut lengths: HashMap<&G::Node, i64> = HashMap::new();
+    let mut next: HashMap<&G::Node, Edge<&G::Node>> = HashMap::new();
+
+    // sinks come first, so every successor has been processed before its predecessors
+    for &u in components.iter().flatten() {
+        let best = graph
+            .neighbors_weighted(u)
+            .filter_map(|(v, weight)| {
+                let length = edge_length(weight)?;
+                Some((length.saturating_add(lengths[v]), edge_to(v, weight)))
+            })
+            .max_by_key(|(length, _)| *length)
+            .filter(|(length, _)| *length > 0);
+
+        match best {
+            Some((length, edge)) => {
+                lengths.insert(u, length);
+                next.insert(u, edge);
+            }
+            None => {
+                lengths.insert(u, 0);
+            }
+        }
+    }
+
+    let (&head, _) = lengths.iter().max_by_key(|(_, length)| **length)?;
+
+    let mut edges = Vec::new();
+    let mut u = head;
+    while let Some(edge) = next.get(u) {
+        edges.push(edge.clone());
+        u = edge.destination();
+    }
+
+    Some(Path::from_parts(head, edges))
+}
+
+/// builds the path edge to `v` with the original weight
+fn edge_to<N: NodeBounds>(v: &N, weight: Option<EdgeWeight>) -> Edge<&N> {
+    match weight {
+        Some(weight) => Edge::new_with_weight(v, weight),
+        None => Edge::new(v),
+    }
+}
+
+/// searches the simple paths from every node depth first, exploring at most `limit` edges,
+/// and returns the longest path found and whether every simple path was explored
+fn search_longest_path<'a, G>(graph: G, limit: usize) -> Option<(Path<'a, G::Node>, bool)>
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a>,
+{
+    let mut nodes = graph.nodes();
+    let mut best_head = nodes.next()?;
+    let mut best_edges: Vec<Edge<&G::Node>> = Vec::new();
+    let mut best_length = 0;
+    let mut explored = 0;
+    let mut exact = true;
+
+    for start in std::iter::once(best_head).chain(nodes) {
+        let mut on_path: HashSet<&G::Node> = [start].iter().copied().collect();
+        let mut edges: Vec<Edge<&G::Node>> = Vec::new();
+        let mut lengths: Vec<i64> = vec![0];
+        let mut stack = vec![graph.neighbors_weighted(start)];
+
+        while let Some(succs) = stack.last_mut() {
+            let next = succs.next();
+            if explored >= limit && matches!(next, Some((v, _)) if !on_path.contains(v)) {
+                exact = false;
+            }
+
+            match next {
+                Some((v, weight)) if !on_path.contains(v) && explored < limit => {
+                    explored += 1;
+                    let length = match edge_length(weight) {
+                        Some(length) => length,
+                        None => continue,
+                    };
+
+                    let total = lengths[lengths.len() - 1].saturating_add(length);
+                    on_path.insert(v);
+                    edges.push(edge_to(v, weight));
+                    lengths.push(total);
+                    stack.push(graph.neighbors_weighted(v));
+
+                    if total > best_length {
+                        best_length = total;
+                        best_head = start;
+                        best_edges = edges.clone();
+                    }
+                }
+                Some(_) if explored < limit => {}
+                _ => {
+                    stack.pop();
+                    lengths.pop();
+                    if let Some(edge) = edges.pop() {
+                        on_path.remove(edge.destination());
+                    }
+                }
+            }
+        }
+    }
+
+    Some((Path::from_parts(best_head, best_edges), exact))
+}
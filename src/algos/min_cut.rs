@@ -0,0 +1,158 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{
+    util::{index_nodes, UnionFind},
+    visit::{IntoNeighbors, IntoNodes},
+    NodeBounds,
+};
+use rand::{seq::SliceRandom, Rng};
+use std::collections::HashSet;
+
+/// A cut of a graph into two parts, found by [`karger_min_cut`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinCut<'a, N: NodeBounds> {
+    /// The edges which cross between the two parts, the size of the cut is their number
+    pub cut_edges: Vec<(&'a N, &'a N)>,
+    /// The nodes on each side of the cut
+    pub partition: (Vec<&'a N>, Vec<&'a N>),
+}
+
+/// Finds a minimum cut with Karger's algorithm, treating every edge as undirected
+///
+/// Edges in both directions between two nodes, including the two halves of an undirected edge,
+/// and parallel edges count as a single edge of the cut.
+/// Each iteration contracts randomly chosen edges until only two super nodes remain,
+/// then the smallest cut seen over all of the iterations is returned.
+/// A single iteration finds a minimum cut with probability at least `2 / n^2`,
+/// so around `n^2 ln n` iterations find one with high probability.
+/// Returns `None` if the graph has fewer than two nodes.
+/// ```
+/// use graph_algos::{graph, ungraph, Graph};
+/// use graph_algos::algos::karger_min_cut;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// // two triangles joined by a single edge
+/// let graph: Graph<u32> = graph! {
+///     1 => [2, 3],
+///     2 => [3],
+///     3 => [4],
+///     4 => [5, 6],
+///     5 => [6],
+/// };
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// let cut = karger_min_cut(&graph, &mut rng, 100).unwrap();
+///
+/// assert_eq!(cut.cut_edges, vec![(&3, &4)]);
+///
+/// let (mut a, mut b) = cut.partition;
+/// a.sort();
+/// b.sort();
+/// let mut sides = vec![a, b];
+/// sides.sort();
+/// assert_eq!(sides, vec![vec![&1, &2, &3], vec![&4, &5, &6]]);
+///
+/// // the same triangles, with every edge stored in both directions
+/// let graph: Graph<u32> = ungraph! {
+///     1 => [2, 3],
+///     2 => [3],
+///     3 => [4],
+///     4 => [5, 6],
+///     5 => [6],
+/// };
+///
+/// let cut = karger_min_cut(&graph, &mut rng, 100).unwrap();
+/// assert_eq!(cut.cut_edges.len(), 1);
+/// let (u, v) = cut.cut_edges[0];
+/// assert_eq!((u.min(v), u.max(v)), (&3, &4));
+/// ```
+pub fn karger_min_cut<'a, G, R>(
+    graph: G,
+    rng: &mut R,
+    iterations: usize,
+) -> Option<MinCut<'a, G::Node>>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+    R: Rng + ?Sized,
+{
+    let (nodes, indices) = index_nodes(graph);
+    if nodes.len() < 2 {
+        return None;
+    }
+
+    // each pair of nodes is joined by at most one edge, in the direction it was first seen
+    let mut seen = HashSet::new();
+    let mut edges: Vec<(usize, usize)> = nodes
+        .iter()
+        .enumerate()
+        .flat_map(|(u, node)| {
+            let indices = &indices;
+            graph
+                .neighbors(node)
+                .filter_map(move |v| indices.get(v).map(|&v| (u, v)))
+        })
+        .filter(|&(u, v)| u != v && seen.insert((u.min(v), u.max(v))))
+        .collect();
+
+    let mut best: Option<(Vec<(usize, usize)>, UnionFind)> = None;
+    for _ in 0..iterations.max(1) {
+        // contracting edges in a random order is the same as picking a random edge each time
+        edges.shuffle(rng);
+        let mut sets = UnionFind::new(nodes.len());
+        for &(u, v) in edges.iter() {
+            if sets.count() == 2 {
+                break;
+            }
+            sets.union(u, v);
+        }
+
+        // a disconnected graph leaves extra parts, merge them into the part holding the first node
+        for u in 1..nodes.len() {
+            if sets.count() > 2 {
+                sets.union(0, u);
+            }
+        }
+
+        let cut: Vec<(usize, usize)> = edges
+            .iter()
+            .copied()
+            .filter(|&(u, v)| !sets.same_set(u, v))
+            .collect();
+
+        let better = match &best {
+            Some((best_cut, _)) => cut.len() < best_cut.len(),
+            None => true,
+        };
+
+        if better {
+            best = Some((cut, sets));
+        }
+    }
+
+    let (cut, mut sets) = best?;
+    let side = sets.find(0);
+    let (a, b) = (0..nodes.len()).partition::<Vec<usize>, _>(|&u| sets.find(u) == side);
+
+    Some(MinCut {
+        cut_edges: cut.into_iter().map(|(u, v)| (nodes[u], nodes[v])).collect(),
+        partition: (
+            a.into_iter().map(|u| nodes[u]).collect(),
+            b.into_iter().map(|u| nodes[u]).collect(),
+        ),
+    })
+}
@@ -0,0 +1,93 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Implementations of common graph algorithms.
+//!
+//! Almost every algorithm is generic over the traits in [`crate::visit`], so they can be
+//! run over a `&Graph<N>` as well as any of the views onto a graph.
+//! The exceptions, like [`multi_source_bfs`], work on the dense ids of a [`crate::frozen::FrozenGraph`].
+
+mod all_pairs;
+pub use all_pairs::{floyd_warshall, floyd_warshall_with_control, DistMatrix};
+
+mod all_topo;
+pub use all_topo::{all_topo_sorts, AllTopoSorts};
+
+pub mod alt;
+
+mod astar;
+pub use astar::astar;
+
+mod bipartite;
+pub use bipartite::is_bipartite;
+
+mod bounded;
+pub use bounded::{bfs_bounded, dijkstra_bounded, BoundedSearch};
+
+mod classify;
+pub use classify::{dfs_classify, DfsClassification};
+
+mod layering;
+pub(crate) use layering::{acyclic_edges, longest_path_layers};
+pub use layering::{layering, layering_with, LayerNode, Layering, LayeringMethod, ProperLayering};
+
+mod lazy;
+pub use lazy::{bfs_lazy, dijkstra_lazy};
+
+mod longest_path;
+pub use longest_path::longest_simple_path;
+
+mod min_cut;
+pub use min_cut::{karger_min_cut, MinCut};
+
+mod multi_source;
+pub use multi_source::multi_source_bfs;
+
+pub mod partition;
+
+mod reach;
+pub use reach::ReachabilityFilter;
+
+mod replacement;
+pub use replacement::{
+    replacement_paths, second_shortest_path, Alternative, Detour, ReplacementPaths,
+};
+
+mod scc;
+pub use scc::strongly_connected_components;
+
+mod search;
+pub(crate) use search::Search;
+
+pub mod semiring;
+
+mod shortest_path;
+pub use shortest_path::{
+    bellman_ford, bellman_ford_owned, bfs_shortest_paths, dijkstra, dijkstra_owned, DistMap,
+    OwnedPredMap,
+};
+
+pub mod sparsify;
+
+mod topo;
+pub use topo::{topo_iter, topo_iter_by_key, topological_sort, TopoIter};
+
+mod turns;
+pub use turns::{astar_with_turns, dijkstra_with_turns, TurnCost};
+
+mod via;
+pub use via::route_via;
@@ -0,0 +1,107 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::frozen::FrozenGraph;
+use std::mem;
+
+/// Finds the nodes reachable from each of up to 64 sources at once,
+/// with a breadth first search whose frontier holds a 64 bit set of sources per node
+///
+/// Returns a set for every node, indexed by node id, with bit `i` set if `sources[i]` reaches the node.
+/// Every source reaches itself, and sources which aren't in the graph reach nothing.
+/// Each edge is followed once per level of the search for all the sources whose searches are
+/// crossing it at that level together, rather than once per source,
+/// which makes computing many rows of a transitive closure far faster than separate searches.
+///
+/// Panics if there are more than 64 sources.
+/// ```
+/// use graph_algos::{graph, Graph, IndexedGraph};
+/// use graph_algos::algos::multi_source_bfs;
+/// use graph_algos::frozen::FrozenGraph;
+///
+/// let graph: Graph<&str> = graph! {
+///     "a" => ["b"],
+///     "b" => ["c"],
+///     "d" => ["c"],
+/// };
+/// let indexed = IndexedGraph::from(&graph);
+/// let frozen = FrozenGraph::from(&indexed);
+/// let id = |u| indexed.id(&u).unwrap();
+///
+/// let reached = multi_source_bfs(&frozen, &[id("a"), id("d")]);
+/// assert_eq!(reached[id("a") as usize], 0b01);
+/// assert_eq!(reached[id("b") as usize], 0b01);
+/// assert_eq!(reached[id("c") as usize], 0b11);
+/// assert_eq!(reached[id("d") as usize], 0b10);
+///
+/// // the same as searching from each source on its own
+/// let sources: Vec<u32> = (0..4).collect();
+/// let reached = multi_source_bfs(&frozen, &sources);
+/// for (i, &source) in sources.iter().enumerate() {
+///     let distances = frozen.dijkstra(source);
+///     for v in 0..4 {
+///         assert_eq!(reached[v] & 1 << i != 0, distances[v].is_some());
+///     }
+/// }
+/// ```
+pub fn multi_source_bfs(graph: &FrozenGraph, sources: &[u32]) -> Vec<u64> {
+    assert!(
+        sources.len() <= 64,
+        "multi_source_bfs searches from at most 64 sources at once, found {}",
+        sources.len()
+    );
+
+    let n = graph.node_count();
+    let mut seen = vec![0_u64; n];
+    let mut frontier = vec![0_u64; n];
+    let mut next = vec![0_u64; n];
+    let mut active = Vec::new();
+    let mut next_active = Vec::new();
+
+    for (i, &source) in sources.iter().enumerate() {
+        if let Some(bits) = frontier.get_mut(source as usize) {
+            if *bits == 0 {
+                active.push(source);
+            }
+            *bits |= 1 << i;
+            seen[source as usize] |= 1 << i;
+        }
+    }
+
+    while !active.is_empty() {
+        for &u in &active {
+            let bits = mem::take(&mut frontier[u as usize]);
+            for v in graph.neighbors(u) {
+                // only the searches which haven't been to v yet carry on through it
+                let new = bits & !seen[v as usize];
+                if new != 0 {
+                    if next[v as usize] == 0 {
+                        next_active.push(v);
+                    }
+                    next[v as usize] |= new;
+                    seen[v as usize] |= new;
+                }
+            }
+        }
+
+        // the frontier has been emptied, so it can be reused for the level after next
+        mem::swap(&mut frontier, &mut next);
+        mem::swap(&mut active, &mut next_active);
+        next_active.clear();
+    }
+
+    seen
+}
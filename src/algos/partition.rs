@@ -0,0 +1,572 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Splitting a graph into balanced parts with few edges between them.
+//!
+//! The edges are treated as undirected, with the weight of the edges between two nodes in either
+//! direction added together, and unweighted edges count as having a weight of 1.
+//! The weight of a cut is the total weight of the edges between nodes in different parts.
+//! Every partitioner panics if any edge has a negative or infinite weight.
+//!
+//! ```
+//! use graph_algos::generators;
+//! use graph_algos::algos::partition::recursive_bisection;
+//!
+//! let graph = generators::cycle_graph(12);
+//! let partition = recursive_bisection(&graph, 4);
+//!
+//! // the best split of a cycle into 4 parts cuts it in 4 places
+//! assert!(partition.parts.iter().all(|part| part.len() == 3));
+//! assert_eq!(partition.cut_weight, 4);
+//! ```
+
+use crate::{
+    util::index_nodes,
+    visit::{EdgeWeighted, IntoNodes},
+    EdgeWeight, NodeBounds,
+};
+use std::collections::{HashMap, VecDeque};
+
+/// The most refinement passes made over each level of the graph
+const MAX_PASSES: usize = 10;
+
+/// The number of tentative swaps a pass makes past its best point before giving up
+const MAX_FRUITLESS_SWAPS: usize = 50;
+
+/// The number of nodes with the highest gains on each side considered for each swap
+const SWAP_CANDIDATES: usize = 5;
+
+/// Multilevel bisection stops coarsening once a graph has this many nodes
+const COARSEST_SIZE: usize = 64;
+
+/// The number of nodes the coarsest graph is grown from, keeping the split with the smallest cut
+const GROW_ATTEMPTS: usize = 8;
+
+/// A split of the nodes of a graph into parts, found by one of the partitioners in this module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Partition<'a, N: NodeBounds> {
+    /// The nodes in each part
+    pub parts: Vec<Vec<&'a N>>,
+    /// The total weight of the edges between nodes in different parts
+    pub cut_weight: i64,
+}
+
+/// Splits the graph into two halves with the Kernighan-Lin algorithm
+///
+/// Starting from a half grown outwards from a node in breadth first order,
+/// each pass tentatively swaps the pair of nodes between the halves which most reduces the cut
+/// weight, over and over, then keeps the swaps up to the point where the cut was smallest.
+/// The halves differ in size by at most one node.
+/// This works well for graphs of up to a few thousand nodes,
+/// for larger graphs [`multilevel_bisection`] is faster and usually finds smaller cuts.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::partition::kernighan_lin;
+///
+/// // two squares joined by a single edge
+/// let graph: Graph<u32> = graph! {
+///     1 => [2, 3],
+///     2 => [4],
+///     3 => [4],
+///     4 => [5],
+///     5 => [6, 7],
+///     6 => [8],
+///     7 => [8],
+/// };
+///
+/// let bisection = kernighan_lin(&graph);
+/// assert_eq!(bisection.cut_weight, 1);
+///
+/// let mut parts: Vec<Vec<u32>> = bisection
+///     .parts
+///     .iter()
+///     .map(|part| {
+///         let mut part: Vec<u32> = part.iter().map(|u| **u).collect();
+///         part.sort();
+///         part
+///     })
+///     .collect();
+/// parts.sort();
+/// assert_eq!(parts, vec![vec![1, 2, 3, 4], vec![5, 6, 7, 8]]);
+/// ```
+pub fn kernighan_lin<'a, G>(graph: G) -> Partition<'a, G::Node>
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a>,
+{
+    let (nodes, level) = index_graph(graph);
+    let n = nodes.len();
+
+    let mut side = grow(&level, (n / 2) as i64, 0);
+    refine(&level, &mut side, (n / 2) as i64, 1);
+
+    let part_of: Vec<usize> = side.iter().map(|&s| s as usize).collect();
+    collect_partition(&nodes, &level, &part_of, 2)
+}
+
+/// Splits the graph into two halves with a multilevel scheme
+///
+/// The graph is repeatedly coarsened by merging each node with the neighbour it shares the heaviest
+/// edge with, the small coarsest graph is split by growing one half outwards from a node,
+/// then the split is carried back through each finer level and refined with Kernighan-Lin swaps.
+/// The halves differ in size by at most one node.
+/// ```
+/// use graph_algos::{Edge, Graph};
+/// use graph_algos::algos::partition::multilevel_bisection;
+///
+/// // a 20 by 20 grid, whose best bisection cuts straight across it
+/// let mut graph: Graph<(u32, u32)> = Graph::empty();
+/// for x in 0..20 {
+///     for y in 0..20 {
+///         if x + 1 < 20 {
+///             graph.add_edge((x, y), Edge::new((x + 1, y)));
+///         }
+///         if y + 1 < 20 {
+///             graph.add_edge((x, y), Edge::new((x, y + 1)));
+///         }
+///     }
+/// }
+///
+/// let bisection = multilevel_bisection(&graph);
+///
+/// assert_eq!(bisection.parts[0].len(), 200);
+/// assert_eq!(bisection.parts[1].len(), 200);
+/// assert!(bisection.cut_weight <= 30);
+/// ```
+pub fn multilevel_bisection<'a, G>(graph: G) -> Partition<'a, G::Node>
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a>,
+{
+    recursive_bisection(graph, 2)
+}
+
+/// Splits the graph into `k` parts of nearly equal size by recursive [`multilevel_bisection`]
+///
+/// Each graph is bisected in proportion to the number of parts each half is then split into,
+/// so the parts differ in size by at most a few nodes.
+/// Some parts are empty if there are fewer than `k` nodes.
+/// Panics if `k` is 0.
+pub fn recursive_bisection<'a, G>(graph: G, k: usize) -> Partition<'a, G::Node>
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a>,
+{
+    assert!(k > 0, "a graph can't be split into 0 parts");
+    let (nodes, level) = index_graph(graph);
+
+    let mut part_of = vec![0; nodes.len()];
+    let mut next_part = 0;
+    split(
+        &level,
+        (0..nodes.len()).collect(),
+        k,
+        &mut part_of,
+        &mut next_part,
+    );
+
+    collect_partition(&nodes, &level, &part_of, k)
+}
+
+/// Recursively bisects the subgraph induced by `members` into `k` parts,
+/// numbering the parts from `next_part`
+fn split(
+    level: &Level,
+    members: Vec<usize>,
+    k: usize,
+    part_of: &mut [usize],
+    next_part: &mut usize,
+) {
+    if k == 1 {
+        for u in members {
+            part_of[u] = *next_part;
+        }
+        *next_part += 1;
+        return;
+    }
+
+    let subgraph = level.induced(&members);
+    let k_first = k / 2;
+    let target = subgraph.total_weight() * k_first as i64 / k as i64;
+    let side = multilevel(&subgraph, target);
+
+    let (mut first, mut second) = (Vec::new(), Vec::new());
+    for (u, on_second) in members.into_iter().zip(side) {
+        if on_second {
+            second.push(u);
+        } else {
+            first.push(u);
+        }
+    }
+
+    split(level, first, k_first, part_of, next_part);
+    split(level, second, k - k_first, part_of, next_part);
+}
+
+/// Finds a split of the level with about `target` weight on the first side with the multilevel scheme,
+/// returning `true` for the nodes on the second side
+///
+/// The first side is kept within one node's weight of the target, so at the finest level,
+/// where every node weighs 1, it hits the target exactly.
+fn multilevel(level: &Level, target: i64) -> Vec<bool> {
+    let coarser = match level.len() > COARSEST_SIZE {
+        true => Some(level.coarsen()),
+        false => None,
+    };
+
+    let bound = level.max_weight() - 1;
+    match coarser {
+        // stop once matching no longer shrinks the graph much, such as for a star
+        Some((coarse, coarse_of)) if coarse.len() * 20 <= level.len() * 19 => {
+            let coarse_side = multilevel(&coarse, target);
+            let mut side: Vec<bool> = coarse_of.iter().map(|&c| coarse_side[c]).collect();
+            rebalance(level, &mut side, target, bound);
+            refine(level, &mut side, target, bound);
+            side
+        }
+        // the coarsest graph is small, so it's cheap to try a few starting points
+        _ => {
+            let attempts = GROW_ATTEMPTS.min(level.len());
+            (0..attempts)
+                .map(|i| {
+                    let mut side = grow(level, target, i * level.len() / attempts);
+                    rebalance(level, &mut side, target, bound);
+                    refine(level, &mut side, target, bound);
+                    side
+                })
+                .min_by_key(|side| level.cut_weight(side))
+                .unwrap_or_default()
+        }
+    }
+}
+
+/// An undirected graph over node indices with weighted nodes, one level of the multilevel scheme
+#[derive(Debug, Clone)]
+struct Level {
+    /// the neighbours of each node and the weight of the edges to them, sorted by neighbour
+    adjacency: Vec<Vec<(usize, i64)>>,
+    /// the weight of each node, the number of original nodes merged into it
+    weights: Vec<i64>,
+}
+
+impl Level {
+    fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    fn total_weight(&self) -> i64 {
+        self.weights.iter().sum()
+    }
+
+    fn max_weight(&self) -> i64 {
+        self.weights.iter().copied().max().unwrap_or(1)
+    }
+
+    /// Returns the weight of the edge between two nodes, 0 if there isn't one
+    fn edge_weight(&self, u: usize, v: usize) -> i64 {
+        let succs = &self.adjacency[u];
+        match succs.binary_search_by_key(&v, |&(x, _)| x) {
+            Ok(i) => succs[i].1,
+            Err(_) => 0,
+        }
+    }
+
+    /// Creates a level from the weights of the edges between each pair of nodes,
+    /// dropping self loops
+    fn from_edges(weights: Vec<i64>, edges: impl Iterator<Item = (usize, usize, i64)>) -> Self {
+        let mut merged: Vec<HashMap<usize, i64>> = vec![HashMap::new(); weights.len()];
+        for (u, v, w) in edges.filter(|&(u, v, _)| u != v) {
+            *merged[u].entry(v).or_default() += w;
+            *merged[v].entry(u).or_default() += w;
+        }
+
+        let adjacency = merged
+            .into_iter()
+            .map(|succs| {
+                let mut succs: Vec<(usize, i64)> = succs.into_iter().collect();
+                succs.sort_unstable();
+                succs
+            })
+            .collect();
+
+        Self { adjacency, weights }
+    }
+
+    /// Returns the subgraph induced by some of the nodes, numbered in the order given
+    fn induced(&self, members: &[usize]) -> Self {
+        let index: HashMap<usize, usize> =
+            members.iter().enumerate().map(|(i, &u)| (u, i)).collect();
+        let weights = members.iter().map(|&u| self.weights[u]).collect();
+
+        // each edge is seen from both ends, so only keep it from the smaller one
+        let edges = members.iter().enumerate().flat_map(|(i, &u)| {
+            let index = &index;
+            self.adjacency[u]
+                .iter()
+                .filter_map(move |&(v, w)| index.get(&v).map(|&j| (i, j, w)))
+                .filter(|&(i, j, _)| i < j)
+        });
+
+        Self::from_edges(weights, edges)
+    }
+
+    /// Merges nodes along a heavy edge matching,
+    /// returning the coarser level and the coarse node each node was merged into
+    fn coarsen(&self) -> (Self, Vec<usize>) {
+        let n = self.len();
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by_key(|&u| self.adjacency[u].len());
+
+        let mut coarse_of = vec![usize::MAX; n];
+        let mut weights = Vec::new();
+        for u in order {
+            if coarse_of[u] != usize::MAX {
+                continue;
+            }
+
+            let partner = self.adjacency[u]
+                .iter()
+                .filter(|&&(v, _)| coarse_of[v] == usize::MAX)
+                .max_by_key(|&&(v, w)| (w, std::cmp::Reverse(v)))
+                .map(|&(v, _)| v);
+
+            coarse_of[u] = weights.len();
+            let mut weight = self.weights[u];
+            if let Some(v) = partner {
+                coarse_of[v] = weights.len();
+                weight += self.weights[v];
+            }
+            weights.push(weight);
+        }
+
+        let edges = (0..n).flat_map(|u| {
+            let coarse_of = &coarse_of;
+            self.adjacency[u]
+                .iter()
+                .filter(move |&&(v, _)| u < v)
+                .map(move |&(v, w)| (coarse_of[u], coarse_of[v], w))
+        });
+
+        (Self::from_edges(weights, edges), coarse_of)
+    }
+
+    /// Returns the total weight of the edges between the two sides
+    fn cut_weight(&self, side: &[bool]) -> i64 {
+        (0..self.len())
+            .flat_map(|u| self.adjacency[u].iter().map(move |&(v, w)| (u, v, w)))
+            .filter(|&(u, v, _)| u < v && side[u] != side[v])
+            .map(|(_, _, w)| w)
+            .sum()
+    }
+
+    /// Returns how much moving each node to the other side would reduce the cut weight
+    fn gain(&self, side: &[bool], u: usize) -> i64 {
+        self.adjacency[u]
+            .iter()
+            .map(|&(v, w)| if side[u] == side[v] { -w } else { w })
+            .sum()
+    }
+}
+
+/// Splits the level by growing the first side outwards from `first` in breadth first order
+/// until it holds `target` weight, returning `true` for the nodes on the second side
+fn grow(level: &Level, target: i64, first: usize) -> Vec<bool> {
+    let mut side = vec![true; level.len()];
+    let mut weight = 0;
+
+    // nodes in other components are taken in index order once a component runs out
+    for start in std::iter::once(first).chain(0..level.len()) {
+        if weight >= target {
+            break;
+        }
+        if !side[start] {
+            continue;
+        }
+
+        side[start] = false;
+        weight += level.weights[start];
+        let mut queue = VecDeque::from(vec![start]);
+        while let Some(u) = queue.pop_front() {
+            for &(v, _) in &level.adjacency[u] {
+                if weight >= target {
+                    break;
+                }
+                if side[v] {
+                    side[v] = false;
+                    weight += level.weights[v];
+                    queue.push_back(v);
+                }
+            }
+        }
+    }
+
+    side
+}
+
+/// Moves single nodes off the heavier side, cheapest first,
+/// until the first side is within `bound` of `target` weight
+fn rebalance(level: &Level, side: &mut [bool], target: i64, bound: i64) {
+    loop {
+        let first: i64 = (0..level.len())
+            .filter(|&u| !side[u])
+            .map(|u| level.weights[u])
+            .sum();
+        let excess = first - target;
+        if excess.abs() <= bound {
+            return;
+        }
+
+        // moving a node only helps if it weighs less than twice the excess
+        let heavy = excess < 0;
+        let best = (0..level.len())
+            .filter(|&u| side[u] == heavy && level.weights[u] < 2 * excess.abs())
+            .max_by_key(|&u| level.gain(side, u));
+
+        match best {
+            Some(u) => side[u] = !side[u],
+            None => return,
+        }
+    }
+}
+
+/// Improves the split with Kernighan-Lin passes of pairwise swaps,
+/// never leaving the first side further than `bound` from `target` weight,
+/// or further than it already is if that is more
+fn refine(level: &Level, side: &mut [bool], target: i64, bound: i64) {
+    let n = level.len();
+
+    for _ in 0..MAX_PASSES {
+        let mut gains: Vec<i64> = (0..n).map(|u| level.gain(side, u)).collect();
+        let mut locked = vec![false; n];
+        let mut first: i64 = (0..n).filter(|&u| !side[u]).map(|u| level.weights[u]).sum();
+
+        let mut swaps = Vec::new();
+        let mut total = 0;
+        let (mut best_total, mut best_len) = (0, 0);
+
+        while swaps.len() < best_len + MAX_FRUITLESS_SWAPS {
+            let candidates = |on_second: bool| {
+                let mut candidates: Vec<usize> = (0..n)
+                    .filter(|&u| !locked[u] && side[u] == on_second)
+                    .collect();
+                let k = SWAP_CANDIDATES.min(candidates.len());
+                if k < candidates.len() {
+                    candidates.select_nth_unstable_by_key(k, |&u| std::cmp::Reverse(gains[u]));
+                }
+                candidates.truncate(k);
+                candidates
+            };
+
+            let allowed = bound.max((first - target).abs());
+            let mut best: Option<(i64, usize, usize)> = None;
+            for &a in &candidates(false) {
+                for &b in &candidates(true) {
+                    let moved = first - level.weights[a] + level.weights[b];
+                    if (moved - target).abs() > allowed {
+                        continue;
+                    }
+
+                    let gain = gains[a] + gains[b] - 2 * level.edge_weight(a, b);
+                    if !matches!(best, Some((best, _, _)) if best >= gain) {
+                        best = Some((gain, a, b));
+                    }
+                }
+            }
+
+            let (gain, a, b) = match best {
+                Some(best) => best,
+                None => break,
+            };
+
+            side[a] = true;
+            side[b] = false;
+            locked[a] = true;
+            locked[b] = true;
+            first += level.weights[b] - level.weights[a];
+            for &u in [a, b]
+                .iter()
+                .chain(level.adjacency[a].iter().map(|(v, _)| v))
+                .chain(level.adjacency[b].iter().map(|(v, _)| v))
+            {
+                gains[u] = level.gain(side, u);
+            }
+
+            swaps.push((a, b));
+            total += gain;
+            if total > best_total {
+                best_total = total;
+                best_len = swaps.len();
+            }
+        }
+
+        for &(a, b) in swaps[best_len..].iter().rev() {
+            side[a] = false;
+            side[b] = true;
+        }
+
+        if best_total <= 0 {
+            return;
+        }
+    }
+}
+
+/// Indexes the nodes of the graph and builds the undirected level of unit weight nodes
+fn index_graph<'a, G>(graph: G) -> (Vec<&'a G::Node>, Level)
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a>,
+{
+    let (nodes, indices) = index_nodes(graph);
+
+    let mut edges = Vec::new();
+    for (u, node) in nodes.iter().enumerate() {
+        for (v, weight) in graph.neighbors_weighted(node) {
+            let w = match weight {
+                None => 1,
+                Some(EdgeWeight::Weight(w)) if w >= 0 => w,
+                Some(w) => panic!(
+                    "partitioning needs non-negative finite weights, found {}",
+                    w
+                ),
+            };
+            if let Some(&v) = indices.get(v) {
+                edges.push((u, v, w));
+            }
+        }
+    }
+
+    let level = Level::from_edges(vec![1; nodes.len()], edges.into_iter());
+    (nodes, level)
+}
+
+/// Gathers the nodes into their parts and finds the weight of the cut
+fn collect_partition<'a, N: NodeBounds>(
+    nodes: &[&'a N],
+    level: &Level,
+    part_of: &[usize],
+    k: usize,
+) -> Partition<'a, N> {
+    let mut parts = vec![Vec::new(); k];
+    for (u, &part) in part_of.iter().enumerate() {
+        parts[part].push(nodes[u]);
+    }
+
+    // each undirected edge is seen from both of its ends
+    let cut_weight = (0..nodes.len())
+        .flat_map(|u| level.adjacency[u].iter().map(move |&(v, w)| (u, v, w)))
+        .filter(|&(u, v, _)| part_of[u] != part_of[v])
+        .map(|(_, _, w)| w)
+        .sum::<i64>()
+        / 2;
+
+    Partition { parts, cut_weight }
+}
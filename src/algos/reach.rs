@@ -0,0 +1,201 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::{
+    algos::strongly_connected_components,
+    visit::{IntoNeighbors, IntoNodes},
+    NodeBounds,
+};
+use std::collections::HashMap;
+
+/// The number of bits in each label used by [`ReachabilityFilter::new`]
+const DEFAULT_BITS: usize = 256;
+
+/// The number of bits set for each component used by [`ReachabilityFilter::new`]
+const DEFAULT_HASHES: usize = 3;
+
+/// An approximate reachability index answering whether one node might reach another in constant time
+///
+/// Every strongly connected component of the graph gets a Bloom filter label,
+/// holding its own bits and the labels of every component it has an edge to.
+/// The labels are built in reverse topological order, so each one covers every component
+/// reachable from it and [`ReachabilityFilter::maybe_reachable`] never has false negatives.
+/// Queries between components in the wrong topological order, or in the same component,
+/// are answered exactly without looking at the labels at all.
+///
+/// The filter is meant to cheaply rule out most of a large batch of queries
+/// before running an exact search on the rest,
+/// it uses `bits / 8` bytes for every component of the graph.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::ReachabilityFilter;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2, 3],
+///     2 => [4],
+///     3 => [4, 1],
+///     5 => [4],
+/// };
+///
+/// let filter = ReachabilityFilter::new(&graph);
+///
+/// // there are never false negatives
+/// assert!(filter.maybe_reachable(&1, &4));
+/// assert!(filter.maybe_reachable(&3, &2));
+/// assert!(filter.maybe_reachable(&5, &5));
+///
+/// // and these are ruled out exactly by the order of the components
+/// assert!(!filter.maybe_reachable(&4, &1));
+/// assert!(!filter.maybe_reachable(&4, &5));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReachabilityFilter<'a, N: NodeBounds> {
+    /// The component of each node, numbered so that edges only go to lower numbered components
+    components: HashMap<&'a N, usize>,
+    /// The label of each component, `words` words at a time
+    labels: Vec<u64>,
+    words: usize,
+    hashes: usize,
+}
+
+impl<'a, N: NodeBounds> ReachabilityFilter<'a, N> {
+    /// Builds a filter with 256 bit labels, 3 bits set for each component
+    pub fn new<G>(graph: G) -> Self
+    where
+        G: IntoNodes<'a, Node = N> + IntoNeighbors<'a>,
+    {
+        Self::with_bits(graph, DEFAULT_BITS, DEFAULT_HASHES)
+    }
+
+    /// Builds a filter with labels of `bits` bits, rounded up to a multiple of 64,
+    /// setting `hashes` bits for each component
+    ///
+    /// Longer labels give fewer false positives on graphs where nodes reach many components,
+    /// at the cost of more memory.
+    ///
+    /// Panics if `bits` or `hashes` is 0.
+    /// ```
+    /// use graph_algos::generators;
+    /// use graph_algos::algos::ReachabilityFilter;
+    ///
+    /// let path = generators::path_graph(1000);
+    /// let small = ReachabilityFilter::with_bits(&path, 64, 1);
+    /// let large = ReachabilityFilter::with_bits(&path, 1 << 12, 2);
+    ///
+    /// assert_eq!(small.bits(), 64);
+    /// assert_eq!(large.bits(), 4096);
+    ///
+    /// // every node can reach the last node
+    /// assert!((0..1000).all(|u| large.maybe_reachable(&u, &999)));
+    /// ```
+    pub fn with_bits<G>(graph: G, bits: usize, hashes: usize) -> Self
+    where
+        G: IntoNodes<'a, Node = N> + IntoNeighbors<'a>,
+    {
+        assert!(bits > 0, "a reachability filter needs at least one bit");
+        assert!(hashes > 0, "a reachability filter needs at least one hash");
+
+        // the components come out in reverse topological order,
+        // so every edge goes to a component with a lower index
+        let sccs = strongly_connected_components(graph);
+        let components: HashMap<&N, usize> = sccs
+            .iter()
+            .enumerate()
+            .flat_map(|(c, members)| members.iter().map(move |&u| (u, c)))
+            .collect();
+
+        let words = bits.div_ceil(64);
+        let mut filter = Self {
+            components,
+            labels: vec![0; sccs.len() * words],
+            words,
+            hashes,
+        };
+
+        for (c, members) in sccs.iter().enumerate() {
+            let mut label = vec![0; words];
+            for (word, mask) in filter.positions(c) {
+                label[word] |= mask;
+            }
+
+            for &u in members {
+                for v in graph.neighbors(u) {
+                    let d = filter.components[v];
+                    if d != c {
+                        let succ_label = &filter.labels[d * words..(d + 1) * words];
+                        for (bits, succ_bits) in label.iter_mut().zip(succ_label) {
+                            *bits |= succ_bits;
+                        }
+                    }
+                }
+            }
+
+            filter.labels[c * words..(c + 1) * words].copy_from_slice(&label);
+        }
+
+        filter
+    }
+
+    /// Returns the number of bits in each label
+    pub fn bits(&self) -> usize {
+        self.words * 64
+    }
+
+    /// Returns the number of strongly connected components, each of which has a label
+    pub fn component_count(&self) -> usize {
+        self.labels.len() / self.words
+    }
+
+    /// Returns whether there might be a path from `u` to `v`
+    ///
+    /// If this returns `false` there is definitely no path,
+    /// if it returns `true` there usually is but it can be a false positive.
+    /// Every node reaches itself, and nodes which aren't in the graph reach nothing.
+    pub fn maybe_reachable(&self, u: &N, v: &N) -> bool {
+        let (cu, cv) = match (self.components.get(u), self.components.get(v)) {
+            (Some(&cu), Some(&cv)) => (cu, cv),
+            _ => return false,
+        };
+
+        if cu == cv {
+            return true;
+        }
+        // edges only go to lower numbered components
+        if cu < cv {
+            return false;
+        }
+
+        let label = &self.labels[cu * self.words..(cu + 1) * self.words];
+        self.positions(cv)
+            .all(|(word, mask)| label[word] & mask == mask)
+    }
+
+    /// Returns the word and mask of each bit set for component `c`
+    fn positions(&self, c: usize) -> impl Iterator<Item = (usize, u64)> {
+        // double hashing, with both hashes from one round of splitmix64
+        let mut h = (c as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+        h = (h ^ (h >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        h = (h ^ (h >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        h ^= h >> 31;
+
+        let bits = self.bits() as u64;
+        let (h1, h2) = (h >> 32, (h & 0xffff_ffff) | 1);
+        (0..self.hashes as u64).map(move |i| {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % bits;
+            ((bit / 64) as usize, 1 << (bit % 64))
+        })
+    }
+}
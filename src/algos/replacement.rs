@@ -0,0 +1,281 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::{astar, dijkstra, DistMap};
+use crate::{
+    util::IndexedHeap,
+    visit::{EdgeFiltered, EdgeWeighted, IntoNodes, Reversed},
+    Edge, EdgeWeight, NodeBounds, Path,
+};
+use std::collections::{HashMap, HashSet};
+
+/// A shortest path along with the best detour around each of its edges,
+/// found by [`replacement_paths`]
+#[derive(Debug, Clone)]
+pub struct ReplacementPaths<'a, N: NodeBounds> {
+    /// the shortest path from the source to the target and its total weight
+    pub shortest: (Path<'a, N>, EdgeWeight),
+    /// the detour around each edge of the shortest path, in the order they appear on the path
+    pub detours: Vec<Detour<'a, N>>,
+}
+
+/// The shortest path avoiding one failed edge of a shortest path
+#[derive(Debug, Clone)]
+pub struct Detour<'a, N: NodeBounds> {
+    /// the source of the failed edge
+    pub source: &'a N,
+    /// the destination of the failed edge
+    pub destination: &'a N,
+    /// the shortest path avoiding the failed edge and its total weight,
+    /// or `None` if losing the edge disconnects the target from the source
+    pub path: Option<(Path<'a, N>, EdgeWeight)>,
+}
+
+/// Finds the shortest path from `s` to `t`, and for each of its edges the shortest path
+/// which avoids that edge
+///
+/// A failed edge takes every parallel edge between the same pair of nodes with it.
+/// The distances to `t` are computed once up front and guide every detour search,
+/// so each search only explores the region around the failure rather than the whole graph.
+/// Returns `None` if `t` can't be reached from `s`.
+///
+/// Panics if an edge without a weight is reached.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::replacement_paths;
+///
+/// let graph: Graph<&str> = graph! {
+///     "a" => ["b" => 1, "c" => 4],
+///     "b" => ["d" => 1, "c" => 1],
+///     "c" => ["d" => 1],
+///     "d" => ["e" => 1],
+/// };
+///
+/// let paths = replacement_paths(&graph, &"a", &"e").unwrap();
+/// assert_eq!(paths.shortest.1, 3.into());
+///
+/// let detours: Vec<(&str, &str, Option<String>)> = paths
+///     .detours
+///     .iter()
+///     .map(|d| (*d.source, *d.destination, d.path.as_ref().map(|(p, _)| p.to_string())))
+///     .collect();
+/// assert_eq!(
+///     detours,
+///     vec![
+///         ("a", "b", Some(r#""a" --(4)-> "c" --(1)-> "d" --(1)-> "e""#.to_string())),
+///         ("b", "d", Some(r#""a" --(1)-> "b" --(1)-> "c" --(1)-> "d" --(1)-> "e""#.to_string())),
+///         ("d", "e", None),
+///     ],
+/// );
+/// ```
+pub fn replacement_paths<'a, G>(
+    graph: G,
+    s: &'a G::Node,
+    t: &'a G::Node,
+) -> Option<ReplacementPaths<'a, G::Node>>
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a>,
+{
+    let to_t = distances_to(graph, t);
+    let shortest = search(graph, s, t, &to_t, &HashSet::new())?;
+
+    let mut source = shortest.0.head();
+    let detours = shortest
+        .0
+        .edges()
+        .iter()
+        .map(|edge| {
+            let destination = *edge.destination();
+            let failed = [(source, destination)].iter().cloned().collect();
+            let path = search(graph, s, t, &to_t, &failed);
+            let detour = Detour {
+                source,
+                destination,
+                path,
+            };
+            source = destination;
+            detour
+        })
+        .collect();
+
+    Some(ReplacementPaths { shortest, detours })
+}
+
+/// What counts as an alternative to the shortest path in [`second_shortest_path`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Alternative {
+    /// A path strictly longer than the shortest path, which may revisit nodes
+    StrictlyLonger,
+    /// A path which shares no edges with the shortest path
+    EdgeDisjoint,
+}
+
+/// Finds the best alternative to the shortest path from `s` to `t`,
+/// along with its total weight
+///
+/// With [`Alternative::StrictlyLonger`] this is the shortest path whose weight is greater
+/// than the shortest path's, found in a single search which keeps the two best distances
+/// to each node.
+/// Such a path can go around a cycle, as that is sometimes the only way to make it longer.
+/// With [`Alternative::EdgeDisjoint`] this is the shortest path which avoids every edge of the
+/// shortest path.
+/// Returns `None` if there is no alternative, or if `t` can't be reached from `s` at all.
+///
+/// Panics if an edge without a weight is reached.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::{second_shortest_path, Alternative};
+///
+/// let graph: Graph<&str> = graph! {
+///     "a" => ["b" => 1, "d" => 5],
+///     "b" => ["d" => 1, "e" => 1],
+///     "e" => ["d" => 1],
+/// };
+///
+/// let (path, weight) = second_shortest_path(&graph, &"a", &"d", Alternative::StrictlyLonger).unwrap();
+/// assert_eq!(path.to_string(), r#""a" --(1)-> "b" --(1)-> "e" --(1)-> "d""#);
+/// assert_eq!(weight, 3.into());
+///
+/// let (path, weight) = second_shortest_path(&graph, &"a", &"d", Alternative::EdgeDisjoint).unwrap();
+/// assert_eq!(path.to_string(), r#""a" --(5)-> "d""#);
+/// assert_eq!(weight, 5.into());
+///
+/// assert!(second_shortest_path(&graph, &"b", &"e", Alternative::StrictlyLonger).is_none());
+/// ```
+pub fn second_shortest_path<'a, G>(
+    graph: G,
+    s: &'a G::Node,
+    t: &'a G::Node,
+    alternative: Alternative,
+) -> Option<(Path<'a, G::Node>, EdgeWeight)>
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a>,
+{
+    match alternative {
+        Alternative::StrictlyLonger => strictly_longer(graph, s, t),
+        Alternative::EdgeDisjoint => {
+            let to_t = distances_to(graph, t);
+            let (shortest, _) = search(graph, s, t, &to_t, &HashSet::new())?;
+
+            let mut source = shortest.head();
+            let mut failed = HashSet::new();
+            for edge in shortest.edges() {
+                failed.insert((source, *edge.destination()));
+                source = edge.destination();
+            }
+
+            search(graph, s, t, &to_t, &failed)
+        }
+    }
+}
+
+/// A node along with whether it was reached by its best (0) or second best (1) distance
+type Label<'a, N> = (&'a N, usize);
+
+/// The label each label was reached from and the weight of the edge taken
+type LabelPreds<'a, N> = HashMap<Label<'a, N>, (Label<'a, N>, EdgeWeight)>;
+
+/// Finds the shortest path from `s` to `t` which is strictly longer than the shortest one,
+/// with a Dijkstra search where every node is reached at most twice:
+/// once by its shortest distance and once by the next shortest distance
+///
+/// This runs its own search loop rather than a [`Search`](super::Search), as a better distance to a node
+/// demotes the node's old best label to second best, moving the label's queue entry and predecessor with it.
+fn strictly_longer<'a, G: EdgeWeighted<'a>>(
+    graph: G,
+    s: &'a G::Node,
+    t: &G::Node,
+) -> Option<(Path<'a, G::Node>, EdgeWeight)> {
+    // the best and second best distance to each node, with the label they were reached from
+    let mut distances: HashMap<&'a G::Node, [Option<EdgeWeight>; 2]> = HashMap::new();
+    let mut preds: LabelPreds<'a, G::Node> = HashMap::new();
+    let mut queue: IndexedHeap<Label<'a, G::Node>, EdgeWeight> = IndexedHeap::new();
+
+    distances.insert(s, [Some(0.into()), None]);
+    queue.push((s, 0), 0.into());
+
+    while let Some(((node, rank), distance)) = queue.pop() {
+        if rank == 1 && node == t {
+            let mut edges = Vec::new();
+            let mut current = (node, rank);
+            while let Some(&(pred, weight)) = preds.get(&current) {
+                edges.push(match weight {
+                    EdgeWeight::Weight(w) => Edge::new_with_weight(current.0, w),
+                    _ => Edge::new(current.0),
+                });
+                current = pred;
+            }
+            edges.reverse();
+
+            return Some((Path::from_parts(s, edges), distance));
+        }
+
+        for (dest, weight) in graph.neighbors_weighted(node) {
+            let weight = weight.expect("Dijkstra requires edges to have weights - panicking.");
+            let new_distance = distance + weight;
+            let best = distances.entry(dest).or_default();
+
+            if !matches!(best[0], Some(d) if d <= new_distance) {
+                // the old best distance becomes the second best,
+                // which is safe as nothing can have been reached from it yet
+                if let Some(old) = best[0] {
+                    best[1] = Some(old);
+                    if let Some(pred) = preds.remove(&(dest, 0)) {
+                        preds.insert((dest, 1), pred);
+                    }
+                    queue.push((dest, 1), old);
+                }
+                best[0] = Some(new_distance);
+                preds.insert((dest, 0), ((node, rank), weight));
+                queue.push((dest, 0), new_distance);
+            } else if best[0] != Some(new_distance)
+                && !matches!(best[1], Some(d) if d <= new_distance)
+            {
+                best[1] = Some(new_distance);
+                preds.insert((dest, 1), ((node, rank), weight));
+                queue.push((dest, 1), new_distance);
+            }
+        }
+    }
+
+    None
+}
+
+/// Returns the distance from every node which can reach `t` to `t`
+fn distances_to<'a, G>(graph: G, t: &'a G::Node) -> DistMap<'a, G::Node>
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a>,
+{
+    dijkstra(&Reversed::new(graph), t).1
+}
+
+/// Finds a shortest path from `s` to `t` which doesn't use any of the `failed` edges,
+/// using the exact distances to `t` without the failures as an A* heuristic
+fn search<'a, G: EdgeWeighted<'a>>(
+    graph: G,
+    s: &'a G::Node,
+    t: &G::Node,
+    to_t: &DistMap<'a, G::Node>,
+    failed: &HashSet<(&'a G::Node, &'a G::Node)>,
+) -> Option<(Path<'a, G::Node>, EdgeWeight)> {
+    // nodes which can't reach t at all can't reach it once an edge has failed either
+    to_t.get(s)?;
+    let usable = |u: &G::Node, v: &G::Node, _| to_t.contains_key(v) && !failed.contains(&(u, v));
+    let to_t = |u: &G::Node| to_t.get(u).copied().unwrap_or(EdgeWeight::PosInfinity);
+
+    astar(EdgeFiltered::new(graph, &usable), s, t, to_t)
+}
@@ -0,0 +1,112 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::visit::{IntoNeighbors, IntoNodes};
+use std::collections::{HashMap, HashSet};
+
+/// Finds the strongly connected components of the graph using Tarjan's algorithm
+///
+/// The components are returned in reverse topological order,
+/// so there are no edges from a component to any component after it.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::strongly_connected_components;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [3],
+///     3 => [1, 4],
+///     4 => [5],
+///     5 => [4],
+/// };
+///
+/// let mut components = strongly_connected_components(&graph);
+/// for component in components.iter_mut() {
+///     component.sort();
+/// }
+///
+/// assert_eq!(components, vec![vec![&4, &5], vec![&1, &2, &3]]);
+/// ```
+pub fn strongly_connected_components<'a, G>(graph: G) -> Vec<Vec<&'a G::Node>>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let mut next_index = 0;
+    let mut indices: HashMap<&G::Node, usize> = HashMap::new();
+    let mut lowlinks: HashMap<&G::Node, usize> = HashMap::new();
+    let mut on_stack: HashSet<&G::Node> = HashSet::new();
+    let mut stack: Vec<&G::Node> = Vec::new();
+    let mut components = Vec::new();
+
+    for root in graph.nodes() {
+        if indices.contains_key(root) {
+            continue;
+        }
+
+        // the recursion of the usual algorithm is kept on an explicit stack
+        // so that very deep graphs can't overflow the call stack
+        let mut call_stack = vec![(root, graph.neighbors(root))];
+        indices.insert(root, next_index);
+        lowlinks.insert(root, next_index);
+        next_index += 1;
+        stack.push(root);
+        on_stack.insert(root);
+
+        while let Some((v, succs)) = call_stack.last_mut() {
+            let v = *v;
+
+            if let Some(w) = succs.next() {
+                if !indices.contains_key(w) {
+                    // "recurse" into w
+                    indices.insert(w, next_index);
+                    lowlinks.insert(w, next_index);
+                    next_index += 1;
+                    stack.push(w);
+                    on_stack.insert(w);
+                    call_stack.push((w, graph.neighbors(w)));
+                } else if on_stack.contains(w) {
+                    let low = lowlinks[v].min(indices[w]);
+                    lowlinks.insert(v, low);
+                }
+            } else {
+                call_stack.pop();
+
+                // v is the root of a component so pop it off the stack
+                if lowlinks[v] == indices[v] {
+                    let mut component = Vec::new();
+                    loop {
+                        let w = stack.pop().unwrap();
+                        on_stack.remove(w);
+                        component.push(w);
+                        if w == v {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+
+                // pass the lowlink back up to the caller
+                if let Some((parent, _)) = call_stack.last() {
+                    let low = lowlinks[*parent].min(lowlinks[v]);
+                    lowlinks.insert(*parent, low);
+                }
+            }
+        }
+    }
+
+    components
+}
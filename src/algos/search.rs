@@ -0,0 +1,182 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::{util::IndexedHeap, visit::EdgeWeighted, Edge, EdgeWeight, NodeBounds, Path, PredMap};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+};
+
+/// A Dijkstra search in progress over states of type `K` with path costs of type `C`,
+/// which settles one state at a time in order of cost
+///
+/// This is the search behind the shortest path algorithms, which differ only in what a state is,
+/// which edges leave it and when to stop: they pop states and relax the edges out of them,
+/// and stop popping when they have what they need.
+/// Queuing each state by its cost plus an estimate of the cost still to come turns the search into A*.
+#[derive(Debug, Clone)]
+pub(crate) struct Search<K, C = EdgeWeight> {
+    /// The lowest cost found so far for every state reached
+    pub(crate) costs: HashMap<K, C>,
+    /// The state each state was reached from and the weight of the edge taken
+    pub(crate) preds: HashMap<K, (K, Option<EdgeWeight>)>,
+    queue: IndexedHeap<K, C>,
+    start: Option<K>,
+    /// The most a state may cost to be reached, if there is a limit
+    budget: Option<C>,
+}
+
+impl<K: Copy + Eq + Hash, C: Copy + Ord> Search<K, C> {
+    /// Starts a search from `start`, where the path with no edges costs `cost`
+    pub(crate) fn new(start: K, cost: C) -> Self {
+        let mut queue = IndexedHeap::new();
+        queue.push(start, cost);
+
+        Self {
+            costs: [(start, cost)].iter().cloned().collect(),
+            preds: HashMap::new(),
+            queue,
+            start: Some(start),
+            budget: None,
+        }
+    }
+
+    /// Never reaches a state costing more than `budget`,
+    /// so the search reaches nothing if the start is already over it
+    pub(crate) fn with_budget(mut self, budget: C) -> Self {
+        if self.costs.values().any(|&cost| cost > budget) {
+            self.costs.clear();
+            self.queue = IndexedHeap::new();
+            self.start = None;
+        }
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Removes the queued state with the lowest priority, returning it with its cost
+    pub(crate) fn pop(&mut self) -> Option<(K, C)> {
+        let (state, _) = self.queue.pop()?;
+        Some((state, self.costs[&state]))
+    }
+
+    /// Records that `v` can be reached for `cost` by an edge of `weight` from `u`,
+    /// queuing it with the priority given by `priority(cost)`,
+    /// unless it has been reached at least as cheaply before
+    ///
+    /// Returns whether `v` was queued.
+    pub(crate) fn relax<P>(
+        &mut self,
+        u: K,
+        v: K,
+        cost: C,
+        weight: Option<EdgeWeight>,
+        priority: P,
+    ) -> bool
+    where
+        P: FnOnce(C) -> C,
+    {
+        // the start is never reached again, which would give it a predecessor
+        if Some(v) == self.start {
+            return false;
+        }
+        if matches!(self.budget, Some(budget) if cost > budget) {
+            return false;
+        }
+
+        match self.costs.entry(v) {
+            Entry::Occupied(o) if *o.get() <= cost => return false,
+            Entry::Occupied(mut o) => {
+                o.insert(cost);
+            }
+            Entry::Vacant(e) => {
+                e.insert(cost);
+            }
+        }
+
+        self.preds.insert(v, (u, weight));
+        self.queue.push(v, priority(cost));
+        true
+    }
+
+    /// Returns the states on the cheapest path found to `state` after the start,
+    /// each with the weight of the edge it was reached by
+    pub(crate) fn path_to(&self, mut state: K) -> Vec<(K, Option<EdgeWeight>)> {
+        let mut path = Vec::new();
+        while let Some(&(pred, weight)) = self.preds.get(&state) {
+            path.push((state, weight));
+            state = pred;
+        }
+        path.reverse();
+        path
+    }
+}
+
+impl<'a, N: NodeBounds, C: Copy + Ord> Search<&'a N, C> {
+    /// Returns the predecessor map of the search, with the start its own predecessor
+    pub(crate) fn pred_map(&self) -> PredMap<'a, N> {
+        let mut pred_map: PredMap<'a, N> = self
+            .preds
+            .iter()
+            .map(|(&v, &(u, weight))| (v, edge_to(u, weight)))
+            .collect();
+        if let Some(start) = self.start {
+            pred_map.insert(start, Edge::new(start));
+        }
+        pred_map
+    }
+
+    /// Returns the cheapest path found from the start to `node`
+    pub(crate) fn path(&self, node: &'a N) -> Path<'a, N> {
+        let edges = self
+            .path_to(node)
+            .into_iter()
+            .map(|(v, weight)| edge_to(v, weight))
+            .collect();
+        Path::from_parts(self.start.unwrap_or(node), edges)
+    }
+}
+
+impl<'a, N: NodeBounds> Search<&'a N> {
+    /// Relaxes the edges of `graph` leaving `node`, which is `distance` from the start,
+    /// queuing each node reached by its distance plus `estimate` of its distance from the goal
+    ///
+    /// Panics if an edge without a weight is reached.
+    pub(crate) fn expand<G, H>(
+        &mut self,
+        graph: G,
+        node: &'a N,
+        distance: EdgeWeight,
+        mut estimate: H,
+    ) where
+        G: EdgeWeighted<'a, Node = N>,
+        H: FnMut(&N) -> EdgeWeight,
+    {
+        for (dest, weight) in graph.neighbors_weighted(node) {
+            let weight = weight.expect("Dijkstra requires edges to have weights - panicking.");
+            self.relax(node, dest, distance + weight, Some(weight), |d| {
+                d + estimate(dest)
+            });
+        }
+    }
+}
+
+/// Returns an edge to `v` with the given weight, if it has one
+fn edge_to<N: NodeBounds>(v: N, weight: Option<EdgeWeight>) -> Edge<N> {
+    match weight {
+        Some(weight) => Edge::new_with_weight(v, weight),
+        None => Edge::new(v),
+    }
+}
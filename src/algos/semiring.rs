@@ -0,0 +1,274 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Shortest paths over path costs other than sums of weights.
+//!
+//! A [`WeightSemiring`] describes how the cost of a path is built up from its edges,
+//! and which of two path costs is better.
+//! [`best_paths`] runs Dijkstra's algorithm with any of them,
+//! so for example the most reliable route through a network whose edges fail independently
+//! can be found directly rather than by taking logarithms of the probabilities.
+//!
+//! ```
+//! use graph_algos::{graph, Graph, Path};
+//! use graph_algos::algos::semiring::{best_paths, MaxTimes};
+//!
+//! // the probability, in percent, that each link works
+//! let network: Graph<&str> = graph! {
+//!     "a" => ["b" => 90, "c" => 50],
+//!     "b" => ["d" => 90],
+//!     "c" => ["d" => 100],
+//! };
+//!
+//! let (pred_map, reliability) = best_paths(&network, &"a", &MaxTimes::new(100.0));
+//! assert!((reliability[&"d"] - 0.81).abs() < 1e-9);
+//!
+//! let path = Path::new_path_to(&pred_map, &"d").unwrap();
+//! assert_eq!(path.to_string(), r#""a" --(90)-> "b" --(90)-> "d""#);
+//! ```
+
+use super::Search;
+use crate::{visit::EdgeWeighted, EdgeWeight, PredMap};
+use std::{cmp::Ordering, collections::HashMap, fmt::Debug, marker::PhantomData};
+
+/// Represents the cost of the best path to each node found by [`best_paths`]
+pub type CostMap<'a, N, S> = HashMap<&'a N, <S as WeightSemiring>::Value>;
+
+/// A way of combining edge weights into path costs, where one of any two costs is the better one
+///
+/// For [`best_paths`] to find the best paths, extending a path by an edge must never make
+/// its cost better, just as Dijkstra's algorithm needs non-negative weights.
+pub trait WeightSemiring {
+    /// The cost of a path
+    type Value: Copy + PartialOrd + Debug;
+
+    /// Whether larger costs are better, otherwise smaller costs are
+    const MAXIMISE: bool;
+
+    /// Returns the cost of the path with no edges
+    fn one(&self) -> Self::Value;
+
+    /// Returns the cost of a single edge with the given weight
+    fn edge(&self, weight: Option<EdgeWeight>) -> Self::Value;
+
+    /// Returns the cost of a path extended by an edge
+    fn extend(&self, path: Self::Value, edge: Self::Value) -> Self::Value;
+}
+
+/// Path costs are the sum of the edge weights and smaller is better, as in [`super::dijkstra`]
+///
+/// Panics if an edge without a weight is reached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct MinPlus;
+
+impl WeightSemiring for MinPlus {
+    type Value = EdgeWeight;
+    const MAXIMISE: bool = false;
+
+    fn one(&self) -> EdgeWeight {
+        0.into()
+    }
+
+    fn edge(&self, weight: Option<EdgeWeight>) -> EdgeWeight {
+        weight.expect("MinPlus requires edges to have weights - panicking.")
+    }
+
+    fn extend(&self, path: EdgeWeight, edge: EdgeWeight) -> EdgeWeight {
+        path + edge
+    }
+}
+
+/// Path costs are the product of the edge probabilities and larger is better,
+/// which finds the most reliable paths
+///
+/// Each edge's probability is its weight divided by the scale,
+/// so weights given in percent use a scale of 100.
+/// Unweighted edges always work, having a probability of 1.
+///
+/// The search only finds the best paths when every probability is between 0 and 1,
+/// as an edge with a larger one would make a path through it more reliable than the path alone,
+/// so [`best_paths`] panics if it reaches an edge whose weight is outside `0..=scale`, including an infinite one.
+/// ```should_panic
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::semiring::{best_paths, MaxTimes};
+///
+/// let network: Graph<&str> = graph! { "a" => ["b" => 150] };
+/// best_paths(&network, &"a", &MaxTimes::new(100.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MaxTimes {
+    scale: f64,
+}
+
+impl MaxTimes {
+    /// Creates a semiring where an edge with weight `scale` is certain to work
+    ///
+    /// Panics if `scale` isn't positive.
+    pub fn new(scale: f64) -> Self {
+        assert!(
+            scale > 0.0,
+            "MaxTimes requires a positive scale, found {}",
+            scale
+        );
+        Self { scale }
+    }
+}
+
+impl WeightSemiring for MaxTimes {
+    type Value = f64;
+    const MAXIMISE: bool = true;
+
+    fn one(&self) -> f64 {
+        1.0
+    }
+
+    fn edge(&self, weight: Option<EdgeWeight>) -> f64 {
+        match weight {
+            None => 1.0,
+            Some(EdgeWeight::Weight(w)) if (0.0..=self.scale).contains(&(w as f64)) => {
+                w as f64 / self.scale
+            }
+            Some(w) => panic!(
+                "MaxTimes requires weights in 0..={}, found {}",
+                self.scale, w
+            ),
+        }
+    }
+
+    fn extend(&self, path: f64, edge: f64) -> f64 {
+        path * edge
+    }
+}
+
+/// Every edge is usable and a path's cost is whether it exists, which finds the reachable nodes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct OrAnd;
+
+impl WeightSemiring for OrAnd {
+    type Value = bool;
+    const MAXIMISE: bool = true;
+
+    fn one(&self) -> bool {
+        true
+    }
+
+    fn edge(&self, _: Option<EdgeWeight>) -> bool {
+        true
+    }
+
+    fn extend(&self, path: bool, edge: bool) -> bool {
+        path && edge
+    }
+}
+
+/// Finds the best path from `s` to every reachable node under the given semiring
+/// with a generalisation of Dijkstra's algorithm
+///
+/// Returns the predecessor map of the best paths, whose edges keep their original weights,
+/// and the cost of the best path to each node.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::{dijkstra, semiring::{best_paths, MinPlus, OrAnd}};
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2 => 4, 3 => 1],
+///     3 => [2 => 1],
+///     4 => [1 => 1],
+/// };
+///
+/// // min-plus is the usual shortest path
+/// let (_, distances) = best_paths(&graph, &1, &MinPlus);
+/// assert_eq!(distances, dijkstra(&graph, &1).1);
+///
+/// let (_, reachable) = best_paths(&graph, &1, &OrAnd);
+/// assert_eq!(reachable.len(), 3);
+/// assert!(!reachable.contains_key(&4));
+/// ```
+pub fn best_paths<'a, G, S>(
+    graph: G,
+    s: &'a G::Node,
+    semiring: &S,
+) -> (PredMap<'a, G::Node>, CostMap<'a, G::Node, S>)
+where
+    G: EdgeWeighted<'a>,
+    S: WeightSemiring + ?Sized,
+{
+    let mut search = Search::new(s, Ranked::<S>::new(semiring.one()));
+    while let Some((node, cost)) = search.pop() {
+        for (dest, weight) in graph.neighbors_weighted(node) {
+            let new_cost = semiring.extend(cost.value, semiring.edge(weight));
+            search.relax(node, dest, Ranked::new(new_cost), weight, |c| c);
+        }
+    }
+
+    let costs = search
+        .costs
+        .iter()
+        .map(|(&node, cost)| (node, cost.value))
+        .collect();
+    (search.pred_map(), costs)
+}
+
+/// A path cost ordered so that better costs come first
+struct Ranked<S: WeightSemiring + ?Sized> {
+    value: S::Value,
+    semiring: PhantomData<S>,
+}
+
+impl<S: WeightSemiring + ?Sized> Ranked<S> {
+    fn new(value: S::Value) -> Self {
+        Self {
+            value,
+            semiring: PhantomData,
+        }
+    }
+}
+
+impl<S: WeightSemiring + ?Sized> Clone for Ranked<S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S: WeightSemiring + ?Sized> Copy for Ranked<S> {}
+
+impl<S: WeightSemiring + ?Sized> Ord for Ranked<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let ordering = self
+            .value
+            .partial_cmp(&other.value)
+            .unwrap_or(Ordering::Equal);
+
+        match S::MAXIMISE {
+            true => ordering.reverse(),
+            false => ordering,
+        }
+    }
+}
+
+impl<S: WeightSemiring + ?Sized> PartialOrd for Ranked<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S: WeightSemiring + ?Sized> PartialEq for Ranked<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S: WeightSemiring + ?Sized> Eq for Ranked<S> {}
@@ -0,0 +1,257 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::Search;
+use crate::{
+    visit::{EdgeWeighted, IntoNeighbors, IntoNodes},
+    Edge, EdgeWeight, NodeBounds, PredMap,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Represents the distance map generated by the shortest path algorithms
+pub type DistMap<'a, N> = HashMap<&'a N, EdgeWeight>;
+
+/// Represents the owned results of [`dijkstra_owned`] and [`bellman_ford_owned`],
+/// mapping each reachable node to its predecessor and its distance from the start
+pub type OwnedPredMap<N> = HashMap<N, (N, EdgeWeight)>;
+
+/// The predecessor and distance maps found by [`bellman_ford`]
+type ShortestPaths<'a, N> = (PredMap<'a, N>, DistMap<'a, N>);
+
+/// Returns the predecessor map of a breadth first search from `s`,
+/// which gives the paths with the fewest edges from `s` to every reachable node
+/// ```
+/// use graph_algos::{graph, Graph, Path};
+/// use graph_algos::algos::bfs_shortest_paths;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2, 3],
+///     2 => [4, 5],
+///     3 => [7, 6],
+///     4 => [9, 8],
+///     6 => [5],
+///     9 => [2],
+/// };
+///
+/// let pred_map = bfs_shortest_paths(&graph, &1);
+/// let path = Path::new_path_to(&pred_map, &8).unwrap();
+///
+/// assert_eq!(path.to_string(), "1 --> 2 --> 4 --> 8");
+/// ```
+pub fn bfs_shortest_paths<'a, G: IntoNeighbors<'a>>(
+    graph: G,
+    s: &'a G::Node,
+) -> PredMap<'a, G::Node> {
+    let mut discovered: VecDeque<&G::Node> = vec![s].into();
+    let mut seen: HashSet<&G::Node> = [s].iter().cloned().collect();
+
+    let mut pred_map: PredMap<'a, G::Node> = HashMap::new();
+    pred_map.insert(s, Edge::new(s));
+
+    while let Some(u) = discovered.pop_front() {
+        for v in graph.neighbors(u) {
+            // the first time we see a node is along a shortest path
+            if seen.insert(v) {
+                pred_map.insert(v, Edge::new(u));
+                discovered.push_back(v);
+            }
+        }
+    }
+
+    pred_map
+}
+
+/// Finds the shortest paths from `s` to every reachable node using Dijkstra's algorithm
+///
+/// Panics if an edge without a weight is reached.
+/// ```
+/// use graph_algos::{graph, Graph, Path};
+/// use graph_algos::algos::dijkstra;
+///
+/// let graph: Graph<&str> = graph! {
+///     "a" => ["c" => 2, "b" => 3],
+///     "b" => ["e" => 6, "d" => 5],
+///     "c" => ["g" => 2, "f" => 1],
+///     "d" => ["i" => 2, "h" => 3],
+///     "e" => ["h" => 7],
+///     "f" => ["e" => 6],
+///     "i" => ["b" => 4],
+/// };
+///
+/// let (pred_map, dist_map) = dijkstra(&graph, &"a");
+/// assert_eq!(dist_map[&"e"], 9.into());
+///
+/// let path = Path::new_path_to(&pred_map, &"e").unwrap();
+/// assert_eq!(path.to_string(), r#""a" --(3)-> "b" --(6)-> "e""#);
+/// ```
+pub fn dijkstra<'a, G: EdgeWeighted<'a>>(
+    graph: G,
+    s: &'a G::Node,
+) -> (PredMap<'a, G::Node>, DistMap<'a, G::Node>) {
+    let mut search = Search::new(s, 0.into());
+    while let Some((node, distance)) = search.pop() {
+        search.expand(graph, node, distance, |_| 0.into());
+    }
+
+    (search.pred_map(), search.costs)
+}
+
+/// Finds the shortest paths from `s` to every node using the Bellman-Ford algorithm
+///
+/// Unlike [`dijkstra`] this handles negative edge weights.
+/// Returns `None` if there is a negative cycle reachable from `s`,
+/// as then there are no shortest paths to the nodes on or after it.
+/// Panics if the graph contains an edge without a weight.
+/// ```
+/// use graph_algos::{graph, Graph, Path, EdgeWeight};
+/// use graph_algos::algos::bellman_ford;
+///
+/// let graph: Graph<&str> = graph! {
+///     "a" => ["b" => 4, "c" => 2],
+///     "b" => ["d" => 1],
+///     "c" => ["b" => -3],
+/// };
+///
+/// let (pred_map, dist_map) = bellman_ford(&graph, &"a").unwrap();
+/// assert_eq!(dist_map[&"d"], 0.into());
+///
+/// let path = Path::new_path_to(&pred_map, &"d").unwrap();
+/// assert_eq!(path.to_string(), r#""a" --(2)-> "c" --(-3)-> "b" --(1)-> "d""#);
+///
+/// let cyclic: Graph<u32> = graph! {
+///     1 => [2 => 1],
+///     2 => [3 => 1],
+///     3 => [1 => -5, 4 => 1],
+/// };
+/// assert!(bellman_ford(&cyclic, &1).is_none());
+/// ```
+pub fn bellman_ford<'a, G>(graph: G, s: &'a G::Node) -> Option<ShortestPaths<'a, G::Node>>
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a>,
+{
+    let mut pred_map: PredMap<'a, G::Node> = Default::default();
+    let mut dist_map: DistMap<'a, G::Node> = graph
+        .nodes()
+        .map(|node| (node, EdgeWeight::infinity()))
+        .collect();
+
+    // insert pred map self link
+    pred_map.insert(s, Edge::new_with_weight(s, 0));
+
+    // assert origin is distance 0 from itself
+    dist_map.insert(s, 0.into());
+
+    // without negative cycles every shortest path has fewer edges than there are nodes,
+    // so a change in the round after that means there is one
+    let nodes = graph.node_count();
+    for _ in 0..nodes {
+        let mut changed = false;
+        // perform relaxation
+        for u in graph.nodes() {
+            // nothing can be relaxed from a node we haven't reached yet
+            if dist_map[u] == EdgeWeight::infinity() {
+                continue;
+            }
+
+            for (v, weight) in graph.neighbors_weighted(u) {
+                // bellman ford requires weights so a graph without weights is malformed
+                let w = weight.expect("No weight for this edge, panicking");
+
+                if dist_map[u] + w < dist_map[v] {
+                    dist_map.insert(v, dist_map[u] + w);
+                    pred_map.insert(v, Edge::new_with_weight(u, w));
+                    changed = true;
+                }
+            }
+        }
+
+        // break out if nothing happened this round
+        if !changed {
+            return Some((pred_map, dist_map));
+        };
+    }
+
+    None
+}
+
+/// Like [`dijkstra`], but returns owned results which don't borrow the graph
+///
+/// Each node reachable from `s` is mapped to its predecessor on a shortest path and its distance
+/// from `s`, with `s` being its own predecessor.
+/// This clones every reachable node, in exchange the graph can be changed or dropped
+/// while the results are still in use.
+/// ```
+/// use graph_algos::{graph, Edge, Graph};
+/// use graph_algos::algos::dijkstra_owned;
+///
+/// let mut graph: Graph<&str> = graph! {
+///     "a" => ["b" => 3, "c" => 1],
+///     "c" => ["b" => 1],
+/// };
+///
+/// let paths = dijkstra_owned(&graph, &"a");
+/// graph.add_edge("b", Edge::new_with_weight("d", 1));
+///
+/// assert_eq!(paths[&"b"], ("c", 2.into()));
+/// assert_eq!(paths[&"a"], ("a", 0.into()));
+/// assert_eq!(paths.len(), 3);
+/// ```
+pub fn dijkstra_owned<'a, G: EdgeWeighted<'a>>(graph: G, s: &'a G::Node) -> OwnedPredMap<G::Node> {
+    let (pred_map, dist_map) = dijkstra(graph, s);
+    to_owned(&pred_map, &dist_map)
+}
+
+/// Like [`bellman_ford`], but returns owned results which don't borrow the graph
+///
+/// Each node reachable from `s` is mapped to its predecessor on a shortest path and its distance
+/// from `s`, with `s` being its own predecessor.
+/// Returns `None` if there is a negative cycle reachable from `s`.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::bellman_ford_owned;
+///
+/// let graph: Graph<&str> = graph! {
+///     "a" => ["b" => 4, "c" => 2],
+///     "c" => ["b" => -3],
+///     "d" => ["a" => 1],
+/// };
+///
+/// let paths = bellman_ford_owned(&graph, &"a").unwrap();
+/// assert_eq!(paths[&"b"], ("c", (-1).into()));
+/// assert!(!paths.contains_key(&"d"));
+/// ```
+pub fn bellman_ford_owned<'a, G>(graph: G, s: &'a G::Node) -> Option<OwnedPredMap<G::Node>>
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a>,
+{
+    let (pred_map, dist_map) = bellman_ford(graph, s)?;
+    Some(to_owned(&pred_map, &dist_map))
+}
+
+/// Combines a predecessor map and distance map into owned results, keeping only reached nodes
+fn to_owned<N: NodeBounds>(
+    pred_map: &PredMap<'_, N>,
+    dist_map: &DistMap<'_, N>,
+) -> OwnedPredMap<N> {
+    pred_map
+        .iter()
+        .map(|(&node, edge)| {
+            let pred = (*edge.destination()).clone();
+            (node.clone(), (pred, dist_map[node]))
+        })
+        .collect()
+}
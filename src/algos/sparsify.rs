@@ -0,0 +1,373 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Sparsifiers, which find lighter subgraphs that approximately preserve distances
+//! or the cut structure of a graph.
+//!
+//! They are for graphs too dense to run expensive queries on directly:
+//! the queries are run on the sparsifier instead, trading some accuracy for far fewer edges.
+
+use crate::{
+    util::index_nodes,
+    visit::{EdgeWeighted, IntoNodes},
+    Edge, EdgeWeight, Graph,
+};
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+/// Finds a `t`-spanner of the graph with the greedy algorithm
+///
+/// A `t`-spanner is a subgraph where the distance between any two nodes is at most `t` times
+/// their distance in the graph.
+/// The edges are considered lightest first, and an edge is only kept if the spanner built so far
+/// has no path between its ends within `t` times its weight.
+/// Larger values of `t` give sparser spanners, and `t` below 1 keeps every edge.
+/// The spanner has every node of the graph and the kept edges with their original weights.
+/// Unweighted edges count as having a weight of 1.
+/// Panics if any edge has a negative or infinite weight.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::{dijkstra, sparsify::greedy_spanner};
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2 => 1, 3 => 3, 4 => 10],
+///     2 => [3 => 1],
+///     3 => [4 => 1],
+/// };
+///
+/// let spanner = greedy_spanner(&graph, 2.0);
+///
+/// // 1 -> 2 -> 3 is within twice the length of 1 -> 3, and 1 -> 2 -> 3 -> 4 is shorter than 1 -> 4
+/// assert_eq!(spanner.edges().count(), 3);
+/// assert!(!spanner.is_edge(&1, &3) && !spanner.is_edge(&1, &4));
+///
+/// let (_, dist_map) = dijkstra(&spanner, &1);
+/// assert_eq!(dist_map[&3], 2.into());
+/// ```
+pub fn greedy_spanner<'a, G>(graph: G, t: f64) -> Graph<G::Node>
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a>,
+{
+    let (nodes, indices) = index_nodes(graph);
+
+    let mut edges = Vec::new();
+    for (u, &node) in nodes.iter().enumerate() {
+        for (v, weight) in graph.neighbors_weighted(node) {
+            let w = match weight {
+                None => 1,
+                Some(EdgeWeight::Weight(w)) if w >= 0 => w,
+                Some(w) => panic!(
+                    "greedy_spanner needs non-negative finite weights, found {}",
+                    w
+                ),
+            };
+            if let Some(&v) = indices.get(v) {
+                edges.push((w, u, v, weight));
+            }
+        }
+    }
+    edges.sort_by_key(|&(w, u, v, _)| (w, u, v));
+
+    let mut spanner = Graph::with_capacity(nodes.len());
+    for node in &nodes {
+        spanner.add_node((*node).clone());
+    }
+
+    let mut adjacency: Vec<Vec<(usize, i64)>> = vec![Vec::new(); nodes.len()];
+    let mut search = BoundedSearch::new(nodes.len());
+    for (w, u, v, weight) in edges {
+        // a self loop never shortens a path
+        if u == v {
+            continue;
+        }
+
+        let bound = t * w as f64;
+        if search.within(&adjacency, u, v, bound) {
+            continue;
+        }
+
+        adjacency[u].push((v, w));
+        let dest = nodes[v].clone();
+        spanner.add_edge(
+            nodes[u].clone(),
+            match weight {
+                Some(weight) => Edge::new_with_weight(dest, weight),
+                None => Edge::new(dest),
+            },
+        );
+    }
+
+    spanner
+}
+
+/// A Dijkstra search which gives up once it passes a distance bound,
+/// reusing its distance table between searches
+struct BoundedSearch {
+    distances: Vec<Option<i64>>,
+    touched: Vec<usize>,
+}
+
+impl BoundedSearch {
+    fn new(nodes: usize) -> Self {
+        Self {
+            distances: vec![None; nodes],
+            touched: Vec::new(),
+        }
+    }
+
+    /// Returns whether the distance from `u` to `v` is at most `bound`
+    fn within(&mut self, adjacency: &[Vec<(usize, i64)>], u: usize, v: usize, bound: f64) -> bool {
+        for x in self.touched.drain(..) {
+            self.distances[x] = None;
+        }
+
+        let mut queue = BinaryHeap::new();
+        self.distances[u] = Some(0);
+        self.touched.push(u);
+        queue.push(Reverse((0, u)));
+
+        while let Some(Reverse((d, x))) = queue.pop() {
+            if d as f64 > bound {
+                return false;
+            }
+            if x == v {
+                return true;
+            }
+            if self.distances[x] < Some(d) {
+                continue;
+            }
+
+            for &(y, w) in &adjacency[x] {
+                let new_distance = d + w;
+                if !matches!(self.distances[y], Some(old) if old <= new_distance) {
+                    if self.distances[y].is_none() {
+                        self.touched.push(y);
+                    }
+                    self.distances[y] = Some(new_distance);
+                    queue.push(Reverse((new_distance, y)));
+                }
+            }
+        }
+
+        false
+    }
+}
+
+/// Sparsifies the graph by sampling `samples` edges, with replacement,
+/// with probability proportional to their weight times their effective resistance,
+/// the spectral sparsifier of Spielman and Srivastava
+///
+/// The graph is treated as undirected, with the weights as conductances:
+/// an edge in either or both directions between two nodes is a single undirected edge,
+/// whose weight is the largest of the weights in each direction.
+/// Unweighted edges count as having a weight of 1, and edges with a weight of 0 are dropped.
+/// Each sampled edge is reweighted so the sparsifier's Laplacian matches the graph's in expectation,
+/// which approximately preserves the weight of every cut.
+/// Edges sampled more than once have their weights added, and the final weights are rounded,
+/// with a minimum of 1.
+/// The sparsifier has every node of the graph, and each kept edge in both directions.
+///
+/// The effective resistances are estimated with random projections,
+/// solving a Laplacian system with the conjugate gradient method for each projection,
+/// so the time taken grows with the number of edges rather than the square of the number of nodes.
+/// Panics if any edge has a negative or infinite weight.
+/// ```
+/// use graph_algos::generators;
+/// use graph_algos::algos::sparsify::effective_resistance_sparsifier;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let graph = generators::complete_graph(50);
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// let sparse = effective_resistance_sparsifier(&graph, 400, &mut rng);
+///
+/// assert_eq!(sparse.len(), 50);
+/// assert!(sparse.edges().count() < graph.edges().count() / 2);
+/// assert!(sparse.edges().all(|(u, edge)| graph.is_edge(u, edge.destination())));
+///
+/// // the total weight is about the same as the 2450 unweighted edges of the original
+/// let total: i64 = sparse
+///     .weighted_edges()
+///     .map(|(_, _, w)| match w {
+///         graph_algos::EdgeWeight::Weight(w) => w,
+///         _ => unreachable!(),
+///     })
+///     .sum();
+/// assert!((2000..3000).contains(&total));
+/// ```
+pub fn effective_resistance_sparsifier<'a, G, R>(
+    graph: G,
+    samples: usize,
+    rng: &mut R,
+) -> Graph<G::Node>
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a>,
+    R: Rng + ?Sized,
+{
+    let (nodes, indices) = index_nodes(graph);
+
+    let mut conductances: HashMap<(usize, usize), f64> = HashMap::new();
+    for (u, &node) in nodes.iter().enumerate() {
+        for (v, weight) in graph.neighbors_weighted(node) {
+            let w = match weight {
+                None => 1.0,
+                Some(EdgeWeight::Weight(w)) if w >= 0 => w as f64,
+                Some(w) => panic!(
+                    "effective_resistance_sparsifier needs non-negative finite weights, found {}",
+                    w
+                ),
+            };
+            let v = match indices.get(v) {
+                Some(&v) if v != u && w > 0.0 => v,
+                _ => continue,
+            };
+
+            let conductance = conductances.entry((u.min(v), u.max(v))).or_default();
+            *conductance = conductance.max(w);
+        }
+    }
+
+    let mut sparsifier = Graph::with_capacity(nodes.len());
+    for node in &nodes {
+        sparsifier.add_node((*node).clone());
+    }
+
+    let mut edges: Vec<(usize, usize, f64)> = conductances
+        .into_iter()
+        .map(|((u, v), w)| (u, v, w))
+        .collect();
+    edges.sort_by_key(|&(u, v, _)| (u, v));
+    if edges.is_empty() || samples == 0 {
+        return sparsifier;
+    }
+
+    let resistances = effective_resistances(nodes.len(), &edges, rng);
+    let importance: Vec<f64> = edges
+        .iter()
+        .zip(&resistances)
+        .map(|(&(_, _, w), r)| w * r)
+        .collect();
+    let total: f64 = importance.iter().sum();
+    let picker = WeightedIndex::new(&importance).expect("every edge has a positive resistance");
+
+    let mut weights: HashMap<usize, f64> = HashMap::new();
+    for _ in 0..samples {
+        let e = picker.sample(rng);
+        let probability = importance[e] / total;
+        *weights.entry(e).or_default() += edges[e].2 / (samples as f64 * probability);
+    }
+
+    for (e, weight) in weights {
+        let (u, v, _) = edges[e];
+        let weight = (weight.round() as i64).max(1);
+        sparsifier.add_edge(
+            nodes[u].clone(),
+            Edge::new_with_weight(nodes[v].clone(), weight),
+        );
+        sparsifier.add_edge(
+            nodes[v].clone(),
+            Edge::new_with_weight(nodes[u].clone(), weight),
+        );
+    }
+
+    sparsifier
+}
+
+/// Estimates the effective resistance of each undirected edge `(u, v, conductance)`
+/// with the random projection method of Spielman and Srivastava
+fn effective_resistances<R: Rng + ?Sized>(
+    nodes: usize,
+    edges: &[(usize, usize, f64)],
+    rng: &mut R,
+) -> Vec<f64> {
+    let mut adjacency: Vec<Vec<(usize, f64)>> = vec![Vec::new(); nodes];
+    for &(u, v, w) in edges {
+        adjacency[u].push((v, w));
+        adjacency[v].push((u, w));
+    }
+
+    // each projection gives an unbiased estimate, averaging O(log n) of them concentrates it
+    let projections = (8.0 * (nodes as f64).ln()).ceil().max(1.0) as usize;
+    let scale = 1.0 / projections as f64;
+
+    let mut resistances = vec![0.0; edges.len()];
+    for _ in 0..projections {
+        // b = B^T W^(1/2) q for a random sign vector q
+        let mut b = vec![0.0; nodes];
+        for &(u, v, w) in edges {
+            let q = if rng.gen() { w.sqrt() } else { -w.sqrt() };
+            b[u] += q;
+            b[v] -= q;
+        }
+
+        let z = solve_laplacian(&adjacency, &b);
+        for (resistance, &(u, v, _)) in resistances.iter_mut().zip(edges) {
+            *resistance += scale * (z[u] - z[v]).powi(2);
+        }
+    }
+
+    resistances
+}
+
+/// Solves `L x = b` with the conjugate gradient method, where `L` is the Laplacian of the graph
+///
+/// The entries of `b` must sum to 0 over each connected component, so that a solution exists.
+fn solve_laplacian(adjacency: &[Vec<(usize, f64)>], b: &[f64]) -> Vec<f64> {
+    let laplacian = |x: &[f64]| -> Vec<f64> {
+        adjacency
+            .iter()
+            .enumerate()
+            .map(|(u, succs)| succs.iter().map(|&(v, w)| w * (x[u] - x[v])).sum())
+            .collect()
+    };
+    let dot = |a: &[f64], b: &[f64]| -> f64 { a.iter().zip(b).map(|(a, b)| a * b).sum() };
+
+    let mut x = vec![0.0; b.len()];
+    let mut r = b.to_vec();
+    let mut p = r.clone();
+    let mut rs = dot(&r, &r);
+    let tolerance = 1e-10 * rs.max(f64::MIN_POSITIVE);
+
+    for _ in 0..10 * b.len() {
+        if rs <= tolerance {
+            break;
+        }
+
+        let lp = laplacian(&p);
+        let curvature = dot(&p, &lp);
+        if curvature <= 0.0 {
+            break;
+        }
+
+        let alpha = rs / curvature;
+        for i in 0..x.len() {
+            x[i] += alpha * p[i];
+            r[i] -= alpha * lp[i];
+        }
+
+        let new_rs = dot(&r, &r);
+        let beta = new_rs / rs;
+        for i in 0..p.len() {
+            p[i] = r[i] + beta * p[i];
+        }
+        rs = new_rs;
+    }
+
+    x
+}
@@ -0,0 +1,305 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{
+    visit::{IntoNeighbors, IntoNodes},
+    Graph, NodeBounds,
+};
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap},
+    fmt,
+    hash::BuildHasher,
+};
+
+/// Topologically sorts the nodes reachable from `u`
+///
+/// Returns `None` if a cycle is reachable from `u`.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::topological_sort;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2, 3],
+///     2 => [4, 6],
+///     3 => [5, 6],
+///     5 => [6],
+/// };
+///
+/// let sorted = topological_sort(&graph, &1).unwrap();
+///
+/// // every edge goes forwards in the ordering
+/// let position = |n: &u32| sorted.iter().position(|m| *m == n).unwrap();
+/// for (u, edge) in graph.edges() {
+///     assert!(position(u) < position(edge.destination()));
+/// }
+///
+/// let cyclic: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [1],
+/// };
+///
+/// assert!(topological_sort(&cyclic, &1).is_none());
+/// ```
+pub fn topological_sort<'a, G: IntoNeighbors<'a>>(
+    graph: G,
+    u: &'a G::Node,
+) -> Option<Vec<&'a G::Node>> {
+    let mut processed: HashMap<&G::Node, bool> = HashMap::new();
+
+    let mut rev_order: Vec<&G::Node> = Vec::new();
+
+    topo_rec(graph, u, &mut processed, &mut rev_order)?;
+
+    rev_order.reverse();
+
+    Some(rev_order)
+}
+
+fn topo_rec<'a, G: IntoNeighbors<'a>>(
+    graph: G,
+    u: &'a G::Node,
+    processed: &mut HashMap<&'a G::Node, bool>,
+    rev_order: &mut Vec<&'a G::Node>,
+) -> Option<()> {
+    match processed.get(u) {
+        // we reached a node which is still being processed so there is a loop
+        Some(false) => return None,
+        Some(true) => return Some(()),
+        None => {}
+    }
+
+    processed.insert(u, false);
+
+    for v in graph.neighbors(u) {
+        topo_rec(graph, v, processed, rev_order)?;
+    }
+
+    // finished processing
+    processed.insert(u, true);
+
+    rev_order.push(u);
+
+    Some(())
+}
+
+/// A lazy topological ordering of a graph using Kahn's algorithm,
+/// created by [`topo_iter`] or [`topo_iter_by_key`]
+///
+/// Whenever several nodes are ready the one with the smallest key is yielded first,
+/// with ties broken by the order the nodes became ready.
+/// If the graph has a cycle, the nodes on or after the cycle are never yielded,
+/// [`TopoIter::remaining`] counts them once the iterator is exhausted.
+pub struct TopoIter<'a, G: IntoNeighbors<'a>, F, K> {
+    graph: G,
+    in_degrees: HashMap<&'a G::Node, usize>,
+    ready: BinaryHeap<ReadyEntry<'a, G::Node, K>>,
+    key: F,
+    next_seq: usize,
+    remaining: usize,
+}
+
+/// Returns a lazy topological ordering of the whole graph,
+/// yielding nodes in the order they become ready
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::topo_iter;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2, 3],
+///     2 => [4],
+///     3 => [4],
+/// };
+///
+/// let order: Vec<&u32> = topo_iter(&graph).collect();
+/// assert_eq!(order.len(), 4);
+/// assert_eq!(order[0], &1);
+/// assert_eq!(order[3], &4);
+/// ```
+pub fn topo_iter<'a, G>(graph: G) -> TopoIter<'a, G, fn(&G::Node), ()>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    topo_iter_by_key(graph, |_| ())
+}
+
+/// Returns a lazy topological ordering of the whole graph,
+/// yielding the ready node with the smallest key first
+///
+/// Using the node itself as the key gives the lexicographically smallest ordering.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::topo_iter_by_key;
+///
+/// let graph: Graph<u32> = graph! {
+///     5 => [2],
+///     3 => [2, 1],
+///     4 => [1],
+/// };
+///
+/// let smallest: Vec<&u32> = topo_iter_by_key(&graph, |&n| n).collect();
+/// assert_eq!(smallest, vec![&3, &4, &1, &5, &2]);
+///
+/// let largest: Vec<&u32> = topo_iter_by_key(&graph, |&n| std::cmp::Reverse(n)).collect();
+/// assert_eq!(largest, vec![&5, &4, &3, &2, &1]);
+/// ```
+pub fn topo_iter_by_key<'a, G, F, K>(graph: G, key: F) -> TopoIter<'a, G, F, K>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+    F: FnMut(&G::Node) -> K,
+    K: Ord,
+{
+    let mut in_degrees: HashMap<&G::Node, usize> = graph.nodes().map(|u| (u, 0)).collect();
+    for u in graph.nodes() {
+        for v in graph.neighbors(u) {
+            *in_degrees.entry(v).or_default() += 1;
+        }
+    }
+
+    let mut iter = TopoIter {
+        graph,
+        remaining: in_degrees.len(),
+        in_degrees,
+        ready: BinaryHeap::new(),
+        key,
+        next_seq: 0,
+    };
+
+    for u in graph.nodes() {
+        if iter.in_degrees[u] == 0 {
+            iter.push_ready(u);
+        }
+    }
+
+    iter
+}
+
+impl<'a, G: IntoNeighbors<'a>, F, K> TopoIter<'a, G, F, K>
+where
+    F: FnMut(&G::Node) -> K,
+    K: Ord,
+{
+    /// Returns the number of nodes which haven't been yielded yet
+    ///
+    /// Once the iterator is exhausted this is the number of nodes which are on or after a cycle,
+    /// so the graph is acyclic if and only if it is 0.
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    /// use graph_algos::algos::topo_iter;
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2],
+    ///     2 => [3],
+    ///     3 => [2],
+    /// };
+    ///
+    /// let mut order = topo_iter(&graph);
+    /// assert_eq!(order.by_ref().collect::<Vec<_>>(), vec![&1]);
+    /// assert_eq!(order.remaining(), 2);
+    /// ```
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// adds a node to the set of nodes which are ready to be yielded
+    fn push_ready(&mut self, u: &'a G::Node) {
+        let key = (self.key)(u);
+        self.ready.push(Reverse((key, self.next_seq, ReadyNode(u))));
+        self.next_seq += 1;
+    }
+}
+
+impl<'a, G: IntoNeighbors<'a>, F, K> Iterator for TopoIter<'a, G, F, K>
+where
+    F: FnMut(&G::Node) -> K,
+    K: Ord,
+{
+    type Item = &'a G::Node;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((_, _, ReadyNode(u))) = self.ready.pop()?;
+        self.remaining -= 1;
+
+        for v in self.graph.neighbors(u) {
+            if let Some(degree) = self.in_degrees.get_mut(v) {
+                *degree -= 1;
+                if *degree == 0 {
+                    self.push_ready(v);
+                }
+            }
+        }
+
+        Some(u)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.ready.len(), Some(self.remaining))
+    }
+}
+
+impl<'a, G: IntoNeighbors<'a>, F, K> fmt::Debug for TopoIter<'a, G, F, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TopoIter")
+            .field("ready", &self.ready.len())
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
+
+/// An entry in the ready queue, ordered so the smallest key and then sequence number is popped first
+type ReadyEntry<'a, N, K> = Reverse<(K, usize, ReadyNode<'a, N>)>;
+
+/// A node waiting in the ready queue, which compares equal to every other node
+/// so the queue is ordered by the key and sequence number alone
+struct ReadyNode<'a, N>(&'a N);
+
+impl<N> PartialEq for ReadyNode<'_, N> {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl<N> Eq for ReadyNode<'_, N> {}
+
+impl<N> PartialOrd for ReadyNode<'_, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> Ord for ReadyNode<'_, N> {
+    fn cmp(&self, _: &Self) -> Ordering {
+        Ordering::Equal
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher> Graph<N, S> {
+    /// Returns a lazy topological ordering of the graph, see [`topo_iter`]
+    pub fn topo_iter(&self) -> TopoIter<'_, &Self, fn(&N), ()> {
+        topo_iter(self)
+    }
+
+    /// Returns a lazy topological ordering of the graph
+    /// which yields the ready node with the smallest key first, see [`topo_iter_by_key`]
+    pub fn topo_iter_by_key<F, K>(&self, key: F) -> TopoIter<'_, &Self, F, K>
+    where
+        F: FnMut(&N) -> K,
+        K: Ord,
+    {
+        topo_iter_by_key(self, key)
+    }
+}
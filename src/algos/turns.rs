@@ -0,0 +1,195 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::{DistMap, Search};
+use crate::{visit::EdgeWeighted, Edge, EdgeWeight, Path};
+use std::collections::HashMap;
+
+/// The extra cost of turning from one edge onto the next, consulted by [`dijkstra_with_turns`]
+/// and [`astar_with_turns`]
+///
+/// This lets road networks model turn penalties and banned turns directly on the node graph,
+/// rather than building a graph with a node for every edge.
+/// Any `Fn(&N, &N, &N) -> Option<EdgeWeight>` closure is a turn cost.
+pub trait TurnCost<N> {
+    /// Returns the cost of arriving at `via` from `from` then leaving it towards `to`,
+    /// on top of the weight of the edge to `to`, or `None` if the turn isn't allowed
+    fn turn_cost(&self, from: &N, via: &N, to: &N) -> Option<EdgeWeight>;
+}
+
+impl<N, F: Fn(&N, &N, &N) -> Option<EdgeWeight>> TurnCost<N> for F {
+    fn turn_cost(&self, from: &N, via: &N, to: &N) -> Option<EdgeWeight> {
+        self(from, via, to)
+    }
+}
+
+/// A node along with the node it was reached from, `None` for the start
+type State<'a, N> = (Option<&'a N>, &'a N);
+
+/// The first state reached at the goal of a search and its distance
+type Reached<'a, N> = Option<(State<'a, N>, EdgeWeight)>;
+
+/// Finds the shortest distance from `s` to every reachable node when each turn has a cost
+///
+/// The distance to a node is the cost of the cheapest way of arriving at it by any edge.
+/// Leaving `s` is free of turn costs, as there is no edge it was arrived at by.
+///
+/// Panics if an edge without a weight is reached.
+/// ```
+/// use graph_algos::{graph, Graph, EdgeWeight};
+/// use graph_algos::algos::dijkstra_with_turns;
+///
+/// // a crossroads approached from the west
+/// let graph: Graph<&str> = graph! {
+///     "west" => ["centre" => 1],
+///     "centre" => ["north" => 1, "east" => 1, "south" => 1, "west" => 1],
+///     "north" => ["east" => 1],
+/// };
+///
+/// // going straight on is free, turning right costs 1 and turning left isn't allowed
+/// let turns = |from: &&str, _: &&str, to: &&str| match (*from, *to) {
+///     ("west", "east") => Some(EdgeWeight::new(0)),
+///     ("west", "south") => Some(EdgeWeight::new(1)),
+///     ("west", "north") => None,
+///     _ => Some(EdgeWeight::new(0)),
+/// };
+///
+/// let distances = dijkstra_with_turns(&graph, &"west", &turns);
+/// assert_eq!(distances[&"east"], 2.into());
+/// assert_eq!(distances[&"south"], 3.into());
+/// assert!(!distances.contains_key(&"north"));
+/// ```
+pub fn dijkstra_with_turns<'a, G, T>(graph: G, s: &'a G::Node, turns: &T) -> DistMap<'a, G::Node>
+where
+    G: EdgeWeighted<'a>,
+    T: TurnCost<G::Node> + ?Sized,
+{
+    let (search, _) = search(graph, s, None, turns, |_| 0.into());
+
+    let mut distances: DistMap<'a, G::Node> = HashMap::new();
+    for ((_, node), distance) in search.costs {
+        let best = distances.entry(node).or_insert(distance);
+        if distance < *best {
+            *best = distance;
+        }
+    }
+
+    distances
+}
+
+/// Finds a shortest path from `start` to `goal` with the A* search algorithm when each turn has
+/// a cost, returning the path and its total weight including the turn costs,
+/// or `None` if the goal can't be reached
+///
+/// As with [`crate::algos::astar`] the path is only guaranteed to be a shortest one
+/// if the heuristic never overestimates.
+///
+/// Panics if the search reaches an edge without a weight.
+/// ```
+/// use graph_algos::{graph, Graph, EdgeWeight};
+/// use graph_algos::algos::astar_with_turns;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2 => 1],
+///     2 => [3 => 1, 4 => 5],
+///     3 => [4 => 1],
+/// };
+///
+/// // the turn 1 -> 2 -> 3 is banned, so the long way round has to be taken
+/// let turns = |&from: &u32, &via: &u32, &to: &u32| match (from, via, to) {
+///     (1, 2, 3) => None,
+///     _ => Some(EdgeWeight::new(0)),
+/// };
+///
+/// let (path, cost) = astar_with_turns(&graph, &1, &4, &turns, |_| 0.into()).unwrap();
+/// assert_eq!(path.to_string(), "1 --(1)-> 2 --(5)-> 4");
+/// assert_eq!(cost, 6.into());
+/// ```
+pub fn astar_with_turns<'a, G, T, H>(
+    graph: G,
+    start: &'a G::Node,
+    goal: &G::Node,
+    turns: &T,
+    heuristic: H,
+) -> Option<(Path<'a, G::Node>, EdgeWeight)>
+where
+    G: EdgeWeighted<'a>,
+    T: TurnCost<G::Node> + ?Sized,
+    H: FnMut(&G::Node) -> EdgeWeight,
+{
+    let (search, reached) = search(graph, start, Some(goal), turns, heuristic);
+    let (state, distance) = reached?;
+
+    let edges = search
+        .path_to(state)
+        .into_iter()
+        .map(|((_, node), weight)| match weight {
+            Some(weight) => Edge::new_with_weight(node, weight),
+            None => Edge::new(node),
+        })
+        .collect();
+
+    Some((Path::from_parts(start, edges), distance))
+}
+
+/// Runs A* over pairs of a node and the node it was reached from,
+/// so the cost of each turn can be charged when leaving a node
+///
+/// Returns the search along with the first state reached at the goal and its distance,
+/// if there is a goal.
+fn search<'a, G, T, H>(
+    graph: G,
+    s: &'a G::Node,
+    goal: Option<&G::Node>,
+    turns: &T,
+    mut heuristic: H,
+) -> (Search<State<'a, G::Node>>, Reached<'a, G::Node>)
+where
+    G: EdgeWeighted<'a>,
+    T: TurnCost<G::Node> + ?Sized,
+    H: FnMut(&G::Node) -> EdgeWeight,
+{
+    let mut search = Search::new((None, s), 0.into());
+
+    while let Some((state, distance)) = search.pop() {
+        let (from, node) = state;
+        if goal == Some(node) {
+            return (search, Some((state, distance)));
+        }
+
+        for (dest, weight) in graph.neighbors_weighted(node) {
+            let weight = weight.expect("Dijkstra requires edges to have weights - panicking.");
+            let turn = match from {
+                Some(from) => match turns.turn_cost(from, node, dest) {
+                    Some(turn) => turn,
+                    None => continue,
+                },
+                None => 0.into(),
+            };
+
+            search.relax(
+                state,
+                (Some(node), dest),
+                distance + turn + weight,
+                Some(weight),
+                |d| d + heuristic(dest),
+            );
+        }
+    }
+
+    (search, None)
+}
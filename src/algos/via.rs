@@ -0,0 +1,93 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::astar;
+use crate::{
+    visit::{EdgeWeighted, NodeFiltered},
+    EdgeWeight, Path,
+};
+use std::collections::HashSet;
+
+/// Finds the shortest path from `s` to `t` which visits each of the `vias` in order
+/// and never passes through a `forbidden` node
+///
+/// The route is built by joining the shortest paths between consecutive waypoints,
+/// so it can pass through the same node more than once.
+/// Returns the path and its total weight, or `None` if some waypoint can't be reached from
+/// the previous one, including when a waypoint is itself forbidden.
+///
+/// Panics if an edge without a weight is reached.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::route_via;
+/// use std::collections::HashSet;
+///
+/// let graph: Graph<&str> = graph! {
+///     "a" => ["b" => 1, "c" => 2],
+///     "b" => ["d" => 1],
+///     "c" => ["d" => 2, "e" => 1],
+///     "e" => ["d" => 1],
+/// };
+///
+/// let (path, cost) = route_via(&graph, &"a", &[&"c"], &"d", &HashSet::new()).unwrap();
+/// assert_eq!(path.to_string(), r#""a" --(2)-> "c" --(2)-> "d""#);
+/// assert_eq!(cost, 4.into());
+///
+/// let forbidden: HashSet<&&str> = [&"b"].iter().cloned().collect();
+/// let (path, _) = route_via(&graph, &"a", &[], &"d", &forbidden).unwrap();
+/// assert_eq!(path.to_string(), r#""a" --(2)-> "c" --(2)-> "d""#);
+///
+/// assert!(route_via(&graph, &"a", &[&"d"], &"c", &HashSet::new()).is_none());
+/// ```
+pub fn route_via<'a, G>(
+    graph: G,
+    s: &'a G::Node,
+    vias: &[&'a G::Node],
+    t: &'a G::Node,
+    forbidden: &HashSet<&G::Node>,
+) -> Option<(Path<'a, G::Node>, EdgeWeight)>
+where
+    G: EdgeWeighted<'a>,
+{
+    let mut edges = Vec::new();
+    let mut total: EdgeWeight = 0.into();
+
+    let mut from = s;
+    for &to in vias.iter().chain(std::iter::once(&t)) {
+        let (leg, weight) = leg(graph, from, to, forbidden)?;
+        edges.extend_from_slice(leg.edges());
+        total = total + weight;
+        from = to;
+    }
+
+    Some((Path::from_parts(s, edges), total))
+}
+
+/// Finds a shortest path from `s` to `t` avoiding the `forbidden` nodes and its total weight
+fn leg<'a, G: EdgeWeighted<'a>>(
+    graph: G,
+    s: &'a G::Node,
+    t: &G::Node,
+    forbidden: &HashSet<&G::Node>,
+) -> Option<(Path<'a, G::Node>, EdgeWeight)> {
+    if forbidden.contains(s) || forbidden.contains(t) {
+        return None;
+    }
+
+    let allowed = |u: &G::Node| !forbidden.contains(u);
+    astar(NodeFiltered::new(graph, &allowed), s, t, |_| 0.into())
+}
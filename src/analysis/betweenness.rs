@@ -0,0 +1,315 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{
+    error::Cancelled,
+    util::index_graph,
+    visit::{IntoNeighbors, IntoNodes},
+    AlgoControl, NodeBounds,
+};
+use std::collections::{HashMap, VecDeque};
+
+/// A score for each edge of a graph
+type EdgeScores<'a, N> = HashMap<(&'a N, &'a N), f64>;
+
+/// One level of the dendrogram produced by [`girvan_newman`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommunitySplit<'a, N: NodeBounds> {
+    /// The edges removed since the previous level, in the order they were removed
+    pub removed_edges: Vec<(&'a N, &'a N)>,
+    /// The weakly connected components of the graph once the edges were removed
+    pub components: Vec<Vec<&'a N>>,
+}
+
+/// Returns the betweenness of every edge, the number of shortest paths between
+/// pairs of nodes which pass through the edge, with paths split evenly between ties
+///
+/// Distances are the number of edges on a path and parallel edges are counted once.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::analysis::edge_betweenness;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2, 3],
+///     2 => [4],
+///     3 => [4],
+/// };
+///
+/// let betweenness = edge_betweenness(&graph);
+/// // 1 -> 2, and half of the paths from 1 to 4
+/// assert_eq!(betweenness[&(&1, &2)], 1.5);
+/// // 2 -> 4, and the other half of the paths from 1 to 4
+/// assert_eq!(betweenness[&(&2, &4)], 1.5);
+/// ```
+pub fn edge_betweenness<'a, G>(graph: G) -> HashMap<(&'a G::Node, &'a G::Node), f64>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    edge_betweenness_with_control(graph, &AlgoControl::new())
+        .expect("a fresh AlgoControl is never cancelled")
+}
+
+/// Returns the betweenness of every edge, reporting progress to `control`
+/// and stopping early if it is cancelled
+///
+/// Progress is counted in the nodes whose shortest paths have been searched, out of every node in the graph.
+/// See [`edge_betweenness`] and [`AlgoControl`].
+/// ```
+/// use graph_algos::{generators, AlgoControl};
+/// use graph_algos::analysis::edge_betweenness_with_control;
+/// use graph_algos::error::Cancelled;
+///
+/// let graph = generators::cycle_graph(100);
+///
+/// let control = AlgoControl::new();
+/// control.cancel();
+/// assert_eq!(edge_betweenness_with_control(&graph, &control), Err(Cancelled));
+/// ```
+pub fn edge_betweenness_with_control<'a, G>(
+    graph: G,
+    control: &AlgoControl,
+) -> Result<EdgeScores<'a, G::Node>, Cancelled>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let (nodes, adjacency) = index_adjacency(graph);
+
+    Ok(brandes(&adjacency, control, true)?
+        .into_iter()
+        .map(|((u, v), betweenness)| ((nodes[u], nodes[v]), betweenness))
+        .collect())
+}
+
+/// Detects communities with the Girvan–Newman algorithm,
+/// repeatedly removing the edge with the highest betweenness
+/// until the graph splits into at least `target_components` weakly connected components
+///
+/// Returns every level of the dendrogram, starting with the components of the original graph
+/// and adding a level each time the number of components increases.
+/// Stops early if all of the edges are removed.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::analysis::girvan_newman;
+///
+/// // two fully connected triangles joined by a single edge
+/// let graph: Graph<u32> = graph! {
+///     1 => [2, 3],
+///     2 => [1, 3],
+///     3 => [1, 2, 4],
+///     4 => [5, 6],
+///     5 => [4, 6],
+///     6 => [4, 5],
+/// };
+///
+/// let dendrogram = girvan_newman(&graph, 2);
+/// assert_eq!(dendrogram.len(), 2);
+///
+/// let split = dendrogram.last().unwrap();
+/// assert_eq!(split.removed_edges, vec![(&3, &4)]);
+///
+/// let mut components = split.components.clone();
+/// for component in components.iter_mut() {
+///     component.sort();
+/// }
+/// components.sort();
+/// assert_eq!(components, vec![vec![&1, &2, &3], vec![&4, &5, &6]]);
+/// ```
+pub fn girvan_newman<'a, G>(graph: G, target_components: usize) -> Vec<CommunitySplit<'a, G::Node>>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    girvan_newman_with_control(graph, target_components, &AlgoControl::new())
+        .expect("a fresh AlgoControl is never cancelled")
+}
+
+/// Splits the graph into communities with the Girvan-Newman algorithm,
+/// reporting progress to `control` and stopping early if it is cancelled
+///
+/// Progress is counted in the edges removed, out of every edge in the graph,
+/// though the algorithm usually reaches `target_components` long before removing them all.
+/// Cancelling takes effect part way through the betweenness calculation after each removal.
+/// See [`girvan_newman`] and [`AlgoControl`].
+pub fn girvan_newman_with_control<'a, G>(
+    graph: G,
+    target_components: usize,
+    control: &AlgoControl,
+) -> Result<Vec<CommunitySplit<'a, G::Node>>, Cancelled>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let (nodes, mut adjacency) = index_adjacency(graph);
+    let to_nodes = |components: Vec<Vec<usize>>| -> Vec<Vec<&'a G::Node>> {
+        components
+            .into_iter()
+            .map(|component| component.into_iter().map(|u| nodes[u]).collect())
+            .collect()
+    };
+
+    let mut components = weak_components(&adjacency);
+    let mut dendrogram = vec![CommunitySplit {
+        removed_edges: Vec::new(),
+        components: to_nodes(components.clone()),
+    }];
+    let mut removed_edges = Vec::new();
+    let edges: usize = adjacency.iter().map(Vec::len).sum();
+    let mut removed = 0;
+
+    while components.len() < target_components {
+        control.step(removed, edges)?;
+        let betweenness = brandes(&adjacency, control, false)?;
+        let (u, v) = match betweenness
+            .into_iter()
+            .max_by(|(a, x), (b, y)| x.partial_cmp(y).unwrap().then_with(|| b.cmp(a)))
+        {
+            Some((edge, _)) => edge,
+            None => break,
+        };
+
+        adjacency[u].retain(|&w| w != v);
+        removed_edges.push((nodes[u], nodes[v]));
+        removed += 1;
+
+        let split = weak_components(&adjacency);
+        if split.len() > components.len() {
+            components = split;
+            dendrogram.push(CommunitySplit {
+                removed_edges: std::mem::take(&mut removed_edges),
+                components: to_nodes(components.clone()),
+            });
+        }
+    }
+
+    control.step(edges, edges)?;
+
+    Ok(dendrogram)
+}
+
+/// Builds deduplicated adjacency lists indexed by the position of each node in the node list
+fn index_adjacency<'a, G>(graph: G) -> (Vec<&'a G::Node>, Vec<Vec<usize>>)
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let (nodes, mut adjacency) = index_graph(graph);
+    for succs in adjacency.iter_mut() {
+        succs.sort_unstable();
+        succs.dedup();
+    }
+
+    (nodes, adjacency)
+}
+
+/// Brandes' algorithm for edge betweenness, running a breadth first search from every node
+///
+/// Checks for cancellation before each search, and also reports the searches as progress if `report` is set.
+fn brandes(
+    adjacency: &[Vec<usize>],
+    control: &AlgoControl,
+    report: bool,
+) -> Result<HashMap<(usize, usize), f64>, Cancelled> {
+    let n = adjacency.len();
+    let mut betweenness: HashMap<(usize, usize), f64> = adjacency
+        .iter()
+        .enumerate()
+        .flat_map(|(u, succs)| succs.iter().map(move |&v| ((u, v), 0.0)))
+        .collect();
+
+    for s in 0..n {
+        if report {
+            control.step(s, n)?;
+        } else {
+            control.check()?;
+        }
+
+        let mut order = Vec::with_capacity(n);
+        let mut preds: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut paths = vec![0.0_f64; n];
+        let mut distance: Vec<Option<usize>> = vec![None; n];
+
+        paths[s] = 1.0;
+        distance[s] = Some(0);
+        let mut queue: VecDeque<usize> = vec![s].into();
+
+        while let Some(u) = queue.pop_front() {
+            order.push(u);
+            let next = distance[u].unwrap() + 1;
+
+            for &v in &adjacency[u] {
+                if distance[v].is_none() {
+                    distance[v] = Some(next);
+                    queue.push_back(v);
+                }
+
+                if distance[v] == Some(next) {
+                    paths[v] += paths[u];
+                    preds[v].push(u);
+                }
+            }
+        }
+
+        // accumulate the dependencies from the furthest nodes back towards s
+        let mut dependency = vec![0.0_f64; n];
+        for &w in order.iter().rev() {
+            for &v in &preds[w] {
+                let share = paths[v] / paths[w] * (1.0 + dependency[w]);
+                *betweenness.get_mut(&(v, w)).unwrap() += share;
+                dependency[v] += share;
+            }
+        }
+    }
+
+    if report {
+        control.step(n, n)?;
+    }
+
+    Ok(betweenness)
+}
+
+/// Finds the weakly connected components, treating every edge as undirected
+fn weak_components(adjacency: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut undirected: Vec<Vec<usize>> = adjacency.to_vec();
+    for (u, succs) in adjacency.iter().enumerate() {
+        for &v in succs {
+            undirected[v].push(u);
+        }
+    }
+
+    let mut seen = vec![false; adjacency.len()];
+    let mut components = Vec::new();
+
+    for root in 0..adjacency.len() {
+        if seen[root] {
+            continue;
+        }
+
+        seen[root] = true;
+        let mut component = vec![root];
+        let mut stack = vec![root];
+        while let Some(u) = stack.pop() {
+            for &v in &undirected[u] {
+                if !seen[v] {
+                    seen[v] = true;
+                    component.push(v);
+                    stack.push(v);
+                }
+            }
+        }
+
+        components.push(component);
+    }
+
+    components
+}
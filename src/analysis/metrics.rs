@@ -0,0 +1,328 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::visit::{IntoNeighbors, IntoNodes};
+use std::collections::{HashMap, VecDeque};
+
+/// A summary of the shape of a graph, created by [`metrics`]
+///
+/// Distances are the number of edges on a shortest path, ignoring edge weights.
+/// The eccentricity of a node is its distance to the furthest node,
+/// which is infinite if there is a node it can't reach.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphMetrics {
+    /// The number of nodes in the graph
+    pub nodes: usize,
+    /// The number of edges in the graph
+    pub edges: usize,
+    /// The fraction of the possible edges between distinct nodes which are present
+    pub density: f64,
+    /// The largest eccentricity of any node,
+    /// `None` for an empty graph or one where some node can't reach another
+    pub diameter: Option<usize>,
+    /// The smallest eccentricity of any node,
+    /// `None` for an empty graph or one where no node can reach every other
+    pub radius: Option<usize>,
+    /// The mean distance between every ordered pair of distinct nodes where a path exists,
+    /// `None` if there are no such pairs
+    pub average_path_length: Option<f64>,
+}
+
+/// Computes all of the metrics in [`GraphMetrics`] with a single breadth first search from every node
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::analysis::metrics;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [3],
+///     3 => [1],
+/// };
+///
+/// let metrics = metrics(&graph);
+/// assert_eq!(metrics.nodes, 3);
+/// assert_eq!(metrics.edges, 3);
+/// assert_eq!(metrics.density, 0.5);
+/// assert_eq!(metrics.diameter, Some(2));
+/// assert_eq!(metrics.radius, Some(2));
+/// assert_eq!(metrics.average_path_length, Some(1.5));
+/// ```
+pub fn metrics<'a, G>(graph: G) -> GraphMetrics
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let mut eccentricities = Vec::new();
+    let mut total_distance = 0;
+    let mut pairs = 0;
+
+    for u in graph.nodes() {
+        let distances = bfs_distances(graph, u);
+        eccentricities.push(furthest(graph, &distances));
+        total_distance += distances.values().sum::<usize>();
+        pairs += distances.len() - 1;
+    }
+
+    // any infinite eccentricity makes the diameter infinite
+    let diameter = eccentricities
+        .iter()
+        .copied()
+        .collect::<Option<Vec<usize>>>()
+        .and_then(|eccentricities| eccentricities.into_iter().max());
+    let radius = eccentricities.into_iter().flatten().min();
+
+    GraphMetrics {
+        nodes: graph.node_count(),
+        edges: edge_count(graph),
+        density: density(graph),
+        diameter,
+        radius,
+        average_path_length: mean(total_distance, pairs),
+    }
+}
+
+/// Returns the number of edges in the graph divided by the number of possible edges
+/// between distinct nodes, so self loops can give a density above 1
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::analysis::density;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2, 3],
+///     2 => [3],
+/// };
+///
+/// assert_eq!(density(&graph), 0.5);
+/// ```
+pub fn density<'a, G>(graph: G) -> f64
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let n = graph.node_count();
+    if n < 2 {
+        0.0
+    } else {
+        edge_count(graph) as f64 / (n * (n - 1)) as f64
+    }
+}
+
+/// Returns the eccentricity of every node, the distance from it to the furthest node in the graph
+///
+/// The eccentricity is `None`, meaning infinite, for a node which can't reach every other node,
+/// so a node with no outbound edges only has an eccentricity if it is the only node.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::analysis::eccentricities;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [3],
+/// };
+///
+/// let eccentricities = eccentricities(&graph);
+/// assert_eq!(eccentricities[&1], Some(2));
+/// assert_eq!(eccentricities[&2], None);
+/// assert_eq!(eccentricities[&3], None);
+/// ```
+pub fn eccentricities<'a, G>(graph: G) -> HashMap<&'a G::Node, Option<usize>>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    graph.nodes().map(|u| (u, eccentricity(graph, u))).collect()
+}
+
+/// Returns the largest eccentricity of any node,
+/// `None` if the graph is empty or some node can't reach another, making the diameter infinite
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::analysis::diameter;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [3],
+///     3 => [1],
+/// };
+/// assert_eq!(diameter(&graph), Some(2));
+///
+/// // 3 can't reach 1
+/// let graph: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [3],
+/// };
+/// assert_eq!(diameter(&graph), None);
+/// ```
+pub fn diameter<'a, G>(graph: G) -> Option<usize>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let mut diameter = None;
+    for u in graph.nodes() {
+        diameter = diameter.max(Some(eccentricity(graph, u)?));
+    }
+
+    diameter
+}
+
+/// Returns the smallest eccentricity of any node,
+/// `None` if the graph is empty or no node can reach every other, making the radius infinite
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::analysis::radius;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [1, 3],
+///     3 => [2],
+/// };
+///
+/// assert_eq!(radius(&graph), Some(1));
+///
+/// // only 1 reaches every node of a path
+/// let graph: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [3],
+/// };
+/// assert_eq!(radius(&graph), Some(2));
+/// ```
+pub fn radius<'a, G>(graph: G) -> Option<usize>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    graph.nodes().filter_map(|u| eccentricity(graph, u)).min()
+}
+
+/// Estimates the diameter with a double sweep,
+/// a search from `start` followed by a search from the furthest node it found
+///
+/// This only needs two breadth first searches and gives a lower bound on the diameter,
+/// which is often exact in practice.
+/// Only the nodes each search reaches are counted, so the result is finite even when the diameter isn't.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::analysis::approximate_diameter;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [1, 3],
+///     3 => [2, 4],
+///     4 => [3],
+/// };
+///
+/// assert_eq!(approximate_diameter(&graph, &2), 3);
+/// ```
+pub fn approximate_diameter<'a, G>(graph: G, start: &'a G::Node) -> usize
+where
+    G: IntoNeighbors<'a>,
+{
+    let furthest = bfs_distances(graph, start)
+        .into_iter()
+        .max_by_key(|&(_, distance)| distance)
+        .map_or(start, |(node, _)| node);
+
+    bfs_distances(graph, furthest)
+        .values()
+        .copied()
+        .max()
+        .unwrap_or(0)
+}
+
+/// Returns the mean distance between every ordered pair of distinct nodes where a path exists,
+/// `None` if there are no such pairs
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::analysis::average_path_length;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [3],
+/// };
+///
+/// // 1 -> 2, 2 -> 3 and 1 -> 3
+/// assert_eq!(average_path_length(&graph), Some(4.0 / 3.0));
+/// ```
+pub fn average_path_length<'a, G>(graph: G) -> Option<f64>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let (total, pairs) = graph
+        .nodes()
+        .map(|u| {
+            let distances = bfs_distances(graph, u);
+            (distances.values().sum::<usize>(), distances.len() - 1)
+        })
+        .fold((0, 0), |(total, pairs), (t, p)| (total + t, pairs + p));
+
+    mean(total, pairs)
+}
+
+/// counts the edges in the graph
+fn edge_count<'a, G>(graph: G) -> usize
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    graph.nodes().map(|u| graph.neighbors(u).count()).sum()
+}
+
+/// returns the distance from `u` to the furthest node in the graph, `None` if it can't reach them all
+fn eccentricity<'a, G>(graph: G, u: &'a G::Node) -> Option<usize>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    furthest(graph, &bfs_distances(graph, u))
+}
+
+/// returns the largest of the distances to the nodes of the graph, `None` if any of them is missing
+fn furthest<'a, G: IntoNodes<'a>>(
+    graph: G,
+    distances: &HashMap<&'a G::Node, usize>,
+) -> Option<usize> {
+    graph
+        .nodes()
+        .try_fold(0, |furthest, v| Some(furthest.max(*distances.get(v)?)))
+}
+
+/// returns the distance to every node reachable from `s`, including `s` itself
+fn bfs_distances<'a, G: IntoNeighbors<'a>>(
+    graph: G,
+    s: &'a G::Node,
+) -> HashMap<&'a G::Node, usize> {
+    let mut distances = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    distances.insert(s, 0);
+    queue.push_back(s);
+
+    while let Some(u) = queue.pop_front() {
+        let next = distances[u] + 1;
+        for v in graph.neighbors(u) {
+            if !distances.contains_key(v) {
+                distances.insert(v, next);
+                queue.push_back(v);
+            }
+        }
+    }
+
+    distances
+}
+
+/// divides without returning NaN for an empty set
+fn mean(total: usize, count: usize) -> Option<f64> {
+    if count == 0 {
+        None
+    } else {
+        Some(total as f64 / count as f64)
+    }
+}
@@ -0,0 +1,45 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Functions which measure the structure of a graph.
+//!
+//! Like [`crate::algos`], everything here is generic over the traits in [`crate::visit`].
+
+mod betweenness;
+pub use betweenness::{
+    edge_betweenness, edge_betweenness_with_control, girvan_newman, girvan_newman_with_control,
+    CommunitySplit,
+};
+
+mod metrics;
+pub use metrics::{
+    approximate_diameter, average_path_length, density, diameter, eccentricities, metrics, radius,
+    GraphMetrics,
+};
+
+mod ranking;
+pub use ranking::{hits, personalized_pagerank, simrank, simrank_with_control, Hits, SimRank};
+
+pub mod similarity;
+
+mod spectral;
+pub use spectral::spectral_embedding;
+
+mod triangles;
+#[cfg(feature = "parallel")]
+pub(crate) use triangles::{collect_triangles, node_triangles, undirected_adjacency};
+pub use triangles::{triangles, Triangles};
@@ -0,0 +1,366 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::{
+    error::Cancelled,
+    util::{index_graph, reverse_adjacency},
+    visit::{IntoNeighbors, IntoNodes},
+    AlgoControl, NodeBounds,
+};
+use std::{cmp::Ordering, collections::HashMap};
+
+/// Computes the personalized PageRank of every node, the chance that a random walk which
+/// restarts at one of the `sources` is at the node at any moment
+///
+/// At each step the walk follows a random outbound edge with probability `damping`,
+/// and otherwise jumps back to one of the sources picked uniformly at random,
+/// as it also does from nodes with no successors.
+/// Stops after `max_iterations`, or once the total change in score in an iteration is below `tolerance`.
+/// The scores sum to 1, and sources which aren't in the graph are ignored,
+/// so the map is empty if none of them are.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::analysis::personalized_pagerank;
+///
+/// // two triangles joined by an edge
+/// let graph: Graph<u32> = graph! {
+///     1 => [2, 3],
+///     2 => [1, 3],
+///     3 => [1, 2, 4],
+///     4 => [3, 5, 6],
+///     5 => [4, 6],
+///     6 => [4, 5],
+/// };
+///
+/// let scores = personalized_pagerank(&graph, &[&1], 0.85, 100, 1e-9);
+///
+/// // the nodes near the source are the most relevant to it
+/// assert!(scores[&2] > scores[&5]);
+/// assert!(scores[&3] > scores[&4]);
+/// assert!((scores.values().sum::<f64>() - 1.0).abs() < 1e-6);
+/// ```
+pub fn personalized_pagerank<'a, G>(
+    graph: G,
+    sources: &[&G::Node],
+    damping: f64,
+    max_iterations: usize,
+    tolerance: f64,
+) -> HashMap<&'a G::Node, f64>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let (nodes, succs) = index_graph(graph);
+    let n = nodes.len();
+
+    let indices: HashMap<&G::Node, usize> =
+        nodes.iter().enumerate().map(|(i, u)| (*u, i)).collect();
+    let mut restart = vec![0.0; n];
+    let mut sources: Vec<usize> = sources
+        .iter()
+        .filter_map(|u| indices.get(*u).copied())
+        .collect();
+    sources.sort_unstable();
+    sources.dedup();
+    if sources.is_empty() {
+        return HashMap::new();
+    }
+    for &u in &sources {
+        restart[u] = 1.0 / sources.len() as f64;
+    }
+
+    let mut scores = restart.clone();
+    for _ in 0..max_iterations {
+        let dangling: f64 = (0..n)
+            .filter(|&u| succs[u].is_empty())
+            .map(|u| scores[u])
+            .sum();
+        let jump = 1.0 - damping + damping * dangling;
+
+        let mut new_scores: Vec<f64> = restart.iter().map(|r| jump * r).collect();
+        for (u, succs) in succs.iter().enumerate() {
+            for &v in succs {
+                new_scores[v] += damping * scores[u] / succs.len() as f64;
+            }
+        }
+
+        let change: f64 = new_scores
+            .iter()
+            .zip(&scores)
+            .map(|(new, old)| (new - old).abs())
+            .sum();
+
+        scores = new_scores;
+
+        if change < tolerance {
+            break;
+        }
+    }
+
+    nodes.into_iter().zip(scores).collect()
+}
+
+/// The hub and authority scores of every node, created by [`hits`]
+///
+/// The scores of each kind sum to 1, unless the graph has no edges when they are all 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hits<'a, N: NodeBounds> {
+    /// How well each node points to good authorities
+    pub hubs: HashMap<&'a N, f64>,
+    /// How much each node is pointed to by good hubs
+    pub authorities: HashMap<&'a N, f64>,
+}
+
+/// Computes the hub and authority scores of every node with Kleinberg's HITS algorithm
+///
+/// The authority score of a node is the sum of the hub scores of its predecessors,
+/// and the hub score of a node is the sum of the authority scores of its successors,
+/// with both renormalised after each iteration.
+/// Stops after `max_iterations`, or once the total change in the hub scores in an iteration
+/// is below `tolerance`.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::analysis::hits;
+///
+/// let links: Graph<&str> = graph! {
+///     "directory" => ["docs", "blog", "shop"],
+///     "forum" => ["docs", "blog"],
+///     "blog" => ["docs"],
+/// };
+///
+/// let hits = hits(&links, 100, 1e-9);
+///
+/// assert!(hits.hubs[&"directory"] > hits.hubs[&"forum"]);
+/// assert!(hits.authorities[&"docs"] > hits.authorities[&"shop"]);
+/// assert_eq!(hits.authorities[&"directory"], 0.0);
+/// assert!((hits.hubs.values().sum::<f64>() - 1.0).abs() < 1e-9);
+/// ```
+pub fn hits<'a, G>(graph: G, max_iterations: usize, tolerance: f64) -> Hits<'a, G::Node>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let (nodes, succs) = index_graph(graph);
+    let n = nodes.len();
+
+    let mut hubs = vec![1.0; n];
+    let mut authorities = vec![0.0; n];
+    normalise(&mut hubs);
+
+    for _ in 0..max_iterations {
+        authorities.iter_mut().for_each(|a| *a = 0.0);
+        for (u, succs) in succs.iter().enumerate() {
+            for &v in succs {
+                authorities[v] += hubs[u];
+            }
+        }
+        normalise(&mut authorities);
+
+        let mut new_hubs: Vec<f64> = succs
+            .iter()
+            .map(|succs| succs.iter().map(|&v| authorities[v]).sum())
+            .collect();
+        normalise(&mut new_hubs);
+
+        let change: f64 = new_hubs
+            .iter()
+            .zip(&hubs)
+            .map(|(new, old)| (new - old).abs())
+            .sum();
+
+        hubs = new_hubs;
+
+        if change < tolerance {
+            break;
+        }
+    }
+
+    Hits {
+        hubs: nodes.iter().copied().zip(hubs).collect(),
+        authorities: nodes.into_iter().zip(authorities).collect(),
+    }
+}
+
+/// Scales the scores to sum to 1, leaving them alone if they are all 0
+fn normalise(scores: &mut [f64]) {
+    let total: f64 = scores.iter().sum();
+    if total > 0.0 {
+        scores.iter_mut().for_each(|score| *score /= total);
+    }
+}
+
+/// The SimRank similarity of every pair of nodes, created by [`simrank`]
+#[derive(Debug, Clone)]
+pub struct SimRank<'a, N: NodeBounds> {
+    nodes: Vec<&'a N>,
+    indices: HashMap<&'a N, usize>,
+    /// the row-major matrix of similarities between the nodes
+    scores: Vec<f64>,
+}
+
+impl<'a, N: NodeBounds> SimRank<'a, N> {
+    /// Returns the similarity of two nodes, between 0 and 1,
+    /// or 0 if either node isn't in the graph
+    pub fn similarity(&self, u: &N, v: &N) -> f64 {
+        match (self.indices.get(u), self.indices.get(v)) {
+            (Some(&u), Some(&v)) => self.scores[u * self.nodes.len() + v],
+            _ => 0.0,
+        }
+    }
+
+    /// Returns up to `k` other nodes most similar to `u`, most similar first,
+    /// leaving out nodes with a similarity of 0
+    pub fn most_similar(&self, u: &N, k: usize) -> Vec<(&'a N, f64)> {
+        let u = match self.indices.get(u) {
+            Some(&u) => u,
+            None => return Vec::new(),
+        };
+
+        let n = self.nodes.len();
+        let mut similar: Vec<(&N, f64)> = self.scores[u * n..(u + 1) * n]
+            .iter()
+            .enumerate()
+            .filter(|&(v, &score)| v != u && score > 0.0)
+            .map(|(v, &score)| (self.nodes[v], score))
+            .collect();
+        similar.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+        similar.truncate(k);
+
+        similar
+    }
+}
+
+/// Computes the SimRank similarity of every pair of nodes,
+/// where two nodes are similar if they are pointed to by similar nodes
+///
+/// Each node has a similarity of 1 with itself, and the similarity of two distinct nodes is
+/// `decay` times the mean similarity of their predecessors, so nodes with no predecessors
+/// are only similar to themselves.
+/// Stops after `max_iterations`, or once no similarity changes by more than `tolerance` in an iteration.
+/// Each iteration takes O(n m) time and the similarities take O(n^2) memory.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::analysis::simrank;
+///
+/// let purchases: Graph<&str> = graph! {
+///     "ann" => ["bread", "milk"],
+///     "bob" => ["bread", "milk", "eggs"],
+///     "cat" => ["tea"],
+///     "dan" => ["eggs", "tea"],
+/// };
+///
+/// let similarity = simrank(&purchases, 0.8, 100, 1e-6);
+///
+/// // bought by the same people
+/// assert!(similarity.similarity(&"bread", &"milk") > similarity.similarity(&"bread", &"eggs"));
+/// assert_eq!(similarity.similarity(&"bread", &"tea"), 0.0);
+/// assert_eq!(similarity.most_similar(&"bread", 1)[0].0, &"milk");
+/// ```
+pub fn simrank<'a, G>(
+    graph: G,
+    decay: f64,
+    max_iterations: usize,
+    tolerance: f64,
+) -> SimRank<'a, G::Node>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    simrank_with_control(graph, decay, max_iterations, tolerance, &AlgoControl::new())
+        .expect("a fresh AlgoControl is never cancelled")
+}
+
+/// Computes the SimRank similarity of every pair of nodes, reporting progress to `control`
+/// and stopping early if it is cancelled
+///
+/// Progress is counted in iterations, out of `max_iterations`,
+/// and jumps to the end if the similarities settle before then.
+/// See [`simrank`] and [`AlgoControl`].
+/// ```
+/// use graph_algos::{generators, AlgoControl};
+/// use graph_algos::analysis::simrank_with_control;
+/// use graph_algos::error::Cancelled;
+///
+/// let graph = generators::cycle_graph(100);
+///
+/// let control = AlgoControl::new();
+/// control.cancel();
+/// assert!(matches!(simrank_with_control(&graph, 0.8, 10, 1e-6, &control), Err(Cancelled)));
+/// ```
+pub fn simrank_with_control<'a, G>(
+    graph: G,
+    decay: f64,
+    max_iterations: usize,
+    tolerance: f64,
+    control: &AlgoControl,
+) -> Result<SimRank<'a, G::Node>, Cancelled>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let (nodes, succs) = index_graph(graph);
+    let n = nodes.len();
+
+    let preds = reverse_adjacency(&succs);
+
+    let mut scores = vec![0.0; n * n];
+    for u in 0..n {
+        scores[u * n + u] = 1.0;
+    }
+
+    // partial[a][j] is the sum of the similarities between j and the predecessors of a,
+    // which turns each iteration from O(n^2 d^2) into O(n m)
+    let mut partial = vec![0.0; n * n];
+    for iteration in 0..max_iterations {
+        control.step(iteration, max_iterations)?;
+
+        for a in 0..n {
+            let row = &mut partial[a * n..(a + 1) * n];
+            row.iter_mut().for_each(|x| *x = 0.0);
+            for &i in &preds[a] {
+                for (x, s) in row.iter_mut().zip(&scores[i * n..(i + 1) * n]) {
+                    *x += s;
+                }
+            }
+        }
+
+        let mut change: f64 = 0.0;
+        for a in 0..n {
+            for b in 0..n {
+                let new = if a == b {
+                    1.0
+                } else if preds[a].is_empty() || preds[b].is_empty() {
+                    0.0
+                } else {
+                    let sum: f64 = preds[b].iter().map(|&j| partial[a * n + j]).sum();
+                    decay * sum / (preds[a].len() * preds[b].len()) as f64
+                };
+
+                change = change.max((new - scores[a * n + b]).abs());
+                scores[a * n + b] = new;
+            }
+        }
+
+        if change < tolerance {
+            break;
+        }
+    }
+
+    control.step(max_iterations, max_iterations)?;
+
+    let indices = nodes.iter().enumerate().map(|(i, u)| (*u, i)).collect();
+    Ok(SimRank {
+        nodes,
+        indices,
+        scores,
+    })
+}
@@ -0,0 +1,417 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Measures of how alike two graphs, or two nodes in a graph, are.
+//!
+//! The graph level measures only look at the shape of the graphs, not at the names of the nodes
+//! or the edge weights, so they can compare graphs over entirely different node types.
+//!
+//! ```
+//! use graph_algos::{graph, Graph};
+//! use graph_algos::analysis::similarity::wl_hash;
+//!
+//! let a: Graph<u32> = graph! {
+//!     1 => [2],
+//!     2 => [3],
+//! };
+//! let b: Graph<&str> = graph! {
+//!     "x" => ["y"],
+//!     "y" => ["z"],
+//! };
+//!
+//! // the same shape hashes the same, so near-identical structures can be grouped by hash
+//! assert_eq!(wl_hash(&a, 3), wl_hash(&b, 3));
+//! ```
+
+use crate::{
+    util::{index_graph, reverse_adjacency},
+    visit::{IntoNeighbors, IntoNodes},
+};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+};
+
+/// The most passes of pairwise swaps [`approximate_edit_distance`] makes to improve its mapping
+const MAX_SWAP_PASSES: usize = 10;
+
+/// Hashes the shape of the graph with the Weisfeiler-Lehman algorithm
+///
+/// Every node starts with a label from its in and out degrees, then on each of the `iterations`
+/// rounds its label is replaced by a hash of its label and the labels of its successors and predecessors.
+/// The hash is of all of the labels seen over every round, so isomorphic graphs always hash the same,
+/// and graphs which aren't isomorphic almost always hash differently,
+/// though some regular graphs can't be told apart.
+/// Self loops and parallel edges count towards the degrees.
+/// The hash is stable between runs of the same build, but shouldn't be stored long term.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::analysis::similarity::wl_hash;
+///
+/// let path: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [3],
+///     3 => [4],
+/// };
+/// let star: Graph<u32> = graph! {
+///     1 => [2, 3, 4],
+/// };
+///
+/// assert_ne!(wl_hash(&path, 2), wl_hash(&star, 2));
+/// ```
+pub fn wl_hash<'a, G>(graph: G, iterations: usize) -> u64
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let mut labels = wl_labels(graph, iterations);
+    labels.sort_unstable();
+
+    hash_of(&labels)
+}
+
+/// Returns the cosine similarity of the Weisfeiler-Lehman label histograms of two graphs,
+/// between 0 for graphs with nothing in common and 1 for graphs [`wl_hash`] can't tell apart
+///
+/// This is the normalised Weisfeiler-Lehman subtree kernel,
+/// which counts the neighbourhoods of each depth up to `iterations` the graphs have in common.
+/// Two empty graphs have a similarity of 1.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::analysis::similarity::wl_similarity;
+///
+/// let cycle: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [3],
+///     3 => [4],
+///     4 => [1],
+/// };
+/// let broken: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [3],
+///     3 => [4],
+/// };
+/// let star: Graph<u32> = graph! {
+///     1 => [2, 3, 4],
+/// };
+///
+/// assert!((wl_similarity(&cycle, &cycle, 3) - 1.0).abs() < 1e-9);
+/// assert!(wl_similarity(&cycle, &broken, 3) > wl_similarity(&cycle, &star, 3));
+/// ```
+pub fn wl_similarity<'a, 'b, G, H>(a: G, b: H, iterations: usize) -> f64
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+    H: IntoNodes<'b> + IntoNeighbors<'b>,
+{
+    let histogram = |labels: Vec<u64>| {
+        let mut counts: HashMap<u64, f64> = HashMap::new();
+        for label in labels {
+            *counts.entry(label).or_default() += 1.0;
+        }
+        counts
+    };
+    let a = histogram(wl_labels(a, iterations));
+    let b = histogram(wl_labels(b, iterations));
+
+    let norm = |counts: &HashMap<u64, f64>| counts.values().map(|c| c * c).sum::<f64>().sqrt();
+    let (norm_a, norm_b) = (norm(&a), norm(&b));
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return if norm_a == norm_b { 1.0 } else { 0.0 };
+    }
+
+    let dot: f64 = a
+        .iter()
+        .filter_map(|(label, count)| b.get(label).map(|other| count * other))
+        .sum();
+
+    dot / (norm_a * norm_b)
+}
+
+/// Returns the labels of every node from every round of the Weisfeiler-Lehman algorithm
+fn wl_labels<'a, G>(graph: G, iterations: usize) -> Vec<u64>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let (succs, preds) = index_adjacency(graph);
+
+    let mut labels: Vec<u64> = succs
+        .iter()
+        .zip(&preds)
+        .map(|(out, inc)| hash_of(&(out.len(), inc.len())))
+        .collect();
+    let mut all = labels.clone();
+
+    for _ in 0..iterations {
+        labels = succs
+            .iter()
+            .zip(&preds)
+            .zip(&labels)
+            .map(|((out, inc), label)| {
+                let mut out: Vec<u64> = out.iter().map(|&v| labels[v]).collect();
+                let mut inc: Vec<u64> = inc.iter().map(|&v| labels[v]).collect();
+                out.sort_unstable();
+                inc.sort_unstable();
+                hash_of(&(label, out, inc))
+            })
+            .collect();
+        all.extend_from_slice(&labels);
+    }
+
+    all
+}
+
+/// Returns the Jaccard similarity of the successors of two nodes,
+/// the number of successors they share over the number of nodes which are a successor of either
+///
+/// Parallel edges are only counted once, and two nodes with no successors have a similarity of 0.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::analysis::similarity::jaccard;
+///
+/// let follows: Graph<&str> = graph! {
+///     "ann" => ["cat", "dan", "eve"],
+///     "bob" => ["dan", "eve", "fay"],
+/// };
+///
+/// assert_eq!(jaccard(&follows, &"ann", &"bob"), 0.5);
+/// assert_eq!(jaccard(&follows, &"ann", &"cat"), 0.0);
+/// ```
+pub fn jaccard<'a, G: IntoNeighbors<'a>>(graph: G, u: &G::Node, v: &G::Node) -> f64 {
+    let u: HashSet<&G::Node> = graph.neighbors(u).collect();
+    let v: HashSet<&G::Node> = graph.neighbors(v).collect();
+
+    let union = u.union(&v).count();
+    if union == 0 {
+        0.0
+    } else {
+        u.intersection(&v).count() as f64 / union as f64
+    }
+}
+
+/// Approximates the edit distance between the shapes of two graphs,
+/// the fewest node and edge insertions and deletions which turn one into the other
+///
+/// Nodes are matched up by solving an assignment problem on the differences in their degrees,
+/// which is then improved by swapping pairs of matches while that lowers the cost,
+/// and the distance is the exact number of edits needed under the final matching.
+/// The result is an upper bound on the true edit distance, which is NP-hard to find,
+/// and is often exact for small or similar graphs.
+/// The assignment takes cubic time in the number of nodes, so this is meant for graphs of up to
+/// a few hundred nodes.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::analysis::similarity::approximate_edit_distance;
+///
+/// let path: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [3],
+/// };
+/// let cycle: Graph<char> = graph! {
+///     'a' => ['b'],
+///     'b' => ['c'],
+///     'c' => ['a'],
+/// };
+/// let bigger: Graph<char> = graph! {
+///     'a' => ['b'],
+///     'b' => ['c'],
+///     'c' => ['d'],
+/// };
+///
+/// assert_eq!(approximate_edit_distance(&path, &path), 0);
+/// // add the edge closing the cycle
+/// assert_eq!(approximate_edit_distance(&path, &cycle), 1);
+/// // add a node and an edge to it
+/// assert_eq!(approximate_edit_distance(&path, &bigger), 2);
+/// ```
+pub fn approximate_edit_distance<'a, 'b, G, H>(a: G, b: H) -> usize
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+    H: IntoNodes<'b> + IntoNeighbors<'b>,
+{
+    let (a_succs, a_preds) = index_adjacency(a);
+    let (b_succs, b_preds) = index_adjacency(b);
+    let (n, m) = (a_succs.len(), b_succs.len());
+
+    // the smaller graph is padded with placeholder nodes, matching a placeholder is an insertion or deletion
+    let size = n.max(m);
+    let degrees = |succs: &[Vec<usize>], preds: &[Vec<usize>], u: usize| match u < succs.len() {
+        true => (succs[u].len() as i64, preds[u].len() as i64),
+        false => (0, 0),
+    };
+    let cost: Vec<Vec<i64>> = (0..size)
+        .map(|u| {
+            let (a_out, a_in) = degrees(&a_succs, &a_preds, u);
+            (0..size)
+                .map(|v| {
+                    let (b_out, b_in) = degrees(&b_succs, &b_preds, v);
+                    let placeholder = i64::from((u < n) != (v < m));
+                    // each edge is seen from both of its ends, so node costs are doubled to match
+                    2 * placeholder + (a_out - b_out).abs() + (a_in - b_in).abs()
+                })
+                .collect()
+        })
+        .collect();
+    let mut mapping = min_cost_assignment(&cost);
+
+    let count_edges = |succs: &[Vec<usize>]| {
+        let mut counts: HashMap<(usize, usize), usize> = HashMap::new();
+        for (u, succs) in succs.iter().enumerate() {
+            for &v in succs {
+                *counts.entry((u, v)).or_default() += 1;
+            }
+        }
+        counts
+    };
+    let a_edges = count_edges(&a_succs);
+    let b_edges = count_edges(&b_succs);
+
+    // the number of edges of `a` touching `u` or `v` which are kept under the mapping
+    let matched_around = |mapping: &[usize], u: usize, v: usize| -> usize {
+        let mut pairs: Vec<(usize, usize)> = [u, v]
+            .iter()
+            .filter(|&&x| x < n)
+            .flat_map(|&x| {
+                let out = a_succs[x].iter().map(move |&y| (x, y));
+                let inc = a_preds[x].iter().map(move |&y| (y, x));
+                out.chain(inc)
+            })
+            .collect();
+        pairs.sort_unstable();
+        pairs.dedup();
+
+        pairs
+            .into_iter()
+            .map(|(x, y)| {
+                let b_count = b_edges.get(&(mapping[x], mapping[y])).copied();
+                a_edges[&(x, y)].min(b_count.unwrap_or(0))
+            })
+            .sum()
+    };
+
+    for _ in 0..MAX_SWAP_PASSES {
+        let mut improved = false;
+        for u in 0..size {
+            for v in u + 1..size {
+                let before = matched_around(&mapping, u, v);
+                mapping.swap(u, v);
+                if matched_around(&mapping, u, v) > before {
+                    improved = true;
+                } else {
+                    mapping.swap(u, v);
+                }
+            }
+        }
+
+        if !improved {
+            break;
+        }
+    }
+
+    let matched: usize = a_edges
+        .iter()
+        .map(|(&(x, y), &count)| {
+            let b_count = b_edges.get(&(mapping[x], mapping[y])).copied();
+            count.min(b_count.unwrap_or(0))
+        })
+        .sum();
+    let (a_total, b_total): (usize, usize) = (a_edges.values().sum(), b_edges.values().sum());
+
+    n.max(m) - n.min(m) + a_total + b_total - 2 * matched
+}
+
+/// Returns the successors and predecessors of every node by index, in the order of `graph.nodes()`
+fn index_adjacency<'a, G>(graph: G) -> (Vec<Vec<usize>>, Vec<Vec<usize>>)
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let (_, succs) = index_graph(graph);
+    let preds = reverse_adjacency(&succs);
+
+    (succs, preds)
+}
+
+/// Solves the square assignment problem with the Hungarian algorithm,
+/// returning the column matched to each row so the total cost is as small as possible
+fn min_cost_assignment(cost: &[Vec<i64>]) -> Vec<usize> {
+    let n = cost.len();
+
+    // rows and columns are numbered from 1, column 0 is a sentinel for the row being added
+    let mut row_potential = vec![0; n + 1];
+    let mut col_potential = vec![0; n + 1];
+    let mut row_of = vec![0; n + 1];
+    let mut way = vec![0; n + 1];
+
+    for row in 1..=n {
+        row_of[0] = row;
+        let mut col = 0;
+        let mut min_slack = vec![i64::MAX; n + 1];
+        let mut used = vec![false; n + 1];
+
+        while row_of[col] != 0 {
+            used[col] = true;
+            let current = row_of[col];
+            let mut delta = i64::MAX;
+            let mut next = 0;
+
+            for j in 1..=n {
+                if used[j] {
+                    continue;
+                }
+
+                let slack = cost[current - 1][j - 1] - row_potential[current] - col_potential[j];
+                if slack < min_slack[j] {
+                    min_slack[j] = slack;
+                    way[j] = col;
+                }
+                if min_slack[j] < delta {
+                    delta = min_slack[j];
+                    next = j;
+                }
+            }
+
+            for j in 0..=n {
+                if used[j] {
+                    row_potential[row_of[j]] += delta;
+                    col_potential[j] -= delta;
+                } else {
+                    min_slack[j] -= delta;
+                }
+            }
+
+            col = next;
+        }
+
+        // flip the augmenting path back to the sentinel
+        while col != 0 {
+            let prev = way[col];
+            row_of[col] = row_of[prev];
+            col = prev;
+        }
+    }
+
+    let mut assignment = vec![0; n];
+    for col in 1..=n {
+        assignment[row_of[col] - 1] = col - 1;
+    }
+
+    assignment
+}
+
+/// Hashes a value with a hasher which gives the same result on every run
+fn hash_of<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,204 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::{
+    util::index_nodes,
+    visit::{EdgeWeighted, IntoNodes},
+    EdgeWeight,
+};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
+
+/// The most rounds of power iteration to run before giving up on converging
+const MAX_ITERATIONS: usize = 2000;
+
+/// How little the vectors have to move in a round to count as converged
+const TOLERANCE: f64 = 1e-9;
+
+/// Embeds the nodes of the graph in `dims` dimensions using the eigenvectors of its Laplacian
+/// with the smallest non-zero eigenvalues, so that nodes which are closely connected end up close together
+///
+/// The graph is treated as undirected, with the weights as conductances:
+/// an edge in either or both directions between two nodes is a single undirected edge,
+/// whose weight is the largest of the weights in each direction.
+/// Unweighted edges count as having a weight of 1, and self loops are ignored.
+/// Panics if any edge has a negative or infinite weight.
+///
+/// The eigenvectors are approximated by power iteration on a shifted Laplacian,
+/// keeping the vectors orthogonal to the constant vector and to each other,
+/// so the time taken grows with the number of edges rather than the square of the number of nodes.
+/// Each coordinate is an entry of a unit length eigenvector, with its sign chosen
+/// so that its largest entry is positive, and the starting vectors are derived from the nodes themselves,
+/// so the same graph always gets the same embedding.
+/// A graph with `n` nodes has at most `n - 1` useful dimensions, and any further coordinates are 0.
+///
+/// The first two coordinates make a good starting point for
+/// [`ForceDirected`](crate::render::ForceDirected), see
+/// [`Layout::from_embedding`](crate::render::Layout::from_embedding),
+/// and the coordinates can also be used as node features.
+/// ```
+/// use graph_algos::{ungraph, Graph};
+/// use graph_algos::analysis::spectral_embedding;
+///
+/// // two triangles joined by a single edge
+/// let graph: Graph<u32> = ungraph! {
+///     1 => [2, 3],
+///     2 => [3],
+///     3 => [4],
+///     4 => [5, 6],
+///     5 => [6],
+/// };
+///
+/// let embedding = spectral_embedding(&graph, 2);
+/// assert_eq!(embedding.len(), 6);
+/// assert!(embedding.values().all(|coords| coords.len() == 2));
+///
+/// // the first coordinate separates the two triangles
+/// let side = |u: u32| embedding[&u][0] > 0.0;
+/// assert!(side(1) == side(2) && side(2) == side(3));
+/// assert!(side(4) == side(5) && side(5) == side(6));
+/// assert_ne!(side(1), side(4));
+/// ```
+pub fn spectral_embedding<'a, G>(graph: G, dims: usize) -> HashMap<&'a G::Node, Vec<f64>>
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a>,
+{
+    let (nodes, indices) = index_nodes(graph);
+    let n = nodes.len();
+
+    let mut conductances: HashMap<(usize, usize), f64> = HashMap::new();
+    for (u, &node) in nodes.iter().enumerate() {
+        for (v, weight) in graph.neighbors_weighted(node) {
+            let w = match weight {
+                None => 1.0,
+                Some(EdgeWeight::Weight(w)) if w >= 0 => w as f64,
+                Some(w) => panic!(
+                    "spectral_embedding needs non-negative finite weights, found {}",
+                    w
+                ),
+            };
+            let v = match indices.get(v) {
+                Some(&v) if v != u && w > 0.0 => v,
+                _ => continue,
+            };
+
+            let conductance = conductances.entry((u.min(v), u.max(v))).or_default();
+            *conductance = conductance.max(w);
+        }
+    }
+
+    let mut adjacency: Vec<Vec<(usize, f64)>> = vec![Vec::new(); n];
+    for (&(u, v), &w) in &conductances {
+        adjacency[u].push((v, w));
+        adjacency[v].push((u, w));
+    }
+    let degrees: Vec<f64> = adjacency
+        .iter()
+        .map(|edges| edges.iter().map(|(_, w)| w).sum())
+        .collect();
+
+    // every eigenvalue of the Laplacian is at most twice the largest degree,
+    // so shifting by that turns the smallest eigenvalues into the largest
+    let shift = 2.0 * degrees.iter().cloned().fold(0.0, f64::max) + 1.0;
+    let useful = dims.min(n.saturating_sub(1));
+
+    let mut vectors: Vec<Vec<f64>> = (0..useful)
+        .map(|k| nodes.iter().map(|u| start_entry(u, k)).collect())
+        .collect();
+    orthonormalise(&mut vectors);
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut next: Vec<Vec<f64>> = vectors
+            .iter()
+            .map(|x| {
+                (0..n)
+                    .map(|u| {
+                        let lx: f64 = degrees[u] * x[u]
+                            - adjacency[u].iter().map(|&(v, w)| w * x[v]).sum::<f64>();
+                        shift * x[u] - lx
+                    })
+                    .collect()
+            })
+            .collect();
+        orthonormalise(&mut next);
+
+        let movement = vectors
+            .iter()
+            .zip(&next)
+            .flat_map(|(x, y)| x.iter().zip(y).map(|(a, b)| (a - b).abs()))
+            .fold(0.0, f64::max);
+        vectors = next;
+
+        if movement < TOLERANCE {
+            break;
+        }
+    }
+
+    for x in &mut vectors {
+        let largest = x
+            .iter()
+            .cloned()
+            .fold(0.0, |m: f64, a| if a.abs() > m.abs() { a } else { m });
+        if largest < 0.0 {
+            x.iter_mut().for_each(|a| *a = -*a);
+        }
+    }
+
+    nodes
+        .into_iter()
+        .enumerate()
+        .map(|(i, u)| {
+            let mut coords: Vec<f64> = vectors.iter().map(|x| x[i]).collect();
+            coords.resize(dims, 0.0);
+            (u, coords)
+        })
+        .collect()
+}
+
+/// Returns the entry for `u` in the `k`th starting vector,
+/// a value in `-1.0..1.0` which depends only on the node and `k`
+fn start_entry<N: Hash>(u: &N, k: usize) -> f64 {
+    let mut hasher = DefaultHasher::new();
+    u.hash(&mut hasher);
+    k.hash(&mut hasher);
+    (hasher.finish() >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+}
+
+/// Makes the vectors orthogonal to the constant vector and each other, and of unit length,
+/// with the Gram–Schmidt process, zeroing any which are left with nothing
+fn orthonormalise(vectors: &mut [Vec<f64>]) {
+    for k in 0..vectors.len() {
+        let (done, rest) = vectors.split_at_mut(k);
+        let x = &mut rest[0];
+
+        let mean = x.iter().sum::<f64>() / x.len() as f64;
+        x.iter_mut().for_each(|a| *a -= mean);
+
+        for y in done.iter() {
+            let dot: f64 = x.iter().zip(y).map(|(a, b)| a * b).sum();
+            x.iter_mut().zip(y).for_each(|(a, b)| *a -= dot * b);
+        }
+
+        let norm = x.iter().map(|a| a * a).sum::<f64>().sqrt();
+        if norm > 1e-12 {
+            x.iter_mut().for_each(|a| *a /= norm);
+        } else {
+            x.iter_mut().for_each(|a| *a = 0.0);
+        }
+    }
+}
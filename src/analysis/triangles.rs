@@ -0,0 +1,171 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{
+    util::index_nodes,
+    visit::{IntoNeighbors, IntoNodes},
+    NodeBounds,
+};
+use std::{cmp::Ordering, collections::HashMap};
+
+/// The triangles in a graph, created by [`triangles`]
+///
+/// Edges are treated as undirected, and self loops and parallel edges are ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Triangles<'a, N: NodeBounds> {
+    /// The number of triangles in the graph
+    pub count: usize,
+    /// The number of triangles each node is part of
+    pub node_triangles: HashMap<&'a N, usize>,
+    /// The local clustering coefficient of each node,
+    /// the fraction of the pairs of its neighbours which are connected
+    pub clustering: HashMap<&'a N, f64>,
+}
+
+impl<'a, N: NodeBounds> Triangles<'a, N> {
+    /// Returns the mean of the local clustering coefficients, 0 for an empty graph
+    pub fn average_clustering(&self) -> f64 {
+        if self.clustering.is_empty() {
+            0.0
+        } else {
+            self.clustering.values().sum::<f64>() / self.clustering.len() as f64
+        }
+    }
+}
+
+/// Counts the triangles in the graph and the local clustering coefficient of each node
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::analysis::triangles;
+///
+/// // a triangle with a tail
+/// let graph: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [3],
+///     3 => [1, 4],
+/// };
+///
+/// let triangles = triangles(&graph);
+/// assert_eq!(triangles.count, 1);
+/// assert_eq!(triangles.node_triangles[&1], 1);
+/// assert_eq!(triangles.node_triangles[&4], 0);
+/// assert_eq!(triangles.clustering[&1], 1.0);
+/// assert_eq!(triangles.clustering[&3], 1.0 / 3.0);
+/// assert_eq!(triangles.clustering[&4], 0.0);
+/// ```
+pub fn triangles<'a, G>(graph: G) -> Triangles<'a, G::Node>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let (nodes, adjacency) = undirected_adjacency(graph);
+    let counts = (0..nodes.len())
+        .map(|u| node_triangles(&adjacency, u))
+        .collect();
+
+    collect_triangles(nodes, &adjacency, counts)
+}
+
+/// Builds sorted, deduplicated, undirected adjacency lists without self loops,
+/// indexed by the position of each node in the returned node list
+pub(crate) fn undirected_adjacency<'a, G>(graph: G) -> (Vec<&'a G::Node>, Vec<Vec<usize>>)
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let (nodes, indices) = index_nodes(graph);
+
+    let mut adjacency = vec![Vec::new(); nodes.len()];
+    for (u, node) in nodes.iter().enumerate() {
+        for v in graph
+            .neighbors(node)
+            .filter_map(|v| indices.get(v).copied())
+        {
+            if u != v {
+                adjacency[u].push(v);
+                adjacency[v].push(u);
+            }
+        }
+    }
+
+    for succs in adjacency.iter_mut() {
+        succs.sort_unstable();
+        succs.dedup();
+    }
+
+    (nodes, adjacency)
+}
+
+/// Counts the triangles through `u` by merge joining its adjacency list with each neighbour's
+pub(crate) fn node_triangles(adjacency: &[Vec<usize>], u: usize) -> usize {
+    let succs = &adjacency[u];
+
+    let connected_pairs: usize = succs
+        .iter()
+        .map(|&v| {
+            let (mut i, mut j) = (0, 0);
+            let other = &adjacency[v];
+            let mut common = 0;
+
+            while i < succs.len() && j < other.len() {
+                match succs[i].cmp(&other[j]) {
+                    Ordering::Less => i += 1,
+                    Ordering::Greater => j += 1,
+                    Ordering::Equal => {
+                        common += 1;
+                        i += 1;
+                        j += 1;
+                    }
+                }
+            }
+
+            common
+        })
+        .sum();
+
+    // each connected pair of neighbours is seen from both ends
+    connected_pairs / 2
+}
+
+/// Builds the result from the number of triangles through each node
+pub(crate) fn collect_triangles<'a, N: NodeBounds>(
+    nodes: Vec<&'a N>,
+    adjacency: &[Vec<usize>],
+    counts: Vec<usize>,
+) -> Triangles<'a, N> {
+    // every triangle is counted once at each of its corners
+    let count = counts.iter().sum::<usize>() / 3;
+
+    let clustering = nodes
+        .iter()
+        .zip(adjacency.iter().zip(counts.iter()))
+        .map(|(node, (succs, &triangles))| {
+            let degree = succs.len();
+            let coefficient = if degree < 2 {
+                0.0
+            } else {
+                (2 * triangles) as f64 / (degree * (degree - 1)) as f64
+            };
+
+            (*node, coefficient)
+        })
+        .collect();
+
+    Triangles {
+        count,
+        node_triangles: nodes.into_iter().zip(counts).collect(),
+        clustering,
+    }
+}
@@ -0,0 +1,197 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Helpers for using the crate from within a tokio runtime.
+//!
+//! Parsing large graphs and running the algorithms are CPU bound,
+//! so the runners here move that work onto tokio's blocking thread pool
+//! and return a future which resolves to an owned result.
+//!
+//! ```
+//! use std::sync::Arc;
+//! use graph_algos::{Graph, EdgeWeight};
+//! use graph_algos::async_helpers::spawn_dijkstra;
+//!
+//! let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+//!
+//! runtime.block_on(async {
+//!     let input: &[u8] = b"a:b,2 c,5\nb:c,1";
+//!     let graph: Graph<String> = Graph::read_from_async(input).await.unwrap();
+//!
+//!     let (preds, dists) = spawn_dijkstra(Arc::new(graph), "a".into()).await.unwrap();
+//!
+//!     assert_eq!(dists["c"], EdgeWeight::new(3));
+//!     assert_eq!(preds["c"], "b");
+//! });
+//! ```
+
+use crate::{algos, error::ReadGraphError, Graph, NodeBounds, PredMap};
+use std::{collections::HashMap, fmt::Debug, str::FromStr, sync::Arc};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt},
+    task::{self, JoinHandle},
+};
+
+impl<N: NodeBounds> Graph<N>
+where
+    N: FromStr + Send + Sync + 'static,
+    <N as FromStr>::Err: Debug,
+{
+    /// Reads the whole of `reader` and parses it as a graph
+    ///
+    /// The format is the same as the one accepted by `str::parse`,
+    /// and the parsing happens on the blocking thread pool.
+    pub async fn read_from_async<R: AsyncRead + Unpin>(
+        mut reader: R,
+    ) -> Result<Self, ReadGraphError> {
+        let mut buf = String::new();
+        reader
+            .read_to_string(&mut buf)
+            .await
+            .map_err(ReadGraphError::Io)?;
+
+        task::spawn_blocking(move || buf.parse())
+            .await
+            .map_err(|err| ReadGraphError::Io(err.into()))?
+            .map_err(ReadGraphError::Parse)
+    }
+}
+
+/// Runs `f` on a shared graph on the blocking thread pool
+///
+/// Like all of the runners this must be called from within a tokio runtime.
+/// This is the building block for the other runners,
+/// and can be used to run any algorithm which produces an owned result.
+/// ```
+/// use std::sync::Arc;
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::async_helpers::run_blocking;
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+///
+/// let graph: Arc<Graph<u32>> = Arc::new(graph! {
+///     1 => [2, 3],
+/// });
+///
+/// let len = runtime
+///     .block_on(async { run_blocking(graph, |graph| graph.len()).await })
+///     .unwrap();
+/// assert_eq!(len, 3);
+/// ```
+pub fn run_blocking<N, T, F>(graph: Arc<Graph<N>>, f: F) -> JoinHandle<T>
+where
+    N: NodeBounds + Send + Sync + 'static,
+    T: Send + 'static,
+    F: FnOnce(&Graph<N>) -> T + Send + 'static,
+{
+    task::spawn_blocking(move || f(&graph))
+}
+
+/// Converts a predecessor map into one which owns its nodes,
+/// mapping each node to its predecessor
+fn owned_preds<N: NodeBounds>(pred_map: &PredMap<'_, N>) -> HashMap<N, N> {
+    pred_map
+        .iter()
+        .map(|(node, edge)| ((*node).clone(), (*edge.destination()).clone()))
+        .collect()
+}
+
+/// Runs [`algos::dijkstra`] on the blocking thread pool
+///
+/// Resolves to maps from each reachable node to its predecessor and to its distance from `start`.
+pub fn spawn_dijkstra<N>(
+    graph: Arc<Graph<N>>,
+    start: N,
+) -> JoinHandle<(HashMap<N, N>, HashMap<N, crate::EdgeWeight>)>
+where
+    N: NodeBounds + Send + Sync + 'static,
+{
+    run_blocking(graph, move |graph| {
+        let (pred_map, dist_map) = algos::dijkstra(graph, &start);
+        let dists = dist_map
+            .into_iter()
+            .map(|(node, dist)| (node.clone(), dist))
+            .collect();
+
+        (owned_preds(&pred_map), dists)
+    })
+}
+
+/// The predecessor and distance maps resolved to by [`spawn_bellman_ford`]
+type OwnedPaths<N> = (HashMap<N, N>, HashMap<N, crate::EdgeWeight>);
+
+/// Runs [`algos::bellman_ford`] on the blocking thread pool
+///
+/// Resolves to maps from each node to its predecessor and to its distance from `start`,
+/// or `None` if there is a negative cycle reachable from `start`.
+/// ```
+/// use std::sync::Arc;
+/// use graph_algos::{graph, Graph, EdgeWeight};
+/// use graph_algos::async_helpers::spawn_bellman_ford;
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+///
+/// let graph: Arc<Graph<u32>> = Arc::new(graph! {
+///     1 => [2 => 4, 3 => 1],
+///     3 => [2 => -2],
+/// });
+///
+/// let (preds, dists) = runtime
+///     .block_on(async { spawn_bellman_ford(graph, 1).await })
+///     .unwrap()
+///     .unwrap();
+/// assert_eq!(dists[&2], EdgeWeight::new(-1));
+/// assert_eq!(preds[&2], 3);
+/// ```
+pub fn spawn_bellman_ford<N>(graph: Arc<Graph<N>>, start: N) -> JoinHandle<Option<OwnedPaths<N>>>
+where
+    N: NodeBounds + Send + Sync + 'static,
+{
+    run_blocking(graph, move |graph| {
+        let (pred_map, dist_map) = algos::bellman_ford(graph, &start)?;
+        let dists = dist_map
+            .into_iter()
+            .map(|(node, dist)| (node.clone(), dist))
+            .collect();
+
+        Some((owned_preds(&pred_map), dists))
+    })
+}
+
+/// Runs [`algos::bfs_shortest_paths`] on the blocking thread pool
+///
+/// Resolves to a map from each reachable node to its predecessor.
+pub fn spawn_bfs_shortest_paths<N>(graph: Arc<Graph<N>>, start: N) -> JoinHandle<HashMap<N, N>>
+where
+    N: NodeBounds + Send + Sync + 'static,
+{
+    run_blocking(graph, move |graph| {
+        owned_preds(&algos::bfs_shortest_paths(graph, &start))
+    })
+}
+
+/// Runs [`algos::topological_sort`] on the blocking thread pool
+///
+/// Resolves to `None` if a cycle is reachable from `start`.
+pub fn spawn_topological_sort<N>(graph: Arc<Graph<N>>, start: N) -> JoinHandle<Option<Vec<N>>>
+where
+    N: NodeBounds + Send + Sync + 'static,
+{
+    run_blocking(graph, move |graph| {
+        algos::topological_sort(graph, &start).map(|order| order.into_iter().cloned().collect())
+    })
+}
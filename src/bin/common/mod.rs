@@ -0,0 +1,236 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Helpers shared by the command line tools.
+
+// each tool only uses some of the helpers
+#![allow(dead_code)]
+
+use graph_algos::{
+    formats::{dimacs, dot},
+    Edge, EdgeWeight, Graph, GraphTextFormat,
+};
+use std::{
+    error::Error,
+    fs,
+    io::{self, Read, Write},
+    path::Path,
+    process,
+};
+
+/// The graph file formats the tools can read and write
+pub const FORMATS: &str = "text, edges, dimacs, dot, graphml, json";
+
+/// Reads a graph from `path`, or from stdin if there is no path or it is `-`
+///
+/// The format is guessed from the file extension when it isn't given,
+/// falling back to the crate's own `node:dest,weight` text format.
+pub fn read_graph(
+    path: Option<&str>,
+    format: Option<&str>,
+) -> Result<Graph<String>, Box<dyn Error>> {
+    let path = path.filter(|&path| path != "-");
+
+    let mut input = String::new();
+    match path {
+        Some(path) => input = fs::read_to_string(path)?,
+        None => {
+            io::stdin().read_to_string(&mut input)?;
+        }
+    }
+
+    let format = format.or_else(|| guess_format(path?)).unwrap_or("text");
+    parse_graph(&input, format)
+}
+
+/// Prints a message to stderr and exits with a failure status
+pub fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
+
+/// Guesses the format of a file from its extension
+pub fn guess_format(path: &str) -> Option<&'static str> {
+    match Path::new(path).extension()?.to_str()? {
+        "gr" => Some("dimacs"),
+        "dot" | "gv" => Some("dot"),
+        "graphml" => Some("graphml"),
+        "json" => Some("json"),
+        "edges" | "el" => Some("edges"),
+        _ => None,
+    }
+}
+
+/// Parses a graph written in the named format
+fn parse_graph(input: &str, format: &str) -> Result<Graph<String>, Box<dyn Error>> {
+    match format {
+        "text" => Ok(Graph::parse_with(
+            input,
+            &GraphTextFormat::adjacency_list(),
+        )?),
+        "edges" => {
+            let format = GraphTextFormat::edge_list().comment_prefix("#");
+            Ok(Graph::parse_with(input, &format)?)
+        }
+        "dimacs" => Ok(stringify_nodes(&dimacs::read_gr(input.as_bytes())?)),
+        "dot" => Ok(dot::read(input.as_bytes())?),
+        #[cfg(feature = "graphml")]
+        "graphml" => Ok(graph_algos::formats::graphml::read(input.as_bytes())?),
+        #[cfg(not(feature = "graphml"))]
+        "graphml" => Err("reading GraphML requires the graphml feature".into()),
+        #[cfg(feature = "json")]
+        "json" => Ok(graph_algos::formats::json::from_str(input)?),
+        #[cfg(not(feature = "json"))]
+        "json" => Err("reading JSON requires the json feature".into()),
+        _ => Err(format!("unknown format {:?}, expected one of {}", format, FORMATS).into()),
+    }
+}
+
+/// Converts a graph with numbered nodes into one with string nodes
+fn stringify_nodes(graph: &Graph<usize>) -> Graph<String> {
+    let mut strings = Graph::with_capacity(graph.len());
+
+    for u in graph.nodes() {
+        strings.add_node(u.to_string());
+    }
+
+    for (u, edge) in graph.edges() {
+        let v = edge.destination().to_string();
+        let edge = match edge.weight() {
+            Some(w) => Edge::new_with_weight(v, w),
+            None => Edge::new(v),
+        };
+        strings.add_edge(u.to_string(), edge);
+    }
+
+    strings
+}
+
+/// Writes a graph in the named format
+pub fn write_graph<W: Write>(
+    graph: &Graph<String>,
+    format: &str,
+    mut writer: W,
+) -> Result<(), Box<dyn Error>> {
+    match format {
+        "text" => write_text(graph, &mut writer, false)?,
+        "edges" => write_text(graph, &mut writer, true)?,
+        "dimacs" => dimacs::write_gr(&number_nodes(graph)?, &mut writer)?,
+        "dot" => dot::write(graph, &mut writer)?,
+        #[cfg(feature = "graphml")]
+        "graphml" => graph_algos::formats::graphml::write(graph, &mut writer)?,
+        #[cfg(not(feature = "graphml"))]
+        "graphml" => return Err("writing GraphML requires the graphml feature".into()),
+        #[cfg(feature = "json")]
+        "json" => {
+            graph_algos::formats::json::to_writer(graph, &mut writer)?;
+            writeln!(writer)?;
+        }
+        #[cfg(not(feature = "json"))]
+        "json" => return Err("writing JSON requires the json feature".into()),
+        _ => {
+            return Err(format!("unknown format {:?}, expected one of {}", format, FORMATS).into())
+        }
+    }
+
+    Ok(writer.flush()?)
+}
+
+/// Writes a graph as lines of text, sorted by their source node
+///
+/// The adjacency list format puts all of a node's edges on one line as `node:dest,weight dest`,
+/// and the edge list format puts each edge on a line of its own as `node dest weight`.
+/// Nodes without edges can only be written in the adjacency list format.
+fn write_text<W: Write>(
+    graph: &Graph<String>,
+    writer: &mut W,
+    edge_list: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut nodes: Vec<&String> = graph.nodes().collect();
+    nodes.sort();
+
+    for u in nodes {
+        if edge_list {
+            for (_, edge) in graph.edges_from(u) {
+                write!(writer, "{} {}", u, edge.destination())?;
+                write_weight(writer, edge, ' ')?;
+                writeln!(writer)?;
+            }
+        } else {
+            write!(writer, "{}:", u)?;
+            for (i, (_, edge)) in graph.edges_from(u).enumerate() {
+                if i > 0 {
+                    write!(writer, " ")?;
+                }
+                write!(writer, "{}", edge.destination())?;
+                write_weight(writer, edge, ',')?;
+            }
+            writeln!(writer)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the weight of an edge after a separator, if it has one
+fn write_weight<W: Write>(
+    writer: &mut W,
+    edge: &Edge<String>,
+    separator: char,
+) -> Result<(), Box<dyn Error>> {
+    match edge.weight() {
+        Some(EdgeWeight::Weight(w)) => write!(writer, "{}{}", separator, w)?,
+        Some(_) => return Err("the text formats can't hold infinite weights".into()),
+        None => {}
+    }
+
+    Ok(())
+}
+
+/// Converts a graph with numeric node names into one with numbered nodes
+fn number_nodes(graph: &Graph<String>) -> Result<Graph<usize>, Box<dyn Error>> {
+    let number = |u: &String| {
+        u.parse::<usize>()
+            .map_err(|_| format!("DIMACS nodes must be numbers, found {:?}", u))
+    };
+
+    let mut numbered = Graph::with_capacity(graph.len());
+    for u in graph.nodes() {
+        numbered.add_node(number(u)?);
+    }
+
+    for (u, edge) in graph.edges() {
+        let v = number(edge.destination())?;
+        let edge = match edge.weight() {
+            Some(w) => Edge::new_with_weight(v, w),
+            None => Edge::new(v),
+        };
+        numbered.add_edge(number(u)?, edge);
+    }
+
+    Ok(numbered)
+}
+
+/// Returns the total weight of a path, or its number of edges if it is unweighted
+pub fn path_length(path: &graph_algos::Path<String>) -> i64 {
+    path.edges()
+        .iter()
+        .map(|edge| match edge.weight() {
+            Some(EdgeWeight::Weight(w)) => w,
+            _ => 1,
+        })
+        .sum()
+}
@@ -0,0 +1,123 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Converts a graph file from one format to another.
+
+mod common;
+
+use common::fail;
+use graph_algos::{Edge, EdgeWeight, Graph};
+use std::{
+    env,
+    fs::File,
+    io::{self, BufWriter},
+};
+
+const USAGE: &str = "\
+usage: graph-convert [--from FORMAT] [--to FORMAT] [--undirected] [--default-weight W]
+                     [INPUT [OUTPUT]]
+
+Reads a graph from INPUT, or stdin if INPUT is missing or -, and writes it to OUTPUT,
+or stdout if OUTPUT is missing or -, in another format.
+Formats not given are guessed from the file extensions, falling back to text.
+
+  --undirected        treat every edge of the input as undirected, adding its reverse
+  --default-weight W  give edges without a weight the weight W";
+
+fn main() {
+    let mut from = None;
+    let mut to = None;
+    let mut undirected = false;
+    let mut default_weight = None;
+    let mut paths = Vec::new();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}\n\nFORMAT is one of {}", USAGE, common::FORMATS);
+                return;
+            }
+            "--from" => from = Some(args.next().unwrap_or_else(|| fail(USAGE))),
+            "--to" => to = Some(args.next().unwrap_or_else(|| fail(USAGE))),
+            "--undirected" => undirected = true,
+            "--default-weight" => {
+                let weight = args.next().unwrap_or_else(|| fail(USAGE));
+                match weight.parse::<i64>() {
+                    Ok(weight) => default_weight = Some(weight),
+                    Err(err) => fail(&format!("invalid weight {:?}: {}", weight, err)),
+                }
+            }
+            _ if paths.len() < 2 => paths.push(arg),
+            _ => fail(USAGE),
+        }
+    }
+
+    let input = paths.first().map(String::as_str);
+    let output = paths.get(1).map(String::as_str).filter(|&path| path != "-");
+
+    let graph = common::read_graph(input, from.as_deref())
+        .unwrap_or_else(|err| fail(&format!("failed to read graph: {}", err)));
+    let graph = transform(&graph, undirected, default_weight);
+
+    let to = to
+        .as_deref()
+        .or_else(|| common::guess_format(output?))
+        .unwrap_or("text");
+
+    let result = match output {
+        Some(path) => File::create(path)
+            .map_err(Box::from)
+            .and_then(|file| common::write_graph(&graph, to, BufWriter::new(file))),
+        None => common::write_graph(&graph, to, io::stdout().lock()),
+    };
+
+    if let Err(err) = result {
+        fail(&format!("failed to write graph: {}", err));
+    }
+}
+
+/// Applies the undirected and default weight options to a graph
+fn transform(
+    graph: &Graph<String>,
+    undirected: bool,
+    default_weight: Option<i64>,
+) -> Graph<String> {
+    let mut transformed = Graph::with_capacity(graph.len());
+
+    for u in graph.nodes() {
+        transformed.add_node(u.clone());
+    }
+
+    for (u, edge) in graph.edges() {
+        let v = edge.destination();
+        let weight = edge
+            .weight()
+            .or_else(|| default_weight.map(EdgeWeight::from));
+        let edge = |dest: &String| match weight {
+            Some(w) => Edge::new_with_weight(dest.clone(), w),
+            None => Edge::new(dest.clone()),
+        };
+
+        transformed.add_edge(u.clone(), edge(v));
+        // don't double up edges which are already present in both directions
+        if undirected && u != v && !graph.is_edge(v, u) {
+            transformed.add_edge(v.clone(), edge(u));
+        }
+    }
+
+    transformed
+}
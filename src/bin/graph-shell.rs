@@ -0,0 +1,239 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! An interactive shell for building and exploring a graph.
+
+mod common;
+
+use common::fail;
+use graph_algos::{
+    algos::{bellman_ford, bfs_shortest_paths, dijkstra, strongly_connected_components, topo_iter},
+    Edge, EdgeWeight, Graph, Path, PredMap,
+};
+use std::{
+    env,
+    error::Error,
+    fs::File,
+    io::{self, BufRead, BufWriter, IsTerminal, Write},
+};
+
+const USAGE: &str = "usage: graph-shell [FILE]";
+
+const HELP: &str = "\
+commands:
+  load FILE [FORMAT]      replace the graph with one read from FILE
+  save FILE [FORMAT]      write the graph to FILE
+  export FORMAT           print the graph in FORMAT
+  clear                   remove every node
+  info                    print the number of nodes and edges
+  nodes                   list the nodes
+  edges                   list the edges
+  add-node NODE           add a node
+  remove-node NODE        remove a node and every edge to or from it
+  add-edge U V [WEIGHT]   add an edge from U to V
+  remove-edge U V         remove every edge from U to V
+  neighbors NODE          list the successors of a node
+  path U V                find the shortest path from U to V
+  scc                     list the strongly connected components
+  topo                    print a topological order of the nodes
+  help                    print this message
+  quit                    leave the shell";
+
+/// The result of running a single command
+type CommandResult = Result<(), Box<dyn Error>>;
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let mut graph = Graph::empty();
+
+    match (args.next(), args.next()) {
+        (Some(arg), None) if arg == "-h" || arg == "--help" => {
+            println!("{}\n\n{}", USAGE, HELP);
+            return;
+        }
+        (Some(path), None) => {
+            graph = common::read_graph(Some(&path), None)
+                .unwrap_or_else(|err| fail(&format!("failed to read graph: {}", err)));
+        }
+        (None, None) => {}
+        _ => fail(USAGE),
+    }
+
+    let stdin = io::stdin();
+    let interactive = stdin.is_terminal();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        if interactive {
+            print!("> ");
+            io::stdout().flush().ok();
+        }
+
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            Some(Err(err)) => fail(&format!("failed to read command: {}", err)),
+            None => break,
+        };
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [] => {}
+            ["quit"] | ["exit"] => break,
+            words => {
+                if let Err(err) = run(&mut graph, words) {
+                    eprintln!("error: {}", err);
+                }
+            }
+        }
+    }
+}
+
+/// Runs a single command against the graph
+fn run(graph: &mut Graph<String>, words: &[&str]) -> CommandResult {
+    match words {
+        ["help"] => println!("{}", HELP),
+        ["load", path, format @ ..] if format.len() <= 1 => {
+            *graph = common::read_graph(Some(path), format.first().copied())?;
+            println!(
+                "loaded {} nodes and {} edges",
+                graph.len(),
+                graph.edges().len()
+            );
+        }
+        ["save", path, format @ ..] if format.len() <= 1 => {
+            let format = format
+                .first()
+                .copied()
+                .or_else(|| common::guess_format(path))
+                .unwrap_or("text");
+            common::write_graph(graph, format, BufWriter::new(File::create(path)?))?;
+        }
+        ["export", format] => common::write_graph(graph, format, io::stdout().lock())?,
+        ["clear"] => *graph = Graph::empty(),
+        ["info"] => println!("{} nodes, {} edges", graph.len(), graph.edges().len()),
+        ["nodes"] => println!("{}", sorted(graph.nodes()).join(" ")),
+        ["edges"] => {
+            let mut edges: Vec<(&String, &Edge<String>)> = graph.edges().collect();
+            edges.sort_by_key(|(u, edge)| (*u, edge.destination()));
+            for (u, edge) in edges {
+                match edge.weight() {
+                    Some(w) => println!("{} -> {} ({})", u, edge.destination(), w),
+                    None => println!("{} -> {}", u, edge.destination()),
+                }
+            }
+        }
+        ["add-node", u] => graph.add_node(u.to_string()),
+        ["remove-node", u] => {
+            contains(graph, u)?;
+            graph.retain_nodes(|node| node != u);
+        }
+        ["add-edge", u, v] => graph.add_edge(u.to_string(), Edge::new(v.to_string())),
+        ["add-edge", u, v, w] => {
+            let w: i64 = w.parse()?;
+            graph.add_edge(u.to_string(), Edge::new_with_weight(v.to_string(), w));
+        }
+        ["remove-edge", u, v] => {
+            let (u, v) = (u.to_string(), v.to_string());
+            if !graph.is_edge(&u, &v) {
+                return Err(format!("there is no edge from {} to {}", u, v).into());
+            }
+            // remove_edge only takes out one of any parallel edges
+            while graph.is_edge(&u, &v) {
+                graph.remove_edge(&u, &v);
+            }
+        }
+        ["neighbors", u] => {
+            contains(graph, u)?;
+            println!("{}", sorted(graph.neighbors(&u.to_string())).join(" "));
+        }
+        ["path", u, v] => {
+            contains(graph, u)?;
+            contains(graph, v)?;
+            shortest_path(graph, &u.to_string(), &v.to_string())?;
+        }
+        ["scc"] => {
+            let mut components: Vec<Vec<&str>> = strongly_connected_components(&*graph)
+                .into_iter()
+                .map(sorted)
+                .collect();
+            components.sort();
+            for component in components {
+                println!("{}", component.join(" "));
+            }
+        }
+        ["topo"] => {
+            let order: Vec<&str> = topo_iter(&*graph).map(String::as_str).collect();
+            if order.len() < graph.len() {
+                return Err("the graph has a cycle".into());
+            }
+            println!("{}", order.join(" "));
+        }
+        [command, ..] => {
+            return Err(
+                format!("unknown command or wrong arguments {:?}, try help", command).into(),
+            )
+        }
+        [] => {}
+    }
+
+    Ok(())
+}
+
+/// Fails if the node isn't in the graph
+fn contains(graph: &Graph<String>, u: &str) -> CommandResult {
+    match graph.succs(&u.to_string()) {
+        Some(_) => Ok(()),
+        None => Err(format!("{} is not in the graph", u).into()),
+    }
+}
+
+/// Sorts some nodes by name
+fn sorted<'a>(nodes: impl IntoIterator<Item = &'a String>) -> Vec<&'a str> {
+    let mut nodes: Vec<&str> = nodes.into_iter().map(String::as_str).collect();
+    nodes.sort_unstable();
+    nodes
+}
+
+/// Prints the shortest path between two nodes
+///
+/// The algorithm is picked to suit the graph: breadth first search when some edges have no weight,
+/// Bellman-Ford when some are negative and Dijkstra otherwise.
+fn shortest_path(graph: &Graph<String>, u: &String, v: &String) -> CommandResult {
+    let weights: Vec<Option<EdgeWeight>> = graph.edges().map(|(_, edge)| edge.weight()).collect();
+
+    let pred_map: PredMap<String> = if weights.iter().any(Option::is_none) {
+        bfs_shortest_paths(graph, u)
+    } else if weights.iter().any(|&w| w < Some(0.into())) {
+        bellman_ford(graph, u)
+            .ok_or("the graph has a negative cycle")?
+            .0
+    } else {
+        dijkstra(graph, u).0
+    };
+
+    let path = Path::new_path_to(&pred_map, v)
+        .map_err(|_| format!("there is no path from {} to {}", u, v))?;
+
+    let mut nodes = vec![path.head().as_str()];
+    nodes.extend(path.edges().iter().map(|edge| edge.destination().as_str()));
+    println!(
+        "{} (distance {})",
+        nodes.join(" -> "),
+        common::path_length(&path)
+    );
+
+    Ok(())
+}
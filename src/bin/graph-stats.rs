@@ -0,0 +1,122 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Prints summary statistics about a graph file.
+
+mod common;
+
+use common::fail;
+use graph_algos::{
+    algos::{strongly_connected_components, topo_iter},
+    util::IncrementalConnectivity,
+    Graph,
+};
+use std::{collections::BTreeMap, env};
+
+const USAGE: &str = "\
+usage: graph-stats [--format FORMAT] [FILE]
+
+Reads a graph from FILE, or stdin if FILE is missing or -, and prints statistics about it.";
+
+fn main() {
+    let mut format = None;
+    let mut path = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}\n\nFORMAT is one of {}", USAGE, common::FORMATS);
+                return;
+            }
+            "-f" | "--format" => format = Some(args.next().unwrap_or_else(|| fail(USAGE))),
+            _ if path.is_none() => path = Some(arg),
+            _ => fail(USAGE),
+        }
+    }
+
+    let graph = common::read_graph(path.as_deref(), format.as_deref())
+        .unwrap_or_else(|err| fail(&format!("failed to read graph: {}", err)));
+
+    print_stats(&graph);
+}
+
+fn print_stats(graph: &Graph<String>) {
+    let nodes = graph.len();
+    let edges = graph.edges().len();
+
+    println!("nodes: {}", nodes);
+    println!("edges: {}", edges);
+    println!("self loops: {}", graph.self_loops().count());
+
+    let mut in_degrees: BTreeMap<&String, usize> = graph.nodes().map(|u| (u, 0)).collect();
+    for (_, edge) in graph.edges() {
+        *in_degrees.entry(edge.destination()).or_insert(0) += 1;
+    }
+    let out_degrees: Vec<usize> = graph.nodes().map(|u| graph.neighbors(u).len()).collect();
+
+    print_degrees("out-degree", out_degrees.iter().copied());
+    print_degrees("in-degree", in_degrees.values().copied());
+
+    let mut distribution: BTreeMap<usize, usize> = BTreeMap::new();
+    for &degree in &out_degrees {
+        *distribution.entry(degree).or_insert(0) += 1;
+    }
+    println!("out-degree distribution:");
+    for (degree, count) in distribution {
+        println!("  {}: {}", degree, count);
+    }
+
+    println!(
+        "weakly connected components: {}",
+        IncrementalConnectivity::from(graph).component_count()
+    );
+    println!(
+        "strongly connected components: {}",
+        strongly_connected_components(graph).len()
+    );
+    let acyclic = topo_iter(graph).count() == nodes;
+    println!("acyclic: {}", if acyclic { "yes" } else { "no" });
+
+    let weights = graph.sorted_weights();
+    println!("weighted edges: {}", weights.len());
+    if let (Some(min), Some(max), Some(median)) =
+        (weights.first(), weights.last(), graph.weight_median())
+    {
+        println!("weights: min {}, max {}, median {}", min, max, median);
+    }
+}
+
+/// Prints the minimum, maximum and mean of some degrees
+fn print_degrees(name: &str, degrees: impl Iterator<Item = usize>) {
+    let (mut min, mut max, mut total, mut count) = (usize::MAX, 0, 0, 0);
+    for degree in degrees {
+        min = min.min(degree);
+        max = max.max(degree);
+        total += degree;
+        count += 1;
+    }
+
+    if count > 0 {
+        println!(
+            "{}: min {}, max {}, mean {:.2}",
+            name,
+            min,
+            max,
+            total as f64 / count as f64
+        );
+    }
+}
@@ -0,0 +1,208 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Finds the shortest path between two nodes of a graph file.
+
+mod common;
+
+use common::fail;
+use graph_algos::{
+    algos::{bellman_ford, bfs_shortest_paths, dijkstra},
+    Graph, Path, PredMap,
+};
+use std::{env, fmt::Write};
+
+const USAGE: &str = "\
+usage: shortest-path --from NODE --to NODE [--input FILE] [--input-format FORMAT]
+                     [--algorithm dijkstra|bellman-ford|bfs] [--format text|dot|json]
+
+Reads a graph from FILE, or stdin if FILE is missing or -, and prints the shortest path
+from one node to another along with its length.";
+
+/// The shortest path algorithms the tool can run
+#[derive(Debug, Clone, Copy)]
+enum Algorithm {
+    Dijkstra,
+    BellmanFord,
+    Bfs,
+}
+
+/// The ways the tool can print a path
+#[derive(Debug, Clone, Copy)]
+enum OutputFormat {
+    Text,
+    Dot,
+    Json,
+}
+
+fn main() {
+    let mut input = None;
+    let mut input_format = None;
+    let mut algorithm = Algorithm::Dijkstra;
+    let mut from = None;
+    let mut to = None;
+    let mut format = OutputFormat::Text;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if let "-h" | "--help" = arg.as_str() {
+            println!("{}\n\nFORMAT is one of {}", USAGE, common::FORMATS);
+            return;
+        }
+
+        let value = args.next().unwrap_or_else(|| fail(USAGE));
+        match arg.as_str() {
+            "-i" | "--input" => input = Some(value),
+            "--input-format" => input_format = Some(value),
+            "-a" | "--algorithm" => {
+                algorithm = match value.as_str() {
+                    "dijkstra" => Algorithm::Dijkstra,
+                    "bellman-ford" => Algorithm::BellmanFord,
+                    "bfs" => Algorithm::Bfs,
+                    _ => fail(&format!("unknown algorithm {:?}\n\n{}", value, USAGE)),
+                }
+            }
+            "--from" => from = Some(value),
+            "--to" => to = Some(value),
+            "-f" | "--format" => {
+                format = match value.as_str() {
+                    "text" => OutputFormat::Text,
+                    "dot" => OutputFormat::Dot,
+                    "json" => OutputFormat::Json,
+                    _ => fail(&format!("unknown output format {:?}\n\n{}", value, USAGE)),
+                }
+            }
+            _ => fail(USAGE),
+        }
+    }
+
+    let (from, to) = match (from, to) {
+        (Some(from), Some(to)) => (from, to),
+        _ => fail(USAGE),
+    };
+
+    let graph = common::read_graph(input.as_deref(), input_format.as_deref())
+        .unwrap_or_else(|err| fail(&format!("failed to read graph: {}", err)));
+
+    for node in [&from, &to] {
+        if graph.succs(node).is_none() {
+            fail(&format!("node {:?} is not in the graph", node));
+        }
+    }
+
+    if let Algorithm::Dijkstra | Algorithm::BellmanFord = algorithm {
+        check_weights(&graph, algorithm);
+    }
+
+    let pred_map: PredMap<String> = match algorithm {
+        Algorithm::Dijkstra => dijkstra(&graph, &from).0,
+        Algorithm::BellmanFord => {
+            let (pred_map, _) = bellman_ford(&graph, &from).unwrap_or_else(|| {
+                fail("the graph contains a negative cycle reachable from the start node")
+            });
+            pred_map
+        }
+        Algorithm::Bfs => bfs_shortest_paths(&graph, &from),
+    };
+
+    let path = Path::new_path_to(&pred_map, &to)
+        .unwrap_or_else(|_| fail(&format!("there is no path from {:?} to {:?}", from, to)));
+
+    print!("{}", render(&path, format));
+}
+
+/// Exits with an error if the graph has edges the algorithm can't handle
+fn check_weights(graph: &Graph<String>, algorithm: Algorithm) {
+    for (u, edge) in graph.edges() {
+        let v = edge.destination();
+        match edge.weight() {
+            None => fail(&format!(
+                "the edge from {:?} to {:?} has no weight, use --algorithm bfs for unweighted graphs",
+                u, v
+            )),
+            Some(w) if w < 0.into() && matches!(algorithm, Algorithm::Dijkstra) => fail(&format!(
+                "the edge from {:?} to {:?} has negative weight {}, use --algorithm bellman-ford",
+                u, v, w
+            )),
+            _ => {}
+        }
+    }
+}
+
+/// Formats a path in the requested output format
+fn render(path: &Path<String>, format: OutputFormat) -> String {
+    let nodes: Vec<&String> = std::iter::once(path.head())
+        .chain(path.edges().iter().map(|edge| *edge.destination()))
+        .collect();
+    let length = common::path_length(path);
+    let mut out = String::new();
+
+    match format {
+        OutputFormat::Text => {
+            let nodes: Vec<&str> = nodes.iter().map(|node| node.as_str()).collect();
+            writeln!(out, "path: {}", nodes.join(" -> ")).unwrap();
+            writeln!(out, "distance: {}", length).unwrap();
+        }
+        OutputFormat::Dot => {
+            writeln!(out, "digraph path {{").unwrap();
+            writeln!(out, "    label=\"distance {}\";", length).unwrap();
+            let mut u = path.head();
+            for edge in path.edges() {
+                let v = *edge.destination();
+                match edge.weight() {
+                    Some(w) => writeln!(out, "    {} -> {} [label=\"{}\"];", quote(u), quote(v), w),
+                    None => writeln!(out, "    {} -> {};", quote(u), quote(v)),
+                }
+                .unwrap();
+                u = v;
+            }
+            writeln!(out, "}}").unwrap();
+        }
+        OutputFormat::Json => {
+            let nodes: Vec<String> = nodes.iter().map(|node| quote(node)).collect();
+            writeln!(
+                out,
+                "{{\"from\":{},\"to\":{},\"distance\":{},\"path\":[{}]}}",
+                quote(path.head()),
+                nodes[nodes.len() - 1],
+                length,
+                nodes.join(",")
+            )
+            .unwrap();
+        }
+    }
+
+    out
+}
+
+/// Quotes and escapes a string for the JSON and DOT output
+fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -15,8 +15,7 @@
  *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use graph_algos::{graph, Graph, NodeBounds};
-use std::collections::HashMap;
+use graph_algos::{algos::topological_sort, graph, Graph};
 
 fn main() {
     // inputs/graph_1.in
@@ -28,50 +27,9 @@ fn main() {
     };
 
     // sort from node 1
-    let sorted = topological_sort(&graph, &1);
+    let sorted = topological_sort(&graph, &1).expect("Loop detected.");
 
     println!("{}", graph);
 
     println!("{:?}", sorted);
 }
-
-fn topological_sort<'a, N: NodeBounds>(graph: &'a Graph<N>, u: &'a N) -> Vec<&'a N> {
-    let mut processed: HashMap<&N, bool> = HashMap::new();
-
-    let mut rev_order: Vec<&N> = Vec::new();
-
-    topo_rec(graph, u, &mut processed, &mut rev_order);
-
-    rev_order.reverse();
-
-    rev_order
-}
-
-fn topo_rec<'a, N: NodeBounds>(
-    graph: &'a Graph<N>,
-    u: &'a N,
-    mut processed: &mut HashMap<&'a N, bool>,
-    mut rev_order: &mut Vec<&'a N>,
-) {
-    let processed_node = *processed
-        .entry(u)
-        .and_modify(|p| {
-            if !*p {
-                panic!("Loop detected.")
-            }
-        })
-        .or_insert(false);
-
-    if !processed_node {
-        if let Some(succs) = graph.succs(u) {
-            for edge in succs {
-                topo_rec(graph, edge.destination(), &mut processed, &mut rev_order);
-            }
-        }
-
-        // finished processing
-        processed.insert(u, true);
-
-        rev_order.push(u);
-    }
-}
@@ -0,0 +1,183 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{
+    error::{BuildError, BuildViolation},
+    Edge, Graph, NodeBounds,
+};
+
+/// Builds a graph while checking that it has the expected shape
+///
+/// Edges which break one of the rules are reported as soon as they are added and left out of the graph,
+/// then [`GraphBuilder::build`] reports every problem found.
+/// ```
+/// use graph_algos::{Edge, GraphBuilder};
+/// use graph_algos::error::BuildViolation;
+///
+/// let mut builder = GraphBuilder::new()
+///     .deny_self_loops()
+///     .require_weights()
+///     .expect_nodes(3);
+///
+/// builder.add_edge(1, Edge::new_with_weight(2, 4)).unwrap();
+/// builder.add_edge(2, Edge::new_with_weight(3, 1)).unwrap();
+///
+/// assert_eq!(
+///     builder.add_edge(3, Edge::new_with_weight(3, 1)).unwrap_err(),
+///     BuildViolation::SelfLoop(3),
+/// );
+///
+/// let error = builder.build().unwrap_err();
+/// assert_eq!(error.violations(), &[BuildViolation::SelfLoop(3)]);
+/// assert_eq!(
+///     error.to_string(),
+///     "Failed to build graph, found 1 problems\n  Self loop on node 3",
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct GraphBuilder<N: NodeBounds> {
+    graph: Graph<N>,
+    violations: Vec<BuildViolation<N>>,
+    deny_self_loops: bool,
+    deny_parallel_edges: bool,
+    require_weights: bool,
+    expected_nodes: Option<usize>,
+}
+
+impl<N: NodeBounds> GraphBuilder<N> {
+    /// Creates a builder which accepts any edge
+    pub fn new() -> Self {
+        Self {
+            graph: Graph::empty(),
+            violations: Vec::new(),
+            deny_self_loops: false,
+            deny_parallel_edges: false,
+            require_weights: false,
+            expected_nodes: None,
+        }
+    }
+
+    /// Rejects edges from a node to itself
+    pub fn deny_self_loops(mut self) -> Self {
+        self.deny_self_loops = true;
+        self
+    }
+
+    /// Rejects a second edge between the same pair of nodes
+    pub fn deny_parallel_edges(mut self) -> Self {
+        self.deny_parallel_edges = true;
+        self
+    }
+
+    /// Rejects edges without a weight
+    pub fn require_weights(mut self) -> Self {
+        self.require_weights = true;
+        self
+    }
+
+    /// Requires the built graph to have exactly `nodes` nodes, and reserves space for them
+    pub fn expect_nodes(mut self, nodes: usize) -> Self {
+        self.expected_nodes = Some(nodes);
+        self.graph.reserve(nodes);
+        self
+    }
+
+    /// adds a node with no edges, if it isn't already in the graph
+    pub fn add_node(&mut self, u: N) -> &mut Self {
+        self.graph.add_node(u);
+        self
+    }
+
+    /// adds an edge to the graph if it follows the rules,
+    /// otherwise records and returns the rule it broke
+    /// ```
+    /// use graph_algos::{Edge, GraphBuilder};
+    /// use graph_algos::error::BuildViolation;
+    ///
+    /// let mut builder = GraphBuilder::new().deny_parallel_edges();
+    ///
+    /// builder.add_edge(1, Edge::new(2)).unwrap();
+    /// let err = builder.add_edge(1, Edge::new(2)).unwrap_err();
+    ///
+    /// assert_eq!(err, BuildViolation::ParallelEdge { source: 1, destination: 2 });
+    /// ```
+    pub fn add_edge(&mut self, u: N, e: Edge<N>) -> Result<&mut Self, BuildViolation<N>> {
+        if let Some(violation) = self.check_edge(&u, &e) {
+            self.violations.push(violation.clone());
+            return Err(violation);
+        }
+
+        self.graph.add_edge(u, e);
+        Ok(self)
+    }
+
+    /// Returns the first rule the edge breaks, if any
+    fn check_edge(&self, u: &N, e: &Edge<N>) -> Option<BuildViolation<N>> {
+        let v = e.destination();
+
+        if self.deny_self_loops && u == v {
+            Some(BuildViolation::SelfLoop(u.clone()))
+        } else if self.deny_parallel_edges && self.graph.is_edge(u, v) {
+            Some(BuildViolation::ParallelEdge {
+                source: u.clone(),
+                destination: v.clone(),
+            })
+        } else if self.require_weights && e.weight().is_none() {
+            Some(BuildViolation::MissingWeight {
+                source: u.clone(),
+                destination: v.clone(),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the graph if no rules were broken, otherwise every violation found
+    /// ```
+    /// use graph_algos::{Edge, GraphBuilder};
+    /// use graph_algos::error::BuildViolation;
+    ///
+    /// let mut builder = GraphBuilder::new().expect_nodes(3);
+    /// builder.add_edge(1, Edge::new(2)).unwrap();
+    ///
+    /// assert_eq!(
+    ///     builder.build().unwrap_err().violations(),
+    ///     &[BuildViolation::NodeCount { expected: 3, found: 2 }],
+    /// );
+    /// ```
+    pub fn build(mut self) -> Result<Graph<N>, BuildError<N>> {
+        if let Some(expected) = self.expected_nodes {
+            let found = self.graph.len();
+            if found != expected {
+                self.violations
+                    .push(BuildViolation::NodeCount { expected, found });
+            }
+        }
+
+        if self.violations.is_empty() {
+            Ok(self.graph)
+        } else {
+            Err(BuildError::new(self.violations))
+        }
+    }
+}
+
+impl<N: NodeBounds> Default for GraphBuilder<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
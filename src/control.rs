@@ -0,0 +1,173 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::error::Cancelled;
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// How far a long running algorithm has got, passed to the callback of an [`AlgoControl`]
+///
+/// The units of work depend on the algorithm, for example the number of intermediate nodes
+/// [`crate::algos::floyd_warshall_with_control`] has processed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// The units of work done so far
+    pub completed: usize,
+    /// The units of work in the whole run
+    pub total: usize,
+}
+
+impl Progress {
+    /// Returns the fraction of the work done so far, between 0 and 1
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.completed as f64 / self.total as f64
+        }
+    }
+}
+
+type ProgressCallback = Box<dyn Fn(Progress) + Send + Sync>;
+
+/// A handle for cancelling a long running algorithm and observing its progress
+///
+/// The `_with_control` variants of the expensive algorithms take one,
+/// report their progress to its callback at regular points,
+/// and return [`Err(Cancelled)`](Cancelled) at the next of those points after it is cancelled.
+/// ```
+/// use graph_algos::{AlgoControl, Graph};
+/// use graph_algos::algos::floyd_warshall_with_control;
+/// use graph_algos::error::Cancelled;
+/// use std::sync::{Arc, Mutex};
+///
+/// let grid = Graph::grid(7, 7, false);
+///
+/// // record the progress, and give up half way through
+/// let seen = Arc::new(Mutex::new(Vec::new()));
+/// let control = AlgoControl::new();
+/// let token = control.cancel_token();
+/// let log = Arc::clone(&seen);
+/// let control = control.on_progress(move |progress| {
+///     log.lock().unwrap().push(progress.completed);
+///     if progress.fraction() >= 0.5 {
+///         token.cancel();
+///     }
+/// });
+///
+/// assert_eq!(floyd_warshall_with_control(&grid, &control).unwrap_err(), Cancelled);
+/// assert_eq!(seen.lock().unwrap().last(), Some(&25));
+///
+/// // without being cancelled it runs to the end
+/// let control = AlgoControl::new();
+/// let matrix = floyd_warshall_with_control(&grid, &control).unwrap();
+/// assert_eq!(matrix.distance(&(0, 0), &(6, 6)), Some(12.into()));
+/// ```
+pub struct AlgoControl {
+    cancelled: Arc<AtomicBool>,
+    progress: Option<ProgressCallback>,
+}
+
+/// A cloneable handle which cancels the algorithm run with the [`AlgoControl`] it came from,
+/// and can be sent to another thread
+#[derive(Debug, Clone)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    /// Asks the algorithm to stop at the next point it checks
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether the algorithm has been asked to stop
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+impl AlgoControl {
+    /// Creates a control which hasn't been cancelled and has no progress callback
+    pub fn new() -> Self {
+        Self {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            progress: None,
+        }
+    }
+
+    /// Sets the callback which is passed the progress of the algorithm,
+    /// replacing any set before
+    pub fn on_progress<F: Fn(Progress) + Send + Sync + 'static>(mut self, callback: F) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Returns a token which cancels any algorithm run with this control
+    pub fn cancel_token(&self) -> CancelToken {
+        CancelToken {
+            cancelled: Arc::clone(&self.cancelled),
+        }
+    }
+
+    /// Asks the algorithm to stop at the next point it checks
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether the algorithm has been asked to stop
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Fails if the algorithm has been cancelled
+    pub(crate) fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Reports progress to the callback, then fails if the algorithm has been cancelled
+    pub(crate) fn step(&self, completed: usize, total: usize) -> Result<(), Cancelled> {
+        if let Some(callback) = &self.progress {
+            callback(Progress { completed, total });
+        }
+
+        self.check()
+    }
+}
+
+impl Default for AlgoControl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for AlgoControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AlgoControl")
+            .field("cancelled", &self.is_cancelled())
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,358 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{Edge, EdgeWeight, Graph, NodeBounds};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    hash::BuildHasher,
+};
+
+/// The differences between two graphs, created by [`Graph::diff`]
+///
+/// Edges into and out of added or removed nodes are included in the added and removed edges.
+/// The order of the entries in each list is unspecified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphDiff<N: NodeBounds> {
+    added_nodes: Vec<N>,
+    removed_nodes: Vec<N>,
+    added_edges: Vec<(N, Edge<N>)>,
+    removed_edges: Vec<(N, Edge<N>)>,
+    reweighted_edges: Vec<ReweightedEdge<N>>,
+}
+
+/// An edge which is present in both graphs of a [`GraphDiff`] but with a different weight
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReweightedEdge<N: NodeBounds> {
+    source: N,
+    destination: N,
+    old_weight: Option<EdgeWeight>,
+    new_weight: Option<EdgeWeight>,
+}
+
+impl<N: NodeBounds> ReweightedEdge<N> {
+    /// Returns the source node of the edge
+    pub fn source(&self) -> &N {
+        &self.source
+    }
+
+    /// Returns the destination node of the edge
+    pub fn destination(&self) -> &N {
+        &self.destination
+    }
+
+    /// Returns the weight of the edge in the original graph
+    pub fn old_weight(&self) -> Option<EdgeWeight> {
+        self.old_weight
+    }
+
+    /// Returns the weight of the edge in the new graph
+    pub fn new_weight(&self) -> Option<EdgeWeight> {
+        self.new_weight
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher> Graph<N, S> {
+    /// Returns the changes needed to turn this graph into `other`
+    ///
+    /// An edge is reported as re-weighted when there is exactly one edge between
+    /// its nodes in each graph and their weights differ.
+    /// ```
+    /// use graph_algos::{graph, Graph, Edge};
+    ///
+    /// let old: Graph<u32> = graph! {
+    ///     1 => [2 => 5, 3 => 1],
+    ///     3 => [4 => 2],
+    /// };
+    ///
+    /// let new: Graph<u32> = graph! {
+    ///     1 => [2 => 7, 3 => 1],
+    ///     3 => [5 => 2],
+    /// };
+    ///
+    /// let diff = old.diff(&new);
+    ///
+    /// assert_eq!(diff.added_nodes(), &[5]);
+    /// assert_eq!(diff.removed_nodes(), &[4]);
+    /// assert_eq!(diff.added_edges(), &[(3, Edge::new_with_weight(5, 2))]);
+    /// assert_eq!(diff.removed_edges(), &[(3, Edge::new_with_weight(4, 2))]);
+    ///
+    /// let reweighted = &diff.reweighted_edges()[0];
+    /// assert_eq!((reweighted.source(), reweighted.destination()), (&1, &2));
+    /// assert_eq!(reweighted.old_weight(), Some(5.into()));
+    /// assert_eq!(reweighted.new_weight(), Some(7.into()));
+    /// ```
+    pub fn diff(&self, other: &Self) -> GraphDiff<N> {
+        let mut diff = GraphDiff {
+            added_nodes: other
+                .nodes()
+                .filter(|node| self.succs(node).is_none())
+                .cloned()
+                .collect(),
+            removed_nodes: self
+                .nodes()
+                .filter(|node| other.succs(node).is_none())
+                .cloned()
+                .collect(),
+            added_edges: Vec::new(),
+            removed_edges: Vec::new(),
+            reweighted_edges: Vec::new(),
+        };
+
+        for node in self.nodes().chain(diff.added_nodes.iter()) {
+            let old = weights_by_destination(self.succs_or_empty(node));
+            let mut new = weights_by_destination(other.succs_or_empty(node));
+
+            for (dest, mut old_weights) in old {
+                let mut new_weights = new.remove(dest).unwrap_or_default();
+
+                // cancel out the edges which are present in both graphs
+                old_weights.retain(|weight| {
+                    if let Some(pos) = new_weights.iter().position(|w| w == weight) {
+                        new_weights.swap_remove(pos);
+                        false
+                    } else {
+                        true
+                    }
+                });
+
+                if let ([old_weight], [new_weight]) = (&old_weights[..], &new_weights[..]) {
+                    diff.reweighted_edges.push(ReweightedEdge {
+                        source: node.clone(),
+                        destination: dest.clone(),
+                        old_weight: *old_weight,
+                        new_weight: *new_weight,
+                    });
+                } else {
+                    diff.removed_edges.extend(
+                        old_weights
+                            .into_iter()
+                            .map(|weight| (node.clone(), edge_with_weight(dest.clone(), weight))),
+                    );
+                    diff.added_edges.extend(
+                        new_weights
+                            .into_iter()
+                            .map(|weight| (node.clone(), edge_with_weight(dest.clone(), weight))),
+                    );
+                }
+            }
+
+            // destinations which only appear in the new graph
+            for (dest, new_weights) in new {
+                diff.added_edges.extend(
+                    new_weights
+                        .into_iter()
+                        .map(|weight| (node.clone(), edge_with_weight(dest.clone(), weight))),
+                );
+            }
+        }
+
+        diff
+    }
+}
+
+impl<N: NodeBounds> GraphDiff<N> {
+    /// Returns the nodes which are only in the new graph
+    pub fn added_nodes(&self) -> &[N] {
+        &self.added_nodes
+    }
+
+    /// Returns the nodes which are only in the original graph
+    pub fn removed_nodes(&self) -> &[N] {
+        &self.removed_nodes
+    }
+
+    /// Returns the edges which are only in the new graph, along with their source nodes
+    pub fn added_edges(&self) -> &[(N, Edge<N>)] {
+        &self.added_edges
+    }
+
+    /// Returns the edges which are only in the original graph, along with their source nodes
+    pub fn removed_edges(&self) -> &[(N, Edge<N>)] {
+        &self.removed_edges
+    }
+
+    /// Returns the edges whose weight changed between the graphs
+    pub fn reweighted_edges(&self) -> &[ReweightedEdge<N>] {
+        &self.reweighted_edges
+    }
+
+    /// Returns whether the two graphs were equal
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2, 3],
+    /// };
+    ///
+    /// assert!(graph.diff(&graph.clone()).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.reweighted_edges.is_empty()
+    }
+
+    /// applies the changes in the diff to a graph
+    ///
+    /// Changes to edges which aren't in `graph` are skipped,
+    /// so applying a diff to the graph it was created from always produces the new graph.
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    ///
+    /// let old: Graph<u32> = graph! {
+    ///     1 => [2 => 5, 3 => 1],
+    ///     3 => [4 => 2],
+    /// };
+    ///
+    /// let new: Graph<u32> = graph! {
+    ///     1 => [2 => 7, 3 => 1],
+    ///     3 => [5 => 2],
+    /// };
+    ///
+    /// let mut patched = old.clone();
+    /// old.diff(&new).apply(&mut patched);
+    ///
+    /// assert_eq!(patched, new);
+    /// ```
+    pub fn apply<S: BuildHasher>(&self, graph: &mut Graph<N, S>) {
+        for node in &self.added_nodes {
+            graph.add_node(node.clone());
+        }
+
+        for (source, edge) in &self.removed_edges {
+            remove_exact_edge(graph, source, edge);
+        }
+
+        for reweighted in &self.reweighted_edges {
+            if let Some(succs) = graph.succs_mut(&reweighted.source) {
+                if let Some(edge) = succs.iter_mut().find(|edge| {
+                    edge.destination() == &reweighted.destination
+                        && edge.weight() == reweighted.old_weight
+                }) {
+                    *edge = edge_with_weight(reweighted.destination.clone(), reweighted.new_weight);
+                }
+            }
+        }
+
+        for (source, edge) in &self.added_edges {
+            graph.add_edge(source.clone(), edge.clone());
+        }
+
+        if !self.removed_nodes.is_empty() {
+            let removed: HashSet<&N> = self.removed_nodes.iter().collect();
+            graph.retain_nodes(|node| !removed.contains(node));
+        }
+    }
+}
+
+/// Displays one change per line,
+/// `+` and `-` for added and removed nodes and edges, and `~` for re-weighted edges
+/// ```
+/// use graph_algos::{graph, Graph};
+///
+/// let old: Graph<u32> = graph! {
+///     1 => [2 => 5],
+/// };
+///
+/// let new: Graph<u32> = graph! {
+///     1 => [2 => 7],
+/// };
+///
+/// assert_eq!(old.diff(&new).to_string(), "~ 1 -> 2 => 7 (was 5)\n");
+/// ```
+impl<N: NodeBounds + fmt::Display> fmt::Display for GraphDiff<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for node in &self.added_nodes {
+            writeln!(f, "+ {}", node)?;
+        }
+
+        for node in &self.removed_nodes {
+            writeln!(f, "- {}", node)?;
+        }
+
+        for (source, edge) in &self.added_edges {
+            write!(f, "+ {} -> {}", source, edge.destination())?;
+            write_weight(f, edge.weight())?;
+            writeln!(f)?;
+        }
+
+        for (source, edge) in &self.removed_edges {
+            write!(f, "- {} -> {}", source, edge.destination())?;
+            write_weight(f, edge.weight())?;
+            writeln!(f)?;
+        }
+
+        for reweighted in &self.reweighted_edges {
+            write!(f, "~ {} -> {}", reweighted.source, reweighted.destination)?;
+            write_weight(f, reweighted.new_weight)?;
+            match reweighted.old_weight {
+                Some(weight) => writeln!(f, " (was {})", weight)?,
+                None => writeln!(f, " (was unweighted)")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// writes the weight of an edge in the same format as the `graph!` macro
+fn write_weight(f: &mut fmt::Formatter<'_>, weight: Option<EdgeWeight>) -> fmt::Result {
+    match weight {
+        Some(weight) => write!(f, " => {}", weight),
+        None => Ok(()),
+    }
+}
+
+/// groups the weights of a list of edges by their destination
+fn weights_by_destination<N: NodeBounds>(
+    edges: &[Edge<N>],
+) -> HashMap<&N, Vec<Option<EdgeWeight>>> {
+    let mut weights: HashMap<&N, Vec<Option<EdgeWeight>>> = HashMap::new();
+
+    for edge in edges {
+        weights
+            .entry(edge.destination())
+            .or_default()
+            .push(edge.weight());
+    }
+
+    weights
+}
+
+/// constructs an edge with an optional weight
+fn edge_with_weight<N: NodeBounds>(destination: N, weight: Option<EdgeWeight>) -> Edge<N> {
+    match weight {
+        Some(weight) => Edge::new_with_weight(destination, weight),
+        None => Edge::new(destination),
+    }
+}
+
+/// removes a single edge exactly matching `edge` from the successors of `source`
+fn remove_exact_edge<N: NodeBounds, S: BuildHasher>(
+    graph: &mut Graph<N, S>,
+    source: &N,
+    edge: &Edge<N>,
+) {
+    if let Some(succs) = graph.succs_mut(source) {
+        if let Some(pos) = succs.iter().position(|e| e == edge) {
+            succs.remove(pos);
+        }
+    }
+}
@@ -15,7 +15,7 @@
  *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use crate::{EdgeWeight, NodeBounds};
+use crate::{error::ParseEdgeError, EdgeWeight, NodeBounds};
 use std::{fmt::Debug, str::FromStr};
 
 /// An Edge in the graph
@@ -84,18 +84,6 @@ impl<N: NodeBounds> Edge<N> {
     }
 }
 
-/// represents the failure to parse an edge
-#[derive(Fail, Debug)]
-pub enum ParseEdgeError {
-    /// Represents the failure to parse the weight of an edge
-    #[fail(display = "Failed to parse edge weight: {}", _0)]
-    WeightParseError(#[fail(cause)] std::num::ParseIntError),
-
-    /// Represents the failure to parse the destination node in an edge
-    #[fail(display = "Failed to parse destination node: {}", _0)]
-    NodeParseError(String),
-}
-
 impl<N: NodeBounds> FromStr for Edge<N>
 where
     N: FromStr,
@@ -109,14 +97,14 @@ where
         if let Some((v, w)) = s.split_once(',') {
             let destination = v
                 .parse()
-                .map_err(|err| ParseEdgeError::NodeParseError(format!("{:?}", err)))?;
-            let weight: i64 = w.parse().map_err(ParseEdgeError::WeightParseError)?;
+                .map_err(|err| ParseEdgeError::Node(format!("{:?}", err)))?;
+            let weight: i64 = w.parse().map_err(ParseEdgeError::Weight)?;
 
             Ok(Edge::new_with_weight(destination, weight))
         } else {
             let destination = s
                 .parse()
-                .map_err(|err| ParseEdgeError::NodeParseError(format!("{:?}", err)))?;
+                .map_err(|err| ParseEdgeError::Node(format!("{:?}", err)))?;
 
             Ok(Edge::new(destination))
         }
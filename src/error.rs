@@ -0,0 +1,554 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! The errors which can occur when parsing, reading, building or querying graphs,
+//! or when an algorithm is cancelled.
+
+use crate::EdgeWeight;
+use std::{
+    error::Error,
+    fmt, io,
+    num::ParseIntError,
+    path::{Path, PathBuf},
+};
+
+/// represents the failure to parse an edge
+#[derive(Debug)]
+pub enum ParseEdgeError {
+    /// Represents the failure to parse the weight of an edge
+    Weight(ParseIntError),
+
+    /// Represents the failure to parse the destination node in an edge
+    Node(String),
+
+    /// Represents an edge attribute which isn't of the form `key=value`,
+    /// when parsing with [`crate::Graph::parse_with_attributes`]
+    Attribute(String),
+}
+
+impl fmt::Display for ParseEdgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Weight(err) => write!(f, "Failed to parse edge weight: {}", err),
+            Self::Node(err) => write!(f, "Failed to parse destination node: {}", err),
+            Self::Attribute(attr) => write!(f, "Malformed edge attribute: {:?}", attr),
+        }
+    }
+}
+
+impl Error for ParseEdgeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Weight(err) => Some(err),
+            Self::Node(_) | Self::Attribute(_) => None,
+        }
+    }
+}
+
+/// represents the failure to parse a graph
+/// ```
+/// use graph_algos::Graph;
+/// use graph_algos::error::{GraphParseError, ParseEdgeError};
+///
+/// let err = "1:2,x".parse::<Graph<u32>>().unwrap_err();
+///
+/// assert!(matches!(err, GraphParseError::Edge(ParseEdgeError::Weight(_))));
+/// assert_eq!(
+///     err.to_string(),
+///     "Failed to parse outbound edge: Failed to parse edge weight: invalid digit found in string",
+/// );
+/// ```
+#[derive(Debug)]
+pub enum GraphParseError {
+    /// Represents the failure to parse an outbound edge
+    Edge(ParseEdgeError),
+
+    /// Represents the failure to parse the source node of a line
+    Node(String),
+
+    /// Represents a line which isn't of the form `node:edges`
+    Format,
+
+    /// Represents an edge which appears more than once with different weights
+    /// when parsing with [`crate::DuplicateEdges::Error`]
+    DuplicateEdge {
+        /// the line the duplicate was found on, starting from 1
+        line: usize,
+        /// a description of the duplicated edge
+        edge: String,
+    },
+
+    /// Represents a missing or malformed header line when parsing with
+    /// [`crate::GraphTextFormat::header`]
+    Header,
+
+    /// Represents a header whose edge count doesn't match the number of edges found
+    EdgeCount {
+        /// the number of edges given by the header
+        expected: usize,
+        /// the number of edges actually found
+        found: usize,
+    },
+}
+
+impl fmt::Display for GraphParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Edge(err) => write!(f, "Failed to parse outbound edge: {}", err),
+            Self::Node(err) => write!(f, "Failed to parse source node: {}", err),
+            Self::Format => write!(f, "Error in graph format."),
+            Self::DuplicateEdge { line, edge } => {
+                write!(f, "Duplicate edge {} on line {}", edge, line)
+            }
+            Self::Header => write!(f, "Missing or malformed header line."),
+            Self::EdgeCount { expected, found } => {
+                write!(f, "Expected {} edges but found {}", expected, found)
+            }
+        }
+    }
+}
+
+impl Error for GraphParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Edge(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseEdgeError> for GraphParseError {
+    fn from(err: ParseEdgeError) -> Self {
+        Self::Edge(err)
+    }
+}
+
+/// A problem with a single line of input, found by [`crate::Graph::parse_report`]
+#[derive(Debug)]
+pub struct LineError {
+    line: usize,
+    error: GraphParseError,
+}
+
+impl LineError {
+    /// creates an error for line `line`, counting from 1
+    pub(crate) fn new(line: usize, error: GraphParseError) -> Self {
+        Self { line, error }
+    }
+
+    /// Returns the number of the line with the problem, counting from 1
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns what was wrong with the line
+    pub fn error(&self) -> &GraphParseError {
+        &self.error
+    }
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.error)
+    }
+}
+
+impl Error for LineError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Represents the failure to read a graph from a reader
+#[derive(Debug)]
+pub enum ReadGraphError {
+    /// Represents the failure to read from the reader
+    Io(io::Error),
+
+    /// Represents the failure to parse the graph which was read
+    Parse(GraphParseError),
+}
+
+impl fmt::Display for ReadGraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "Failed to read graph: {}", err),
+            Self::Parse(err) => write!(f, "Failed to parse graph: {}", err),
+        }
+    }
+}
+
+impl Error for ReadGraphError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Parse(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for ReadGraphError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<GraphParseError> for ReadGraphError {
+    fn from(err: GraphParseError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+/// Represents the failure to load one of the shards passed to [`crate::Graph::from_shards`]
+#[derive(Debug)]
+pub struct ShardError {
+    path: PathBuf,
+    error: ReadGraphError,
+}
+
+impl ShardError {
+    /// creates an error for the shard at `path`
+    pub(crate) fn new(path: PathBuf, error: ReadGraphError) -> Self {
+        Self { path, error }
+    }
+
+    /// Returns the path of the shard which failed to load
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the reason the shard failed to load
+    pub fn error(&self) -> &ReadGraphError {
+        &self.error
+    }
+}
+
+impl fmt::Display for ShardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to load shard {}: {}",
+            self.path.display(),
+            self.error
+        )
+    }
+}
+
+impl Error for ShardError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// A single problem found by a [`crate::GraphBuilder`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildViolation<N> {
+    /// An edge from a node to itself, when self loops are denied
+    SelfLoop(N),
+
+    /// A second edge between the same pair of nodes, when parallel edges are denied
+    ParallelEdge {
+        /// the source node of the edge
+        source: N,
+        /// the destination node of the edge
+        destination: N,
+    },
+
+    /// An edge with no weight, when weights are required
+    MissingWeight {
+        /// the source node of the edge
+        source: N,
+        /// the destination node of the edge
+        destination: N,
+    },
+
+    /// The built graph has a different number of nodes to the number expected
+    NodeCount {
+        /// the number of nodes the graph should have
+        expected: usize,
+        /// the number of nodes the graph actually has
+        found: usize,
+    },
+}
+
+impl<N: fmt::Debug> fmt::Display for BuildViolation<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SelfLoop(node) => write!(f, "Self loop on node {:?}", node),
+            Self::ParallelEdge {
+                source,
+                destination,
+            } => write!(f, "Parallel edge from {:?} to {:?}", source, destination),
+            Self::MissingWeight {
+                source,
+                destination,
+            } => write!(
+                f,
+                "Missing weight on edge from {:?} to {:?}",
+                source, destination
+            ),
+            Self::NodeCount { expected, found } => {
+                write!(f, "Expected {} nodes but found {}", expected, found)
+            }
+        }
+    }
+}
+
+impl<N: fmt::Debug> Error for BuildViolation<N> {}
+
+/// Every problem found while building a graph with a [`crate::GraphBuilder`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildError<N> {
+    violations: Vec<BuildViolation<N>>,
+}
+
+impl<N> BuildError<N> {
+    /// creates an error report from the violations found
+    pub(crate) fn new(violations: Vec<BuildViolation<N>>) -> Self {
+        Self { violations }
+    }
+
+    /// Returns the violations in the order they were found
+    pub fn violations(&self) -> &[BuildViolation<N>] {
+        &self.violations
+    }
+}
+
+impl<N: fmt::Debug> fmt::Display for BuildError<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to build graph, found {} problems",
+            self.violations.len()
+        )?;
+        for violation in &self.violations {
+            write!(f, "\n  {}", violation)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<N: fmt::Debug> Error for BuildError<N> {}
+
+/// A single problem found by [`crate::Graph::validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphViolation<N> {
+    /// An edge to a node which isn't in the graph
+    DanglingEdge {
+        /// the source node of the edge
+        source: N,
+        /// the destination node of the edge, which is missing from the graph
+        destination: N,
+    },
+
+    /// More than one edge between the same pair of nodes, reported once per pair
+    ParallelEdge {
+        /// the source node of the edges
+        source: N,
+        /// the destination node of the edges
+        destination: N,
+    },
+
+    /// An edge from a node to itself
+    SelfLoop(N),
+
+    /// An edge with no weight in a graph where other edges have weights
+    MissingWeight {
+        /// the source node of the edge
+        source: N,
+        /// the destination node of the edge
+        destination: N,
+    },
+
+    /// An edge with a negative weight
+    NegativeWeight {
+        /// the source node of the edge
+        source: N,
+        /// the destination node of the edge
+        destination: N,
+        /// the weight of the edge
+        weight: EdgeWeight,
+    },
+}
+
+impl<N: fmt::Debug> fmt::Display for GraphViolation<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DanglingEdge {
+                source,
+                destination,
+            } => write!(
+                f,
+                "Edge from {:?} to {:?} leads to a missing node",
+                source, destination
+            ),
+            Self::ParallelEdge {
+                source,
+                destination,
+            } => write!(f, "Parallel edges from {:?} to {:?}", source, destination),
+            Self::SelfLoop(node) => write!(f, "Self loop on node {:?}", node),
+            Self::MissingWeight {
+                source,
+                destination,
+            } => write!(
+                f,
+                "Missing weight on edge from {:?} to {:?}",
+                source, destination
+            ),
+            Self::NegativeWeight {
+                source,
+                destination,
+                weight,
+            } => write!(
+                f,
+                "Negative weight {} on edge from {:?} to {:?}",
+                weight, source, destination
+            ),
+        }
+    }
+}
+
+impl<N: fmt::Debug> Error for GraphViolation<N> {}
+
+/// The nodes which would be given the same name by [`crate::Graph::relabel`]
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use std::collections::HashMap;
+///
+/// let graph: Graph<&str> = graph! {
+///     "a" => ["b"],
+/// };
+///
+/// let renames: HashMap<&str, &str> = vec![("a", "b")].into_iter().collect();
+/// let err = graph.relabel(&renames).unwrap_err();
+///
+/// let (name, mut nodes) = err.collisions()[0].clone();
+/// nodes.sort();
+/// assert_eq!((name, nodes), ("b", vec!["a", "b"]));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelabelError<N> {
+    collisions: Vec<(N, Vec<N>)>,
+}
+
+impl<N> RelabelError<N> {
+    /// creates an error from each new name given to more than one node and the nodes given it
+    pub(crate) fn new(collisions: Vec<(N, Vec<N>)>) -> Self {
+        Self { collisions }
+    }
+
+    /// Returns each new name given to more than one node along with the nodes given it
+    pub fn collisions(&self) -> &[(N, Vec<N>)] {
+        &self.collisions
+    }
+}
+
+impl<N: fmt::Debug> fmt::Display for RelabelError<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to relabel graph, {} names are given to more than one node",
+            self.collisions.len()
+        )?;
+        for (name, nodes) in &self.collisions {
+            write!(f, "\n  {:?} is given to {:?}", name, nodes)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<N: fmt::Debug> Error for RelabelError<N> {}
+
+/// The reason a node sequence passed to [`crate::Graph::path_weight`] isn't a valid path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathError<N> {
+    /// The sequence contains no nodes
+    Empty,
+
+    /// A node which isn't in the graph
+    MissingNode(N),
+
+    /// Two consecutive nodes which aren't joined by an edge
+    MissingEdge {
+        /// the node the hop starts at
+        source: N,
+        /// the node the hop should reach
+        destination: N,
+    },
+
+    /// Two consecutive nodes which are only joined by edges without weights
+    MissingWeight {
+        /// the node the hop starts at
+        source: N,
+        /// the node the hop should reach
+        destination: N,
+    },
+
+    /// A hop whose weight is the opposite infinity to the weight of the path before it,
+    /// so the total is undefined
+    UndefinedWeight {
+        /// the node the hop starts at
+        source: N,
+        /// the node the hop reaches
+        destination: N,
+    },
+}
+
+impl<N: fmt::Debug> fmt::Display for PathError<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Path contains no nodes"),
+            Self::MissingNode(node) => write!(f, "Node {:?} is not in the graph", node),
+            Self::MissingEdge {
+                source,
+                destination,
+            } => write!(f, "No edge from {:?} to {:?}", source, destination),
+            Self::MissingWeight {
+                source,
+                destination,
+            } => write!(
+                f,
+                "Missing weight on edge from {:?} to {:?}",
+                source, destination
+            ),
+            Self::UndefinedWeight {
+                source,
+                destination,
+            } => write!(
+                f,
+                "Adding the edge from {:?} to {:?} gives an undefined weight",
+                source, destination
+            ),
+        }
+    }
+}
+
+impl<N: fmt::Debug> Error for PathError<N> {}
+
+/// Returned by an algorithm which was stopped through its [`crate::AlgoControl`] before it finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Algorithm was cancelled")
+    }
+}
+
+impl Error for Cancelled {}
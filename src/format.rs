@@ -0,0 +1,372 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{
+    property::{EdgeAttributes, PropertyValue},
+    Edge, EdgeWeight, Graph, NodeBounds,
+};
+use std::{cmp::Ordering, collections::HashMap, fmt, hash::BuildHasher, slice};
+
+/// The layouts a [`GraphFormatter`] can write a graph in
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum FormatStyle {
+    /// The syntax of the `graph!` macro, which is what `Display` uses
+    #[default]
+    Macro,
+
+    /// The text format accepted by `str::parse`, one `node:edges` line per node with outbound edges
+    AdjacencyList,
+
+    /// One `source -> destination: weight` line per weighted edge, leaving out unweighted edges
+    Weights,
+
+    /// A Mermaid flowchart, with the weights as edge labels
+    Mermaid,
+
+    /// A PlantUML diagram, with the weights as edge labels
+    PlantUml,
+}
+
+/// Writes a graph in a configurable layout, created by [`Graph::formatter`]
+///
+/// By default the nodes are written in the order of their `Display` output,
+/// so the output is the same every time the program runs.
+/// ```
+/// use graph_algos::{graph, Graph, FormatStyle};
+///
+/// let graph: Graph<u32> = graph! {
+///     10 => [2 => 1],
+///     2 => [10 => 4, 3 => 2],
+/// };
+///
+/// let adjacency = graph.formatter().style(FormatStyle::AdjacencyList).sorted();
+/// assert_eq!(adjacency.to_string(), "2:10,4 3,2\n10:2,1\n");
+///
+/// let weights = graph.formatter().style(FormatStyle::Weights);
+/// assert_eq!(weights.to_string(), "10 -> 2: 1\n2 -> 10: 4\n2 -> 3: 2\n");
+/// ```
+pub struct GraphFormatter<'a, N: NodeBounds, S> {
+    graph: &'a Graph<N, S>,
+    style: FormatStyle,
+    order: Option<fn(&N, &N) -> Ordering>,
+    attributes: Option<&'a EdgeAttributes<N>>,
+}
+
+impl<N: NodeBounds, S: BuildHasher> Graph<N, S> {
+    /// Returns a formatter for writing the graph in one of the [`FormatStyle`]s
+    pub fn formatter(&self) -> GraphFormatter<'_, N, S> {
+        GraphFormatter {
+            graph: self,
+            style: FormatStyle::default(),
+            order: None,
+            attributes: None,
+        }
+    }
+
+    /// Writes the graph as a Mermaid flowchart, ready to paste into Markdown
+    ///
+    /// The nodes are labelled with their `Display` output and the edges with their weights.
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    ///
+    /// let graph: Graph<&str> = graph! {
+    ///     "a" => ["b" => 3],
+    ///     "b" => ["c" => 1],
+    /// };
+    ///
+    /// assert_eq!(
+    ///     graph.to_mermaid(),
+    ///     "flowchart LR\n    \
+    ///         n0[\"a\"]\n    n1[\"b\"]\n    n2[\"c\"]\n    \
+    ///         n0 -->|3| n1\n    n1 -->|1| n2\n",
+    /// );
+    /// ```
+    pub fn to_mermaid(&self) -> String
+    where
+        N: fmt::Display,
+    {
+        self.formatter().style(FormatStyle::Mermaid).to_string()
+    }
+
+    /// Writes the graph as a PlantUML diagram
+    ///
+    /// The nodes are labelled with their `Display` output and the edges with their weights.
+    /// ```
+    /// use graph_algos::{graph, Edge, Graph};
+    ///
+    /// let mut graph: Graph<u32> = graph! {
+    ///     1 => [2 => 5],
+    /// };
+    /// graph.add_edge(1, Edge::new(3));
+    ///
+    /// assert_eq!(
+    ///     graph.to_plantuml(),
+    ///     "@startuml\n\
+    ///         rectangle \"1\" as n0\nrectangle \"2\" as n1\nrectangle \"3\" as n2\n\
+    ///         n0 --> n1 : 5\nn0 --> n2\n\
+    ///         @enduml\n",
+    /// );
+    /// ```
+    pub fn to_plantuml(&self) -> String
+    where
+        N: fmt::Display,
+    {
+        self.formatter().style(FormatStyle::PlantUml).to_string()
+    }
+}
+
+impl<'a, N: NodeBounds, S> GraphFormatter<'a, N, S> {
+    /// Sets the layout to write the graph in
+    pub fn style(mut self, style: FormatStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Writes the nodes in ascending order instead of the order of their `Display` output
+    pub fn sorted(mut self) -> Self
+    where
+        N: Ord,
+    {
+        self.order = Some(N::cmp);
+        self
+    }
+
+    /// Writes the named attributes of each edge after its weight in the
+    /// [`AdjacencyList`](FormatStyle::AdjacencyList) style,
+    /// in the format read by [`Graph::parse_with_attributes`]
+    ///
+    /// The attributes of an edge are written in order of their keys.
+    /// String values that would read back as something else, such as `"3"` or ones containing
+    /// spaces, commas or line breaks, are written in double quotes with `\` escapes.
+    /// The other styles ignore the attributes.
+    pub fn edge_attributes(mut self, attributes: &'a EdgeAttributes<N>) -> Self {
+        self.attributes = Some(attributes);
+        self
+    }
+}
+
+impl<'a, N: NodeBounds + fmt::Display, S: BuildHasher> GraphFormatter<'a, N, S> {
+    /// Returns the nodes and their successors in the order they should be written
+    fn ordered_nodes(&self) -> Vec<(&'a N, &'a [Edge<N>])> {
+        let graph = self.graph;
+        let mut nodes: Vec<(&N, &[Edge<N>])> = graph
+            .nodes()
+            .map(|node| (node, graph.succs_or_empty(node)))
+            .collect();
+
+        match self.order {
+            Some(order) => nodes.sort_unstable_by(|(a, _), (b, _)| order(a, b)),
+            None => nodes.sort_by_cached_key(|(node, _)| node.to_string()),
+        }
+
+        nodes
+    }
+}
+
+impl<'a, N: NodeBounds + fmt::Display, S: BuildHasher> fmt::Display for GraphFormatter<'a, N, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nodes = self.ordered_nodes();
+
+        match self.style {
+            FormatStyle::Macro => {
+                // the intention of this is to mimic the macro syntax
+                if nodes.is_empty() {
+                    return write!(f, "{{}}");
+                }
+
+                writeln!(f, "{{")?;
+                for (node, succs) in nodes {
+                    write!(f, "    {} => [", node)?;
+                    write_edges(f, succs, ", ", " => ")?;
+                    writeln!(f, "],")?;
+                }
+                write!(f, "}}")
+            }
+            FormatStyle::AdjacencyList => {
+                for (node, succs) in nodes.into_iter().filter(|(_, succs)| !succs.is_empty()) {
+                    write!(f, "{}:", node)?;
+                    match self.attributes {
+                        Some(attributes) => write_attributed_edges(f, node, succs, attributes)?,
+                        None => write_edges(f, succs, " ", ",")?,
+                    }
+                    writeln!(f)?;
+                }
+                Ok(())
+            }
+            FormatStyle::Weights => {
+                for (node, succs) in nodes {
+                    for edge in succs {
+                        if let Some(weight) = edge.weight() {
+                            writeln!(f, "{} -> {}: {}", node, edge.destination(), weight)?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+            FormatStyle::Mermaid => {
+                writeln!(f, "flowchart LR")?;
+                write_diagram(
+                    f,
+                    &nodes,
+                    |f, id, node| {
+                        // mermaid labels can't contain a plain double quote
+                        let label = node.to_string().replace('"', "#quot;");
+                        writeln!(f, "    n{}[\"{}\"]", id, label)
+                    },
+                    |f, u, v, weight| match weight {
+                        Some(w) => writeln!(f, "    n{} -->|{}| n{}", u, w, v),
+                        None => writeln!(f, "    n{} --> n{}", u, v),
+                    },
+                )
+            }
+            FormatStyle::PlantUml => {
+                writeln!(f, "@startuml")?;
+                write_diagram(
+                    f,
+                    &nodes,
+                    |f, id, node| {
+                        let label = node.to_string().replace('"', "<U+0022>");
+                        writeln!(f, "rectangle \"{}\" as n{}", label, id)
+                    },
+                    |f, u, v, weight| match weight {
+                        Some(w) => writeln!(f, "n{} --> n{} : {}", u, v, w),
+                        None => writeln!(f, "n{} --> n{}", u, v),
+                    },
+                )?;
+                writeln!(f, "@enduml")
+            }
+        }
+    }
+}
+
+/// Writes the node declarations and then the edges of a diagram,
+/// with each node named by its position in `nodes`
+///
+/// Edges to a node missing from `nodes` are skipped, as there is no declaration for them to point at.
+fn write_diagram<N, W, E>(
+    f: &mut fmt::Formatter<'_>,
+    nodes: &[(&N, &[Edge<N>])],
+    mut write_node: W,
+    mut write_edge: E,
+) -> fmt::Result
+where
+    N: NodeBounds,
+    W: FnMut(&mut fmt::Formatter<'_>, usize, &N) -> fmt::Result,
+    E: FnMut(&mut fmt::Formatter<'_>, usize, usize, Option<EdgeWeight>) -> fmt::Result,
+{
+    let ids: HashMap<&N, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(id, (node, _))| (*node, id))
+        .collect();
+
+    for (id, (node, _)) in nodes.iter().enumerate() {
+        write_node(f, id, node)?;
+    }
+
+    for (id, (_, succs)) in nodes.iter().enumerate() {
+        for edge in succs.iter() {
+            if let Some(&dest) = ids.get(edge.destination()) {
+                write_edge(f, id, dest, edge.weight())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// writes a list of edges, with finite weights written after `weight_sep`
+fn write_edges<N: NodeBounds + fmt::Display>(
+    f: &mut fmt::Formatter<'_>,
+    succs: &[Edge<N>],
+    edge_sep: &str,
+    weight_sep: &str,
+) -> fmt::Result {
+    for (i, edge) in succs.iter().enumerate() {
+        if i > 0 {
+            f.write_str(edge_sep)?;
+        }
+
+        write!(f, "{}", edge.destination())?;
+        if let Some(EdgeWeight::Weight(w)) = edge.weight() {
+            write!(f, "{}{}", weight_sep, w)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// writes the edges from `u` in the adjacency list style, each followed by its sorted attributes
+fn write_attributed_edges<N: NodeBounds + fmt::Display>(
+    f: &mut fmt::Formatter<'_>,
+    u: &N,
+    succs: &[Edge<N>],
+    attributes: &EdgeAttributes<N>,
+) -> fmt::Result {
+    for (i, edge) in succs.iter().enumerate() {
+        if i > 0 {
+            f.write_str(" ")?;
+        }
+        write_edges(f, slice::from_ref(edge), "", ",")?;
+
+        let properties = attributes.get(&(u.clone(), edge.destination().clone()));
+        let mut properties: Vec<_> = properties.into_iter().flatten().collect();
+        properties.sort_unstable_by_key(|(key, _)| *key);
+        for (key, value) in properties {
+            write!(f, ",{}=", key)?;
+            match value {
+                PropertyValue::Str(value) if needs_quotes(value) => write_quoted(f, value)?,
+                value => write!(f, "{}", value)?,
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// whether a string value would read back as something else if written as is
+fn needs_quotes(value: &str) -> bool {
+    value.starts_with('"')
+        || value.contains(&[' ', ',', '=', '\n', '\r'][..])
+        || value.parse::<PropertyValue>().ok() != Some(PropertyValue::from(value))
+}
+
+/// writes a string value in double quotes, escaping quotes, backslashes and line breaks
+fn write_quoted(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+impl<'a, N: NodeBounds, S> fmt::Debug for GraphFormatter<'a, N, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GraphFormatter")
+            .field("style", &self.style)
+            .field("sorted", &self.order.is_some())
+            .field("edge_attributes", &self.attributes.is_some())
+            .finish()
+    }
+}
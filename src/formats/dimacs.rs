@@ -0,0 +1,264 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! The DIMACS formats from the 9th DIMACS implementation challenge.
+//!
+//! Shortest path instances (`.gr` files) start with a `p sp <nodes> <arcs>` line
+//! followed by one `a <source> <destination> <weight>` line per arc.
+//! Maximum flow instances (`.max` files) use `p max <nodes> <arcs>`,
+//! mark the source and sink with `n <id> s` and `n <id> t`,
+//! and give each arc a capacity instead of a weight.
+//! Lines starting with `c` are comments, and the nodes are numbered from 1.
+//!
+//! ```
+//! use graph_algos::formats::dimacs;
+//! use graph_algos::algos::dijkstra;
+//!
+//! let input = "\
+//! c a tiny road network
+//! p sp 3 3
+//! a 1 2 4
+//! a 2 3 1
+//! a 1 3 7
+//! ";
+//!
+//! let graph = dimacs::read_gr(input.as_bytes()).unwrap();
+//! let (_, dists) = dijkstra(&graph, &1);
+//! assert_eq!(dists[&3], 5.into());
+//!
+//! let mut output = Vec::new();
+//! dimacs::write_gr(&graph, &mut output).unwrap();
+//! assert_eq!(dimacs::read_gr(output.as_slice()).unwrap(), graph);
+//! ```
+
+use crate::{
+    error::{GraphParseError, ParseEdgeError, ReadGraphError},
+    Edge, EdgeWeight, Graph,
+};
+use std::{
+    hash::BuildHasher,
+    io::{self, BufRead, Write},
+};
+
+/// A maximum flow instance read from a `.max` file
+///
+/// The capacity of each arc is stored as the weight of its edge.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowNetwork {
+    /// The arcs of the network, weighted by their capacities
+    pub graph: Graph<usize>,
+    /// The node the flow starts from
+    pub source: usize,
+    /// The node the flow ends at
+    pub sink: usize,
+}
+
+/// Reads a shortest path instance from a DIMACS `.gr` file
+///
+/// Every node from 1 up to the node count in the problem line is added,
+/// even if it has no arcs.
+/// The node count can be at most twice the arc count plus the length of the input in bytes,
+/// so that a corrupt problem line fails with [`GraphParseError::Header`] instead of exhausting memory.
+/// ```
+/// use graph_algos::formats::dimacs;
+///
+/// assert!(dimacs::read_gr("p sp 18446744073709551615 0".as_bytes()).is_err());
+/// assert_eq!(dimacs::read_gr("p sp 3 0".as_bytes()).unwrap().len(), 3);
+/// ```
+pub fn read_gr<R: BufRead>(reader: R) -> Result<Graph<usize>, ReadGraphError> {
+    let (graph, _) = read(reader, "sp")?;
+    Ok(graph)
+}
+
+/// Reads a maximum flow instance from a DIMACS `.max` file
+/// ```
+/// use graph_algos::formats::dimacs;
+///
+/// let input = "\
+/// p max 4 4
+/// n 1 s
+/// n 4 t
+/// a 1 2 3
+/// a 1 3 2
+/// a 2 4 2
+/// a 3 4 3
+/// ";
+///
+/// let network = dimacs::read_max(input.as_bytes()).unwrap();
+/// assert_eq!((network.source, network.sink), (1, 4));
+/// assert_eq!(network.graph.edges().len(), 4);
+///
+/// let mut output = Vec::new();
+/// dimacs::write_max(&network.graph, network.source, network.sink, &mut output).unwrap();
+/// assert_eq!(dimacs::read_max(output.as_slice()).unwrap(), network);
+/// ```
+pub fn read_max<R: BufRead>(reader: R) -> Result<FlowNetwork, ReadGraphError> {
+    match read(reader, "max")? {
+        (graph, (Some(source), Some(sink))) => Ok(FlowNetwork {
+            graph,
+            source,
+            sink,
+        }),
+        _ => Err(GraphParseError::Format.into()),
+    }
+}
+
+/// Writes a graph as a DIMACS `.gr` shortest path instance
+///
+/// The nodes must be numbered from 1 and every edge must have a finite weight,
+/// otherwise an [`io::ErrorKind::InvalidInput`] error is returned.
+pub fn write_gr<S: BuildHasher, W: Write>(graph: &Graph<usize, S>, writer: W) -> io::Result<()> {
+    write(graph, "sp", &[], writer)
+}
+
+/// Writes a graph weighted by capacities as a DIMACS `.max` maximum flow instance
+///
+/// The nodes must be numbered from 1 and every edge must have a finite weight,
+/// otherwise an [`io::ErrorKind::InvalidInput`] error is returned.
+pub fn write_max<S: BuildHasher, W: Write>(
+    graph: &Graph<usize, S>,
+    source: usize,
+    sink: usize,
+    writer: W,
+) -> io::Result<()> {
+    write(graph, "max", &[(source, 's'), (sink, 't')], writer)
+}
+
+/// The source and sink designators of a maximum flow instance, if they were given
+type Terminals = (Option<usize>, Option<usize>);
+
+/// Reads a DIMACS file with the given problem type,
+/// returning the graph and any source and sink node designators
+fn read<R: BufRead>(reader: R, problem: &str) -> Result<(Graph<usize>, Terminals), ReadGraphError> {
+    let mut graph = Graph::empty();
+    let mut counts = None;
+    let mut arcs = 0;
+    let (mut source, mut sink) = (None, None);
+    let mut length = 0;
+
+    for line in reader.lines() {
+        let line = line?;
+        length += line.len() + 1;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+
+        match fields.as_slice() {
+            [] | ["c", ..] => {}
+            ["p", kind, n, m] if counts.is_none() && *kind == problem => {
+                let n: usize = n.parse().map_err(|_| GraphParseError::Header)?;
+                let m: usize = m.parse().map_err(|_| GraphParseError::Header)?;
+                counts = Some((n, m));
+            }
+            ["n", id, designator] if problem == "max" => {
+                let id = node(id, counts)?;
+                match *designator {
+                    "s" => source = Some(id),
+                    "t" => sink = Some(id),
+                    _ => return Err(GraphParseError::Format.into()),
+                }
+            }
+            ["a", u, v, w] => {
+                let u = node(u, counts)?;
+                let v = node(v, counts).map_err(|err| match err {
+                    GraphParseError::Node(err) => GraphParseError::Edge(ParseEdgeError::Node(err)),
+                    err => err,
+                })?;
+                let w: i64 = w
+                    .parse()
+                    .map_err(|err| GraphParseError::Edge(ParseEdgeError::Weight(err)))?;
+                graph.add_edge(u, Edge::new_with_weight(v, w));
+                arcs += 1;
+            }
+            ["p", ..] => return Err(GraphParseError::Header.into()),
+            _ => return Err(GraphParseError::Format.into()),
+        }
+    }
+
+    match counts {
+        None => Err(GraphParseError::Header.into()),
+        Some((_, expected)) if expected != arcs => Err(GraphParseError::EdgeCount {
+            expected,
+            found: arcs,
+        }
+        .into()),
+        // nodes without arcs cost nothing in the file, so don't trust any number of them
+        Some((nodes, _)) if nodes > arcs.saturating_mul(2).saturating_add(length) => {
+            Err(GraphParseError::Header.into())
+        }
+        Some((nodes, _)) => {
+            for node in 1..=nodes {
+                graph.add_node(node);
+            }
+            Ok((graph, (source, sink)))
+        }
+    }
+}
+
+/// Parses a node id, which must come after the problem line and be within its node count
+fn node(id: &str, counts: Option<(usize, usize)>) -> Result<usize, GraphParseError> {
+    let (nodes, _) = counts.ok_or(GraphParseError::Header)?;
+    let id: usize = id
+        .parse()
+        .map_err(|err| GraphParseError::Node(format!("{:?}", err)))?;
+
+    if (1..=nodes).contains(&id) {
+        Ok(id)
+    } else {
+        Err(GraphParseError::Node(format!(
+            "node {} out of range 1..={}",
+            id, nodes
+        )))
+    }
+}
+
+/// Writes a DIMACS file with the given problem type and node designators
+fn write<S: BuildHasher, W: Write>(
+    graph: &Graph<usize, S>,
+    problem: &str,
+    designators: &[(usize, char)],
+    mut writer: W,
+) -> io::Result<()> {
+    if graph.succs(&0).is_some() {
+        return Err(invalid_input("DIMACS nodes are numbered from 1"));
+    }
+
+    let nodes = graph.nodes().copied().max().unwrap_or(0);
+    writeln!(writer, "p {} {} {}", problem, nodes, graph.edges().len())?;
+
+    for &(node, designator) in designators {
+        writeln!(writer, "n {} {}", node, designator)?;
+    }
+
+    // write the arcs in a stable order so the output is reproducible
+    let mut sources: Vec<&usize> = graph.nodes().collect();
+    sources.sort();
+    for u in sources {
+        for (_, edge) in graph.edges_from(u) {
+            match edge.weight() {
+                Some(EdgeWeight::Weight(w)) => {
+                    writeln!(writer, "a {} {} {}", u, edge.destination(), w)?
+                }
+                _ => return Err(invalid_input("DIMACS arcs need a finite weight")),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates an error for a graph which can't be written in the DIMACS format
+fn invalid_input(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
@@ -0,0 +1,393 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! The DOT language used by Graphviz.
+//!
+//! Reading understands the common subset of the language: `graph` and `digraph` blocks,
+//! node statements, chains of edges like `a -> b -> c`, attribute lists and comments.
+//! Subgraphs, ports and HTML labels aren't supported.
+//! An edge takes its weight from its `weight` attribute, or failing that from a numeric `label`,
+//! and the edges of an undirected `graph` are added in both directions.
+//!
+//! ```
+//! use graph_algos::{graph, Graph};
+//! use graph_algos::formats::dot;
+//!
+//! let input = r#"
+//! digraph roads {
+//!     // the weights are distances in km
+//!     a -> b [weight=4];
+//!     b -> c -> d [label="2"];
+//!     "e f";
+//! }
+//! "#;
+//!
+//! let graph = dot::read(input.as_bytes()).unwrap();
+//! assert_eq!(graph.len(), 5);
+//! assert!(graph.is_edge(&"c".to_string(), &"d".to_string()));
+//!
+//! let mut output = Vec::new();
+//! dot::write(&graph, &mut output).unwrap();
+//! assert_eq!(dot::read(output.as_slice()).unwrap(), graph);
+//! ```
+
+use crate::{
+    error::{GraphParseError, ParseEdgeError, ReadGraphError},
+    Edge, EdgeWeight, Graph, NodeBounds,
+};
+use std::{
+    fmt::Display,
+    hash::BuildHasher,
+    io::{self, Read, Write},
+    iter::Peekable,
+    str::Chars,
+};
+
+/// A single token of a DOT file
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// An identifier, number or quoted string
+    Id(String),
+    /// A directed edge, `->`
+    Arrow,
+    /// An undirected edge, `--`
+    Line,
+    Open,
+    Close,
+    OpenAttrs,
+    CloseAttrs,
+    Equals,
+    Separator,
+}
+
+/// Splits a DOT file into tokens, skipping whitespace and comments
+fn tokenize(input: &str) -> Result<Vec<Token>, GraphParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    let mut line_start = true;
+
+    while let Some(c) = chars.next() {
+        let starts_line = line_start;
+        line_start = c == '\n' || (line_start && c.is_whitespace());
+
+        match c {
+            c if c.is_whitespace() => {}
+            // lines starting with # are preprocessor output and are ignored
+            '#' if starts_line => skip_line(&mut chars),
+            '/' if chars.peek() == Some(&'/') => skip_line(&mut chars),
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if last == '*' => break,
+                        Some(c) => last = c,
+                        None => return Err(GraphParseError::Format),
+                    }
+                }
+            }
+            '{' => tokens.push(Token::Open),
+            '}' => tokens.push(Token::Close),
+            '[' => tokens.push(Token::OpenAttrs),
+            ']' => tokens.push(Token::CloseAttrs),
+            '=' => tokens.push(Token::Equals),
+            ';' | ',' => tokens.push(Token::Separator),
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                tokens.push(Token::Arrow);
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                chars.next();
+                tokens.push(Token::Line);
+            }
+            '"' => {
+                let mut id = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => id.push(c),
+                            // a backslash before a newline continues the string
+                            Some('\n') => {}
+                            Some(c) => {
+                                id.push('\\');
+                                id.push(c);
+                            }
+                            None => return Err(GraphParseError::Format),
+                        },
+                        Some(c) => id.push(c),
+                        None => return Err(GraphParseError::Format),
+                    }
+                }
+                tokens.push(Token::Id(id));
+            }
+            c if is_id_char(c) || c == '-' || c == '.' => {
+                let mut id = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if !(is_id_char(c) || c == '.') {
+                        break;
+                    }
+                    id.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Id(id));
+            }
+            _ => return Err(GraphParseError::Format),
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Returns whether a character can appear in an unquoted identifier
+fn is_id_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || !c.is_ascii()
+}
+
+/// Skips the rest of the current line
+fn skip_line(chars: &mut Peekable<Chars<'_>>) {
+    for c in chars {
+        if c == '\n' {
+            break;
+        }
+    }
+}
+
+/// Returns whether an identifier is the given keyword, which are case insensitive in DOT
+fn is_keyword(token: Option<&Token>, keyword: &str) -> bool {
+    matches!(token, Some(Token::Id(id)) if id.eq_ignore_ascii_case(keyword))
+}
+
+/// Parses a DOT file into a graph
+fn parse(input: &str) -> Result<Graph<String>, GraphParseError> {
+    let tokens = tokenize(input)?;
+    let mut tokens = tokens.into_iter().peekable();
+
+    if is_keyword(tokens.peek(), "strict") {
+        tokens.next();
+    }
+
+    let directed = match tokens.next() {
+        Some(Token::Id(id)) if id.eq_ignore_ascii_case("digraph") => true,
+        Some(Token::Id(id)) if id.eq_ignore_ascii_case("graph") => false,
+        _ => return Err(GraphParseError::Header),
+    };
+    let edge_op = if directed { Token::Arrow } else { Token::Line };
+
+    // the graph's name is optional
+    if let Some(Token::Id(_)) = tokens.peek() {
+        tokens.next();
+    }
+    if tokens.next() != Some(Token::Open) {
+        return Err(GraphParseError::Header);
+    }
+
+    let mut graph = Graph::empty();
+    loop {
+        let id = match tokens.next() {
+            Some(Token::Close) => break,
+            Some(Token::Separator) => continue,
+            Some(Token::Id(id)) => id,
+            _ => return Err(GraphParseError::Format),
+        };
+
+        if ["graph", "node", "edge"]
+            .iter()
+            .any(|keyword| id.eq_ignore_ascii_case(keyword))
+        {
+            // default attributes don't affect the structure of the graph
+            attributes(&mut tokens)?;
+            continue;
+        }
+        if id.eq_ignore_ascii_case("subgraph") {
+            return Err(GraphParseError::Format);
+        }
+        if tokens.peek() == Some(&Token::Equals) {
+            // a graph attribute, `id = value`
+            tokens.next();
+            match tokens.next() {
+                Some(Token::Id(_)) => continue,
+                _ => return Err(GraphParseError::Format),
+            }
+        }
+
+        let mut chain = vec![id];
+        while tokens.peek() == Some(&edge_op) {
+            tokens.next();
+            match tokens.next() {
+                Some(Token::Id(id)) => chain.push(id),
+                _ => return Err(GraphParseError::Format),
+            }
+        }
+
+        let attrs = attributes(&mut tokens)?;
+        if chain.len() == 1 {
+            graph.add_node(chain.pop().unwrap());
+            continue;
+        }
+
+        let weight = edge_weight(&attrs)?;
+        for pair in chain.windows(2) {
+            let (u, v) = (&pair[0], &pair[1]);
+            let edge = |dest: &String| match weight {
+                Some(w) => Edge::new_with_weight(dest.clone(), w),
+                None => Edge::new(dest.clone()),
+            };
+
+            if !directed && u != v {
+                graph.add_edge(v.clone(), edge(u));
+            }
+            graph.add_edge(u.clone(), edge(v));
+        }
+    }
+
+    match tokens.next() {
+        None => Ok(graph),
+        Some(_) => Err(GraphParseError::Format),
+    }
+}
+
+/// Parses any attribute lists following a statement into key value pairs
+fn attributes<I>(tokens: &mut Peekable<I>) -> Result<Vec<(String, String)>, GraphParseError>
+where
+    I: Iterator<Item = Token>,
+{
+    let mut attrs = Vec::new();
+
+    while tokens.peek() == Some(&Token::OpenAttrs) {
+        tokens.next();
+        loop {
+            match tokens.next() {
+                Some(Token::CloseAttrs) => break,
+                Some(Token::Separator) => {}
+                Some(Token::Id(key)) => {
+                    if tokens.peek() != Some(&Token::Equals) {
+                        return Err(GraphParseError::Format);
+                    }
+                    tokens.next();
+                    match tokens.next() {
+                        Some(Token::Id(value)) => attrs.push((key, value)),
+                        _ => return Err(GraphParseError::Format),
+                    }
+                }
+                _ => return Err(GraphParseError::Format),
+            }
+        }
+    }
+
+    Ok(attrs)
+}
+
+/// Finds the weight of an edge from its `weight` attribute or a numeric `label`
+fn edge_weight(attrs: &[(String, String)]) -> Result<Option<i64>, GraphParseError> {
+    let value = |name: &str| {
+        attrs
+            .iter()
+            .rev()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value)
+    };
+
+    match (value("weight"), value("label")) {
+        (Some(weight), _) => weight
+            .parse()
+            .map(Some)
+            .map_err(|err| GraphParseError::Edge(ParseEdgeError::Weight(err))),
+        (None, Some(label)) => Ok(label.parse().ok()),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Reads a graph written in the DOT language from `reader`
+///
+/// The nodes are named by their DOT identifiers, with any quotes removed.
+pub fn read<R: Read>(mut reader: R) -> Result<Graph<String>, ReadGraphError> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    Ok(parse(&input)?)
+}
+
+/// Writes a graph as a DOT `digraph` to `writer`
+///
+/// Every node gets its own statement so that nodes without edges aren't lost,
+/// and the nodes and edges are written in a stable order.
+/// Fails if the graph contains an infinite edge weight.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::formats::dot;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2 => 3],
+/// };
+///
+/// let mut output = Vec::new();
+/// dot::write(&graph, &mut output).unwrap();
+/// assert_eq!(
+///     String::from_utf8(output).unwrap(),
+///     "digraph {\n    \"1\";\n    \"2\";\n    \"1\" -> \"2\" [weight=3];\n}\n",
+/// );
+/// ```
+pub fn write<N, S, W>(graph: &Graph<N, S>, mut writer: W) -> io::Result<()>
+where
+    N: NodeBounds + Display,
+    S: BuildHasher,
+    W: Write,
+{
+    let mut nodes: Vec<(String, &N)> = graph.nodes().map(|u| (quote(u), u)).collect();
+    nodes.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    writeln!(writer, "digraph {{")?;
+    for (name, _) in &nodes {
+        writeln!(writer, "    {};", name)?;
+    }
+
+    for (name, u) in &nodes {
+        for (_, edge) in graph.edges_from(u) {
+            let v = quote(edge.destination());
+            match edge.weight() {
+                None => writeln!(writer, "    {} -> {};", name, v)?,
+                Some(EdgeWeight::Weight(w)) => {
+                    writeln!(writer, "    {} -> {} [weight={}];", name, v, w)?
+                }
+                Some(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "DOT edges can't have infinite weights",
+                    ))
+                }
+            }
+        }
+    }
+
+    writeln!(writer, "}}")
+}
+
+/// Writes a node as a quoted DOT identifier
+fn quote<N: Display>(node: &N) -> String {
+    let node = node.to_string();
+    let mut quoted = String::with_capacity(node.len() + 2);
+
+    quoted.push('"');
+    for c in node.chars() {
+        if let '"' | '\\' = c {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+
+    quoted
+}
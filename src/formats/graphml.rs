@@ -0,0 +1,247 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! The XML based GraphML format read and written by tools such as yEd, Gephi and networkx.
+//!
+//! Edge weights are stored as `data` elements for a key whose `attr.name` is `weight`.
+//! When reading, only the top level graph is used, any other attributes are ignored,
+//! and undirected edges are added in both directions.
+//! The `edgedefault` of the graph decides whether edges are directed,
+//! which a `directed` attribute on an edge overrides.
+//!
+//! ```
+//! use graph_algos::Graph;
+//! use graph_algos::formats::graphml;
+//!
+//! let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+//! <graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+//!   <key id="d0" for="edge" attr.name="weight" attr.type="long"/>
+//!   <graph id="G" edgedefault="undirected">
+//!     <node id="a"/>
+//!     <node id="b"/>
+//!     <node id="c"/>
+//!     <edge source="a" target="b"><data key="d0">5</data></edge>
+//!     <edge source="b" target="c" directed="true"/>
+//!   </graph>
+//! </graphml>"#;
+//!
+//! let graph = graphml::read(input.as_bytes()).unwrap();
+//! assert!(graph.is_edge(&"b".to_string(), &"a".to_string()));
+//! assert!(!graph.is_edge(&"c".to_string(), &"b".to_string()));
+//!
+//! let mut output = Vec::new();
+//! graphml::write(&graph, &mut output).unwrap();
+//! assert_eq!(graphml::read(output.as_slice()).unwrap(), graph);
+//! ```
+
+use crate::{
+    error::{GraphParseError, ParseEdgeError, ReadGraphError},
+    Edge, EdgeWeight, Graph, NodeBounds,
+};
+use roxmltree::{Document, Node};
+use std::{
+    fmt::Display,
+    hash::BuildHasher,
+    io::{self, Read, Write},
+};
+
+/// The namespace of GraphML elements
+const NAMESPACE: &str = "http://graphml.graphdrawing.org/xmlns";
+
+/// Returns the children of an element with the given name, in any namespace
+fn children<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children()
+        .filter(move |child| child.tag_name().name() == name)
+}
+
+/// Parses a GraphML document into a graph
+fn parse(document: &Document<'_>) -> Result<Graph<String>, GraphParseError> {
+    let root = document.root_element();
+
+    // the ids of the keys holding edge weights, along with their default values
+    let mut weight_keys = Vec::new();
+    for key in children(root, "key") {
+        let is_weight = key.attribute("attr.name") == Some("weight")
+            && matches!(key.attribute("for"), Some("edge") | Some("all") | None);
+
+        if let (true, Some(id)) = (is_weight, key.attribute("id")) {
+            let default = match children(key, "default").next().and_then(|d| d.text()) {
+                Some(text) => Some(parse_weight(text)?),
+                None => None,
+            };
+            weight_keys.push((id, default));
+        }
+    }
+
+    let graph_element = children(root, "graph")
+        .next()
+        .ok_or(GraphParseError::Header)?;
+    let directed_default = graph_element.attribute("edgedefault") != Some("undirected");
+
+    let mut graph = Graph::empty();
+    for node in children(graph_element, "node") {
+        let id = node
+            .attribute("id")
+            .ok_or_else(|| GraphParseError::Node("node without an id".into()))?;
+        graph.add_node(id.to_string());
+    }
+
+    for edge in children(graph_element, "edge") {
+        let (u, v) = match (edge.attribute("source"), edge.attribute("target")) {
+            (Some(u), Some(v)) => (u.to_string(), v.to_string()),
+            _ => return Err(GraphParseError::Format),
+        };
+
+        let directed = match edge.attribute("directed") {
+            Some("true") => true,
+            Some("false") => false,
+            Some(_) => return Err(GraphParseError::Format),
+            None => directed_default,
+        };
+
+        let mut weight = None;
+        for &(id, default) in &weight_keys {
+            let data = children(edge, "data").find(|data| data.attribute("key") == Some(id));
+            weight = match data {
+                Some(data) => Some(parse_weight(data.text().unwrap_or_default())?),
+                None => weight.or(default),
+            };
+        }
+
+        let edge = |dest: &String| match weight {
+            Some(w) => Edge::new_with_weight(dest.clone(), w),
+            None => Edge::new(dest.clone()),
+        };
+
+        if !directed && u != v {
+            graph.add_edge(v.clone(), edge(&u));
+        }
+        graph.add_edge(u, edge(&v));
+    }
+
+    Ok(graph)
+}
+
+/// Parses a weight, allowing floating point values with no fractional part
+fn parse_weight(text: &str) -> Result<i64, GraphParseError> {
+    let text = text.trim();
+    text.parse().or_else(|err| match text.parse::<f64>() {
+        Ok(w) if w.fract() == 0.0 && w.abs() < i64::MAX as f64 => Ok(w as i64),
+        _ => Err(GraphParseError::Edge(ParseEdgeError::Weight(err))),
+    })
+}
+
+/// Reads a GraphML document from `reader`
+///
+/// The nodes are named by their GraphML ids.
+/// Malformed XML is reported as an [`io::ErrorKind::InvalidData`] error.
+pub fn read<R: Read>(mut reader: R) -> Result<Graph<String>, ReadGraphError> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+
+    let document =
+        Document::parse(&input).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    Ok(parse(&document)?)
+}
+
+/// Writes a graph as a GraphML document to `writer`
+///
+/// The nodes and edges are written in a stable order,
+/// and fails if the graph contains an infinite edge weight.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::formats::graphml;
+///
+/// let graph: Graph<&str> = graph! {
+///     "a" => ["b" => 3],
+/// };
+///
+/// let mut output = Vec::new();
+/// graphml::write(&graph, &mut output).unwrap();
+///
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.contains(r#"<edge source="a" target="b"><data key="weight">3</data></edge>"#));
+/// ```
+pub fn write<N, S, W>(graph: &Graph<N, S>, mut writer: W) -> io::Result<()>
+where
+    N: NodeBounds + Display,
+    S: BuildHasher,
+    W: Write,
+{
+    let mut nodes: Vec<(String, &N)> = graph.nodes().map(|u| (escape(u), u)).collect();
+    nodes.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(writer, r#"<graphml xmlns="{}">"#, NAMESPACE)?;
+    writeln!(
+        writer,
+        r#"  <key id="weight" for="edge" attr.name="weight" attr.type="long"/>"#
+    )?;
+    writeln!(writer, r#"  <graph id="G" edgedefault="directed">"#)?;
+
+    for (id, _) in &nodes {
+        writeln!(writer, r#"    <node id="{}"/>"#, id)?;
+    }
+
+    for (id, u) in &nodes {
+        for (_, edge) in graph.edges_from(u) {
+            let v = escape(edge.destination());
+            match edge.weight() {
+                None => writeln!(writer, r#"    <edge source="{}" target="{}"/>"#, id, v)?,
+                Some(EdgeWeight::Weight(w)) => writeln!(
+                    writer,
+                    r#"    <edge source="{}" target="{}"><data key="weight">{}</data></edge>"#,
+                    id, v, w
+                )?,
+                Some(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "GraphML weights can't be infinite",
+                    ))
+                }
+            }
+        }
+    }
+
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")
+}
+
+/// Escapes a node for use in an XML attribute
+fn escape<N: Display>(node: &N) -> String {
+    let node = node.to_string();
+    let mut escaped = String::with_capacity(node.len());
+
+    for c in node.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // parsers normalise literal whitespace in attributes to spaces
+            '\n' => escaped.push_str("&#10;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\t' => escaped.push_str("&#9;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
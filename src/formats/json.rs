@@ -0,0 +1,251 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! The node-link JSON format used by D3 and networkx's `json_graph` module.
+//!
+//! A graph is written as a list of nodes and a list of links between them:
+//! `{"directed": true, "nodes": [{"id": 1}, ...], "links": [{"source": 1, "target": 2, "weight": 5}, ...]}`.
+//! When reading, the links may also be called `edges` as in newer versions of networkx,
+//! any extra attributes are ignored, and an undirected graph gets an edge in each direction,
+//! apart from self loops which get one edge.
+//! Weights must be whole numbers, though they can be written as floats like `4.0` as networkx does.
+//!
+//! ```
+//! use graph_algos::{graph, Graph};
+//! use graph_algos::formats::json;
+//!
+//! let graph: Graph<&str> = graph! {
+//!     "a" => ["b" => 2],
+//!     "b" => ["c" => 3],
+//! };
+//!
+//! let text = json::to_string(&graph).unwrap();
+//! assert!(text.contains(r#"{"source":"a","target":"b","weight":2}"#));
+//!
+//! let back: Graph<String> = json::from_str(&text).unwrap();
+//! assert!(back.is_edge(&"b".into(), &"c".into()));
+//! ```
+
+use crate::{Edge, EdgeWeight, Graph, NodeBounds};
+use serde::{
+    de::{self, DeserializeOwned},
+    ser::Error as _,
+    Deserialize, Deserializer, Serialize,
+};
+use std::{
+    collections::HashSet,
+    hash::BuildHasher,
+    io::{Read, Write},
+};
+
+/// The top level of a node-link document
+#[derive(Serialize, Deserialize)]
+struct NodeLink<N> {
+    #[serde(default = "directed")]
+    directed: bool,
+    #[serde(default)]
+    multigraph: bool,
+    nodes: Vec<NodeData<N>>,
+    #[serde(alias = "edges")]
+    links: Vec<LinkData<N>>,
+}
+
+/// A node in a node-link document
+#[derive(Serialize, Deserialize)]
+struct NodeData<N> {
+    id: N,
+}
+
+/// A link in a node-link document
+#[derive(Serialize, Deserialize)]
+struct LinkData<N> {
+    source: N,
+    target: N,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "whole_weight"
+    )]
+    weight: Option<i64>,
+}
+
+/// Reads a weight written either as an integer or as a float with no fractional part
+fn whole_weight<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Number {
+        Int(i64),
+        Float(f64),
+    }
+
+    match Option::<Number>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Number::Int(w)) => Ok(Some(w)),
+        // the upper bound is exclusive as i64::MAX rounds up to 2^63 as a float
+        Some(Number::Float(w))
+            if w.fract() == 0.0 && w >= i64::MIN as f64 && w < i64::MAX as f64 =>
+        {
+            Ok(Some(w as i64))
+        }
+        Some(Number::Float(w)) => Err(de::Error::custom(format!(
+            "the weight {} is not a whole number",
+            w
+        ))),
+    }
+}
+
+/// Documents are directed unless they say otherwise
+fn directed() -> bool {
+    true
+}
+
+/// Converts a graph into a node-link document, failing on infinite weights
+fn to_node_link<N, S>(graph: &Graph<N, S>) -> serde_json::Result<NodeLink<&N>>
+where
+    N: NodeBounds,
+    S: BuildHasher,
+{
+    let links = graph
+        .edges()
+        .map(|(u, edge)| {
+            let weight = match edge.weight() {
+                None => None,
+                Some(EdgeWeight::Weight(w)) => Some(w),
+                Some(infinite) => {
+                    return Err(serde_json::Error::custom(format!(
+                        "cannot write the infinite weight {} as JSON",
+                        infinite
+                    )))
+                }
+            };
+
+            Ok(LinkData {
+                source: u,
+                target: edge.destination(),
+                weight,
+            })
+        })
+        .collect::<serde_json::Result<Vec<_>>>()?;
+
+    let mut seen = HashSet::new();
+    let multigraph = !links
+        .iter()
+        .all(|link| seen.insert((link.source, link.target)));
+
+    Ok(NodeLink {
+        directed: true,
+        multigraph,
+        nodes: graph.nodes().map(|id| NodeData { id }).collect(),
+        links,
+    })
+}
+
+/// Converts a node-link document into a graph
+fn from_node_link<N: NodeBounds>(document: NodeLink<N>) -> Graph<N> {
+    let mut graph = Graph::with_capacity(document.nodes.len());
+
+    for node in document.nodes {
+        graph.add_node(node.id);
+    }
+
+    for link in document.links {
+        let weight = link.weight;
+        let edge = |dest: N| match weight {
+            Some(w) => Edge::new_with_weight(dest, w),
+            None => Edge::new(dest),
+        };
+
+        if !document.directed && link.source != link.target {
+            graph.add_edge(link.target.clone(), edge(link.source.clone()));
+        }
+        graph.add_edge(link.source, edge(link.target));
+    }
+
+    graph
+}
+
+/// Writes a graph as a node-link JSON string
+///
+/// Fails if the graph contains an infinite edge weight, which JSON can't represent.
+pub fn to_string<N, S>(graph: &Graph<N, S>) -> serde_json::Result<String>
+where
+    N: NodeBounds + Serialize,
+    S: BuildHasher,
+{
+    serde_json::to_string(&to_node_link(graph)?)
+}
+
+/// Writes a graph as node-link JSON to `writer`
+///
+/// Fails if the graph contains an infinite edge weight, which JSON can't represent.
+pub fn to_writer<N, S, W>(graph: &Graph<N, S>, writer: W) -> serde_json::Result<()>
+where
+    N: NodeBounds + Serialize,
+    S: BuildHasher,
+    W: Write,
+{
+    serde_json::to_writer(writer, &to_node_link(graph)?)
+}
+
+/// Reads a graph from a node-link JSON string
+/// ```
+/// use graph_algos::Graph;
+/// use graph_algos::formats::json;
+///
+/// // as written by networkx.node_link_data for an undirected graph
+/// let text = r#"{
+///     "directed": false,
+///     "multigraph": false,
+///     "graph": {},
+///     "nodes": [{"id": 1}, {"id": 2}, {"id": 3, "color": "red"}],
+///     "edges": [{"source": 1, "target": 2, "weight": 4}]
+/// }"#;
+///
+/// let graph: Graph<u32> = json::from_str(text).unwrap();
+/// assert_eq!(graph.len(), 3);
+/// assert!(graph.is_edge(&1, &2));
+/// assert!(graph.is_edge(&2, &1));
+///
+/// // networkx writes weights as floats, and a self loop is a single edge
+/// let text = r#"{
+///     "directed": false,
+///     "nodes": [{"id": 1}, {"id": 2}],
+///     "links": [{"source": 1, "target": 2, "weight": 1.0}, {"source": 1, "target": 1}]
+/// }"#;
+///
+/// let graph: Graph<u32> = json::from_str(text).unwrap();
+/// assert_eq!(graph.succs(&2).unwrap()[0].weight(), Some(1.into()));
+/// assert_eq!(graph.succs(&1).unwrap().len(), 2);
+///
+/// let fractional = r#"{"nodes": [{"id": 1}], "links": [{"source": 1, "target": 1, "weight": 0.5}]}"#;
+/// let error = json::from_str::<u32>(fractional).unwrap_err();
+/// assert!(error.to_string().contains("the weight 0.5 is not a whole number"));
+/// ```
+pub fn from_str<N>(s: &str) -> serde_json::Result<Graph<N>>
+where
+    N: NodeBounds + DeserializeOwned,
+{
+    serde_json::from_str(s).map(from_node_link)
+}
+
+/// Reads a graph as node-link JSON from `reader`
+pub fn from_reader<N, R>(reader: R) -> serde_json::Result<Graph<N>>
+where
+    N: NodeBounds + DeserializeOwned,
+    R: Read,
+{
+    serde_json::from_reader(reader).map(from_node_link)
+}
@@ -0,0 +1,325 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use super::FrozenGraph;
+use std::collections::VecDeque;
+
+/// A read-only unweighted graph over `u32` node ids with its adjacency lists compressed
+///
+/// The successors of each node are sorted and stored as variable length integers:
+/// the number of successors, then the first successor as the difference from the node itself,
+/// then the gap from each successor to the next.
+/// On large integer graphs whose ids follow their locality, like crawled web graphs,
+/// most gaps fit in a single byte, so the edges take several times less memory than
+/// the four bytes each of a [`FrozenGraph`].
+/// The lists are decoded on the fly as they are iterated, so traversals need no extra memory.
+///
+/// The weights of the edges are not kept, and the successors are visited in increasing order
+/// rather than the order of the original graph.
+/// ```
+/// use graph_algos::{Edge, IndexedGraph};
+/// use graph_algos::frozen::{CompressedGraph, FrozenGraph};
+///
+/// // pages linking to the next ten pages
+/// let mut graph = IndexedGraph::new();
+/// for u in 0..1000 {
+///     graph.add_node(u);
+/// }
+/// for u in 0..1000 {
+///     for v in u + 1..(u + 11).min(1000) {
+///         graph.add_edge(u, Edge::new(v));
+///     }
+/// }
+/// let frozen = FrozenGraph::from(&graph);
+/// let compressed = CompressedGraph::from(&frozen);
+///
+/// // a frozen graph has at least 8 bytes per node and 4 per edge, the gaps here take a byte each
+/// assert_eq!(compressed.edge_count(), frozen.edge_count());
+/// assert!(compressed.size_in_bytes() * 2 < 8 * 1001 + 4 * frozen.edge_count());
+///
+/// for u in 0..1000 {
+///     let mut succs: Vec<u32> = frozen.neighbors(u).collect();
+///     succs.sort_unstable();
+///     assert!(compressed.neighbors(u).eq(succs));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CompressedGraph {
+    /// The start of each node's adjacency list in `bytes`, plus one for the end
+    offsets: Vec<usize>,
+    bytes: Vec<u8>,
+    edges: usize,
+}
+
+impl CompressedGraph {
+    /// Compresses the edges of a frozen graph, dropping their weights
+    pub fn new(graph: &FrozenGraph) -> Self {
+        let n = graph.node_count();
+        let mut offsets = Vec::with_capacity(n + 1);
+        let mut bytes = Vec::new();
+        let mut succs = Vec::new();
+
+        for u in 0..n as u32 {
+            offsets.push(bytes.len());
+
+            succs.clear();
+            succs.extend(graph.neighbors(u));
+            succs.sort_unstable();
+
+            write_varint(&mut bytes, succs.len() as u64);
+            if let Some(&first) = succs.first() {
+                write_varint(&mut bytes, zigzag(i64::from(first) - i64::from(u)));
+            }
+            for pair in succs.windows(2) {
+                write_varint(&mut bytes, u64::from(pair[1] - pair[0]));
+            }
+        }
+        offsets.push(bytes.len());
+        bytes.shrink_to_fit();
+
+        Self {
+            offsets,
+            bytes,
+            edges: graph.edge_count(),
+        }
+    }
+
+    /// Returns the number of nodes, whose ids are `0..node_count`
+    pub fn node_count(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Returns the number of edges
+    pub fn edge_count(&self) -> usize {
+        self.edges
+    }
+
+    /// Returns the number of bytes used by the adjacency lists and the offsets into them
+    pub fn size_in_bytes(&self) -> usize {
+        self.bytes.len() + self.offsets.len() * std::mem::size_of::<usize>()
+    }
+
+    /// Returns the number of edges leaving a node
+    pub fn degree(&self, u: u32) -> usize {
+        self.neighbors(u).len()
+    }
+
+    /// Returns an iterator decoding the successors of a node in increasing order
+    ///
+    /// Unknown nodes have no successors.
+    pub fn neighbors(&self, u: u32) -> CompressedNeighbors<'_> {
+        if u as usize >= self.node_count() {
+            return CompressedNeighbors {
+                bytes: &[],
+                remaining: 0,
+                previous: None,
+                node: u,
+            };
+        }
+
+        let mut bytes = &self.bytes[self.offsets[u as usize]..self.offsets[u as usize + 1]];
+        let remaining = read_varint(&mut bytes) as usize;
+        CompressedNeighbors {
+            bytes,
+            remaining,
+            previous: None,
+            node: u,
+        }
+    }
+
+    /// Finds the number of edges on a shortest path from `source` to every node with a breadth first search,
+    /// indexed by node id, with `None` for the nodes which can't be reached
+    /// ```
+    /// use graph_algos::{graph, Graph, IndexedGraph};
+    /// use graph_algos::frozen::{CompressedGraph, FrozenGraph};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     0 => [1, 2],
+    ///     1 => [3],
+    ///     2 => [3],
+    ///     3 => [4],
+    /// };
+    /// let indexed = IndexedGraph::from(&graph);
+    /// let compressed = CompressedGraph::from(&FrozenGraph::from(&indexed));
+    ///
+    /// let hops = compressed.bfs(indexed.id(&0).unwrap());
+    /// assert_eq!(hops[indexed.id(&4).unwrap() as usize], Some(3));
+    /// assert_eq!(compressed.bfs(indexed.id(&4).unwrap()).iter().flatten().count(), 1);
+    /// ```
+    pub fn bfs(&self, source: u32) -> Vec<Option<u32>> {
+        let mut hops = vec![None; self.node_count()];
+        if source as usize >= self.node_count() {
+            return hops;
+        }
+
+        let mut queue = VecDeque::new();
+        hops[source as usize] = Some(0);
+        queue.push_back((source, 0));
+
+        while let Some((u, distance)) = queue.pop_front() {
+            for v in self.neighbors(u) {
+                if hops[v as usize].is_none() {
+                    hops[v as usize] = Some(distance + 1);
+                    queue.push_back((v, distance + 1));
+                }
+            }
+        }
+
+        hops
+    }
+
+    /// Computes the PageRank of every node, indexed by node id
+    ///
+    /// At each step a random walk follows a random outbound edge with probability `damping`,
+    /// and otherwise jumps to a node picked uniformly at random, as it also does from nodes with no successors.
+    /// Stops after `max_iterations`, or once the total change in score in an iteration is below `tolerance`.
+    /// The scores sum to 1.
+    /// ```
+    /// use graph_algos::{graph, Graph, IndexedGraph};
+    /// use graph_algos::frozen::{CompressedGraph, FrozenGraph};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2],
+    ///     2 => [3],
+    ///     3 => [1],
+    ///     4 => [3],
+    /// };
+    /// let indexed = IndexedGraph::from(&graph);
+    /// let compressed = CompressedGraph::from(&FrozenGraph::from(&indexed));
+    ///
+    /// let scores = compressed.pagerank(0.85, 100, 1e-9);
+    /// let score = |u| scores[indexed.id(&u).unwrap() as usize];
+    ///
+    /// // 3 is linked to by two nodes, 4 by none
+    /// assert!(score(3) > score(1));
+    /// assert!(score(4) < score(2));
+    /// assert!((scores.iter().sum::<f64>() - 1.0).abs() < 1e-6);
+    /// ```
+    pub fn pagerank(&self, damping: f64, max_iterations: usize, tolerance: f64) -> Vec<f64> {
+        let n = self.node_count();
+        if n == 0 {
+            return Vec::new();
+        }
+
+        let mut scores = vec![1.0 / n as f64; n];
+        for _ in 0..max_iterations {
+            let mut dangling = 0.0;
+            let mut new_scores = vec![0.0; n];
+            for (u, &score) in scores.iter().enumerate() {
+                let succs = self.neighbors(u as u32);
+                if succs.len() == 0 {
+                    dangling += score;
+                    continue;
+                }
+
+                let share = damping * score / succs.len() as f64;
+                for v in succs {
+                    new_scores[v as usize] += share;
+                }
+            }
+
+            let jump = (1.0 - damping + damping * dangling) / n as f64;
+            let mut change = 0.0;
+            for (new, old) in new_scores.iter_mut().zip(&scores) {
+                *new += jump;
+                change += (*new - old).abs();
+            }
+
+            scores = new_scores;
+
+            if change < tolerance {
+                break;
+            }
+        }
+
+        scores
+    }
+}
+
+impl From<&FrozenGraph> for CompressedGraph {
+    fn from(graph: &FrozenGraph) -> Self {
+        Self::new(graph)
+    }
+}
+
+/// An iterator decoding the successors of a node in a [`CompressedGraph`]
+#[derive(Debug, Clone)]
+pub struct CompressedNeighbors<'a> {
+    bytes: &'a [u8],
+    remaining: usize,
+    previous: Option<u32>,
+    node: u32,
+}
+
+impl Iterator for CompressedNeighbors<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let delta = read_varint(&mut self.bytes);
+        let v = match self.previous {
+            Some(previous) => previous + delta as u32,
+            None => (i64::from(self.node) + unzigzag(delta)) as u32,
+        };
+        self.previous = Some(v);
+
+        Some(v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for CompressedNeighbors<'_> {}
+
+/// Appends `value` as a LEB128 variable length integer, 7 bits per byte with the top bit set on all but the last
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Reads a variable length integer from the front of `bytes`, advancing past it
+fn read_varint(bytes: &mut &[u8]) -> u64 {
+    let mut value = 0;
+    let mut shift = 0;
+    loop {
+        let byte = bytes[0];
+        *bytes = &bytes[1..];
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte < 0x80 {
+            return value;
+        }
+        shift += 7;
+    }
+}
+
+/// Maps signed integers to unsigned ones so that small magnitudes stay small
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Reverses [`zigzag`]
+fn unzigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
@@ -0,0 +1,295 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use super::FrozenGraph;
+use crate::{error::Cancelled, AlgoControl};
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+};
+
+/// An exact distance oracle for a [`FrozenGraph`] using pruned landmark labeling
+///
+/// Every node gets two labels, the hubs it can reach and the hubs which can reach it,
+/// each with its distance, such that every shortest path passes through a hub
+/// in the out label of its start and the in label of its end (a 2-hop cover).
+/// A distance query merges two sorted labels, taking microseconds even on graphs
+/// where a single Dijkstra search takes far longer.
+///
+/// The labels are built by running a Dijkstra search forwards and backwards from each node in turn,
+/// from the highest degree down, and pruning the search wherever the labels built so far
+/// already give a distance at least as short.
+/// On graphs with a few well connected hubs, like social networks and road networks,
+/// this keeps the labels small, but on graphs without that structure they can grow to the size of the graph.
+/// ```
+/// use graph_algos::{graph, Graph, IndexedGraph};
+/// use graph_algos::frozen::{FrozenGraph, PrunedLandmarkIndex};
+///
+/// let graph: Graph<&str> = graph! {
+///     "a" => ["b" => 4, "c" => 1],
+///     "c" => ["b" => 2, "d" => 7],
+///     "b" => ["d" => 1],
+/// };
+/// let indexed = IndexedGraph::from(&graph);
+/// let frozen = FrozenGraph::from(&indexed);
+/// let index = PrunedLandmarkIndex::new(&frozen);
+///
+/// let id = |u| indexed.id(&u).unwrap();
+/// assert_eq!(index.distance(id("a"), id("d")), Some(4));
+/// assert_eq!(index.distance(id("c"), id("c")), Some(0));
+/// assert_eq!(index.distance(id("d"), id("a")), None);
+///
+/// // the same as searching the graph
+/// for u in 0..frozen.node_count() as u32 {
+///     let distances = frozen.dijkstra(u);
+///     for v in 0..frozen.node_count() as u32 {
+///         assert_eq!(index.distance(u, v), distances[v as usize]);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PrunedLandmarkIndex {
+    out_labels: Labels,
+    in_labels: Labels,
+}
+
+/// The labels of every node, stored contiguously and sorted by hub rank
+#[derive(Debug, Clone, Default)]
+struct Labels {
+    offsets: Vec<usize>,
+    hubs: Vec<u32>,
+    distances: Vec<i64>,
+}
+
+impl Labels {
+    /// Flattens the labels built for each node
+    fn new(labels: Vec<Vec<(u32, i64)>>) -> Self {
+        let mut flat = Self::default();
+        flat.offsets.push(0);
+        for label in labels {
+            for (hub, distance) in label {
+                flat.hubs.push(hub);
+                flat.distances.push(distance);
+            }
+            flat.offsets.push(flat.hubs.len());
+        }
+
+        flat
+    }
+
+    /// Returns the hub ranks and distances in the label of `u`
+    fn get(&self, u: u32) -> (&[u32], &[i64]) {
+        let range = self.offsets[u as usize]..self.offsets[u as usize + 1];
+        (&self.hubs[range.clone()], &self.distances[range])
+    }
+}
+
+/// The edges of a graph grouped by node in compressed sparse row form
+struct Adjacency {
+    offsets: Vec<usize>,
+    edges: Vec<(u32, i64)>,
+}
+
+impl Adjacency {
+    /// Collects the edges of the frozen graph, reversed if `reverse` is set
+    fn new(graph: &FrozenGraph, reverse: bool) -> Self {
+        let n = graph.node_count();
+        let mut edges = Vec::with_capacity(graph.edge_count());
+        for u in 0..n as u32 {
+            for (v, w) in graph.neighbors_weighted(u) {
+                assert!(
+                    w >= 0,
+                    "PrunedLandmarkIndex needs non-negative weights, found {}",
+                    w
+                );
+                edges.push(if reverse { (v, u, w) } else { (u, v, w) });
+            }
+        }
+        edges.sort_unstable_by_key(|&(u, v, _)| (u, v));
+
+        let mut offsets = vec![0; n + 1];
+        for &(u, _, _) in &edges {
+            offsets[u as usize + 1] += 1;
+        }
+        for i in 0..n {
+            offsets[i + 1] += offsets[i];
+        }
+
+        Self {
+            offsets,
+            edges: edges.into_iter().map(|(_, v, w)| (v, w)).collect(),
+        }
+    }
+
+    fn edges(&self, u: u32) -> &[(u32, i64)] {
+        &self.edges[self.offsets[u as usize]..self.offsets[u as usize + 1]]
+    }
+}
+
+impl PrunedLandmarkIndex {
+    /// Builds the labels of every node in the graph
+    ///
+    /// The edges of an unweighted graph all have weight 1.
+    ///
+    /// Panics if any edge has a negative weight.
+    pub fn new(graph: &FrozenGraph) -> Self {
+        Self::new_with_control(graph, &AlgoControl::new())
+            .expect("a fresh AlgoControl is never cancelled")
+    }
+
+    /// Builds the labels of every node in the graph, reporting progress to `control`
+    /// and stopping early if it is cancelled
+    ///
+    /// Progress is counted in the hubs whose searches have finished, out of every node in the graph.
+    /// See [`PrunedLandmarkIndex::new`] and [`AlgoControl`].
+    /// ```
+    /// use graph_algos::{generators, AlgoControl, IndexedGraph};
+    /// use graph_algos::frozen::{FrozenGraph, PrunedLandmarkIndex};
+    /// use graph_algos::error::Cancelled;
+    ///
+    /// let graph = generators::cycle_graph(100);
+    /// let frozen = FrozenGraph::from(&IndexedGraph::from(&graph));
+    ///
+    /// let control = AlgoControl::new();
+    /// control.cancel();
+    /// assert!(matches!(PrunedLandmarkIndex::new_with_control(&frozen, &control), Err(Cancelled)));
+    /// ```
+    pub fn new_with_control(graph: &FrozenGraph, control: &AlgoControl) -> Result<Self, Cancelled> {
+        let n = graph.node_count();
+        let forward = Adjacency::new(graph, false);
+        let backward = Adjacency::new(graph, true);
+
+        // the best hubs are the nodes with the most edges, which lie on the most shortest paths
+        let mut order: Vec<u32> = (0..n as u32).collect();
+        order.sort_unstable_by_key(|&u| Reverse(forward.edges(u).len() + backward.edges(u).len()));
+
+        let mut out_labels: Vec<Vec<(u32, i64)>> = vec![Vec::new(); n];
+        let mut in_labels: Vec<Vec<(u32, i64)>> = vec![Vec::new(); n];
+
+        // scratch space reused by every search
+        let mut hub_distances: Vec<Option<i64>> = vec![None; n];
+        let mut distances: Vec<Option<i64>> = vec![None; n];
+        let mut visited = Vec::new();
+        let mut queue = BinaryHeap::new();
+
+        for (rank, &hub) in order.iter().enumerate() {
+            control.step(rank, n)?;
+            let rank = rank as u32;
+
+            // the forward search adds the hub to the in labels of the nodes it reaches,
+            // then the backward search adds it to the out labels of the nodes reaching it
+            for &(adjacency, is_forward) in &[(&forward, true), (&backward, false)] {
+                let (hub_label, labels) = if is_forward {
+                    (&out_labels[hub as usize], &mut in_labels)
+                } else {
+                    (&in_labels[hub as usize], &mut out_labels)
+                };
+                let hub_label = hub_label.clone();
+                for &(r, d) in &hub_label {
+                    hub_distances[r as usize] = Some(d);
+                }
+
+                distances[hub as usize] = Some(0);
+                visited.push(hub);
+                queue.push(Reverse((0, hub)));
+
+                while let Some(Reverse((distance, u))) = queue.pop() {
+                    // skip stale entries for nodes which have since got closer
+                    if distances[u as usize] < Some(distance) {
+                        continue;
+                    }
+
+                    // prune if an earlier hub already covers this pair
+                    let covered = labels[u as usize].iter().any(|&(r, d)| {
+                        matches!(hub_distances[r as usize], Some(hd) if hd + d <= distance)
+                    });
+                    if covered {
+                        continue;
+                    }
+                    labels[u as usize].push((rank, distance));
+
+                    for &(v, w) in adjacency.edges(u) {
+                        let new_distance = distance + w;
+                        if !matches!(distances[v as usize], Some(d) if d <= new_distance) {
+                            if distances[v as usize].is_none() {
+                                visited.push(v);
+                            }
+                            distances[v as usize] = Some(new_distance);
+                            queue.push(Reverse((new_distance, v)));
+                        }
+                    }
+                }
+
+                for u in visited.drain(..) {
+                    distances[u as usize] = None;
+                }
+                for &(r, _) in &hub_label {
+                    hub_distances[r as usize] = None;
+                }
+            }
+        }
+
+        control.step(n, n)?;
+
+        Ok(Self {
+            out_labels: Labels::new(out_labels),
+            in_labels: Labels::new(in_labels),
+        })
+    }
+
+    /// Returns the number of nodes in the indexed graph
+    pub fn node_count(&self) -> usize {
+        self.out_labels.offsets.len() - 1
+    }
+
+    /// Returns the total number of entries in all the labels,
+    /// each of which takes 12 bytes
+    pub fn label_entries(&self) -> usize {
+        self.out_labels.hubs.len() + self.in_labels.hubs.len()
+    }
+
+    /// Returns the length of the shortest path from `u` to `v`,
+    /// or `None` if there isn't one or either node isn't in the graph
+    pub fn distance(&self, u: u32, v: u32) -> Option<i64> {
+        if u as usize >= self.node_count() || v as usize >= self.node_count() {
+            return None;
+        }
+
+        let (out_hubs, out_distances) = self.out_labels.get(u);
+        let (in_hubs, in_distances) = self.in_labels.get(v);
+
+        // both labels are sorted by hub rank, so the common hubs are found by merging them
+        let (mut i, mut j) = (0, 0);
+        let mut best = None;
+        while i < out_hubs.len() && j < in_hubs.len() {
+            match out_hubs[i].cmp(&in_hubs[j]) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => {
+                    let distance = out_distances[i] + in_distances[j];
+                    best = Some(match best {
+                        Some(b) if b <= distance => b,
+                        _ => distance,
+                    });
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+
+        best
+    }
+}
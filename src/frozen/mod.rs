@@ -0,0 +1,734 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! A compact read-only graph format which can be memory-mapped.
+//!
+//! A [`FrozenGraph`] stores its edges in compressed sparse row form, with the edges of every node
+//! stored contiguously and found through an offset table rather than pointers.
+//! The same bytes are used in memory and on disk, so with the `mmap` feature a frozen graph
+//! written to a file can be opened with [`FrozenGraph::open_mmap`] and queried straight away,
+//! with the operating system paging in only the parts of the file which are used.
+//!
+//! The nodes are the dense `u32` ids of an [`IndexedGraph`], whose interner maps them back to the original nodes.
+//! For repeated distance queries a [`PrunedLandmarkIndex`] can be built from a frozen graph,
+//! and for traversals of huge unweighted graphs a [`CompressedGraph`] holds the same edges in far less memory.
+//!
+//! ```
+//! use graph_algos::{graph, Graph, IndexedGraph};
+//! use graph_algos::frozen::FrozenGraph;
+//!
+//! let graph: Graph<&str> = graph! {
+//!     "a" => ["b" => 4, "c" => 1],
+//!     "c" => ["b" => 2],
+//! };
+//! let indexed = IndexedGraph::from(&graph);
+//!
+//! let mut bytes = Vec::new();
+//! FrozenGraph::write(&indexed, &mut bytes).unwrap();
+//! let frozen = FrozenGraph::from_bytes(bytes).unwrap();
+//!
+//! let (a, b) = (indexed.id(&"a").unwrap(), indexed.id(&"b").unwrap());
+//! assert_eq!(frozen.dijkstra(a)[b as usize], Some(3));
+//! ```
+
+mod compressed;
+pub use compressed::{CompressedGraph, CompressedNeighbors};
+
+mod labeling;
+pub use labeling::PrunedLandmarkIndex;
+
+use crate::{
+    algos,
+    visit::{EdgeWeighted, GraphRef, IntoNeighbors, IntoNodes},
+    EdgeWeight, IndexedGraph, NodeBounds,
+};
+use std::{
+    convert::TryInto,
+    fmt,
+    io::{self, Read, Write},
+    ops::Range,
+    slice,
+};
+
+/// The first bytes of every frozen graph
+const MAGIC: &[u8; 8] = b"GAFROZEN";
+
+/// The version of the layout written by this version of the crate
+const VERSION: u32 = 2;
+
+/// The header flag marking a graph with edge weights
+const WEIGHTED: u32 = 1;
+
+/// The size of the header: the magic, version, flags, node count and edge count
+const HEADER_LEN: usize = 32;
+
+/// The bytes behind a frozen graph
+enum Storage {
+    Owned(Vec<u8>),
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+impl Storage {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Self::Owned(bytes) => bytes,
+            #[cfg(feature = "mmap")]
+            Self::Mapped(map) => map,
+        }
+    }
+}
+
+/// A read-only directed graph over `u32` node ids in the frozen graph format
+///
+/// The layout, with every integer little-endian, is a 32 byte header
+/// (the magic `GAFROZEN`, a `u32` version, `u32` flags, a `u64` node count and a `u64` edge count),
+/// then a `u64` offset into the edges for each node plus one for the end,
+/// then the `u32` id of each node in order, padded to a multiple of 8 bytes,
+/// then the `u32` destination of each edge, padded the same way,
+/// and for weighted graphs the `i64` weight of each edge.
+///
+/// Only the header and the sizes of the tables are checked when a graph is loaded,
+/// so that opening a huge graph doesn't read all of it.
+/// The visit traits hand out references to nodes in the id table rather than copying it,
+/// except on big-endian targets where it can't be read in place.
+/// Queries on a corrupted graph may return nonsense or panic.
+///
+/// A `&FrozenGraph` implements the traits in [`crate::visit`], so the algorithms in [`crate::algos`]
+/// run over it directly, with unweighted edges having weight 1.
+/// ```
+/// use graph_algos::{graph, Graph, IndexedGraph};
+/// use graph_algos::algos::strongly_connected_components;
+/// use graph_algos::frozen::FrozenGraph;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [1, 3],
+/// };
+/// let frozen = FrozenGraph::from(&IndexedGraph::from(&graph));
+///
+/// assert_eq!(strongly_connected_components(&frozen).len(), 2);
+/// ```
+pub struct FrozenGraph {
+    storage: Storage,
+    nodes: usize,
+    edges: usize,
+    weighted: bool,
+    /// A copy of the id table, made when loading only if the table can't be read in place
+    ids_copy: Option<Vec<u32>>,
+}
+
+impl fmt::Debug for FrozenGraph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrozenGraph")
+            .field("nodes", &self.nodes)
+            .field("edges", &self.edges)
+            .field("weighted", &self.weighted)
+            .field("mapped", &!matches!(self.storage, Storage::Owned(_)))
+            .finish()
+    }
+}
+
+impl FrozenGraph {
+    /// Writes an indexed graph in the frozen graph format
+    ///
+    /// The graph is weighted if all of its edges have a weight.
+    /// Fails with [`io::ErrorKind::InvalidInput`] if only some of the edges have weights,
+    /// or any weight is infinite.
+    pub fn write<N: NodeBounds, W: Write>(graph: &IndexedGraph<N>, writer: W) -> io::Result<()> {
+        let mut writer = io::BufWriter::new(writer);
+        let ids = 0..graph.len() as u32;
+        let edges: usize = ids.clone().map(|u| graph.succs(u).len()).sum();
+
+        let weights: Vec<Option<EdgeWeight>> = ids
+            .clone()
+            .flat_map(|u| graph.succs(u).iter().map(|edge| edge.weight()))
+            .collect();
+        let weighted = match (
+            weights.iter().all(Option::is_some),
+            weights.iter().all(Option::is_none),
+        ) {
+            (true, _) if !weights.is_empty() => true,
+            (_, true) => false,
+            _ => {
+                return Err(invalid_input(
+                    "either every edge or no edge must have a weight",
+                ))
+            }
+        };
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&(if weighted { WEIGHTED } else { 0 }).to_le_bytes())?;
+        writer.write_all(&(graph.len() as u64).to_le_bytes())?;
+        writer.write_all(&(edges as u64).to_le_bytes())?;
+
+        let mut offset = 0_u64;
+        writer.write_all(&offset.to_le_bytes())?;
+        for u in ids.clone() {
+            offset += graph.succs(u).len() as u64;
+            writer.write_all(&offset.to_le_bytes())?;
+        }
+
+        for u in ids.clone() {
+            writer.write_all(&u.to_le_bytes())?;
+        }
+        if graph.len() % 2 == 1 {
+            writer.write_all(&[0; 4])?;
+        }
+
+        for u in ids {
+            for edge in graph.succs(u) {
+                writer.write_all(&edge.destination().to_le_bytes())?;
+            }
+        }
+        if edges % 2 == 1 {
+            writer.write_all(&[0; 4])?;
+        }
+
+        if weighted {
+            for weight in weights.into_iter().flatten() {
+                match weight {
+                    EdgeWeight::Weight(w) => writer.write_all(&w.to_le_bytes())?,
+                    _ => return Err(invalid_input("frozen graphs can't hold infinite weights")),
+                }
+            }
+        }
+
+        writer.flush()
+    }
+
+    /// Loads a frozen graph from bytes in the frozen graph format
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] if the header is wrong
+    /// or the bytes are the wrong length for it.
+    pub fn from_bytes(bytes: Vec<u8>) -> io::Result<Self> {
+        Self::new(Storage::Owned(bytes))
+    }
+
+    /// Reads a whole frozen graph into memory from `reader`
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Self::from_bytes(bytes)
+    }
+
+    /// Memory-maps a frozen graph written to a file
+    ///
+    /// Only the header is read straight away,
+    /// the rest of the file is paged in by the operating system as the graph is queried.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the graph is in use,
+    /// as changes would show up in memory the graph assumes is immutable.
+    /// ```
+    /// use graph_algos::{graph, Graph, IndexedGraph};
+    /// use graph_algos::frozen::FrozenGraph;
+    /// use std::fs::File;
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2, 3],
+    ///     2 => [3],
+    /// };
+    /// let indexed = IndexedGraph::from(&graph);
+    ///
+    /// let name = format!("graph_algos_open_mmap_doctest_{}.frozen", std::process::id());
+    /// let path = std::env::temp_dir().join(name);
+    /// FrozenGraph::write(&indexed, File::create(&path).unwrap()).unwrap();
+    ///
+    /// // nothing else touches the file while it is mapped
+    /// let frozen = unsafe { FrozenGraph::open_mmap(&path) }.unwrap();
+    /// assert_eq!(frozen.edge_count(), 3);
+    ///
+    /// let one = indexed.id(&1).unwrap();
+    /// assert_eq!(frozen.neighbors(one).len(), 2);
+    /// # drop(frozen);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    #[cfg(feature = "mmap")]
+    pub unsafe fn open_mmap<P: AsRef<std::path::Path>>(path: P) -> io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        Self::new(Storage::Mapped(memmap2::Mmap::map(&file)?))
+    }
+
+    /// Checks the header against the length of the storage
+    fn new(storage: Storage) -> io::Result<Self> {
+        let bytes = storage.bytes();
+        if bytes.len() < HEADER_LEN || &bytes[..8] != MAGIC {
+            return Err(invalid_data("not a frozen graph"));
+        }
+
+        let version = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+        if version != VERSION {
+            return Err(invalid_data("unsupported frozen graph version"));
+        }
+
+        let weighted = u32::from_le_bytes(bytes[12..16].try_into().unwrap()) & WEIGHTED != 0;
+        let nodes = u64::from_le_bytes(bytes[16..24].try_into().unwrap());
+        let edges = u64::from_le_bytes(bytes[24..32].try_into().unwrap());
+
+        let expected = layout(nodes, edges, weighted)
+            .map(|sections| sections.end)
+            .filter(|&len| len == bytes.len() as u64);
+        if expected.is_none() {
+            return Err(invalid_data("frozen graph has the wrong length"));
+        }
+
+        let mut graph = Self {
+            storage,
+            nodes: nodes as usize,
+            edges: edges as usize,
+            weighted,
+            ids_copy: None,
+        };
+        if graph.id_table().is_none() {
+            graph.ids_copy = Some((0..graph.nodes as u32).collect());
+        }
+
+        Ok(graph)
+    }
+
+    /// Returns the number of nodes, whose ids are `0..node_count`
+    pub fn node_count(&self) -> usize {
+        self.nodes
+    }
+
+    /// Returns the number of edges
+    pub fn edge_count(&self) -> usize {
+        self.edges
+    }
+
+    /// Returns whether the edges have weights
+    pub fn is_weighted(&self) -> bool {
+        self.weighted
+    }
+
+    /// Returns every node id in order
+    fn ids(&self) -> &[u32] {
+        match &self.ids_copy {
+            Some(ids) => ids,
+            None => self.id_table().expect("id table was checked when loading"),
+        }
+    }
+
+    /// Returns the id table as it is stored, or `None` if it can't be read in place
+    /// because the target is big-endian or the bytes aren't aligned
+    fn id_table(&self) -> Option<&[u32]> {
+        let start = HEADER_LEN + 8 * (self.nodes + 1);
+        let bytes = &self.storage.bytes()[start..start + 4 * self.nodes];
+
+        // SAFETY: every bit pattern is a valid u32
+        let (prefix, ids, _) = unsafe { bytes.align_to::<u32>() };
+        Some(ids).filter(|ids| {
+            cfg!(target_endian = "little") && prefix.is_empty() && ids.len() == self.nodes
+        })
+    }
+
+    /// Returns the range of indices into the edge tables for a node's edges,
+    /// which is empty for unknown nodes
+    fn edge_range(&self, u: u32) -> Range<usize> {
+        let u = u as usize;
+        if u >= self.nodes {
+            return 0..0;
+        }
+
+        let bytes = &self.storage.bytes()[HEADER_LEN + 8 * u..];
+        let start = u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize;
+        let end = u64::from_le_bytes(bytes[8..16].try_into().unwrap()) as usize;
+        start..end
+    }
+
+    /// Returns the number of edges leaving a node
+    pub fn degree(&self, u: u32) -> usize {
+        self.edge_range(u).len()
+    }
+
+    /// Returns an iterator over the successors of a node
+    ///
+    /// Unknown nodes have no successors.
+    pub fn neighbors(&self, u: u32) -> FrozenNeighbors<'_> {
+        let range = self.edge_range(u);
+        let targets = HEADER_LEN + 8 * (self.nodes + 1) + 4 * (self.nodes + self.nodes % 2);
+        let bytes = &self.storage.bytes()[targets + 4 * range.start..targets + 4 * range.end];
+
+        FrozenNeighbors {
+            inner: bytes.chunks_exact(4),
+        }
+    }
+
+    /// Returns an iterator over the successors of a node and the weights of the edges to them
+    ///
+    /// The edges of an unweighted graph all have weight 1.
+    /// ```
+    /// use graph_algos::{graph, Graph, IndexedGraph};
+    /// use graph_algos::frozen::FrozenGraph;
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2 => 7],
+    /// };
+    /// let indexed = IndexedGraph::from(&graph);
+    /// let frozen = FrozenGraph::from(&indexed);
+    ///
+    /// let (one, two) = (indexed.id(&1).unwrap(), indexed.id(&2).unwrap());
+    /// assert_eq!(frozen.neighbors_weighted(one).collect::<Vec<_>>(), vec![(two, 7)]);
+    /// ```
+    pub fn neighbors_weighted(&self, u: u32) -> FrozenNeighborsWeighted<'_> {
+        let range = self.edge_range(u);
+        let weights = if self.weighted {
+            let start = layout(self.nodes as u64, self.edges as u64, true)
+                .unwrap()
+                .start as usize;
+            Some(&self.storage.bytes()[start + 8 * range.start..start + 8 * range.end])
+        } else {
+            None
+        };
+
+        FrozenNeighborsWeighted {
+            neighbors: self.neighbors(u),
+            weights: weights.map(|bytes| bytes.chunks_exact(8)),
+        }
+    }
+
+    /// Finds the distance of every node from `source` with Dijkstra's algorithm,
+    /// indexed by node id, with `None` for the nodes which can't be reached
+    ///
+    /// The edge weights must not be negative, and the edges of an unweighted graph all have weight 1.
+    /// This runs [`algos::dijkstra`] over the graph and lays the distances out by id.
+    pub fn dijkstra(&self, source: u32) -> Vec<Option<i64>> {
+        let mut distances = vec![None; self.nodes];
+        if let Some(source) = self.ids().get(source as usize) {
+            for (&v, distance) in algos::dijkstra(self, source).1 {
+                if let EdgeWeight::Weight(d) = distance {
+                    distances[v as usize] = Some(d);
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// Finds the distance of every node from `source` with the Bellman-Ford algorithm,
+    /// indexed by node id, with `None` for the nodes which can't be reached
+    ///
+    /// Unlike [`FrozenGraph::dijkstra`] the edge weights can be negative,
+    /// and the edges of an unweighted graph all have weight 1.
+    /// Each round relaxes the edges in the order they are stored, reading them straight out
+    /// of the edge tables rather than looking nodes up in a map, and new distances are used
+    /// as soon as they are found, so most graphs need only a few rounds.
+    /// Returns `None` if there is a negative cycle reachable from `source`.
+    /// ```
+    /// use graph_algos::{graph, Graph, IndexedGraph};
+    /// use graph_algos::frozen::FrozenGraph;
+    ///
+    /// let graph: Graph<&str> = graph! {
+    ///     "a" => ["b" => 4, "c" => 2],
+    ///     "b" => ["d" => 1],
+    ///     "c" => ["b" => -3],
+    /// };
+    /// let indexed = IndexedGraph::from(&graph);
+    /// let frozen = FrozenGraph::from(&indexed);
+    /// let id = |u| indexed.id(&u).unwrap();
+    ///
+    /// let distances = frozen.bellman_ford(id("a")).unwrap();
+    /// assert_eq!(distances[id("d") as usize], Some(0));
+    ///
+    /// let cyclic: Graph<u32> = graph! {
+    ///     1 => [2 => 1],
+    ///     2 => [1 => -2],
+    /// };
+    /// let indexed = IndexedGraph::from(&cyclic);
+    /// assert!(FrozenGraph::from(&indexed).bellman_ford(0).is_none());
+    /// ```
+    pub fn bellman_ford(&self, source: u32) -> Option<Vec<Option<i64>>> {
+        let mut distances = vec![None; self.nodes];
+        if source as usize >= self.nodes {
+            return Some(distances);
+        }
+        distances[source as usize] = Some(0);
+
+        // without negative cycles every shortest path has fewer than `nodes` edges,
+        // so a change in the round after that means there is one
+        for _ in 0..self.nodes {
+            let mut changed = false;
+            for u in 0..self.nodes as u32 {
+                let distance = match distances[u as usize] {
+                    Some(distance) => distance,
+                    None => continue,
+                };
+
+                for (v, w) in self.neighbors_weighted(u) {
+                    let new_distance = distance.saturating_add(w);
+                    if !matches!(distances[v as usize], Some(d) if d <= new_distance) {
+                        distances[v as usize] = Some(new_distance);
+                        changed = true;
+                    }
+                }
+            }
+
+            if !changed {
+                return Some(distances);
+            }
+        }
+
+        None
+    }
+
+    /// Finds the distance of every node from `source` with the Bellman-Ford algorithm,
+    /// relaxing the edges out of different nodes in parallel
+    ///
+    /// Gives the same result as [`FrozenGraph::bellman_ford`].
+    /// ```
+    /// use graph_algos::{generators, IndexedGraph};
+    /// use graph_algos::frozen::FrozenGraph;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let mut rng = StdRng::seed_from_u64(42);
+    /// let graph = generators::gnm_random(1000, 5000, 20, &mut rng);
+    /// let frozen = FrozenGraph::from(&IndexedGraph::from(&graph));
+    ///
+    /// assert_eq!(frozen.par_bellman_ford(0), frozen.bellman_ford(0));
+    /// ```
+    #[cfg(feature = "parallel")]
+    pub fn par_bellman_ford(&self, source: u32) -> Option<Vec<Option<i64>>> {
+        use rayon::prelude::*;
+        use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+
+        /// Stands in for the distance of the nodes which haven't been reached
+        const UNREACHED: i64 = i64::MAX;
+
+        let distances: Vec<AtomicI64> =
+            (0..self.nodes).map(|_| AtomicI64::new(UNREACHED)).collect();
+        if source as usize >= self.nodes {
+            return Some(vec![None; self.nodes]);
+        }
+        distances[source as usize].store(0, Ordering::Relaxed);
+
+        let mut converged = false;
+        for _ in 0..self.nodes {
+            let changed = AtomicBool::new(false);
+            (0..self.nodes as u32)
+                .into_par_iter()
+                .with_min_len(1024)
+                .for_each(|u| {
+                    let distance = distances[u as usize].load(Ordering::Relaxed);
+                    if distance == UNREACHED {
+                        return;
+                    }
+
+                    for (v, w) in self.neighbors_weighted(u) {
+                        // distances only ever go down, so racing relaxations settle on the smallest
+                        let new_distance = distance.saturating_add(w).min(UNREACHED - 1);
+                        if distances[v as usize].fetch_min(new_distance, Ordering::Relaxed)
+                            > new_distance
+                        {
+                            changed.store(true, Ordering::Relaxed);
+                        }
+                    }
+                });
+
+            if !changed.into_inner() {
+                converged = true;
+                break;
+            }
+        }
+
+        if !converged {
+            return None;
+        }
+
+        Some(
+            distances
+                .into_iter()
+                .map(|d| Some(d.into_inner()).filter(|&d| d != UNREACHED))
+                .collect(),
+        )
+    }
+}
+
+impl<N: NodeBounds> From<&IndexedGraph<N>> for FrozenGraph {
+    /// Freezes an indexed graph in memory
+    ///
+    /// Panics if only some of the edges have weights, or any weight is infinite.
+    fn from(graph: &IndexedGraph<N>) -> Self {
+        let mut bytes = Vec::new();
+        Self::write(graph, &mut bytes).expect("graph can't be frozen");
+        Self::from_bytes(bytes).expect("freshly written frozen graph is valid")
+    }
+}
+
+impl<'a> GraphRef<'a> for &'a FrozenGraph {
+    type Node = u32;
+
+    fn contains_node(self, u: &u32) -> bool {
+        (*u as usize) < self.nodes
+    }
+}
+
+impl<'a> IntoNodes<'a> for &'a FrozenGraph {
+    type Nodes = slice::Iter<'a, u32>;
+
+    fn nodes(self) -> Self::Nodes {
+        self.ids().iter()
+    }
+
+    fn node_count(self) -> usize {
+        self.nodes
+    }
+}
+
+impl<'a> IntoNeighbors<'a> for &'a FrozenGraph {
+    type Neighbors = FrozenNeighborRefs<'a>;
+
+    fn neighbors(self, u: &u32) -> Self::Neighbors {
+        FrozenNeighborRefs {
+            ids: self.ids(),
+            inner: FrozenGraph::neighbors(self, *u),
+        }
+    }
+}
+
+impl<'a> EdgeWeighted<'a> for &'a FrozenGraph {
+    type NeighborsWeighted = FrozenNeighborRefsWeighted<'a>;
+
+    fn neighbors_weighted(self, u: &u32) -> Self::NeighborsWeighted {
+        FrozenNeighborRefsWeighted {
+            ids: self.ids(),
+            inner: FrozenGraph::neighbors_weighted(self, *u),
+        }
+    }
+}
+
+/// Returns the range of bytes holding the weights, whose end is the length of the whole graph,
+/// or `None` if the sizes overflow
+fn layout(nodes: u64, edges: u64, weighted: bool) -> Option<Range<u64>> {
+    let offsets = nodes.checked_add(1)?.checked_mul(8)?;
+    // the ids and destinations are padded to keep the tables after them aligned
+    let ids = nodes.checked_add(nodes % 2)?.checked_mul(4)?;
+    let targets = edges.checked_add(edges % 2)?.checked_mul(4)?;
+    let weights = if weighted { edges.checked_mul(8)? } else { 0 };
+
+    let start = (HEADER_LEN as u64)
+        .checked_add(offsets)?
+        .checked_add(ids)?
+        .checked_add(targets)?;
+    Some(start..start.checked_add(weights)?)
+}
+
+/// Creates an error for a graph which can't be frozen
+fn invalid_input(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+/// Creates an error for bytes which aren't a valid frozen graph
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// An iterator over the successors of a node in a [`FrozenGraph`]
+#[derive(Debug, Clone)]
+pub struct FrozenNeighbors<'a> {
+    inner: std::slice::ChunksExact<'a, u8>,
+}
+
+impl Iterator for FrozenNeighbors<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl ExactSizeIterator for FrozenNeighbors<'_> {}
+
+/// An iterator over the successors of a node in a [`FrozenGraph`] and the weights of the edges to them
+#[derive(Debug, Clone)]
+pub struct FrozenNeighborsWeighted<'a> {
+    neighbors: FrozenNeighbors<'a>,
+    weights: Option<std::slice::ChunksExact<'a, u8>>,
+}
+
+impl Iterator for FrozenNeighborsWeighted<'_> {
+    type Item = (u32, i64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = self.neighbors.next()?;
+        let w = match &mut self.weights {
+            Some(weights) => i64::from_le_bytes(weights.next()?.try_into().unwrap()),
+            None => 1,
+        };
+
+        Some((v, w))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.neighbors.size_hint()
+    }
+}
+
+impl ExactSizeIterator for FrozenNeighborsWeighted<'_> {}
+
+/// An iterator over references to the successors of a node in a [`FrozenGraph`],
+/// used by the traits in [`crate::visit`]
+#[derive(Debug, Clone)]
+pub struct FrozenNeighborRefs<'a> {
+    ids: &'a [u32],
+    inner: FrozenNeighbors<'a>,
+}
+
+impl<'a> Iterator for FrozenNeighborRefs<'a> {
+    type Item = &'a u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|v| &self.ids[v as usize])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl ExactSizeIterator for FrozenNeighborRefs<'_> {}
+
+/// An iterator over references to the successors of a node in a [`FrozenGraph`]
+/// and the weights of the edges to them, used by the traits in [`crate::visit`]
+#[derive(Debug, Clone)]
+pub struct FrozenNeighborRefsWeighted<'a> {
+    ids: &'a [u32],
+    inner: FrozenNeighborsWeighted<'a>,
+}
+
+impl<'a> Iterator for FrozenNeighborRefsWeighted<'a> {
+    type Item = (&'a u32, Option<EdgeWeight>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|(v, w)| (&self.ids[v as usize], Some(w.into())))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl ExactSizeIterator for FrozenNeighborRefsWeighted<'_> {}
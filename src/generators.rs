@@ -0,0 +1,209 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Generators for common families of graphs.
+//!
+//! The random generators take the random number generator to use,
+//! so seeding it gives the same graph every time.
+//!
+//! ```
+//! use graph_algos::generators;
+//! use rand::{rngs::StdRng, SeedableRng};
+//!
+//! let mut rng = StdRng::seed_from_u64(42);
+//! let graph = generators::gnm_random(1000, 5000, 100, &mut rng);
+//!
+//! assert_eq!(graph.len(), 1000);
+//! assert_eq!(graph.edges().count(), 5000);
+//! ```
+
+use crate::{Edge, Graph};
+use rand::Rng;
+
+/// Generates a random graph with `nodes` nodes and `edges` edges chosen uniformly at random
+///
+/// Every edge has a weight between 1 and `max_weight` inclusive.
+/// The edges may include self loops and parallel edges.
+pub fn gnm_random<R: Rng + ?Sized>(
+    nodes: usize,
+    edges: usize,
+    max_weight: i64,
+    rng: &mut R,
+) -> Graph<usize> {
+    let mut graph = empty_with_nodes(nodes);
+
+    if nodes == 0 {
+        return graph;
+    }
+
+    for _ in 0..edges {
+        let u = rng.gen_range(0..nodes);
+        let v = rng.gen_range(0..nodes);
+        let w = rng.gen_range(1..=max_weight);
+        graph.add_edge(u, Edge::new_with_weight(v, w));
+    }
+
+    graph
+}
+
+/// Generates a random unweighted graph where each possible edge between two distinct nodes
+/// is present with probability `p`
+/// ```
+/// use graph_algos::generators;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+///
+/// let full = generators::gnp_random(10, 1.0, &mut rng);
+/// assert_eq!(full.edges().count(), 90);
+///
+/// let empty = generators::gnp_random(10, 0.0, &mut rng);
+/// assert_eq!(empty.edges().count(), 0);
+/// ```
+pub fn gnp_random<R: Rng + ?Sized>(nodes: usize, p: f64, rng: &mut R) -> Graph<usize> {
+    let mut graph = empty_with_nodes(nodes);
+
+    for u in 0..nodes {
+        for v in (0..nodes).filter(|v| *v != u) {
+            if rng.gen_bool(p.clamp(0.0, 1.0)) {
+                graph.add_edge(u, Edge::new(v));
+            }
+        }
+    }
+
+    graph
+}
+
+/// Generates a random directed acyclic graph with `nodes` nodes and `edges` weighted edges
+///
+/// Every edge goes from a smaller node to a larger one,
+/// so the nodes in ascending order are a topological ordering.
+/// ```
+/// use graph_algos::generators;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// let dag = generators::random_dag(100, 500, 10, &mut rng);
+///
+/// assert!(dag.edges().all(|(u, edge)| u < edge.destination()));
+/// ```
+pub fn random_dag<R: Rng + ?Sized>(
+    nodes: usize,
+    edges: usize,
+    max_weight: i64,
+    rng: &mut R,
+) -> Graph<usize> {
+    let mut graph = empty_with_nodes(nodes);
+
+    // a DAG on fewer than 2 nodes has no edges
+    if nodes < 2 {
+        return graph;
+    }
+
+    for _ in 0..edges {
+        let u = rng.gen_range(0..nodes - 1);
+        let v = rng.gen_range(u + 1..nodes);
+        let w = rng.gen_range(1..=max_weight);
+        graph.add_edge(u, Edge::new_with_weight(v, w));
+    }
+
+    graph
+}
+
+/// Generates the path 0 -> 1 -> ... -> n - 1
+/// ```
+/// use graph_algos::generators;
+///
+/// let path = generators::path_graph(4);
+/// assert!(path.is_edge(&0, &1));
+/// assert!(path.is_edge(&2, &3));
+/// assert_eq!(path.edges().count(), 3);
+/// ```
+pub fn path_graph(n: usize) -> Graph<usize> {
+    let mut graph = empty_with_nodes(n);
+
+    for u in 1..n {
+        graph.add_edge(u - 1, Edge::new(u));
+    }
+
+    graph
+}
+
+/// Generates the cycle 0 -> 1 -> ... -> n - 1 -> 0
+/// ```
+/// use graph_algos::generators;
+///
+/// let cycle = generators::cycle_graph(4);
+/// assert!(cycle.is_edge(&3, &0));
+/// assert_eq!(cycle.edges().count(), 4);
+/// ```
+pub fn cycle_graph(n: usize) -> Graph<usize> {
+    let mut graph = path_graph(n);
+
+    if n > 0 {
+        graph.add_edge(n - 1, Edge::new(0));
+    }
+
+    graph
+}
+
+/// Generates the complete graph on `n` nodes, with an edge between every pair of distinct nodes
+/// ```
+/// use graph_algos::generators;
+///
+/// let complete = generators::complete_graph(5);
+/// assert_eq!(complete.edges().count(), 20);
+/// ```
+pub fn complete_graph(n: usize) -> Graph<usize> {
+    let mut graph = empty_with_nodes(n);
+
+    for u in 0..n {
+        for v in (0..n).filter(|v| *v != u) {
+            graph.add_edge(u, Edge::new(v));
+        }
+    }
+
+    graph
+}
+
+/// Generates a star with an edge from the centre node 0 to each of the nodes 1 to n - 1
+/// ```
+/// use graph_algos::generators;
+///
+/// let star = generators::star_graph(5);
+/// assert_eq!(star.succs(&0).unwrap().len(), 4);
+/// ```
+pub fn star_graph(n: usize) -> Graph<usize> {
+    let mut graph = empty_with_nodes(n);
+
+    for v in 1..n {
+        graph.add_edge(0, Edge::new(v));
+    }
+
+    graph
+}
+
+/// Creates a graph containing the nodes 0 to n - 1 with no edges
+fn empty_with_nodes(n: usize) -> Graph<usize> {
+    let mut graph = Graph::with_capacity(n);
+
+    for u in 0..n {
+        graph.add_node(u);
+    }
+
+    graph
+}
@@ -0,0 +1,294 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use super::{GeoGraph, KdTree, Point};
+use crate::{
+    algos::{dijkstra_bounded, BoundedSearch},
+    EdgeWeight, NodeBounds,
+};
+use std::cmp::Ordering;
+
+/// The concavity [`isochrone`] draws its polygons with
+const ISOCHRONE_CONCAVITY: f64 = 2.0;
+
+/// The region of a [`GeoGraph`] reachable from a node within a budget, created by [`isochrone`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Isochrone<'a, N: NodeBounds> {
+    /// The search which found the region, with the distance and a shortest path to every reached node
+    pub reached: BoundedSearch<'a, N, EdgeWeight>,
+    /// The corners of a concave polygon around the positions of the reached nodes,
+    /// in order around it
+    pub polygon: Vec<Point>,
+}
+
+impl<'a, N: NodeBounds> Isochrone<'a, N> {
+    /// Returns the reached nodes with an edge leading out of the region,
+    /// where the budget ran out
+    pub fn boundary(&self) -> &[&'a N] {
+        &self.reached.frontier
+    }
+
+    /// Returns whether `u` can be reached within the budget
+    pub fn contains(&self, u: &N) -> bool {
+        self.reached.contains(u)
+    }
+}
+
+/// Finds the region reachable from `source` within `budget`, and a polygon outlining it for drawing on a map
+///
+/// The nodes are found with [`dijkstra_bounded`], so for a graph weighted by travel time
+/// this answers "where can I get to in 30 minutes".
+/// The polygon is a [`concave_hull`] of the positions of the reached nodes,
+/// which follows the shape of the region much more closely than their convex hull would.
+/// ```
+/// use graph_algos::geo::{isochrone, GeoGraph, Point};
+///
+/// // an L shaped street grid with 1 unit between junctions
+/// let mut streets = GeoGraph::new();
+/// for x in 0..6 {
+///     for y in 0..6 {
+///         if x < 2 || y < 2 {
+///             streets.add_node((x, y), Point::new(x as f64, y as f64));
+///         }
+///     }
+/// }
+/// streets.connect_within(1.0);
+///
+/// let region = isochrone(&streets, &(0, 0), 12.into());
+/// assert_eq!(region.reached.len(), 20);
+///
+/// // the polygon hugs the inside corner of the L rather than cutting across it
+/// assert!(region.polygon.contains(&Point::new(2.0, 1.0)));
+/// assert!(region.polygon.contains(&Point::new(1.0, 2.0)));
+///
+/// // with a smaller budget the far ends of the L are out of reach
+/// let region = isochrone(&streets, &(0, 0), 4.into());
+/// assert!(!region.contains(&(5, 0)) && region.contains(&(4, 0)));
+/// let mut boundary = region.boundary().to_vec();
+/// boundary.sort();
+/// assert_eq!(boundary, vec![&(0, 4), &(1, 3), &(3, 1), &(4, 0)]);
+/// ```
+pub fn isochrone<'a, N: NodeBounds>(
+    graph: &'a GeoGraph<N>,
+    source: &'a N,
+    budget: EdgeWeight,
+) -> Isochrone<'a, N> {
+    let reached = dijkstra_bounded(graph.graph(), source, budget);
+    let points: Vec<Point> = reached
+        .distances
+        .keys()
+        .filter_map(|u| graph.position(u))
+        .collect();
+    let polygon = concave_hull(&points, ISOCHRONE_CONCAVITY);
+
+    Isochrone { reached, polygon }
+}
+
+/// Returns the corners of a concave polygon containing every point, in order around it
+///
+/// The polygon starts as the convex hull and has each edge dug inwards to the nearest point inside it
+/// out of those whose distance from the nearer end of the edge is less than the edge's length divided by `concavity`,
+/// as long as digging keeps every point inside a simple polygon.
+/// Smaller values of `concavity` follow the points more closely,
+/// and infinity gives the convex hull, with the points along its edges as corners.
+/// Fewer than three distinct points, or points all on one line, give the distinct extreme points.
+/// ```
+/// use graph_algos::geo::{concave_hull, Point};
+///
+/// // a U shape
+/// let points: Vec<Point> = vec![
+///     (0.0, 0.0), (0.0, 1.0), (0.0, 2.0), (0.0, 3.0),
+///     (1.0, 0.0), (2.0, 0.0),
+///     (3.0, 0.0), (3.0, 1.0), (3.0, 2.0), (3.0, 3.0),
+///     (1.5, 1.0),
+/// ]
+/// .into_iter()
+/// .map(|(x, y)| Point::new(x, y))
+/// .collect();
+///
+/// // the convex hull keeps the points along its edges
+/// assert_eq!(concave_hull(&points, f64::INFINITY).len(), 10);
+///
+/// // the top edge is three times longer than the distance to the point in the middle of the U
+/// assert!(!concave_hull(&points, 2.0).contains(&Point::new(1.5, 1.0)));
+/// assert!(concave_hull(&points, 1.0).contains(&Point::new(1.5, 1.0)));
+/// ```
+pub fn concave_hull(points: &[Point], concavity: f64) -> Vec<Point> {
+    let mut hull = convex_hull(points);
+    if hull.len() < 3 {
+        return hull;
+    }
+
+    let mut corners = hull.clone();
+    corners.sort_by(|p, q| compare(*p, *q));
+    let mut inner = points.to_vec();
+    // sorted so ties are broken the same way whatever order the points came in
+    inner.sort_by(|p, q| compare(*p, *q));
+    inner.dedup();
+    inner.retain(|p| corners.binary_search_by(|q| compare(*q, *p)).is_err());
+
+    // the points stay in the tree once they are dug to, so are skipped from then on
+    let tree: KdTree<usize> = inner.iter().copied().zip(0..).collect();
+    let mut dug = vec![false; inner.len()];
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+
+        let mut i = 0;
+        while i < hull.len() {
+            let n = hull.len();
+            let (a, b) = (hull[i], hull[(i + 1) % n]);
+
+            // only points this close to one of the ends can be dug to
+            let reach = a.distance(b) / concavity;
+            let candidate = tree
+                .within(a, reach)
+                .into_iter()
+                .chain(tree.within(b, reach))
+                .filter(|&&(p, k)| {
+                    !dug[k] && a.distance(b) > concavity * a.distance(p).min(b.distance(p))
+                })
+                .map(|&(p, k)| (k, p, segment_distance(p, a, b)))
+                .min_by(|x, y| x.2.partial_cmp(&y.2).unwrap().then(x.0.cmp(&y.0)));
+
+            if let Some((k, p, _)) = candidate {
+                if can_dig(&hull, &tree, &dug, i, p) {
+                    hull.insert(i + 1, p);
+                    dug[k] = true;
+                    changed = true;
+                    // look at the first of the two new edges again
+                    continue;
+                }
+            }
+
+            i += 1;
+        }
+    }
+
+    hull
+}
+
+/// Returns whether replacing the edge from `hull[i]` to the next corner with edges to and from `p`
+/// keeps the polygon simple and every point inside it
+fn can_dig(hull: &[Point], tree: &KdTree<usize>, dug: &[bool], i: usize, p: Point) -> bool {
+    let n = hull.len();
+    let (a, b) = (hull[i], hull[(i + 1) % n]);
+
+    // the polygon loses the triangle a p b, so nothing else can be in it,
+    // and every point in it is no further from a than p or b are
+    let in_triangle = |q: Point| q != a && q != b && q != p && in_triangle(q, a, p, b);
+    let inner = tree
+        .within(a, a.distance(p).max(a.distance(b)))
+        .into_iter()
+        .filter(|&&(_, k)| !dug[k])
+        .map(|&(q, _)| q);
+    if inner.chain(hull.iter().copied()).any(in_triangle) {
+        return false;
+    }
+
+    // and no other edge can cross the new ones
+    (0..n).filter(|&j| j != i).all(|j| {
+        let (c, d) = (hull[j], hull[(j + 1) % n]);
+        !segments_cross(a, p, c, d) && !segments_cross(p, b, c, d)
+    })
+}
+
+/// Returns the convex hull of the points in counter-clockwise order using Andrew's monotone chain algorithm,
+/// keeping points on its edges as corners so they can be dug from
+fn convex_hull(points: &[Point]) -> Vec<Point> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|p, q| compare(*p, *q));
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted;
+    }
+    let (first, last) = (sorted[0], sorted[sorted.len() - 1]);
+    if sorted.iter().all(|&p| cross(first, last, p) == 0.0) {
+        return vec![first, last];
+    }
+
+    let mut hull: Vec<Point> = Vec::with_capacity(2 * sorted.len());
+    for pass in 0..2 {
+        let start = hull.len();
+        let points: Box<dyn Iterator<Item = &Point>> = if pass == 0 {
+            Box::new(sorted.iter())
+        } else {
+            Box::new(sorted.iter().rev())
+        };
+
+        for &p in points {
+            while hull.len() >= start + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) < 0.0
+            {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        // the last point of each chain is the first of the other
+        hull.pop();
+    }
+
+    hull
+}
+
+/// Orders points by x and then by y
+fn compare(p: Point, q: Point) -> Ordering {
+    p.x.partial_cmp(&q.x)
+        .unwrap()
+        .then(p.y.partial_cmp(&q.y).unwrap())
+}
+
+/// Returns the z component of the cross product of `a -> b` and `a -> c`,
+/// positive if `c` is to the left of `a -> b` in a y-up frame
+fn cross(a: Point, b: Point, c: Point) -> f64 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+/// Returns the distance from `p` to the segment from `a` to `b`
+fn segment_distance(p: Point, a: Point, b: Point) -> f64 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let length_squared = dx * dx + dy * dy;
+    if length_squared == 0.0 {
+        return p.distance(a);
+    }
+
+    let t = (((p.x - a.x) * dx + (p.y - a.y) * dy) / length_squared).clamp(0.0, 1.0);
+    p.distance(Point::new(a.x + t * dx, a.y + t * dy))
+}
+
+/// Returns whether `q` is inside or on the edge of the triangle `a b c`
+fn in_triangle(q: Point, a: Point, b: Point, c: Point) -> bool {
+    let (d1, d2, d3) = (cross(a, b, q), cross(b, c, q), cross(c, a, q));
+    let has_negative = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+    let has_positive = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+    !(has_negative && has_positive)
+}
+
+/// Returns whether the segments `a b` and `c d` cross at a point other than an end they share
+fn segments_cross(a: Point, b: Point, c: Point, d: Point) -> bool {
+    if a == c || a == d || b == c || b == d {
+        return false;
+    }
+
+    let (d1, d2) = (cross(a, b, c), cross(a, b, d));
+    let (d3, d4) = (cross(c, d, a), cross(c, d, b));
+    (d1 > 0.0) != (d2 > 0.0) && (d3 > 0.0) != (d4 > 0.0)
+        || d1 == 0.0 && segment_distance(c, a, b) == 0.0
+        || d2 == 0.0 && segment_distance(d, a, b) == 0.0
+        || d3 == 0.0 && segment_distance(a, c, d) == 0.0
+        || d4 == 0.0 && segment_distance(b, c, d) == 0.0
+}
@@ -0,0 +1,176 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use super::Point;
+use std::{cmp::Ordering, iter::FromIterator};
+
+/// A static two dimensional k-d tree for nearest neighbour and range queries
+///
+/// The tree is balanced when it is built, and is stored in a single `Vec`
+/// with each subtree's root at the middle of its range.
+/// ```
+/// use graph_algos::geo::{KdTree, Point};
+///
+/// let tree: KdTree<&str> = vec![
+///     (Point::new(0.0, 0.0), "origin"),
+///     (Point::new(5.0, 5.0), "middle"),
+///     (Point::new(9.0, 1.0), "corner"),
+/// ]
+/// .into_iter()
+/// .collect();
+///
+/// assert_eq!(tree.nearest(Point::new(8.0, 0.0)).map(|(_, item)| *item), Some("corner"));
+///
+/// let mut close: Vec<&str> = tree
+///     .within(Point::new(1.0, 1.0), 7.0)
+///     .into_iter()
+///     .map(|(_, item)| *item)
+///     .collect();
+/// close.sort();
+/// assert_eq!(close, vec!["middle", "origin"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct KdTree<T> {
+    points: Vec<(Point, T)>,
+}
+
+impl<T> KdTree<T> {
+    /// Builds a tree from points and the items stored at them
+    pub fn new(mut points: Vec<(Point, T)>) -> Self {
+        build(&mut points, 0);
+        Self { points }
+    }
+
+    /// Returns the number of points in the tree
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Returns whether the tree has no points
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Returns the point closest to `target` and its item, or `None` if the tree is empty
+    pub fn nearest(&self, target: Point) -> Option<&(Point, T)> {
+        let mut best = None;
+        self.nearest_in(0, self.points.len(), 0, target, &mut best);
+        best.map(|(i, _)| &self.points[i])
+    }
+
+    /// Returns every point within `radius` of `target` along with its item, in no particular order
+    pub fn within(&self, target: Point, radius: f64) -> Vec<&(Point, T)> {
+        let mut found = Vec::new();
+        self.within_in(0, self.points.len(), 0, target, radius, &mut found);
+        found
+    }
+
+    /// Searches the subtree in `lo..hi`, keeping the index and distance of the closest point so far
+    fn nearest_in(
+        &self,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        target: Point,
+        best: &mut Option<(usize, f64)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        let (point, _) = &self.points[mid];
+        let distance = point.distance(target);
+        if !matches!(*best, Some((_, d)) if d <= distance) {
+            *best = Some((mid, distance));
+        }
+
+        // search the side the target is on first, and the other only if it could be closer
+        let delta = axis(target, depth) - axis(*point, depth);
+        let (near, far) = if delta < 0.0 {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+
+        self.nearest_in(near.0, near.1, depth + 1, target, best);
+        if !matches!(*best, Some((_, d)) if d <= delta.abs()) {
+            self.nearest_in(far.0, far.1, depth + 1, target, best);
+        }
+    }
+
+    /// Collects the points of the subtree in `lo..hi` within `radius` of `target`
+    fn within_in<'a>(
+        &'a self,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        target: Point,
+        radius: f64,
+        found: &mut Vec<&'a (Point, T)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        let entry = &self.points[mid];
+        if entry.0.distance(target) <= radius {
+            found.push(entry);
+        }
+
+        let delta = axis(target, depth) - axis(entry.0, depth);
+        if delta - radius <= 0.0 {
+            self.within_in(lo, mid, depth + 1, target, radius, found);
+        }
+        if delta + radius >= 0.0 {
+            self.within_in(mid + 1, hi, depth + 1, target, radius, found);
+        }
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for KdTree<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+/// Returns the coordinate a level of the tree splits on
+fn axis(point: Point, depth: usize) -> f64 {
+    match depth % 2 {
+        0 => point.x,
+        _ => point.y,
+    }
+}
+
+/// Arranges the points so that every subtree's median is at the middle of its range
+fn build<T>(points: &mut [(Point, T)], depth: usize) {
+    if points.len() <= 1 {
+        return;
+    }
+
+    let mid = points.len() / 2;
+    points.select_nth_unstable_by(mid, |(a, _), (b, _)| compare(*a, *b, depth));
+
+    let (left, right) = points.split_at_mut(mid);
+    build(left, depth + 1);
+    build(&mut right[1..], depth + 1);
+}
+
+/// Compares two points along the axis a level of the tree splits on
+fn compare(a: Point, b: Point, depth: usize) -> Ordering {
+    axis(a, depth).total_cmp(&axis(b, depth))
+}
@@ -0,0 +1,231 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Graphs whose nodes have positions in the plane.
+//!
+//! A [`GeoGraph`] keeps a position for every node alongside the graph, which gives
+//! an admissible straight line heuristic for [`crate::algos::astar`],
+//! nearest node lookups through a [`KdTree`], and edges weighted by the distance they span.
+//! The region reachable from a node within a budget, and a polygon outlining it,
+//! are found with [`isochrone`].
+//!
+//! ```
+//! use graph_algos::geo::{GeoGraph, Point};
+//!
+//! let mut city = GeoGraph::new();
+//! city.add_node("station", Point::new(0.0, 0.0));
+//! city.add_node("market", Point::new(3.0, 4.0));
+//! city.add_node("park", Point::new(6.0, 8.0));
+//! city.add_node("harbour", Point::new(30.0, 40.0));
+//!
+//! // join every pair of places less than 6 apart
+//! assert_eq!(city.connect_within(6.0), 4);
+//!
+//! let (path, cost) = city.astar(&"station", &"park").unwrap();
+//! assert_eq!(path.to_string(), r#""station" --(5)-> "market" --(5)-> "park""#);
+//! assert_eq!(cost, 10.into());
+//!
+//! assert_eq!(city.nearest(Point::new(25.0, 30.0)), Some(&"harbour"));
+//! ```
+
+mod isochrone;
+pub use isochrone::{concave_hull, isochrone, Isochrone};
+
+mod kd_tree;
+pub use kd_tree::KdTree;
+
+pub use crate::render::Point;
+
+use crate::{algos::astar, Edge, EdgeWeight, Graph, NodeBounds, Path};
+use std::{cell::OnceCell, collections::HashMap};
+
+/// A graph with a position for every node
+///
+/// Edges added with [`GeoGraph::connect`] or [`GeoGraph::connect_within`] are weighted by
+/// the distance between their ends multiplied by the graph's scale, rounded up.
+/// The A* heuristic is the straight line distance to the goal scaled the same way and rounded down,
+/// so it never overestimates as long as no edge weighs less than the distance it spans.
+#[derive(Debug, Clone)]
+pub struct GeoGraph<N: NodeBounds> {
+    graph: Graph<N>,
+    positions: HashMap<N, Point>,
+    scale: f64,
+    // built on the first lookup after the positions change
+    index: OnceCell<KdTree<N>>,
+}
+
+impl<N: NodeBounds> Default for GeoGraph<N> {
+    fn default() -> Self {
+        Self::with_scale(1.0)
+    }
+}
+
+impl<N: NodeBounds> GeoGraph<N> {
+    /// Creates an empty graph where an edge weighs the distance it spans
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty graph where an edge weighs `scale` times the distance it spans
+    ///
+    /// A larger scale keeps more precision when the distances are small.
+    /// ```
+    /// use graph_algos::geo::{GeoGraph, Point};
+    ///
+    /// let mut graph = GeoGraph::with_scale(100.0);
+    /// graph.add_node(1, Point::new(0.0, 0.0));
+    /// graph.add_node(2, Point::new(0.5, 0.25));
+    /// graph.connect(1, 2);
+    ///
+    /// // the distance is 0.559..., which is 55.9... at this scale
+    /// assert!(graph.graph().is_edge(&1, &2));
+    /// assert_eq!(graph.astar(&1, &2).unwrap().1, 56.into());
+    /// ```
+    pub fn with_scale(scale: f64) -> Self {
+        Self {
+            graph: Graph::empty(),
+            positions: HashMap::new(),
+            scale,
+            index: OnceCell::new(),
+        }
+    }
+
+    /// Returns the underlying graph, for use with the rest of the crate's algorithms
+    pub fn graph(&self) -> &Graph<N> {
+        &self.graph
+    }
+
+    /// Returns the number of nodes in the graph
+    pub fn len(&self) -> usize {
+        self.graph.len()
+    }
+
+    /// Returns whether the graph has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.graph.is_empty()
+    }
+
+    /// Adds a node at a position, or moves it if it is already in the graph,
+    /// returning its previous position
+    ///
+    /// Moving a node doesn't change the weights of the edges already touching it.
+    pub fn add_node(&mut self, u: N, position: Point) -> Option<Point> {
+        self.index.take();
+        self.graph.add_node(u.clone());
+        self.positions.insert(u, position)
+    }
+
+    /// Returns the position of a node
+    pub fn position(&self, u: &N) -> Option<Point> {
+        self.positions.get(u).copied()
+    }
+
+    /// Adds an edge with any weight between two nodes already in the graph,
+    /// returning false and leaving the graph unchanged if either node is missing
+    pub fn add_edge(&mut self, u: N, e: Edge<N>) -> bool {
+        if !(self.positions.contains_key(&u) && self.positions.contains_key(e.destination())) {
+            return false;
+        }
+
+        self.graph.add_edge(u, e);
+        true
+    }
+
+    /// Adds an edge weighted by the distance between two nodes already in the graph,
+    /// returning false and leaving the graph unchanged if either node is missing
+    pub fn connect(&mut self, u: N, v: N) -> bool {
+        match (self.position(&u), self.position(&v)) {
+            (Some(p), Some(q)) => {
+                let w = self.edge_weight(p, q);
+                self.graph.add_edge(u, Edge::new_with_weight(v, w));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Adds an edge in each direction between every pair of distinct nodes at most `radius` apart,
+    /// weighted by the distance between them, and returns the number of edges added
+    ///
+    /// Pairs which are already joined get another edge.
+    pub fn connect_within(&mut self, radius: f64) -> usize {
+        let mut edges = Vec::new();
+
+        let index = self.index();
+        for (u, &p) in &self.positions {
+            for (q, v) in index.within(p, radius) {
+                if u != v {
+                    edges.push((u.clone(), v.clone(), self.edge_weight(p, *q)));
+                }
+            }
+        }
+
+        let added = edges.len();
+        for (u, v, w) in edges {
+            self.graph.add_edge(u, Edge::new_with_weight(v, w));
+        }
+
+        added
+    }
+
+    /// Returns the node closest to a point, or `None` if the graph is empty
+    pub fn nearest(&self, point: Point) -> Option<&N> {
+        self.index().nearest(point).map(|(_, u)| u)
+    }
+
+    /// Returns every node at most `radius` from a point, in no particular order
+    pub fn within(&self, point: Point, radius: f64) -> Vec<&N> {
+        self.index()
+            .within(point, radius)
+            .into_iter()
+            .map(|(_, u)| u)
+            .collect()
+    }
+
+    /// Returns the A* heuristic for reaching `goal`,
+    /// the scaled straight line distance to it rounded down
+    ///
+    /// Nodes without a position, or a goal without one, get an estimate of 0.
+    pub fn heuristic<'a>(&'a self, goal: &N) -> impl Fn(&N) -> EdgeWeight + 'a {
+        let goal = self.position(goal);
+        move |u| match (self.position(u), goal) {
+            (Some(p), Some(q)) => EdgeWeight::new((p.distance(q) * self.scale).floor() as i64),
+            _ => 0.into(),
+        }
+    }
+
+    /// Finds a shortest path between two nodes with A*, guided by [`GeoGraph::heuristic`]
+    ///
+    /// Returns the path and its total weight, or `None` if the goal can't be reached.
+    pub fn astar<'a>(&'a self, start: &'a N, goal: &N) -> Option<(Path<'a, N>, EdgeWeight)> {
+        astar(&self.graph, start, goal, self.heuristic(goal))
+    }
+
+    /// Returns the weight of an edge spanning two points
+    fn edge_weight(&self, p: Point, q: Point) -> i64 {
+        (p.distance(q) * self.scale).ceil() as i64
+    }
+
+    /// Returns the k-d tree of the node positions, building it if they have changed
+    fn index(&self) -> &KdTree<N> {
+        self.index.get_or_init(|| {
+            self.positions
+                .iter()
+                .map(|(u, &p)| (p, u.clone()))
+                .collect()
+        })
+    }
+}
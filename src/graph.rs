@@ -15,16 +15,25 @@
  *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use std::collections::{hash_map, HashMap};
+use std::collections::{
+    hash_map::{self, DefaultHasher, Entry, RandomState},
+    HashMap, HashSet,
+};
 
 // Trait imports
 use std::{
     fmt::{self, Debug},
-    hash::Hash,
+    hash::{BuildHasher, Hash, Hasher},
+    mem, slice,
     str::FromStr,
+    sync::Arc,
 };
 
-use crate::{Edge, EdgeWeight, ParseEdgeError};
+use crate::{
+    error::GraphParseError,
+    visit::{Neighbors, NeighborsWeighted},
+    Edge, EdgeWeight, FormatStyle,
+};
 
 /// A trait to represent all of the bounds that a node in the graph must provide
 pub trait NodeBounds: Hash + Debug + Eq + Clone {}
@@ -32,12 +41,32 @@ impl<T: Hash + Debug + Eq + Clone> NodeBounds for T {}
 
 /// A node-generic graph type implemented using an adjacency list
 /// Where the successors of a node are stored in a hashmap.
-#[derive(Debug, Clone, Eq, PartialEq)]
-pub struct Graph<N: NodeBounds> {
+///
+/// The hashmap uses the hasher built by `S`, which defaults to the same
+/// DoS resistant hasher as [`HashMap`].
+/// Graphs with small keys such as integers can be made much faster
+/// by using a faster hasher with [`Graph::with_hasher`].
+///
+/// A graph is [`Send`] and [`Sync`] whenever its nodes and hasher are,
+/// so a graph in an [`Arc`] can be read from many threads at once,
+/// see [`GraphReader`](crate::GraphReader) for a convenient way to do so.
+#[derive(Debug, Clone)]
+pub struct Graph<N: NodeBounds, S = RandomState> {
     /// the graph is backed by a hashmap from a node to a vector of nodes
-    backing_map: HashMap<N, Vec<Edge<N>>>,
+    backing_map: HashMap<N, Succs<N>, S>,
 }
 
+/// Checks at compile time that graphs can be shared between threads whenever their nodes can
+#[allow(dead_code)]
+fn assert_send_sync<N: NodeBounds + Send + Sync, S: Send + Sync>() {
+    fn is_send_sync<T: Send + Sync>() {}
+    is_send_sync::<Graph<N, S>>();
+}
+
+/// The successors of a node, which are shared between snapshots of a graph until one of them
+/// modifies the node's edges
+type Succs<N> = Arc<Vec<Edge<N>>>;
+
 /// A macro to construct graphs in a more visual way
 ///
 /// This can be used for unweighted graphs:
@@ -94,21 +123,200 @@ pub struct Graph<N: NodeBounds> {
 ///
 /// assert_eq!(graph1, graph2);
 /// ```
+///
+/// Weighted and unweighted edges can be mixed in the same list, and a node
+/// with an empty list is added to the graph even if it has no edges:
+/// ```
+/// use graph_algos::{graph, Edge, Graph};
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2, 3 => 5],
+///     4 => [],
+/// };
+///
+/// assert_eq!(graph.len(), 4);
+/// assert!(graph.succs(&4).unwrap().is_empty());
+/// assert_eq!(
+///     graph.succs(&1).unwrap(),
+///     &[Edge::new(2), Edge::new_with_weight(3, 5)],
+/// );
+/// ```
+///
+/// An optional attribute section after a `;` attaches
+/// [`Properties`](crate::property::Properties) to nodes,
+/// in which case the macro evaluates to the graph and a map from each node to its properties.
+/// Attribute keys are identifiers and values are anything convertible into a
+/// [`PropertyValue`](crate::property::PropertyValue):
+/// ```
+/// use graph_algos::{graph, property::PropertyValue};
+///
+/// let (graph, attrs) = graph! {
+///     "a" => ["b" => 2],
+///     "b" => [];
+///     "a" => { colour: "red", size: 3 },
+///     "c" => { visited: false },
+/// };
+///
+/// // nodes only mentioned in the attributes are still added to the graph
+/// assert_eq!(graph.len(), 3);
+/// assert_eq!(attrs["a"]["colour"], PropertyValue::Str("red".into()));
+/// assert_eq!(attrs["a"]["size"], PropertyValue::Int(3));
+/// assert_eq!(attrs["c"]["visited"], PropertyValue::Bool(false));
+/// assert!(!attrs.contains_key("b"));
+/// ```
+///
+/// Malformed input is rejected at compile time, such as an edge list without brackets:
+/// ```compile_fail
+/// use graph_algos::{graph, Graph};
+///
+/// let graph: Graph<u32> = graph! { 1 => 2 };
+/// ```
+///
+/// an edge with a missing weight:
+/// ```compile_fail
+/// use graph_algos::{graph, Graph};
+///
+/// let graph: Graph<u32> = graph! { 1 => [2 =>] };
+/// ```
+///
+/// or an attribute with a non-identifier key:
+/// ```compile_fail
+/// use graph_algos::graph;
+///
+/// let (graph, attrs) = graph! { 1 => [2]; 1 => { "colour": "red" } };
+/// ```
+///
+/// Listing the same edge twice is almost always a mistake in a literal,
+/// so in debug builds the macro panics when it sees an edge with the same
+/// destination and weight as an earlier one:
+/// ```should_panic
+/// use graph_algos::{graph, Graph};
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2, 3, 2],
+/// };
+/// ```
+///
+/// Parallel edges with different weights are still allowed:
+/// ```
+/// use graph_algos::{graph, Graph};
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2 => 1, 2 => 5],
+/// };
+///
+/// assert_eq!(graph.succs(&1).unwrap().len(), 2);
+/// ```
 #[macro_export]
 macro_rules! graph {
-    ($($node:expr => [$($edge:expr),* $(,)*]),* $(,)*) => {{
-        let mut graph = ::graph_algos::Graph::empty();
-        $($(graph.add_edge($node, ::graph_algos::Edge::new($edge));)*)*
+    (@edges $undirected:literal $graph:ident $node:ident) => {};
+    (@edges $undirected:literal $graph:ident $node:ident $edge:expr => $weight:expr $(, $($rest:tt)*)?) => {
+        $graph.__add_literal_edge(
+            ::core::clone::Clone::clone(&$node),
+            $crate::Edge::new_with_weight($edge, $weight),
+            $undirected,
+        );
+        $crate::graph!(@edges $undirected $graph $node $($($rest)*)?);
+    };
+    (@edges $undirected:literal $graph:ident $node:ident $edge:expr $(, $($rest:tt)*)?) => {
+        $graph.__add_literal_edge(
+            ::core::clone::Clone::clone(&$node),
+            $crate::Edge::new($edge),
+            $undirected,
+        );
+        $crate::graph!(@edges $undirected $graph $node $($($rest)*)?);
+    };
+    (@build $undirected:literal $($node:expr => [$($edges:tt)*]),* $(,)*) => {{
+        let mut graph = $crate::Graph::empty();
+        $({
+            let node = $node;
+            $crate::graph!(@edges $undirected graph node $($edges)*);
+            graph.add_node(node);
+        })*
         graph
     }};
-    ($($node:expr => [$($edge:expr => $weight:expr),* $(,)*]),* $(,)*) => {{
-        let mut graph = ::graph_algos::Graph::empty();
-        $($(graph.add_edge($node, ::graph_algos::Edge::new_with_weight($edge, $weight));)*)*
-        graph
+    (
+        @build $undirected:literal
+        $($node:expr => [$($edges:tt)*]),* $(,)* ;
+        $($attr_node:expr => { $($key:ident : $value:expr),* $(,)? }),* $(,)?
+    ) => {{
+        let mut graph = $crate::graph!(@build $undirected $($node => [$($edges)*]),*);
+        let mut attrs: ::std::collections::HashMap<_, $crate::property::Properties> =
+            ::std::collections::HashMap::new();
+        $({
+            let node = $attr_node;
+            graph.add_node(::core::clone::Clone::clone(&node));
+            let properties = attrs.entry(node).or_default();
+            $(properties.insert(
+                ::std::string::String::from(::core::stringify!($key)),
+                $crate::property::PropertyValue::from($value),
+            );)*
+        })*
+        (graph, attrs)
     }};
+    ($($tokens:tt)*) => {
+        $crate::graph!(@build false $($tokens)*)
+    };
+}
+
+/// An alias for [`graph!`] which makes it explicit that the graph is directed
+/// ```
+/// use graph_algos::{digraph, graph, Graph};
+///
+/// let graph: Graph<u32> = digraph! {
+///     1 => [2, 3 => 4],
+/// };
+///
+/// assert_eq!(graph, graph! { 1 => [2, 3 => 4] });
+/// assert!(!graph.is_edge(&2, &1));
+/// ```
+#[macro_export]
+macro_rules! digraph {
+    ($($tokens:tt)*) => {
+        $crate::graph!($($tokens)*)
+    };
+}
+
+/// Constructs an undirected graph using the same syntax as [`graph!`]
+///
+/// Every edge is added in both directions with the same weight,
+/// apart from self loops which are only added once.
+/// ```
+/// use graph_algos::{graph, ungraph, Graph};
+///
+/// let graph: Graph<u32> = ungraph! {
+///     1 => [2 => 5, 3],
+///     3 => [3],
+/// };
+///
+/// assert_eq!(
+///     graph,
+///     graph! {
+///         1 => [2 => 5, 3],
+///         2 => [1 => 5],
+///         3 => [1, 3],
+///     }
+/// );
+/// ```
+///
+/// Listing both directions of an edge adds it twice,
+/// which panics in debug builds like any other duplicate edge:
+/// ```should_panic
+/// use graph_algos::{ungraph, Graph};
+///
+/// let graph: Graph<u32> = ungraph! {
+///     1 => [2],
+///     2 => [1],
+/// };
+/// ```
+#[macro_export]
+macro_rules! ungraph {
+    ($($tokens:tt)*) => {
+        $crate::graph!(@build true $($tokens)*)
+    };
 }
 
-impl<N: NodeBounds> Graph<N> {
+impl<N: NodeBounds> Graph<N, RandomState> {
     /// creates a new empty graph
     /// ```
     /// use graph_algos::Graph;
@@ -121,6 +329,88 @@ impl<N: NodeBounds> Graph<N> {
         Default::default()
     }
 
+    /// creates a new empty graph with space for at least `nodes` nodes
+    /// ```
+    /// use graph_algos::Graph;
+    ///
+    /// let graph: Graph<u32> = Graph::with_capacity(100);
+    ///
+    /// assert!(graph.is_empty());
+    /// assert!(graph.capacity() >= 100);
+    /// ```
+    pub fn with_capacity(nodes: usize) -> Self {
+        Self {
+            backing_map: HashMap::with_capacity(nodes),
+        }
+    }
+
+    /// creates a graph from a set of nodes and a function giving the successors of each node
+    ///
+    /// Destinations of edges returned by `succ` are added to the graph even if they
+    /// aren't in `nodes`, but `succ` is only called on the nodes given.
+    /// ```
+    /// use graph_algos::{Edge, Graph};
+    ///
+    /// // a counter which wraps around at 4
+    /// let graph: Graph<u32> = Graph::from_fn(0..4, |n| vec![Edge::new((n + 1) % 4)]);
+    ///
+    /// assert_eq!(graph.len(), 4);
+    /// assert!(graph.is_edge(&3, &0));
+    /// assert_eq!(graph.edges().count(), 4);
+    /// ```
+    pub fn from_fn<I, F>(nodes: I, succ: F) -> Self
+    where
+        I: IntoIterator<Item = N>,
+        F: Fn(&N) -> Vec<Edge<N>>,
+    {
+        let nodes = nodes.into_iter();
+        let mut graph = Self::with_capacity(nodes.size_hint().0);
+
+        for node in nodes {
+            for edge in succ(&node) {
+                graph.add_edge(node.clone(), edge);
+            }
+            graph.add_node(node);
+        }
+
+        graph
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher> Graph<N, S> {
+    /// creates a new empty graph which uses the given hasher to hash the nodes
+    /// ```
+    /// use graph_algos::{Graph, Edge};
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::BuildHasherDefault;
+    ///
+    /// // any other hasher, such as FxHash, can be used in the same way
+    /// type Hasher = BuildHasherDefault<DefaultHasher>;
+    ///
+    /// let mut graph: Graph<u32, Hasher> = Graph::with_hasher(Hasher::default());
+    /// graph.add_edge(1, Edge::new(2));
+    ///
+    /// assert!(graph.is_edge(&1, &2));
+    /// ```
+    pub fn with_hasher(hasher: S) -> Self {
+        Self {
+            backing_map: HashMap::with_hasher(hasher),
+        }
+    }
+
+    /// creates a new empty graph with space for at least `nodes` nodes,
+    /// which uses the given hasher to hash the nodes
+    pub fn with_capacity_and_hasher(nodes: usize, hasher: S) -> Self {
+        Self {
+            backing_map: HashMap::with_capacity_and_hasher(nodes, hasher),
+        }
+    }
+
+    /// Returns a reference to the graph's hasher
+    pub fn hasher(&self) -> &S {
+        self.backing_map.hasher()
+    }
+
     /// adds an edge to the graph
     /// ```
     /// use graph_algos::{Graph, Edge};
@@ -135,11 +425,111 @@ impl<N: NodeBounds> Graph<N> {
     /// assert!(graph.is_edge(&5, &6));
     /// ```
     pub fn add_edge(&mut self, u: N, e: Edge<N>) {
-        self.backing_map
-            .entry(e.destination().clone())
-            .or_insert_with(Vec::new);
+        self.backing_map.entry(e.destination().clone()).or_default();
+
+        Arc::make_mut(self.backing_map.entry(u).or_default()).push(e);
+    }
+
+    /// adds an edge from a [`graph!`] literal, checking for duplicates in debug builds
+    #[doc(hidden)]
+    pub fn __add_literal_edge(&mut self, u: N, e: Edge<N>, undirected: bool) {
+        debug_assert!(
+            !matches!(self.backing_map.get(&u), Some(succs) if succs.contains(&e)),
+            "duplicate edge {:?} -> {:?} in graph literal",
+            u,
+            e.destination(),
+        );
+
+        if undirected && &u != e.destination() {
+            let reverse = match e.weight() {
+                Some(weight) => Edge::new_with_weight(u.clone(), weight),
+                None => Edge::new(u.clone()),
+            };
+            self.__add_literal_edge(e.destination().clone(), reverse, false);
+        }
+
+        self.add_edge(u, e);
+    }
+
+    /// Returns the number of nodes the graph can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.backing_map.capacity()
+    }
+
+    /// reserves space for at least `additional_nodes` more nodes
+    /// ```
+    /// use graph_algos::{Graph, Edge};
+    ///
+    /// let mut graph: Graph<u32> = Graph::empty();
+    /// graph.add_edge(1, Edge::new(2));
+    ///
+    /// graph.reserve(50);
+    /// assert!(graph.capacity() >= 52);
+    /// ```
+    pub fn reserve(&mut self, additional_nodes: usize) {
+        self.backing_map.reserve(additional_nodes);
+    }
+
+    /// reserves space for at least `additional` more edges out of `u`
+    ///
+    /// Adds `u` to the graph if it is not already present.
+    /// ```
+    /// use graph_algos::{Graph, Edge};
+    ///
+    /// let mut graph: Graph<u32> = Graph::empty();
+    ///
+    /// graph.reserve_edges(&1, 1000);
+    /// for i in 2..1002 {
+    ///     graph.add_edge(1, Edge::new(i));
+    /// }
+    ///
+    /// assert_eq!(graph.succs(&1).unwrap().len(), 1000);
+    /// ```
+    pub fn reserve_edges(&mut self, u: &N, additional: usize) {
+        if let Some(succs) = self.backing_map.get_mut(u) {
+            Arc::make_mut(succs).reserve(additional);
+        } else {
+            self.backing_map
+                .insert(u.clone(), Arc::new(Vec::with_capacity(additional)));
+        }
+    }
+
+    /// shrinks the capacity of the graph and every successor list as much as possible
+    ///
+    /// Successor lists shared with a [snapshot](Graph::snapshot) are left as they are,
+    /// as shrinking them would mean copying them.
+    /// ```
+    /// use graph_algos::{Graph, Edge};
+    ///
+    /// let mut graph: Graph<u32> = Graph::with_capacity(1000);
+    /// graph.reserve_edges(&1, 1000);
+    /// graph.add_edge(1, Edge::new(2));
+    ///
+    /// graph.shrink_to_fit();
+    /// assert!(graph.capacity() < 1000);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        self.backing_map.shrink_to_fit();
 
-        self.backing_map.entry(u).or_insert_with(Vec::new).push(e);
+        for succs in self.backing_map.values_mut() {
+            if let Some(succs) = Arc::get_mut(succs) {
+                succs.shrink_to_fit();
+            }
+        }
+    }
+
+    /// adds a node with no edges to the graph, if it isn't already in the graph
+    /// ```
+    /// use graph_algos::Graph;
+    ///
+    /// let mut graph: Graph<u32> = Graph::empty();
+    ///
+    /// graph.add_node(5);
+    /// assert_eq!(graph.len(), 1);
+    /// assert!(graph.succs(&5).unwrap().is_empty());
+    /// ```
+    pub fn add_node(&mut self, u: N) {
+        self.backing_map.entry(u).or_default();
     }
 
     /// removes an edge from the graph
@@ -165,11 +555,162 @@ impl<N: NodeBounds> Graph<N> {
     pub fn remove_edge(&mut self, u: &N, v: &N) {
         if let Some(edges) = self.backing_map.get_mut(u) {
             if let Some(pos) = edges.iter().position(|e| e.destination() == v) {
-                edges.remove(pos);
+                Arc::make_mut(edges).remove(pos);
+            }
+        }
+    }
+
+    /// removes every node for which `keep` returns false, along with all edges into and out of it
+    /// ```
+    /// use graph_algos::{Graph, graph};
+    ///
+    /// let mut graph: Graph<u32> = graph! {
+    ///     1 => [2, 3, 4],
+    ///     2 => [3],
+    ///     4 => [1],
+    /// };
+    ///
+    /// graph.retain_nodes(|&node| node % 2 == 1 || node == 4);
+    ///
+    /// assert_eq!(graph.len(), 3);
+    /// assert!(graph.is_edge(&1, &3));
+    /// assert!(graph.is_edge(&4, &1));
+    /// assert!(!graph.is_edge(&1, &2));
+    /// ```
+    pub fn retain_nodes<F: FnMut(&N) -> bool>(&mut self, mut keep: F) {
+        let mut removed = HashSet::new();
+        self.backing_map.retain(|node, _| {
+            keep(node) || {
+                removed.insert(node.clone());
+                false
             }
+        });
+
+        // only walk the edges if there can be dangling references
+        if !removed.is_empty() {
+            self.retain_edges(|_, edge| !removed.contains(edge.destination()));
+        }
+    }
+
+    /// removes every edge for which `keep` returns false, given the source node and the edge
+    /// ```
+    /// use graph_algos::{Graph, graph, EdgeWeight};
+    ///
+    /// let mut graph: Graph<u32> = graph! {
+    ///     1 => [2 => 5, 3 => 1],
+    ///     2 => [3 => 7],
+    /// };
+    ///
+    /// graph.retain_edges(|_, edge| edge.weight() != Some(EdgeWeight::Weight(7)));
+    ///
+    /// assert!(graph.is_edge(&1, &2));
+    /// assert!(graph.is_edge(&1, &3));
+    /// assert!(!graph.is_edge(&2, &3));
+    /// // nodes are never removed
+    /// assert_eq!(graph.len(), 3);
+    /// ```
+    pub fn retain_edges<F: FnMut(&N, &Edge<N>) -> bool>(&mut self, mut keep: F) {
+        for (node, succs) in self.backing_map.iter_mut() {
+            // only copy a shared list if an edge is actually removed from it
+            let first_removed = match succs.iter().position(|edge| !keep(node, edge)) {
+                Some(i) => i,
+                None => continue,
+            };
+
+            let mut i = 0;
+            Arc::make_mut(succs).retain(|edge| {
+                let kept = i < first_removed || (i > first_removed && keep(node, edge));
+                i += 1;
+                kept
+            });
+        }
+    }
+
+    /// removes every node which has no edges into or out of it
+    /// ```
+    /// use graph_algos::{Graph, graph};
+    ///
+    /// let mut graph: Graph<u32> = graph! {
+    ///     1 => [2],
+    ///     3 => [],
+    /// };
+    /// graph.add_node(4);
+    ///
+    /// graph.prune_isolated();
+    ///
+    /// assert_eq!(graph.len(), 2);
+    /// assert!(graph.is_edge(&1, &2));
+    /// ```
+    pub fn prune_isolated(&mut self) {
+        let has_in_edges: HashSet<&N> = self
+            .backing_map
+            .values()
+            .flat_map(|succs| succs.iter().map(Edge::destination))
+            .collect();
+
+        let isolated: Vec<N> = self
+            .backing_map
+            .iter()
+            .filter(|(node, succs)| succs.is_empty() && !has_in_edges.contains(node))
+            .map(|(node, _)| node.clone())
+            .collect();
+
+        for node in isolated {
+            self.backing_map.remove(&node);
+        }
+    }
+
+    /// Returns whether there is an edge from `u` to itself
+    /// ```
+    /// use graph_algos::{Graph, graph};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [1, 2],
+    /// };
+    ///
+    /// assert!(graph.has_self_loop(&1));
+    /// assert!(!graph.has_self_loop(&2));
+    /// ```
+    pub fn has_self_loop(&self, u: &N) -> bool {
+        self.is_edge(u, u)
+    }
+
+    /// Returns an iterator over the edges from a node to itself
+    /// ```
+    /// use graph_algos::{Graph, graph, Edge};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [1 => 3, 2 => 1],
+    ///     2 => [3 => 1],
+    /// };
+    ///
+    /// let loops: Vec<_> = graph.self_loops().collect();
+    /// assert_eq!(loops, vec![(&1, &Edge::new_with_weight(1, 3))]);
+    /// ```
+    pub fn self_loops(&self) -> SelfLoops<'_, N> {
+        SelfLoops {
+            inner: self.edges(),
         }
     }
 
+    /// removes every edge from a node to itself
+    /// ```
+    /// use graph_algos::{Graph, graph};
+    ///
+    /// let mut graph: Graph<u32> = graph! {
+    ///     1 => [1, 2],
+    ///     2 => [2],
+    /// };
+    ///
+    /// graph.remove_self_loops();
+    ///
+    /// assert_eq!(graph.self_loops().count(), 0);
+    /// assert!(graph.is_edge(&1, &2));
+    /// ```
+    pub fn remove_self_loops(&mut self) {
+        self.retain_edges(|u, edge| edge.destination() != u);
+    }
+
     /// Returns whether an edge exists in the graph
     /// ```
     /// use graph_algos::{Graph, graph, Edge};
@@ -188,6 +729,40 @@ impl<N: NodeBounds> Graph<N> {
         }
     }
 
+    /// Returns an iterator over the successors of a node, which is empty if the node isn't in the graph
+    ///
+    /// Prefer this over [`Graph::succs`], which exposes how the edges are stored.
+    /// ```
+    /// use graph_algos::{Graph, graph};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     5 => [6, 7],
+    ///     6 => [7],
+    /// };
+    ///
+    /// assert_eq!(graph.neighbors(&5).collect::<Vec<_>>(), vec![&6, &7]);
+    /// assert_eq!(graph.neighbors(&7).len(), 0);
+    /// assert_eq!(graph.neighbors(&8).len(), 0);
+    /// ```
+    pub fn neighbors(&self, u: &N) -> Neighbors<'_, N> {
+        Neighbors::new(self.succs_or_empty(u))
+    }
+
+    /// Returns an iterator over the successors of a node along with the weights of the edges to them
+    /// ```
+    /// use graph_algos::{Graph, graph, EdgeWeight};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     5 => [6 => 2, 7 => 3],
+    /// };
+    ///
+    /// let neighbors: Vec<(&u32, Option<EdgeWeight>)> = graph.neighbors_weighted(&5).collect();
+    /// assert_eq!(neighbors, vec![(&6, Some(2.into())), (&7, Some(3.into()))]);
+    /// ```
+    pub fn neighbors_weighted(&self, u: &N) -> NeighborsWeighted<'_, N> {
+        NeighborsWeighted::new(self.succs_or_empty(u))
+    }
+
     /// Returns the successors of a node in the graph
     /// ```
     /// use graph_algos::{Graph, graph, Edge};
@@ -207,6 +782,55 @@ impl<N: NodeBounds> Graph<N> {
         self.backing_map.get(u).map(|vec| vec.as_slice())
     }
 
+    /// Returns the successors of a node in the graph, which is empty if the node isn't in the graph
+    /// ```
+    /// use graph_algos::{Graph, graph, Edge};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     5 => [6 => 2],
+    /// };
+    ///
+    /// assert_eq!(graph.succs_or_empty(&5), &[Edge::new_with_weight(6, 2)]);
+    /// assert!(graph.succs_or_empty(&6).is_empty());
+    /// assert!(graph.succs_or_empty(&7).is_empty());
+    /// ```
+    pub fn succs_or_empty(&self, u: &N) -> &[Edge<N>] {
+        self.succs(u).unwrap_or_default()
+    }
+
+    /// Returns the graph's own copy of a node, so it can be borrowed for as long as the graph
+    pub(crate) fn node_ref(&self, u: &N) -> Option<&N> {
+        self.backing_map.get_key_value(u).map(|(node, _)| node)
+    }
+
+    /// Returns a mutable reference to the successors of a node in the graph
+    pub(crate) fn succs_mut(&mut self, u: &N) -> Option<&mut Vec<Edge<N>>> {
+        self.backing_map.get_mut(u).map(Arc::make_mut)
+    }
+
+    /// Removes a node and the edges out of it, leaving any edges into it dangling
+    pub(crate) fn remove_node_unchecked(&mut self, u: &N) {
+        self.backing_map.remove(u);
+    }
+
+    /// Moves every node and edge of `other` into this graph,
+    /// combining the edges of nodes which are in both
+    pub(crate) fn absorb(&mut self, other: Self) {
+        if self.backing_map.len() < other.backing_map.len() {
+            let smaller = mem::replace(self, other);
+            return self.absorb(smaller);
+        }
+
+        for (node, edges) in other.backing_map {
+            match self.backing_map.entry(node) {
+                Entry::Occupied(mut o) => Arc::make_mut(o.get_mut()).extend(edges.iter().cloned()),
+                Entry::Vacant(v) => {
+                    v.insert(edges);
+                }
+            }
+        }
+    }
+
     /// Returns the number of nodes in a graph
     /// ```
     /// use graph_algos::{Graph, graph, Edge};
@@ -289,18 +913,178 @@ impl<N: NodeBounds> Graph<N> {
             inner: self.backing_map.iter(),
             curr_node: None,
             curr_dest_no: 0,
+            remaining: self.backing_map.values().map(|succs| succs.len()).sum(),
+        }
+    }
+
+    /// Returns an iterator over the weighted edges in the graph as `(source, destination, weight)`,
+    /// skipping any edges without a weight
+    /// ```
+    /// use graph_algos::{Graph, Edge, EdgeWeight};
+    ///
+    /// let mut graph: Graph<u32> = Graph::empty();
+    /// graph.add_edge(1, Edge::new_with_weight(2, 5));
+    /// graph.add_edge(2, Edge::new(3));
+    ///
+    /// let weighted: Vec<(&u32, &u32, EdgeWeight)> = graph.weighted_edges().collect();
+    /// assert_eq!(weighted, vec![(&1, &2, 5.into())]);
+    /// ```
+    pub fn weighted_edges(&self) -> WeightedEdges<'_, N> {
+        WeightedEdges {
+            inner: self.edges(),
+        }
+    }
+
+    /// Returns an iterator over the edges out of `u`, which is empty if `u` isn't in the graph
+    /// ```
+    /// use graph_algos::{Graph, graph, Edge};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2, 3],
+    ///     2 => [3],
+    /// };
+    ///
+    /// let edges: Vec<(&u32, &Edge<u32>)> = graph.edges_from(&1).collect();
+    /// assert_eq!(edges, vec![(&1, &Edge::new(2)), (&1, &Edge::new(3))]);
+    ///
+    /// assert_eq!(graph.edges_from(&3).len(), 0);
+    /// assert_eq!(graph.edges_from(&4).len(), 0);
+    /// ```
+    pub fn edges_from(&self, u: &N) -> EdgesFrom<'_, N> {
+        match self.backing_map.get_key_value(u) {
+            Some((source, succs)) => EdgesFrom {
+                source: Some(source),
+                inner: succs.iter(),
+            },
+            None => EdgesFrom {
+                source: None,
+                inner: [].iter(),
+            },
+        }
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher + Clone> Graph<N, S> {
+    /// Returns a copy of the graph which shares its edges with this graph
+    ///
+    /// Only the nodes are copied, each node's successors are shared until either graph
+    /// changes the edges out of that node, at which point just that node's successors are copied.
+    /// This makes it cheap to take a scratch copy of a large graph for a few what-if edits.
+    /// [`Clone`] behaves in the same way.
+    /// ```
+    /// use graph_algos::{Graph, graph, Edge};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2, 3],
+    ///     2 => [3],
+    /// };
+    ///
+    /// let mut what_if = graph.snapshot();
+    /// what_if.remove_edge(&1, &2);
+    /// what_if.add_edge(3, Edge::new(1));
+    ///
+    /// assert!(graph.is_edge(&1, &2));
+    /// assert!(!graph.is_edge(&3, &1));
+    /// assert!(!what_if.is_edge(&1, &2));
+    /// assert!(what_if.is_edge(&3, &1));
+    /// ```
+    pub fn snapshot(&self) -> Self {
+        self.clone()
+    }
+
+    /// Returns the complement of the graph, which has the same nodes
+    /// and an unweighted edge between every pair of distinct nodes with no edge in this graph
+    /// ```
+    /// use graph_algos::{Graph, graph};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2, 1],
+    ///     2 => [3],
+    ///     3 => [1, 2],
+    /// };
+    ///
+    /// let complement: Graph<u32> = graph! {
+    ///     1 => [3],
+    ///     2 => [1],
+    /// };
+    ///
+    /// assert_eq!(graph.complement(), complement);
+    /// ```
+    pub fn complement(&self) -> Self {
+        let mut complement = Self::with_capacity_and_hasher(self.len(), self.hasher().clone());
+
+        for (u, succs) in self.backing_map.iter() {
+            let adjacent: HashSet<&N> = succs.iter().map(Edge::destination).collect();
+            let missing = self
+                .backing_map
+                .keys()
+                .filter(|v| *v != u && !adjacent.contains(v))
+                .map(|v| Edge::new(v.clone()))
+                .collect();
+
+            complement.backing_map.insert(u.clone(), Arc::new(missing));
         }
+
+        complement
+    }
+}
+
+impl<N: NodeBounds + Ord, S: BuildHasher> Graph<N, S> {
+    /// Returns the nodes in ascending order,
+    /// which unlike [`Graph::nodes`] is the same every time the program runs
+    /// ```
+    /// use graph_algos::{Graph, graph};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     3 => [1],
+    ///     2 => [4],
+    /// };
+    ///
+    /// assert_eq!(graph.sorted_nodes(), vec![&1, &2, &3, &4]);
+    /// ```
+    pub fn sorted_nodes(&self) -> Vec<&N> {
+        let mut nodes: Vec<&N> = self.nodes().collect();
+        nodes.sort_unstable();
+        nodes
+    }
+
+    /// Returns the edges in ascending order of source node, then destination node, then weight,
+    /// which unlike [`Graph::edges`] is the same every time the program runs
+    /// ```
+    /// use graph_algos::{Graph, graph, Edge};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     2 => [1 => 3],
+    ///     1 => [3 => 2, 2 => 5, 2 => 1],
+    /// };
+    ///
+    /// let edges: Vec<(&u32, &Edge<u32>)> = graph.sorted_edges();
+    /// assert_eq!(
+    ///     edges,
+    ///     vec![
+    ///         (&1, &Edge::new_with_weight(2, 1)),
+    ///         (&1, &Edge::new_with_weight(2, 5)),
+    ///         (&1, &Edge::new_with_weight(3, 2)),
+    ///         (&2, &Edge::new_with_weight(1, 3)),
+    ///     ],
+    /// );
+    /// ```
+    pub fn sorted_edges(&self) -> Vec<(&N, &Edge<N>)> {
+        let mut edges: Vec<(&N, &Edge<N>)> = self.edges().collect();
+        edges.sort_unstable();
+        edges
     }
 }
 
-impl<N: NodeBounds + Ord + fmt::Display> Graph<N> {
+impl<N: NodeBounds + Ord + fmt::Display, S: BuildHasher> Graph<N, S> {
+    #[allow(clippy::inherent_to_string_shadow_display)]
     /// [`Graph::to_string`] is intended to be a direct inverse of the parse method
     /// it relies on the fmt::Display implementation for the node type
     /// being able to produce a string which can be parsed with `.parse()`
     ///
-    /// Note: this is very expensive and requires additional trait bounds.
-    /// This is because we copy the data into a `BTreeMap` which has an implicit
-    /// ordering and thus we will always get a consistent output.
+    /// Note: this is more expensive than [`Graph::to_string_unstable`] and requires additional trait bounds.
+    /// This is because the nodes are sorted so we will always get a consistent output,
+    /// see [`Graph::formatter`] for other layouts.
     /// ```
     /// use graph_algos::{Graph, graph};
     ///
@@ -332,46 +1116,14 @@ impl<N: NodeBounds + Ord + fmt::Display> Graph<N> {
     /// assert_eq!(graph, parsed_graph);
     /// ```
     pub fn to_string(&self) -> String {
-        let mut buf = String::new();
-
-        // collect the backing hashmap into a BTreeMap so we can read it out sorted
-        let btree_graph: std::collections::BTreeMap<_, _> = self.backing_map.iter().collect();
-
-        for (node, succs) in btree_graph.iter() {
-            if !succs.is_empty() {
-                // start the line
-                buf.push_str(format!("{}:", node).as_str());
-
-                // get an iterator over the successors
-                let mut out_it = succs.iter();
-
-                // handle the first now now so we don't get trailing spaces later
-                if let Some(first_edge) = out_it.next() {
-                    buf.push_str(format!("{}", first_edge.destination()).as_str());
-                    if let Some(EdgeWeight::Weight(w)) = first_edge.weight() {
-                        buf.push_str(format!(",{}", w).as_str());
-                    }
-                }
-
-                // handle the remaining edges
-                for edge in out_it {
-                    buf.push_str(" ");
-                    buf.push_str(format!("{}", edge.destination()).as_str());
-                    if let Some(EdgeWeight::Weight(w)) = edge.weight() {
-                        buf.push_str(format!(",{}", w).as_str());
-                    }
-                }
-
-                // end the line
-                buf.push('\n');
-            }
-        }
-
-        buf
+        self.formatter()
+            .style(FormatStyle::AdjacencyList)
+            .sorted()
+            .to_string()
     }
 }
 
-impl<N: NodeBounds + Ord + fmt::Display> Graph<N> {
+impl<N: NodeBounds + Ord + fmt::Display, S: BuildHasher> Graph<N, S> {
     /// [`Graph::to_string_unstable`] is intended to be a direct inverse of the parse method
     /// it relies on the fmt::Display implementation for the node type
     /// being able to produce a string which can be parsed with `.parse()`
@@ -426,7 +1178,7 @@ impl<N: NodeBounds + Ord + fmt::Display> Graph<N> {
 
                 // handle the remaining edges
                 for edge in out_it {
-                    buf.push_str(" ");
+                    buf.push(' ');
                     buf.push_str(format!("{}", edge.destination()).as_str());
                     if let Some(EdgeWeight::Weight(w)) = edge.weight() {
                         buf.push_str(format!(",{}", w).as_str());
@@ -442,108 +1194,150 @@ impl<N: NodeBounds + Ord + fmt::Display> Graph<N> {
     }
 }
 
-impl<N: NodeBounds + fmt::Display> fmt::Display for Graph<N> {
+/// Writes the graph in the syntax of the `graph!` macro,
+/// with the nodes in the order of their `Display` output
+/// ```
+/// use graph_algos::{Graph, graph};
+///
+/// let graph: Graph<u32> = graph! {
+///     2 => [3 => 1, 1 => 4],
+///     1 => [2 => 2],
+/// };
+///
+/// assert_eq!(
+///     graph.to_string(),
+///     "1:2,2\n2:3,1 1,4\n",
+/// );
+/// assert_eq!(
+///     format!("{}", graph),
+///     "{\n    1 => [2 => 2],\n    2 => [3 => 1, 1 => 4],\n    3 => [],\n}",
+/// );
+/// ```
+impl<N: NodeBounds + fmt::Display, S: BuildHasher> fmt::Display for Graph<N, S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // the intention of this is to mimic the macro syntax
-        if self.is_empty() {
-            write!(f, "{{}}")?;
-        } else {
-            writeln!(f, "{{")?;
-            for (node, edges) in self.backing_map.iter() {
-                if edges.is_empty() {
-                    writeln!(f, "    {} => [],", node)?;
-                } else {
-                    write!(f, "    {} => [", node)?;
-                    let mut edge_it = edges.iter();
-
-                    // handle the first edge so we don't get trailing ", " later
-                    if let Some(first_edge) = edge_it.next() {
-                        write!(f, "{}", first_edge.destination())?;
-                        if let Some(EdgeWeight::Weight(w)) = first_edge.weight() {
-                            write!(f, " => {}", w)?;
-                        }
-                    }
-
-                    // write out the remaining edges
-                    for edge in edge_it.next() {
-                        write!(f, ", {}", edge.destination())?;
-                        if let Some(EdgeWeight::Weight(w)) = edge.weight() {
-                            write!(f, " => {}", w)?;
-                        }
-                    }
-
-                    writeln!(f, "],")?;
-                }
-            }
-            write!(f, "}}")?;
-        }
-
-        Ok(())
+        fmt::Display::fmt(&self.formatter(), f)
     }
 }
 
-impl<N: NodeBounds> Default for Graph<N> {
+impl<N: NodeBounds, S: Default> Default for Graph<N, S> {
     fn default() -> Self {
         Self {
-            backing_map: HashMap::new(),
+            backing_map: HashMap::default(),
         }
     }
 }
 
-/// represents the failure to parse a node
-#[derive(Fail, Debug)]
-pub enum GraphParseError {
-    /// Represents the failure to parse an outbound edge
-    #[fail(display = "Failed to parse outbound edge: {}", _0)]
-    EdgeParseError(#[fail(cause)] ParseEdgeError),
+/// Graphs are equal when they have the same nodes and the same multiset of
+/// outbound edges from each node, regardless of the order the edges were added in.
+/// ```
+/// use graph_algos::{graph, Graph};
+///
+/// let g1: Graph<u32> = graph! {
+///     1 => [2 => 1, 3 => 4],
+///     2 => [],
+///     3 => [],
+/// };
+///
+/// let g2: Graph<u32> = graph! {
+///     1 => [3 => 4, 2 => 1],
+///     2 => [],
+///     3 => [],
+/// };
+///
+/// assert_eq!(g1, g2);
+/// ```
+impl<N: NodeBounds, S: BuildHasher> PartialEq for Graph<N, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.backing_map.len() == other.backing_map.len()
+            && self.backing_map.iter().all(|(node, edges)| {
+                other
+                    .backing_map
+                    .get(node)
+                    .is_some_and(|other_edges| same_edges(edges, other_edges))
+            })
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher> Eq for Graph<N, S> {}
 
-    /// Represents the failure to parse the destination node in an edge
-    #[fail(display = "Failed to parse source node: {}", _0)]
-    NodeParseError(String),
+/// Hashes the graph consistently with its order insensitive [`PartialEq`] implementation,
+/// by combining the hashes of the nodes and edges with a commutative operation.
+/// ```
+/// use std::collections::HashSet;
+/// use graph_algos::{graph, Graph};
+///
+/// let mut seen: HashSet<Graph<u32>> = HashSet::new();
+///
+/// seen.insert(graph! { 1 => [2, 3], 2 => [], 3 => [] });
+///
+/// assert!(seen.contains(&graph! { 1 => [3, 2], 2 => [], 3 => [] }));
+/// assert!(!seen.contains(&graph! { 1 => [2], 2 => [], 3 => [] }));
+/// ```
+impl<N: NodeBounds, S: BuildHasher> Hash for Graph<N, S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let combined = self
+            .backing_map
+            .iter()
+            .map(|(node, edges)| {
+                let edges_hash = edges.iter().map(hash_one).fold(0, u64::wrapping_add);
+                hash_one(&(node, edges_hash))
+            })
+            .fold(0, u64::wrapping_add);
 
-    #[fail(display = "Error in graph format.")]
-    FormatError,
+        state.write_usize(self.backing_map.len());
+        state.write_u64(combined);
+    }
 }
 
-impl<N: NodeBounds> FromStr for Graph<N>
-where
-    N: FromStr,
-    <N as FromStr>::Err: Debug,
-{
-    type Err = GraphParseError;
+/// hashes a single value with a fixed hasher, so the results can be combined
+fn hash_one<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // fill an unweighted directed graph from a string
-        // each line is node:edges seperated by spaces
+/// checks whether two edge lists contain the same edges, ignoring their order
+fn same_edges<N: NodeBounds>(a: &[Edge<N>], b: &[Edge<N>]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
 
-        // new empty graph
-        let mut graph: Graph<N> = Graph::empty();
+    // fast path for the common case of edges being added in the same order
+    if a == b {
+        return true;
+    }
 
-        for line in s.lines() {
-            let (u, edges) = line.split_once(':').ok_or(GraphParseError::FormatError)?;
+    let mut counts: HashMap<&Edge<N>, isize> = HashMap::with_capacity(a.len());
+    for edge in a {
+        *counts.entry(edge).or_default() += 1;
+    }
 
-            let u_fs: N = u
-                .parse()
-                .map_err(|err| GraphParseError::NodeParseError(format!("{:?}", err)))?;
+    for edge in b {
+        match counts.get_mut(edge) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => return false,
+        }
+    }
 
-            let edges = edges
-                .split(' ')
-                .map(|edge| edge.parse().map_err(GraphParseError::EdgeParseError));
+    true
+}
 
-            for edge in edges {
-                let e = edge?;
-                graph.add_edge(u_fs.clone(), e);
-            }
-        }
+impl<N: NodeBounds, S: BuildHasher + Default> FromStr for Graph<N, S>
+where
+    N: FromStr,
+    <N as FromStr>::Err: Debug,
+{
+    type Err = GraphParseError;
 
-        Ok(graph)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Graph::parse_with_options(s, &Default::default())
     }
 }
 
 /// An iterator over the nodes of the graph
 #[derive(Debug)]
 pub struct Nodes<'a, N: NodeBounds> {
-    inner: hash_map::Keys<'a, N, Vec<Edge<N>>>,
+    inner: hash_map::Keys<'a, N, Succs<N>>,
 }
 
 impl<'a, N: NodeBounds> Iterator for Nodes<'a, N> {
@@ -558,12 +1352,27 @@ impl<'a, N: NodeBounds> Iterator for Nodes<'a, N> {
     }
 }
 
+/// An iterator over the self loops of the graph
+#[derive(Debug)]
+pub struct SelfLoops<'a, N: NodeBounds> {
+    inner: Edges<'a, N>,
+}
+
+impl<'a, N: NodeBounds> Iterator for SelfLoops<'a, N> {
+    type Item = (&'a N, &'a Edge<N>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.find(|(u, edge)| edge.destination() == *u)
+    }
+}
+
 /// An iterator over the Edges of the graph
 #[derive(Debug)]
 pub struct Edges<'a, N: NodeBounds> {
-    inner: hash_map::Iter<'a, N, Vec<Edge<N>>>,
-    curr_node: Option<(&'a N, &'a Vec<Edge<N>>)>,
+    inner: hash_map::Iter<'a, N, Succs<N>>,
+    curr_node: Option<(&'a N, &'a [Edge<N>])>,
     curr_dest_no: usize,
+    remaining: usize,
 }
 
 impl<'a, N: NodeBounds> Iterator for Edges<'a, N> {
@@ -576,13 +1385,14 @@ impl<'a, N: NodeBounds> Iterator for Edges<'a, N> {
                 // we have a node and enough remaining dests
                 Some((node, dests)) if self.curr_dest_no < dests.len() => {
                     self.curr_dest_no += 1;
+                    self.remaining -= 1;
                     break Some((node, &dests[self.curr_dest_no - 1]));
                 }
 
                 // get next thing from inner iterator
                 _ => {
-                    if let Some(node) = self.inner.next() {
-                        self.curr_node = Some(node);
+                    if let Some((node, succs)) = self.inner.next() {
+                        self.curr_node = Some((node, succs.as_slice()));
 
                         self.curr_dest_no = 0;
                     } else {
@@ -595,18 +1405,57 @@ impl<'a, N: NodeBounds> Iterator for Edges<'a, N> {
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        // we can only know how many more edges there are attached to the current node
-        if let Some((_, dests)) = self.curr_node {
-            (dests.len() - self.curr_dest_no, None)
-        } else {
-            (0, None)
-        }
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, N: NodeBounds> ExactSizeIterator for Edges<'a, N> {}
+
+/// An iterator over the weighted edges of the graph
+#[derive(Debug)]
+pub struct WeightedEdges<'a, N: NodeBounds> {
+    inner: Edges<'a, N>,
+}
+
+impl<'a, N: NodeBounds> Iterator for WeightedEdges<'a, N> {
+    type Item = (&'a N, &'a N, EdgeWeight);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .find_map(|(u, edge)| Some((u, edge.destination(), edge.weight()?)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.inner.size_hint().1)
+    }
+}
+
+/// An iterator over the edges out of a single node of the graph
+#[derive(Debug)]
+pub struct EdgesFrom<'a, N: NodeBounds> {
+    source: Option<&'a N>,
+    inner: slice::Iter<'a, Edge<N>>,
+}
+
+impl<'a, N: NodeBounds> Iterator for EdgesFrom<'a, N> {
+    type Item = (&'a N, &'a Edge<N>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some((self.source?, self.inner.next()?))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
     }
 }
 
-impl<N: NodeBounds> std::iter::FromIterator<(N, Edge<N>)> for Graph<N> {
+impl<'a, N: NodeBounds> ExactSizeIterator for EdgesFrom<'a, N> {}
+
+impl<N: NodeBounds, S: BuildHasher + Default> std::iter::FromIterator<(N, Edge<N>)>
+    for Graph<N, S>
+{
     fn from_iter<I: IntoIterator<Item = (N, Edge<N>)>>(iter: I) -> Self {
-        let mut graph: Graph<N> = Graph::empty();
+        let mut graph: Graph<N, S> = Graph::default();
 
         for (src, edge) in iter {
             graph.add_edge(src, edge);
@@ -0,0 +1,123 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{Edge, Graph};
+
+/// The offsets to the orthogonal neighbours of a cell
+const ORTHOGONAL: [(isize, isize); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+/// The offsets to the diagonal neighbours of a cell
+const DIAGONAL: [(isize, isize); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+impl Graph<(usize, usize)> {
+    /// Creates a `width` by `height` grid with a node for each `(x, y)` coordinate
+    /// and an edge with weight 1 in each direction between neighbouring cells
+    ///
+    /// When `diagonal` is true, diagonally adjacent cells are neighbours as well.
+    /// ```
+    /// use graph_algos::Graph;
+    /// use graph_algos::algos::dijkstra;
+    ///
+    /// let grid = Graph::grid(3, 2, false);
+    /// assert_eq!(grid.len(), 6);
+    /// assert!(grid.is_edge(&(0, 0), &(1, 0)));
+    /// assert!(!grid.is_edge(&(0, 0), &(1, 1)));
+    ///
+    /// let (_, distances) = dijkstra(&grid, &(0, 0));
+    /// assert_eq!(distances[&(2, 1)], 3.into());
+    ///
+    /// let diagonal = Graph::grid(3, 2, true);
+    /// let (_, distances) = dijkstra(&diagonal, &(0, 0));
+    /// assert_eq!(distances[&(2, 1)], 2.into());
+    /// ```
+    pub fn grid(width: usize, height: usize, diagonal: bool) -> Self {
+        grid_graph(width, height, diagonal, |_, _| true)
+    }
+
+    /// Parses a maze drawn in ASCII art, with a node for the `(x, y)` coordinate of every cell
+    /// which isn't a wall, and an edge with weight 1 in each direction between neighbouring open cells
+    ///
+    /// `x` is the column and `y` is the line, starting from 0 in the top left.
+    /// When `diagonal` is true, diagonally adjacent cells are neighbours as well.
+    /// ```
+    /// use graph_algos::{Graph, Path};
+    /// use graph_algos::algos::bfs_shortest_paths;
+    ///
+    /// let maze = "\
+    ///     S.#.\n\
+    ///     .##.\n\
+    ///     ...E\n\
+    /// ";
+    ///
+    /// let graph = Graph::from_ascii_maze(maze, |c| c == '#', false);
+    /// assert_eq!(graph.len(), 9);
+    ///
+    /// let pred_map = bfs_shortest_paths(&graph, &(0, 0));
+    /// let path = Path::new_path_to(&pred_map, &(3, 2)).unwrap();
+    /// assert_eq!(path.edges().len(), 5);
+    /// ```
+    pub fn from_ascii_maze<F: Fn(char) -> bool>(maze: &str, is_wall: F, diagonal: bool) -> Self {
+        let cells: Vec<Vec<bool>> = maze
+            .lines()
+            .map(|line| line.chars().map(|c| !is_wall(c)).collect())
+            .collect();
+
+        let height = cells.len();
+        let width = cells.iter().map(Vec::len).max().unwrap_or(0);
+
+        grid_graph(width, height, diagonal, |x, y| {
+            cells[y].get(x).copied().unwrap_or(false)
+        })
+    }
+}
+
+/// builds a grid graph over the cells for which `open` returns true
+fn grid_graph<F: Fn(usize, usize) -> bool>(
+    width: usize,
+    height: usize,
+    diagonal: bool,
+    open: F,
+) -> Graph<(usize, usize)> {
+    let mut graph = Graph::with_capacity(width * height);
+    let offsets = if diagonal {
+        &[ORTHOGONAL, DIAGONAL][..]
+    } else {
+        &[ORTHOGONAL][..]
+    };
+
+    for y in 0..height {
+        for x in 0..width {
+            if !open(x, y) {
+                continue;
+            }
+
+            graph.add_node((x, y));
+            for &(dx, dy) in offsets.iter().flatten() {
+                let neighbour = x
+                    .checked_add_signed(dx)
+                    .zip(y.checked_add_signed(dy))
+                    .filter(|&(nx, ny)| nx < width && ny < height && open(nx, ny));
+
+                if let Some(neighbour) = neighbour {
+                    graph.add_edge((x, y), Edge::new_with_weight(neighbour, 1));
+                }
+            }
+        }
+    }
+
+    graph
+}
@@ -0,0 +1,285 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Priority queues for the shortest path and spanning tree algorithms.
+//!
+//! [`PairingHeap`] is a min-heap with O(1) insertion and merging
+//! and O(1) amortised decrease-key, with O(log n) amortised removal of the minimum.
+//! Its nodes are allocated separately, so in practice a `BinaryHeap` with duplicate entries
+//! or a [`crate::util::IndexedHeap`] is often faster;
+//! the `heap` benchmark compares all three inside Dijkstra's and Prim's algorithms.
+//!
+//! ```
+//! use graph_algos::heap::PairingHeap;
+//!
+//! let mut heap = PairingHeap::new();
+//! heap.push("a", 5);
+//! let b = heap.push("b", 7);
+//!
+//! let mut other = PairingHeap::new();
+//! other.push("c", 6);
+//! heap.merge(other);
+//!
+//! assert!(heap.decrease_key(&b, 1));
+//!
+//! assert_eq!(heap.pop(), Some(("b", 1)));
+//! assert_eq!(heap.pop(), Some(("a", 5)));
+//! assert_eq!(heap.pop(), Some(("c", 6)));
+//! assert!(heap.is_empty());
+//! ```
+
+use std::{
+    cell::RefCell,
+    fmt,
+    rc::{Rc, Weak},
+};
+
+/// A shared pointer to a node of the heap
+type Link<T, P> = Rc<RefCell<Node<T, P>>>;
+
+/// A node of the heap, whose children are stored as a linked list of siblings
+struct Node<T, P> {
+    item: T,
+    priority: P,
+    /// the first child of the node
+    child: Option<Link<T, P>>,
+    /// the next sibling of the node
+    next: Option<Link<T, P>>,
+    /// the previous sibling of the node, or its parent if it is the first child
+    prev: Weak<RefCell<Node<T, P>>>,
+}
+
+/// A reference to an item in a [`PairingHeap`], used to change its priority
+///
+/// The handle stays valid when its heap is merged into another,
+/// and is simply ignored once its item has been popped.
+pub struct Handle<T, P>(Weak<RefCell<Node<T, P>>>);
+
+impl<T, P> Clone for Handle<T, P> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T, P> fmt::Debug for Handle<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Handle").finish()
+    }
+}
+
+/// A min-heap which supports merging and decrease-key, see the [module documentation](self)
+pub struct PairingHeap<T, P> {
+    root: Option<Link<T, P>>,
+    len: usize,
+}
+
+impl<T, P: Ord> PairingHeap<T, P> {
+    /// Creates an empty heap
+    pub fn new() -> Self {
+        Self { root: None, len: 0 }
+    }
+
+    /// Returns the number of items in the heap
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the heap is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the smallest priority in the heap
+    pub fn min_priority(&self) -> Option<P>
+    where
+        P: Clone,
+    {
+        self.root
+            .as_ref()
+            .map(|root| root.borrow().priority.clone())
+    }
+
+    /// Returns the current priority of the item behind `handle`, if it is still in a heap
+    pub fn priority(&self, handle: &Handle<T, P>) -> Option<P>
+    where
+        P: Clone,
+    {
+        handle
+            .0
+            .upgrade()
+            .map(|node| node.borrow().priority.clone())
+    }
+
+    /// Adds an item to the heap, returning a handle which can be used to lower its priority
+    pub fn push(&mut self, item: T, priority: P) -> Handle<T, P> {
+        let node = Rc::new(RefCell::new(Node {
+            item,
+            priority,
+            child: None,
+            next: None,
+            prev: Weak::new(),
+        }));
+        let handle = Handle(Rc::downgrade(&node));
+
+        self.root = Some(match self.root.take() {
+            Some(root) => meld(root, node),
+            None => node,
+        });
+        self.len += 1;
+
+        handle
+    }
+
+    /// Moves every item of `other` into this heap
+    ///
+    /// Handles to items of `other` can be used with this heap afterwards.
+    pub fn merge(&mut self, mut other: Self) {
+        self.root = match (self.root.take(), other.root.take()) {
+            (Some(a), Some(b)) => Some(meld(a, b)),
+            (a, b) => a.or(b),
+        };
+        self.len += other.len;
+        other.len = 0;
+    }
+
+    /// Lowers the priority of the item behind `handle`,
+    /// returns false if it has been popped or `priority` isn't lower than its current priority
+    ///
+    /// The handle must come from this heap, or a heap which was merged into it.
+    pub fn decrease_key(&mut self, handle: &Handle<T, P>, priority: P) -> bool {
+        let node = match handle.0.upgrade() {
+            Some(node) => node,
+            None => return false,
+        };
+
+        if priority >= node.borrow().priority {
+            return false;
+        }
+        node.borrow_mut().priority = priority;
+
+        // the root has no parent to get out of order with
+        let prev = match node.borrow().prev.upgrade() {
+            Some(prev) => prev,
+            None => return true,
+        };
+
+        // cut the subtree out of its parent's list of children
+        let next = node.borrow_mut().next.take();
+        if let Some(next) = &next {
+            next.borrow_mut().prev = Rc::downgrade(&prev);
+        }
+
+        let first_child = prev
+            .borrow()
+            .child
+            .as_ref()
+            .is_some_and(|child| Rc::ptr_eq(child, &node));
+        if first_child {
+            prev.borrow_mut().child = next;
+        } else {
+            prev.borrow_mut().next = next;
+        }
+        node.borrow_mut().prev = Weak::new();
+
+        let root = self.root.take().expect("a heap with a cut node has a root");
+        self.root = Some(meld(root, node));
+
+        true
+    }
+
+    /// Removes and returns the item with the smallest priority
+    pub fn pop(&mut self) -> Option<(T, P)> {
+        let root = self.root.take()?;
+        self.len -= 1;
+
+        // detach the children into a list of separate heaps
+        let mut children = Vec::new();
+        let mut child = root.borrow_mut().child.take();
+        while let Some(node) = child {
+            child = node.borrow_mut().next.take();
+            node.borrow_mut().prev = Weak::new();
+            children.push(node);
+        }
+
+        // meld the children in pairs from the left, then fold the pairs together from the right
+        let mut pairs = Vec::new();
+        let mut children = children.into_iter();
+        while let Some(a) = children.next() {
+            pairs.push(match children.next() {
+                Some(b) => meld(a, b),
+                None => a,
+            });
+        }
+        self.root = pairs.into_iter().rev().reduce(|acc, pair| meld(pair, acc));
+
+        // handles only hold weak references, so the root is no longer shared
+        let node = match Rc::try_unwrap(root) {
+            Ok(node) => node.into_inner(),
+            Err(_) => unreachable!("heap nodes are only owned by the heap"),
+        };
+
+        Some((node.item, node.priority))
+    }
+}
+
+impl<T, P: Ord> Default for PairingHeap<T, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, P> fmt::Debug for PairingHeap<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PairingHeap")
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, P> Drop for PairingHeap<T, P> {
+    fn drop(&mut self) {
+        // dropping the nodes recursively could overflow the stack on long lists of siblings
+        let mut stack: Vec<Link<T, P>> = self.root.take().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            let mut node = node.borrow_mut();
+            stack.extend(node.child.take());
+            stack.extend(node.next.take());
+        }
+    }
+}
+
+/// Links two heaps together, making the one with the larger root the first child of the other
+fn meld<T, P: Ord>(a: Link<T, P>, b: Link<T, P>) -> Link<T, P> {
+    let (parent, child) = if b.borrow().priority < a.borrow().priority {
+        (b, a)
+    } else {
+        (a, b)
+    };
+
+    {
+        let mut parent_ref = parent.borrow_mut();
+        let mut child_ref = child.borrow_mut();
+
+        if let Some(sibling) = &parent_ref.child {
+            sibling.borrow_mut().prev = Rc::downgrade(&child);
+        }
+        child_ref.next = parent_ref.child.take();
+        child_ref.prev = Rc::downgrade(&parent);
+    }
+    parent.borrow_mut().child = Some(child);
+
+    parent
+}
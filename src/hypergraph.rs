@@ -0,0 +1,278 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::{Edge, Graph, NodeBounds};
+use std::{
+    collections::{hash_map, HashMap, HashSet},
+    fmt,
+};
+
+/// Identifies a hyperedge in a [`Hypergraph`], in the order the hyperedges were added
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HyperedgeId(usize);
+
+impl HyperedgeId {
+    /// Returns the index of the hyperedge, the first hyperedge added has index 0
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl fmt::Display for HyperedgeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "h{}", self.0)
+    }
+}
+
+/// A node of the star expansion of a [`Hypergraph`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum StarNode<N> {
+    /// A node of the hypergraph
+    Node(N),
+    /// A hyperedge of the hypergraph
+    Hyperedge(HyperedgeId),
+}
+
+impl<N: fmt::Display> fmt::Display for StarNode<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Node(u) => u.fmt(f),
+            Self::Hyperedge(id) => id.fmt(f),
+        }
+    }
+}
+
+/// A hypergraph, where each hyperedge joins a whole set of nodes
+///
+/// This fits set membership data such as the authors of papers or the tags on posts,
+/// which can then be projected into a [`Graph`] to run the existing algorithms over it.
+/// ```
+/// use graph_algos::Hypergraph;
+/// use graph_algos::algos::dijkstra;
+///
+/// // the authors of three papers
+/// let mut papers = Hypergraph::new();
+/// papers.add_hyperedge(vec!["ada", "bob", "cat"]);
+/// papers.add_hyperedge(vec!["cat", "dan"]);
+/// papers.add_hyperedge(vec!["eve"]);
+///
+/// assert_eq!(papers.len(), 5);
+/// assert_eq!(papers.degree(&"cat"), 2);
+///
+/// // collaboration distance between the authors
+/// let coauthors = papers.clique_expansion();
+/// let (_, dist_map) = dijkstra(&coauthors, &"ada");
+/// assert_eq!(dist_map[&"dan"], 2.into());
+/// assert!(!dist_map.contains_key(&"eve"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Hypergraph<N: NodeBounds> {
+    /// the hyperedges each node is a member of
+    memberships: HashMap<N, Vec<HyperedgeId>>,
+
+    /// the members of each hyperedge indexed by id, in the order they were given
+    hyperedges: Vec<Vec<N>>,
+}
+
+impl<N: NodeBounds> Hypergraph<N> {
+    /// Creates a new hypergraph with no nodes or hyperedges
+    pub fn new() -> Self {
+        Self {
+            memberships: HashMap::new(),
+            hyperedges: Vec::new(),
+        }
+    }
+
+    /// Returns the number of nodes in the hypergraph
+    pub fn len(&self) -> usize {
+        self.memberships.len()
+    }
+
+    /// Returns whether the hypergraph has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.memberships.is_empty()
+    }
+
+    /// Returns the number of hyperedges in the hypergraph
+    pub fn hyperedge_count(&self) -> usize {
+        self.hyperedges.len()
+    }
+
+    /// Returns an iterator over the nodes of the hypergraph
+    pub fn nodes(&self) -> hash_map::Keys<'_, N, Vec<HyperedgeId>> {
+        self.memberships.keys()
+    }
+
+    /// Returns whether the node is in the hypergraph
+    pub fn contains_node(&self, u: &N) -> bool {
+        self.memberships.contains_key(u)
+    }
+
+    /// Adds a node which isn't in any hyperedge, if it isn't already present
+    pub fn add_node(&mut self, u: N) {
+        self.memberships.entry(u).or_default();
+    }
+
+    /// Adds a hyperedge joining the given nodes, adding any which aren't already present,
+    /// and returns its id
+    ///
+    /// Repeated members are only added once.
+    /// ```
+    /// use graph_algos::Hypergraph;
+    ///
+    /// let mut graph = Hypergraph::new();
+    /// let id = graph.add_hyperedge(vec![3, 1, 3, 2]);
+    ///
+    /// assert_eq!(graph.members(id), Some(&[3, 1, 2][..]));
+    /// assert_eq!(graph.hyperedges_of(&1), &[id]);
+    /// ```
+    pub fn add_hyperedge<I: IntoIterator<Item = N>>(&mut self, members: I) -> HyperedgeId {
+        let id = HyperedgeId(self.hyperedges.len());
+
+        let mut seen = HashSet::new();
+        let members: Vec<N> = members
+            .into_iter()
+            .filter(|u| seen.insert(u.clone()))
+            .collect();
+        for u in &members {
+            self.memberships.entry(u.clone()).or_default().push(id);
+        }
+        self.hyperedges.push(members);
+
+        id
+    }
+
+    /// Returns the members of a hyperedge,
+    /// or `None` if there is no hyperedge with that id
+    pub fn members(&self, id: HyperedgeId) -> Option<&[N]> {
+        self.hyperedges.get(id.0).map(Vec::as_slice)
+    }
+
+    /// Returns the hyperedges a node is a member of, in the order they were added
+    ///
+    /// Nodes which are not in the hypergraph aren't members of any hyperedge.
+    pub fn hyperedges_of(&self, u: &N) -> &[HyperedgeId] {
+        self.memberships.get(u).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the number of hyperedges a node is a member of
+    pub fn degree(&self, u: &N) -> usize {
+        self.hyperedges_of(u).len()
+    }
+
+    /// Returns an iterator over the hyperedges and their members, in id order
+    pub fn hyperedges(&self) -> impl Iterator<Item = (HyperedgeId, &[N])> + '_ {
+        self.hyperedges
+            .iter()
+            .enumerate()
+            .map(|(i, members)| (HyperedgeId(i), members.as_slice()))
+    }
+
+    /// Projects the hypergraph into a [`Graph`] on the same nodes,
+    /// with an edge each way between every two nodes which share a hyperedge
+    ///
+    /// The weight of each edge is the number of hyperedges the two nodes share.
+    /// ```
+    /// use graph_algos::{graph, Graph, Hypergraph};
+    ///
+    /// let mut tags = Hypergraph::new();
+    /// tags.add_hyperedge(vec!["rust", "graphs"]);
+    /// tags.add_hyperedge(vec!["rust", "graphs", "wasm"]);
+    ///
+    /// let expected: Graph<&str> = graph! {
+    ///     "rust" => ["graphs" => 2, "wasm" => 1],
+    ///     "graphs" => ["rust" => 2, "wasm" => 1],
+    ///     "wasm" => ["rust" => 1, "graphs" => 1],
+    /// };
+    /// assert_eq!(tags.clique_expansion(), expected);
+    /// ```
+    pub fn clique_expansion(&self) -> Graph<N> {
+        let mut shared: HashMap<(&N, &N), i64> = HashMap::new();
+        for members in &self.hyperedges {
+            for u in members {
+                for v in members.iter().filter(|v| *v != u) {
+                    *shared.entry((u, v)).or_default() += 1;
+                }
+            }
+        }
+
+        let mut graph = Graph::with_capacity(self.len());
+        for u in self.nodes() {
+            graph.add_node(u.clone());
+        }
+        for ((u, v), count) in shared {
+            graph.add_edge(u.clone(), Edge::new_with_weight(v.clone(), count));
+        }
+
+        graph
+    }
+
+    /// Projects the hypergraph into a bipartite [`Graph`] with a node for every node and hyperedge,
+    /// and an unweighted edge each way between each hyperedge and its members
+    /// ```
+    /// use graph_algos::{Hypergraph, StarNode};
+    ///
+    /// let mut graph = Hypergraph::new();
+    /// let id = graph.add_hyperedge(vec!['a', 'b']);
+    /// graph.add_node('c');
+    ///
+    /// let star = graph.star_expansion();
+    ///
+    /// assert_eq!(star.len(), 4);
+    /// assert!(star.is_edge(&StarNode::Node('a'), &StarNode::Hyperedge(id)));
+    /// assert!(star.is_edge(&StarNode::Hyperedge(id), &StarNode::Node('b')));
+    /// assert_eq!(star.neighbors(&StarNode::Node('c')).len(), 0);
+    /// ```
+    pub fn star_expansion(&self) -> Graph<StarNode<N>> {
+        let mut graph = Graph::with_capacity(self.len() + self.hyperedge_count());
+
+        for u in self.nodes() {
+            graph.add_node(StarNode::Node(u.clone()));
+        }
+        for (id, members) in self.hyperedges() {
+            graph.add_node(StarNode::Hyperedge(id));
+            for u in members {
+                graph.add_edge(
+                    StarNode::Hyperedge(id),
+                    Edge::new(StarNode::Node(u.clone())),
+                );
+                graph.add_edge(
+                    StarNode::Node(u.clone()),
+                    Edge::new(StarNode::Hyperedge(id)),
+                );
+            }
+        }
+
+        graph
+    }
+}
+
+impl<N: NodeBounds> Default for Hypergraph<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: NodeBounds, I: IntoIterator<Item = N>> std::iter::FromIterator<I> for Hypergraph<N> {
+    /// Creates a hypergraph with a hyperedge for each set of members
+    fn from_iter<T: IntoIterator<Item = I>>(iter: T) -> Self {
+        let mut graph = Self::new();
+        for members in iter {
+            graph.add_hyperedge(members);
+        }
+        graph
+    }
+}
@@ -0,0 +1,338 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{
+    visit::{EdgeWeighted, GraphRef, IntoNeighbors, IntoNodes},
+    Edge, EdgeWeight, Graph, NodeBounds,
+};
+use std::{collections::HashMap, convert::TryFrom, hash::BuildHasher, slice};
+
+/// Assigns a dense `u32` id to each distinct node
+///
+/// Ids are assigned in the order nodes are first interned, starting from 0.
+/// ```
+/// use graph_algos::NodeInterner;
+///
+/// let mut interner = NodeInterner::new();
+///
+/// let a = interner.intern("a long node name".to_string());
+/// let b = interner.intern("another long node name".to_string());
+///
+/// assert_eq!((a, b), (0, 1));
+/// assert_eq!(interner.intern("a long node name".to_string()), a);
+/// assert_eq!(interner.id(&"another long node name".to_string()), Some(b));
+/// assert_eq!(interner.resolve(a).unwrap(), "a long node name");
+/// ```
+#[derive(Debug, Clone)]
+pub struct NodeInterner<N: NodeBounds> {
+    /// the nodes in id order
+    nodes: Vec<N>,
+
+    /// a map from each node to its id
+    ids: HashMap<N, u32>,
+
+    /// every id in order, so that references to ids can be handed out
+    id_refs: Vec<u32>,
+}
+
+impl<N: NodeBounds> Default for NodeInterner<N> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            ids: HashMap::new(),
+            id_refs: Vec::new(),
+        }
+    }
+}
+
+impl<N: NodeBounds> NodeInterner<N> {
+    /// Creates a new empty interner
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the id of a node, assigning it the next id if it hasn't been seen before
+    ///
+    /// Panics if more than `u32::MAX` nodes are interned.
+    pub fn intern(&mut self, node: N) -> u32 {
+        if let Some(&id) = self.ids.get(&node) {
+            return id;
+        }
+
+        let id = u32::try_from(self.nodes.len()).expect("too many nodes to intern");
+        self.ids.insert(node.clone(), id);
+        self.nodes.push(node);
+        self.id_refs.push(id);
+
+        id
+    }
+
+    /// Returns the id of a node if it has been interned
+    pub fn id(&self, node: &N) -> Option<u32> {
+        self.ids.get(node).copied()
+    }
+
+    /// Returns the node with the given id
+    pub fn resolve(&self, id: u32) -> Option<&N> {
+        self.nodes.get(id as usize)
+    }
+
+    /// Returns the number of interned nodes
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns whether no nodes have been interned
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the interned nodes in id order
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+}
+
+/// A graph whose nodes are interned to dense `u32` ids,
+/// with the successors of each node stored in a `Vec` indexed by id
+///
+/// Running the algorithms over an indexed graph only ever hashes `u32` ids,
+/// which is much cheaper than hashing and cloning large node types such as strings.
+/// ```
+/// use graph_algos::{graph, Graph, IndexedGraph};
+/// use graph_algos::algos::dijkstra;
+///
+/// let graph: Graph<String> = graph! {
+///     "london".into() => ["paris".into() => 5, "berlin".into() => 9],
+///     "paris".into() => ["berlin".into() => 3],
+/// };
+///
+/// let indexed = IndexedGraph::from(&graph);
+///
+/// let london = indexed.id(&"london".into()).unwrap();
+/// let berlin = indexed.id(&"berlin".into()).unwrap();
+///
+/// let (_, dist_map) = dijkstra(&indexed, &london);
+/// assert_eq!(dist_map[&berlin], 8.into());
+///
+/// assert_eq!(indexed.to_graph(), graph);
+/// ```
+#[derive(Debug, Clone)]
+pub struct IndexedGraph<N: NodeBounds> {
+    interner: NodeInterner<N>,
+
+    /// the successors of each node, indexed by id
+    adjacency: Vec<Vec<Edge<u32>>>,
+}
+
+impl<N: NodeBounds> Default for IndexedGraph<N> {
+    fn default() -> Self {
+        Self {
+            interner: NodeInterner::new(),
+            adjacency: Vec::new(),
+        }
+    }
+}
+
+impl<N: NodeBounds> IndexedGraph<N> {
+    /// Creates a new empty indexed graph
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the interner mapping between nodes and ids
+    pub fn interner(&self) -> &NodeInterner<N> {
+        &self.interner
+    }
+
+    /// Returns the id of a node
+    pub fn id(&self, node: &N) -> Option<u32> {
+        self.interner.id(node)
+    }
+
+    /// Returns the node with the given id
+    pub fn resolve(&self, id: u32) -> Option<&N> {
+        self.interner.resolve(id)
+    }
+
+    /// Returns the number of nodes in the graph
+    pub fn len(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    /// Returns whether the graph has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.adjacency.is_empty()
+    }
+
+    /// Adds a node with no edges to the graph, returning its id
+    pub fn add_node(&mut self, node: N) -> u32 {
+        let id = self.interner.intern(node);
+
+        if id as usize == self.adjacency.len() {
+            self.adjacency.push(Vec::new());
+        }
+
+        id
+    }
+
+    /// Adds an edge to the graph, interning both ends
+    /// ```
+    /// use graph_algos::{Edge, IndexedGraph};
+    ///
+    /// let mut graph = IndexedGraph::new();
+    /// graph.add_edge("a", Edge::new_with_weight("b", 2));
+    ///
+    /// let a = graph.id(&"a").unwrap();
+    /// let b = graph.id(&"b").unwrap();
+    /// assert_eq!(graph.succs(a), &[Edge::new_with_weight(b, 2)]);
+    /// ```
+    pub fn add_edge(&mut self, u: N, e: Edge<N>) {
+        let v = self.add_node(e.destination().clone());
+        let u = self.add_node(u);
+
+        let edge = match e.weight() {
+            Some(w) => Edge::new_with_weight(v, w),
+            None => Edge::new(v),
+        };
+
+        self.adjacency[u as usize].push(edge);
+    }
+
+    /// Returns the successors of the node with the given id
+    ///
+    /// Unknown ids have no successors.
+    pub fn succs(&self, id: u32) -> &[Edge<u32>] {
+        self.adjacency.get(id as usize).map_or(&[], Vec::as_slice)
+    }
+
+    /// Converts the graph back into a [`Graph`] over the original nodes
+    pub fn to_graph(&self) -> Graph<N> {
+        let mut graph = Graph::with_capacity(self.len());
+
+        for (u, succs) in self.interner.nodes().iter().zip(&self.adjacency) {
+            graph.add_node(u.clone());
+
+            for edge in succs {
+                let dest = self.interner.nodes[*edge.destination() as usize].clone();
+                let edge = match edge.weight() {
+                    Some(w) => Edge::new_with_weight(dest, w),
+                    None => Edge::new(dest),
+                };
+
+                graph.add_edge(u.clone(), edge);
+            }
+        }
+
+        graph
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher> From<&Graph<N, S>> for IndexedGraph<N> {
+    fn from(graph: &Graph<N, S>) -> Self {
+        let mut indexed = IndexedGraph::new();
+
+        for node in graph.nodes() {
+            indexed.add_node(node.clone());
+        }
+
+        for (u, edge) in graph.edges() {
+            indexed.add_edge(u.clone(), edge.clone());
+        }
+
+        indexed
+    }
+}
+
+impl<'a, N: NodeBounds> GraphRef<'a> for &'a IndexedGraph<N> {
+    type Node = u32;
+
+    fn contains_node(self, u: &u32) -> bool {
+        (*u as usize) < self.len()
+    }
+}
+
+impl<'a, N: NodeBounds> IntoNodes<'a> for &'a IndexedGraph<N> {
+    type Nodes = slice::Iter<'a, u32>;
+
+    fn nodes(self) -> Self::Nodes {
+        self.interner.id_refs.iter()
+    }
+
+    fn node_count(self) -> usize {
+        self.len()
+    }
+}
+
+impl<'a, N: NodeBounds> IntoNeighbors<'a> for &'a IndexedGraph<N> {
+    type Neighbors = IndexedNeighbors<'a>;
+
+    fn neighbors(self, u: &u32) -> Self::Neighbors {
+        IndexedNeighbors {
+            inner: self.succs(*u).iter(),
+        }
+    }
+}
+
+impl<'a, N: NodeBounds> EdgeWeighted<'a> for &'a IndexedGraph<N> {
+    type NeighborsWeighted = IndexedNeighborsWeighted<'a>;
+
+    fn neighbors_weighted(self, u: &u32) -> Self::NeighborsWeighted {
+        IndexedNeighborsWeighted {
+            inner: self.succs(*u).iter(),
+        }
+    }
+}
+
+/// An iterator over the ids of the successors of a node in an [`IndexedGraph`]
+#[derive(Debug, Clone)]
+pub struct IndexedNeighbors<'a> {
+    inner: slice::Iter<'a, Edge<u32>>,
+}
+
+impl<'a> Iterator for IndexedNeighbors<'a> {
+    type Item = &'a u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(Edge::destination)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator over the ids of the successors of a node in an [`IndexedGraph`]
+/// along with the edge weights
+#[derive(Debug, Clone)]
+pub struct IndexedNeighborsWeighted<'a> {
+    inner: slice::Iter<'a, Edge<u32>>,
+}
+
+impl<'a> Iterator for IndexedNeighborsWeighted<'a> {
+    type Item = (&'a u32, Option<EdgeWeight>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|edge| (edge.destination(), edge.weight()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
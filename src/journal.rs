@@ -0,0 +1,270 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::{watch, Edge, Graph, GraphEvent, NodeBounds};
+use std::{collections::hash_map::RandomState, hash::BuildHasher};
+
+/// A graph which records every change made to it so the changes can be undone and redone
+///
+/// Each call to a mutating method is one step of the history, which [`JournaledGraph::undo`]
+/// reverts as a whole, even if it changed many nodes and edges.
+/// Making a change after undoing some discards the steps which could have been redone.
+/// ```
+/// use graph_algos::{graph, Edge, Graph, JournaledGraph};
+///
+/// let mut graph: JournaledGraph<u32> = JournaledGraph::new();
+/// graph.add_edge(1, Edge::new(2));
+/// graph.add_edge(2, Edge::new(3));
+/// graph.retain_nodes(|&node| node != 2);
+/// assert_eq!(graph.graph(), &graph! { 1 => [], 3 => [] });
+///
+/// assert!(graph.undo());
+/// assert_eq!(graph.graph(), &graph! { 1 => [2], 2 => [3] });
+///
+/// assert!(graph.undo());
+/// assert!(graph.redo());
+/// assert!(graph.is_edge(&2, &3));
+///
+/// assert!(graph.undo() && graph.undo());
+/// assert!(graph.graph().is_empty());
+/// assert!(!graph.undo());
+/// ```
+#[derive(Debug, Clone)]
+pub struct JournaledGraph<N: NodeBounds, S = RandomState> {
+    graph: Graph<N, S>,
+    undo: Vec<Step<N>>,
+    redo: Vec<Step<N>>,
+    next_id: u64,
+}
+
+/// The changes made by one call to a mutating method of a [`JournaledGraph`]
+#[derive(Debug, Clone)]
+struct Step<N: NodeBounds> {
+    id: u64,
+    events: Vec<GraphEvent<N>>,
+}
+
+/// A point in the history of a [`JournaledGraph`] which it can be rolled back to,
+/// created by [`JournaledGraph::checkpoint`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Checkpoint {
+    /// the id of the last step before the checkpoint
+    last_step: Option<u64>,
+}
+
+impl<N: NodeBounds, S: BuildHasher + Default> JournaledGraph<N, S> {
+    /// Creates an empty graph with no history
+    pub fn new() -> Self {
+        Self::from(Graph::default())
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher> JournaledGraph<N, S> {
+    /// Returns the graph as it currently is
+    pub fn graph(&self) -> &Graph<N, S> {
+        &self.graph
+    }
+
+    /// Discards the history and returns the graph
+    pub fn into_inner(self) -> Graph<N, S> {
+        self.graph
+    }
+
+    /// Returns whether there is an edge from `u` to `v`
+    pub fn is_edge(&self, u: &N, v: &N) -> bool {
+        self.graph.is_edge(u, v)
+    }
+
+    /// Adds a node with no edges to the graph, if it isn't already in the graph
+    pub fn add_node(&mut self, u: N) {
+        let events = watch::add_node(&mut self.graph, u);
+        self.record(events);
+    }
+
+    /// Adds an edge to the graph, along with either of its nodes which aren't in the graph yet
+    pub fn add_edge(&mut self, u: N, e: Edge<N>) {
+        let events = watch::add_edge(&mut self.graph, u, e);
+        self.record(events);
+    }
+
+    /// Removes an edge from `u` to `v`, if there is one
+    pub fn remove_edge(&mut self, u: &N, v: &N) {
+        let events = watch::remove_edge(&mut self.graph, u, v);
+        self.record(events);
+    }
+
+    /// Removes every node for which `keep` returns false, along with all edges into and out of it
+    pub fn retain_nodes<F: FnMut(&N) -> bool>(&mut self, keep: F) {
+        let events = watch::retain_nodes(&mut self.graph, keep);
+        self.record(events);
+    }
+
+    /// Removes every edge for which `keep` returns false, given the source node and the edge
+    pub fn retain_edges<F: FnMut(&N, &Edge<N>) -> bool>(&mut self, keep: F) {
+        let events = watch::retain_edges(&mut self.graph, keep);
+        self.record(events);
+    }
+
+    /// Reverts the most recent change which hasn't been undone,
+    /// returning whether there was one
+    pub fn undo(&mut self) -> bool {
+        let step = match self.undo.pop() {
+            Some(step) => step,
+            None => return false,
+        };
+
+        for event in step.events.iter().rev() {
+            self.apply(inverse(event.clone()));
+        }
+        self.redo.push(step);
+
+        true
+    }
+
+    /// Makes the most recently undone change again, returning whether there was one
+    pub fn redo(&mut self) -> bool {
+        let step = match self.redo.pop() {
+            Some(step) => step,
+            None => return false,
+        };
+
+        for event in &step.events {
+            self.apply(event.clone());
+        }
+        self.undo.push(step);
+
+        true
+    }
+
+    /// Returns whether there is a change which [`JournaledGraph::undo`] would revert
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Returns whether there is a change which [`JournaledGraph::redo`] would make again
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Marks the current point in the history so it can be returned to with [`JournaledGraph::rollback`]
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            last_step: self.undo.last().map(|step| step.id),
+        }
+    }
+
+    /// Undoes every change made since `checkpoint`, returning whether the graph is back as it was then
+    ///
+    /// The rolled back changes can be redone one by one with [`JournaledGraph::redo`].
+    /// If changes from before the checkpoint have since been undone, or the history
+    /// has been cleared, the graph can't be returned to how it was at the checkpoint:
+    /// every change made after it is undone and `false` is returned.
+    /// ```
+    /// use graph_algos::{Edge, JournaledGraph};
+    ///
+    /// let mut graph: JournaledGraph<&str> = JournaledGraph::new();
+    /// graph.add_edge("a", Edge::new_with_weight("b", 3));
+    ///
+    /// // try out a change, and throw it away if it doesn't help
+    /// let before = graph.checkpoint();
+    /// graph.remove_edge(&"a", &"b");
+    /// graph.add_edge("a", Edge::new_with_weight("c", 1));
+    /// graph.add_edge("c", Edge::new_with_weight("b", 1));
+    ///
+    /// assert!(graph.rollback(before));
+    /// assert!(graph.is_edge(&"a", &"b"));
+    /// assert_eq!(graph.graph().len(), 2);
+    /// ```
+    pub fn rollback(&mut self, checkpoint: Checkpoint) -> bool {
+        while self.checkpoint() != checkpoint {
+            match self.undo.last() {
+                Some(step) if Some(step.id) > checkpoint.last_step => {
+                    self.undo();
+                }
+                _ => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Forgets every change made so far, so none of them can be undone or redone
+    pub fn clear_history(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Adds a step to the history for a change which has already been made to the graph
+    fn record(&mut self, events: Vec<GraphEvent<N>>) {
+        if events.is_empty() {
+            return;
+        }
+
+        self.redo.clear();
+        self.undo.push(Step {
+            id: self.next_id,
+            events,
+        });
+        self.next_id += 1;
+    }
+
+    /// Makes the change described by an event
+    fn apply(&mut self, event: GraphEvent<N>) {
+        match event {
+            GraphEvent::NodeAdded(node) => self.graph.add_node(node),
+            // the edges of the node have already been removed by earlier events
+            GraphEvent::NodeRemoved(node) => self.graph.remove_node_unchecked(&node),
+            GraphEvent::EdgeAdded { source, edge } => self.graph.add_edge(source, edge),
+            GraphEvent::EdgeRemoved { source, edge } => {
+                if let Some(succs) = self.graph.succs_mut(&source) {
+                    // parallel edges are only told apart by their weights,
+                    // and the most recently added copy is at the end
+                    if let Some(pos) = succs.iter().rposition(|e| *e == edge) {
+                        succs.remove(pos);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the event which reverts `event`
+fn inverse<N: NodeBounds>(event: GraphEvent<N>) -> GraphEvent<N> {
+    match event {
+        GraphEvent::NodeAdded(node) => GraphEvent::NodeRemoved(node),
+        GraphEvent::NodeRemoved(node) => GraphEvent::NodeAdded(node),
+        GraphEvent::EdgeAdded { source, edge } => GraphEvent::EdgeRemoved { source, edge },
+        GraphEvent::EdgeRemoved { source, edge } => GraphEvent::EdgeAdded { source, edge },
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher + Default> Default for JournaledGraph<N, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: NodeBounds, S> From<Graph<N, S>> for JournaledGraph<N, S> {
+    /// Starts journaling an existing graph, only changes made from now on can be undone
+    fn from(graph: Graph<N, S>) -> Self {
+        Self {
+            graph,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            next_id: 0,
+        }
+    }
+}
@@ -43,19 +43,117 @@
 //! );
 //! ```
 
-// use macros from the failure crate
-#[macro_use]
-extern crate failure;
-
 mod graph;
 pub use graph::{Graph, NodeBounds};
 
 mod edge;
 pub use edge::Edge;
-pub(crate) use edge::ParseEdgeError;
+
+pub mod error;
 
 mod edge_weight;
 pub use edge_weight::EdgeWeight;
 
 mod path;
 pub use path::{Path, PredMap};
+
+mod weights;
+
+mod validate;
+
+mod shards;
+
+mod relabel;
+
+mod spectral;
+pub use spectral::NodeMatrix;
+
+mod line_graph;
+pub use line_graph::LineWeight;
+
+mod reader;
+pub use reader::GraphReader;
+
+mod control;
+pub use control::{AlgoControl, CancelToken, Progress};
+
+mod watch;
+pub use watch::{GraphEvent, WatchedGraph};
+
+mod journal;
+pub use journal::{Checkpoint, JournaledGraph};
+
+mod diff;
+pub use diff::{GraphDiff, ReweightedEdge};
+
+mod walk;
+pub use walk::RandomWalker;
+
+mod traverse;
+pub use traverse::SpanningForest;
+
+mod grid;
+
+mod indexed;
+pub use indexed::{IndexedGraph, NodeInterner};
+
+mod matrix;
+pub use matrix::MatrixGraph;
+
+mod multigraph;
+pub use multigraph::{EdgeId, MaxFlow, MultiGraph};
+
+mod hypergraph;
+pub use hypergraph::{HyperedgeId, Hypergraph, StarNode};
+
+mod parse;
+pub use parse::{DuplicateEdges, GraphTextFormat, ParseOptions, TextLayout};
+
+mod builder;
+pub use builder::GraphBuilder;
+
+mod format;
+pub use format::{FormatStyle, GraphFormatter};
+
+pub mod visit;
+
+pub mod property;
+
+pub mod algos;
+
+pub mod analysis;
+
+pub mod generators;
+
+pub mod sampling;
+
+pub mod schedule;
+
+pub mod formats;
+
+pub mod tree;
+
+pub mod util;
+
+pub mod heap;
+
+pub mod render;
+
+pub mod geo;
+
+pub mod frozen;
+
+#[cfg(feature = "tokio")]
+pub mod async_helpers;
+
+#[cfg(feature = "parallel")]
+pub mod parallel;
+
+#[cfg(feature = "petgraph")]
+pub mod petgraph_compat;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(any(feature = "proptest", feature = "quickcheck", feature = "arbitrary"))]
+pub mod testing;
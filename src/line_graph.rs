@@ -0,0 +1,125 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{Edge, EdgeWeight, Graph, NodeBounds};
+use std::{collections::HashMap, hash::BuildHasher};
+
+/// How the weight of an edge in a line graph is derived from the pair of edges it joins,
+/// see [`Graph::line_graph`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum LineWeight {
+    /// Use the weight of the second edge, so a path through the line graph costs the same as
+    /// the path through the original graph minus its first edge
+    #[default]
+    Outgoing,
+
+    /// Use the weight of the first edge
+    Incoming,
+
+    /// Use the sum of both weights, leaving the edge unweighted unless both edges have weights
+    Sum,
+
+    /// Leave every edge unweighted
+    Unweighted,
+}
+
+impl LineWeight {
+    /// Combines the weights of two consecutive edges
+    fn combine(
+        self,
+        incoming: Option<EdgeWeight>,
+        outgoing: Option<EdgeWeight>,
+    ) -> Option<EdgeWeight> {
+        match self {
+            Self::Outgoing => outgoing,
+            Self::Incoming => incoming,
+            Self::Sum => Some(incoming? + outgoing?),
+            Self::Unweighted => None,
+        }
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher> Graph<N, S> {
+    /// Returns the line graph, which has a node `(u, v)` for each edge from `u` to `v`
+    /// and an edge from `(u, v)` to `(v, w)` for each pair of consecutive edges
+    ///
+    /// The weights of the new edges are derived from the weights of the edges they join by `rule`.
+    /// Parallel edges become a single node, standing for the lightest of them.
+    ///
+    /// Routing over the line graph is the standard way of handling turn restrictions,
+    /// as each turn becomes an edge which can be removed or reweighted.
+    /// ```
+    /// use graph_algos::{graph, Graph, LineWeight};
+    /// use graph_algos::algos::dijkstra;
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2 => 1],
+    ///     2 => [3 => 1, 4 => 5],
+    ///     3 => [4 => 1],
+    /// };
+    ///
+    /// let mut lines = graph.line_graph(LineWeight::Outgoing);
+    /// assert_eq!(lines.len(), 4);
+    /// assert!(lines.is_edge(&(1, 2), &(2, 3)));
+    ///
+    /// // ban turning from 1 -> 2 onto 2 -> 3
+    /// lines.remove_edge(&(1, 2), &(2, 3));
+    ///
+    /// let (_, distances) = dijkstra(&lines, &(1, 2));
+    /// // the distances leave out the weight of the first edge
+    /// assert_eq!(distances[&(2, 4)], 5.into());
+    /// assert_eq!(distances.get(&(3, 4)), None);
+    /// ```
+    pub fn line_graph(&self, rule: LineWeight) -> Graph<(N, N)> {
+        // the lightest edge between each pair of nodes, and the edges out of each node
+        let mut lightest: HashMap<(&N, &N), Option<EdgeWeight>> = HashMap::new();
+        let mut out_edges: HashMap<&N, Vec<&N>> = HashMap::new();
+        for (u, edge) in self.edges() {
+            let v = edge.destination();
+            match lightest.get_mut(&(u, v)) {
+                Some(weight) => {
+                    if let Some(new) = edge.weight() {
+                        if !matches!(weight, Some(old) if *old <= new) {
+                            *weight = Some(new);
+                        }
+                    }
+                }
+                None => {
+                    lightest.insert((u, v), edge.weight());
+                    out_edges.entry(u).or_default().push(v);
+                }
+            }
+        }
+
+        let mut lines = Graph::with_capacity(lightest.len());
+        for (&(u, v), &incoming) in &lightest {
+            let line = (u.clone(), v.clone());
+            lines.add_node(line.clone());
+
+            for &w in out_edges.get(v).into_iter().flatten() {
+                let next = (v.clone(), w.clone());
+                let edge = match rule.combine(incoming, lightest[&(v, w)]) {
+                    Some(weight) => Edge::new_with_weight(next, weight),
+                    None => Edge::new(next),
+                };
+                lines.add_edge(line.clone(), edge);
+            }
+        }
+
+        lines
+    }
+}
@@ -0,0 +1,384 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{
+    visit::{EdgeWeighted, GraphRef, IntoNeighbors, IntoNodes},
+    Edge, EdgeWeight, Graph, NodeBounds,
+};
+use std::{collections::HashMap, hash::BuildHasher, slice};
+
+/// The number of bits in each word of the adjacency bitset
+const WORD_BITS: usize = 64;
+
+/// A graph type implemented using an adjacency matrix
+///
+/// Looking up whether an edge exists or the weight of an edge is O(1),
+/// which makes this a better fit than [`Graph`] for dense graphs.
+/// The set of nodes is fixed when the matrix is created,
+/// and parallel edges are collapsed into the first copy of the edge.
+/// ```
+/// use graph_algos::{graph, Graph, MatrixGraph};
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2 => 4, 3 => 1],
+///     3 => [2 => 2],
+/// };
+///
+/// let mut matrix = MatrixGraph::from(&graph);
+///
+/// assert!(matrix.is_edge(&1, &2));
+/// assert_eq!(matrix.weight(&3, &2), Some(2.into()));
+///
+/// matrix.remove_edge(&1, &2);
+/// assert!(!matrix.is_edge(&1, &2));
+///
+/// let back: Graph<u32> = matrix.to_graph();
+/// assert!(back.is_edge(&1, &3));
+/// assert!(!back.is_edge(&1, &2));
+/// ```
+///
+/// The algorithms in [`crate::algos`] can run directly on a matrix graph:
+/// ```
+/// use graph_algos::{graph, Graph, MatrixGraph};
+/// use graph_algos::algos::dijkstra;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2 => 4, 3 => 1],
+///     3 => [2 => 2],
+/// };
+/// let matrix = MatrixGraph::from(&graph);
+///
+/// let (_, dist_map) = dijkstra(&matrix, &1);
+/// assert_eq!(dist_map[&2], 3.into());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MatrixGraph<N: NodeBounds> {
+    /// the nodes in index order
+    nodes: Vec<N>,
+
+    /// a map from each node to its index
+    indices: HashMap<N, usize>,
+
+    /// the number of words in each row of the bitset
+    row_words: usize,
+
+    /// a row-major bitset where bit (u, v) is set if there is an edge u -> v
+    adjacency: Vec<u64>,
+
+    /// a row-major matrix of the weights of the edges
+    weights: Vec<Option<EdgeWeight>>,
+}
+
+impl<N: NodeBounds> MatrixGraph<N> {
+    /// Creates a matrix graph over the given nodes with no edges
+    ///
+    /// Duplicate nodes are only added once.
+    /// ```
+    /// use graph_algos::MatrixGraph;
+    ///
+    /// let matrix = MatrixGraph::with_nodes(vec!['a', 'b', 'c', 'a']);
+    ///
+    /// assert_eq!(matrix.len(), 3);
+    /// assert_eq!(matrix.index_of(&'b'), Some(1));
+    /// assert!(!matrix.is_edge(&'a', &'b'));
+    /// ```
+    pub fn with_nodes(nodes: impl IntoIterator<Item = N>) -> Self {
+        let mut indices = HashMap::new();
+        let mut node_vec = Vec::new();
+
+        for node in nodes {
+            if !indices.contains_key(&node) {
+                indices.insert(node.clone(), node_vec.len());
+                node_vec.push(node);
+            }
+        }
+
+        let n = node_vec.len();
+        let row_words = n.div_ceil(WORD_BITS);
+
+        Self {
+            nodes: node_vec,
+            indices,
+            row_words,
+            adjacency: vec![0; n * row_words],
+            weights: vec![None; n * n],
+        }
+    }
+
+    /// Returns the number of nodes in the graph
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns whether the graph has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the nodes of the graph in index order
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    /// Returns the index of a node in the matrix
+    pub fn index_of(&self, u: &N) -> Option<usize> {
+        self.indices.get(u).copied()
+    }
+
+    /// Returns the node at an index in the matrix
+    pub fn node(&self, index: usize) -> Option<&N> {
+        self.nodes.get(index)
+    }
+
+    /// Returns whether there is an edge between the nodes at the two indices
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn is_edge_index(&self, u: usize, v: usize) -> bool {
+        assert!(u < self.len() && v < self.len(), "node index out of bounds");
+        self.adjacency[u * self.row_words + v / WORD_BITS] & (1 << (v % WORD_BITS)) != 0
+    }
+
+    /// Returns the weight of the edge between the nodes at the two indices
+    ///
+    /// Returns `None` if there is no edge, or if the edge has no weight.
+    /// Panics if either index is out of bounds.
+    pub fn weight_index(&self, u: usize, v: usize) -> Option<EdgeWeight> {
+        assert!(u < self.len() && v < self.len(), "node index out of bounds");
+        self.weights[u * self.len() + v]
+    }
+
+    /// Returns whether an edge exists in the graph
+    pub fn is_edge(&self, u: &N, v: &N) -> bool {
+        match (self.index_of(u), self.index_of(v)) {
+            (Some(u), Some(v)) => self.is_edge_index(u, v),
+            _ => false,
+        }
+    }
+
+    /// Returns the weight of an edge in the graph
+    ///
+    /// Returns `None` if there is no edge, or if the edge has no weight.
+    pub fn weight(&self, u: &N, v: &N) -> Option<EdgeWeight> {
+        self.weight_index(self.index_of(u)?, self.index_of(v)?)
+    }
+
+    /// Adds an edge between two nodes already in the matrix, replacing any existing edge
+    ///
+    /// Returns false if either node is not in the matrix.
+    /// ```
+    /// use graph_algos::MatrixGraph;
+    ///
+    /// let mut matrix = MatrixGraph::with_nodes(vec![1, 2]);
+    ///
+    /// assert!(matrix.add_edge(&1, &2, Some(5.into())));
+    /// assert_eq!(matrix.weight(&1, &2), Some(5.into()));
+    ///
+    /// // 3 is not a node in the matrix
+    /// assert!(!matrix.add_edge(&1, &3, None));
+    /// ```
+    pub fn add_edge(&mut self, u: &N, v: &N, weight: Option<EdgeWeight>) -> bool {
+        match (self.index_of(u), self.index_of(v)) {
+            (Some(u), Some(v)) => {
+                self.adjacency[u * self.row_words + v / WORD_BITS] |= 1 << (v % WORD_BITS);
+                self.weights[u * self.nodes.len() + v] = weight;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes the edge between two nodes, returning whether there was an edge to remove
+    pub fn remove_edge(&mut self, u: &N, v: &N) -> bool {
+        match (self.index_of(u), self.index_of(v)) {
+            (Some(u), Some(v)) if self.is_edge_index(u, v) => {
+                self.adjacency[u * self.row_words + v / WORD_BITS] &= !(1 << (v % WORD_BITS));
+                self.weights[u * self.nodes.len() + v] = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns an iterator over the indices of the successors of the node at an index
+    ///
+    /// Panics if the index is out of bounds.
+    /// ```
+    /// use graph_algos::MatrixGraph;
+    ///
+    /// let mut matrix = MatrixGraph::with_nodes(0..200);
+    /// matrix.add_edge(&0, &3, None);
+    /// matrix.add_edge(&0, &64, None);
+    /// matrix.add_edge(&0, &199, None);
+    ///
+    /// let succs: Vec<usize> = matrix.succ_indices(0).collect();
+    /// assert_eq!(succs, vec![3, 64, 199]);
+    /// ```
+    pub fn succ_indices(&self, u: usize) -> SuccIndices<'_> {
+        assert!(u < self.len(), "node index out of bounds");
+        let row = &self.adjacency[u * self.row_words..(u + 1) * self.row_words];
+
+        SuccIndices {
+            words: row.iter(),
+            next_base: 0,
+            base: 0,
+            current: 0,
+        }
+    }
+
+    /// Converts the matrix back into an adjacency list [`Graph`]
+    pub fn to_graph(&self) -> Graph<N> {
+        let mut graph = Graph::empty();
+
+        for (u, node) in self.nodes.iter().enumerate() {
+            graph.add_node(node.clone());
+
+            for v in self.succ_indices(u) {
+                let dest = self.nodes[v].clone();
+                let edge = match self.weight_index(u, v) {
+                    Some(w) => Edge::new_with_weight(dest, w),
+                    None => Edge::new(dest),
+                };
+
+                graph.add_edge(node.clone(), edge);
+            }
+        }
+
+        graph
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher> From<&Graph<N, S>> for MatrixGraph<N> {
+    fn from(graph: &Graph<N, S>) -> Self {
+        let mut matrix = MatrixGraph::with_nodes(graph.nodes().cloned());
+
+        for (u, edge) in graph.edges() {
+            // keep the first of any parallel edges
+            if !matrix.is_edge(u, edge.destination()) {
+                matrix.add_edge(u, edge.destination(), edge.weight());
+            }
+        }
+
+        matrix
+    }
+}
+
+impl<N: NodeBounds> From<&MatrixGraph<N>> for Graph<N> {
+    fn from(matrix: &MatrixGraph<N>) -> Self {
+        matrix.to_graph()
+    }
+}
+
+/// An iterator over the indices of the successors of a node in a [`MatrixGraph`]
+#[derive(Debug, Clone)]
+pub struct SuccIndices<'a> {
+    words: slice::Iter<'a, u64>,
+    next_base: usize,
+    base: usize,
+    current: u64,
+}
+
+impl Iterator for SuccIndices<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // move to the next word with a set bit
+        while self.current == 0 {
+            self.current = *self.words.next()?;
+            self.base = self.next_base;
+            self.next_base += WORD_BITS;
+        }
+
+        // clear the lowest set bit and return its index
+        let bit = self.current.trailing_zeros() as usize;
+        self.current &= self.current - 1;
+
+        Some(self.base + bit)
+    }
+}
+
+impl<'a, N: NodeBounds + 'a> GraphRef<'a> for &'a MatrixGraph<N> {
+    type Node = N;
+
+    fn contains_node(self, u: &N) -> bool {
+        self.indices.contains_key(u)
+    }
+}
+
+impl<'a, N: NodeBounds + 'a> IntoNodes<'a> for &'a MatrixGraph<N> {
+    type Nodes = slice::Iter<'a, N>;
+
+    fn nodes(self) -> Self::Nodes {
+        self.nodes.iter()
+    }
+
+    fn node_count(self) -> usize {
+        self.len()
+    }
+}
+
+impl<'a, N: NodeBounds + 'a> IntoNeighbors<'a> for &'a MatrixGraph<N> {
+    type Neighbors = MatrixNeighbors<'a, N>;
+
+    fn neighbors(self, u: &N) -> Self::Neighbors {
+        MatrixNeighbors {
+            inner: self.neighbors_weighted(u),
+        }
+    }
+}
+
+impl<'a, N: NodeBounds + 'a> EdgeWeighted<'a> for &'a MatrixGraph<N> {
+    type NeighborsWeighted = MatrixNeighborsWeighted<'a, N>;
+
+    fn neighbors_weighted(self, u: &N) -> Self::NeighborsWeighted {
+        MatrixNeighborsWeighted {
+            matrix: self,
+            inner: self.index_of(u).map(|u| (u, self.succ_indices(u))),
+        }
+    }
+}
+
+/// An iterator over the successors of a node in a [`MatrixGraph`]
+#[derive(Debug, Clone)]
+pub struct MatrixNeighbors<'a, N: NodeBounds> {
+    inner: MatrixNeighborsWeighted<'a, N>,
+}
+
+impl<'a, N: NodeBounds> Iterator for MatrixNeighbors<'a, N> {
+    type Item = &'a N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(v, _)| v)
+    }
+}
+
+/// An iterator over the successors of a node in a [`MatrixGraph`] along with the edge weights
+#[derive(Debug, Clone)]
+pub struct MatrixNeighborsWeighted<'a, N: NodeBounds> {
+    matrix: &'a MatrixGraph<N>,
+    inner: Option<(usize, SuccIndices<'a>)>,
+}
+
+impl<'a, N: NodeBounds> Iterator for MatrixNeighborsWeighted<'a, N> {
+    type Item = (&'a N, Option<EdgeWeight>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (u, succs) = self.inner.as_mut()?;
+        let v = succs.next()?;
+
+        Some((&self.matrix.nodes[v], self.matrix.weight_index(*u, v)))
+    }
+}
@@ -0,0 +1,506 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::{
+    visit::{EdgeWeighted, GraphRef, IntoNeighbors, IntoNodes},
+    Edge, EdgeWeight, Graph, NodeBounds,
+};
+use std::{
+    collections::{hash_map, HashMap, VecDeque},
+    fmt,
+    hash::BuildHasher,
+    slice,
+};
+
+/// An edge and its source, or `None` once the edge has been removed
+type Slot<N> = Option<(N, Edge<N>)>;
+
+/// Identifies a single edge in a [`MultiGraph`]
+///
+/// Ids are handed out in increasing order and are never reused,
+/// so an id held onto after its edge is removed never refers to a different edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EdgeId(usize);
+
+impl EdgeId {
+    /// Returns the index of the edge, which is less than [`MultiGraph::edge_bound`]
+    ///
+    /// Per-edge state can be kept in a `Vec` indexed by the edge ids.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl fmt::Display for EdgeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "e{}", self.0)
+    }
+}
+
+/// A graph where every edge has its own [`EdgeId`], so parallel edges are told apart
+///
+/// [`Graph`] can hold parallel edges, but it can only refer to an edge by its endpoints,
+/// so removing one copy of a parallel edge or keeping state per edge is ambiguous.
+/// Here each edge is added with an id which picks it out exactly.
+/// ```
+/// use graph_algos::{Edge, MultiGraph};
+///
+/// let mut graph = MultiGraph::new();
+/// let short = graph.add_edge(1, Edge::new_with_weight(2, 3));
+/// let long = graph.add_edge(1, Edge::new_with_weight(2, 8));
+///
+/// assert_eq!(graph.edges_between(&1, &2), vec![short, long]);
+///
+/// let (u, edge) = graph.remove_edge_by_id(long).unwrap();
+/// assert_eq!((u, edge.weight()), (1, Some(8.into())));
+/// assert_eq!(graph.edges_between(&1, &2), vec![short]);
+///
+/// // the id is gone for good
+/// assert!(graph.edge(long).is_none());
+/// ```
+///
+/// The algorithms in [`crate::algos`] can run directly on a multigraph:
+/// ```
+/// use graph_algos::{Edge, MultiGraph};
+/// use graph_algos::algos::dijkstra;
+///
+/// let mut graph = MultiGraph::new();
+/// graph.add_edge(1, Edge::new_with_weight(2, 4));
+/// graph.add_edge(1, Edge::new_with_weight(2, 1));
+/// graph.add_edge(2, Edge::new_with_weight(3, 2));
+///
+/// let (_, dist_map) = dijkstra(&graph, &1);
+/// assert_eq!(dist_map[&3], 3.into());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultiGraph<N: NodeBounds> {
+    /// the ids of the edges leaving each node, in the order they were added
+    out: HashMap<N, Vec<EdgeId>>,
+
+    /// every edge ever added indexed by id, removed edges are left as `None` so ids aren't reused
+    edges: Vec<Slot<N>>,
+
+    /// the number of edges which haven't been removed
+    edge_count: usize,
+}
+
+impl<N: NodeBounds> MultiGraph<N> {
+    /// Creates a new multigraph with no nodes or edges
+    pub fn new() -> Self {
+        Self {
+            out: HashMap::new(),
+            edges: Vec::new(),
+            edge_count: 0,
+        }
+    }
+
+    /// Returns the number of nodes in the graph
+    pub fn len(&self) -> usize {
+        self.out.len()
+    }
+
+    /// Returns whether the graph has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.out.is_empty()
+    }
+
+    /// Returns the number of edges in the graph
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    /// Returns one more than the largest index of any edge id handed out by the graph
+    ///
+    /// This only ever grows, even as edges are removed.
+    pub fn edge_bound(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Returns an iterator over the nodes of the graph
+    pub fn nodes(&self) -> hash_map::Keys<'_, N, Vec<EdgeId>> {
+        self.out.keys()
+    }
+
+    /// Returns whether the node is in the graph
+    pub fn contains_node(&self, u: &N) -> bool {
+        self.out.contains_key(u)
+    }
+
+    /// Adds a node to the graph if it isn't already present
+    pub fn add_node(&mut self, u: N) {
+        self.out.entry(u).or_default();
+    }
+
+    /// Adds an edge from `u` to the graph, adding either endpoint if they aren't already present,
+    /// and returns the id of the new edge
+    ///
+    /// The edge is always added, even if there is already an edge between the same nodes.
+    pub fn add_edge(&mut self, u: N, edge: Edge<N>) -> EdgeId {
+        let id = EdgeId(self.edges.len());
+
+        self.add_node(edge.destination().clone());
+        self.out.entry(u.clone()).or_default().push(id);
+        self.edges.push(Some((u, edge)));
+        self.edge_count += 1;
+
+        id
+    }
+
+    /// Returns the source of an edge and the edge itself,
+    /// or `None` if there is no edge with that id
+    pub fn edge(&self, id: EdgeId) -> Option<(&N, &Edge<N>)> {
+        self.edges.get(id.0)?.as_ref().map(|(u, edge)| (u, edge))
+    }
+
+    /// Removes the edge with the given id, leaving any parallel edges in place,
+    /// and returns its source and the edge
+    ///
+    /// Returns `None` if there is no edge with that id.
+    pub fn remove_edge_by_id(&mut self, id: EdgeId) -> Slot<N> {
+        let (u, edge) = self.edges.get_mut(id.0)?.take()?;
+
+        if let Some(ids) = self.out.get_mut(&u) {
+            ids.retain(|&other| other != id);
+        }
+        self.edge_count -= 1;
+
+        Some((u, edge))
+    }
+
+    /// Returns the ids of all of the edges from `u` to `v`, in the order they were added
+    pub fn edges_between(&self, u: &N, v: &N) -> Vec<EdgeId> {
+        self.edges_from(u)
+            .filter(|(_, edge)| edge.destination() == v)
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Returns an iterator over the edges leaving `u` and their ids, in the order they were added
+    ///
+    /// Nodes which are not in the graph have no edges.
+    pub fn edges_from(&self, u: &N) -> MultiEdgesFrom<'_, N> {
+        MultiEdgesFrom {
+            ids: self.out.get(u).map_or(&[][..], Vec::as_slice).iter(),
+            edges: &self.edges,
+        }
+    }
+
+    /// Returns an iterator over every edge in the graph along with its id and source, in id order
+    pub fn edges(&self) -> MultiEdges<'_, N> {
+        MultiEdges {
+            inner: self.edges.iter().enumerate(),
+        }
+    }
+
+    /// Converts the multigraph into an adjacency list [`Graph`], keeping every parallel edge
+    pub fn to_graph(&self) -> Graph<N> {
+        let mut graph = Graph::with_capacity(self.len());
+
+        for u in self.nodes() {
+            graph.add_node(u.clone());
+        }
+        for (_, u, edge) in self.edges() {
+            graph.add_edge(u.clone(), edge.clone());
+        }
+
+        graph
+    }
+
+    /// Finds a maximum flow from `source` to `sink` with the Edmonds-Karp algorithm,
+    /// treating the edge weights as capacities
+    ///
+    /// The flow is given per edge id, so parallel edges each carry their own share of it.
+    /// Unweighted edges have a capacity of 1, which makes the flow value the number of
+    /// edge-disjoint paths from `source` to `sink`.
+    /// Returns `None` if either node isn't in the graph or they're the same node.
+    /// Panics if any edge has a negative or infinite weight.
+    /// ```
+    /// use graph_algos::{Edge, MultiGraph};
+    ///
+    /// let mut graph = MultiGraph::new();
+    /// let a = graph.add_edge('s', Edge::new_with_weight('t', 3));
+    /// let b = graph.add_edge('s', Edge::new_with_weight('t', 2));
+    /// let c = graph.add_edge('s', Edge::new_with_weight('m', 4));
+    /// let d = graph.add_edge('m', Edge::new_with_weight('t', 1));
+    ///
+    /// let flow = graph.max_flow(&'s', &'t').unwrap();
+    ///
+    /// assert_eq!(flow.value, 6);
+    /// assert_eq!(flow.flow(a), 3);
+    /// assert_eq!(flow.flow(b), 2);
+    /// assert_eq!(flow.flow(c), 1);
+    /// assert_eq!(flow.flow(d), 1);
+    /// ```
+    pub fn max_flow(&self, source: &N, sink: &N) -> Option<MaxFlow> {
+        if source == sink || !self.contains_node(source) || !self.contains_node(sink) {
+            return None;
+        }
+
+        let indices: HashMap<&N, usize> = self.nodes().enumerate().map(|(i, u)| (u, i)).collect();
+        let (source, sink) = (indices[source], indices[sink]);
+
+        // the residual graph holds each edge forwards and backwards,
+        // the backwards arc lets flow already sent along the edge be pushed back
+        let mut capacity = vec![0; self.edge_bound()];
+        let mut ends = vec![(0, 0); self.edge_bound()];
+        let mut arcs: Vec<Vec<(EdgeId, bool)>> = vec![Vec::new(); self.len()];
+        for (id, u, edge) in self.edges() {
+            let (u, v) = (indices[u], indices[edge.destination()]);
+            capacity[id.0] = match edge.weight() {
+                None => 1,
+                Some(EdgeWeight::Weight(w)) if w >= 0 => w,
+                Some(w) => panic!("edge {} has capacity {}", id, w),
+            };
+            ends[id.0] = (u, v);
+            arcs[u].push((id, true));
+            arcs[v].push((id, false));
+        }
+
+        let mut flow = vec![0; self.edge_bound()];
+        let mut value = 0;
+        loop {
+            // find the shortest path in the residual graph, recording the arc used to reach each node
+            let mut parent: Vec<Option<(EdgeId, bool)>> = vec![None; self.len()];
+            let mut queue = VecDeque::from(vec![source]);
+            while let Some(u) = queue.pop_front() {
+                if u == sink {
+                    break;
+                }
+
+                for &(id, forwards) in &arcs[u] {
+                    let (v, residual) = match forwards {
+                        true => (ends[id.0].1, capacity[id.0] - flow[id.0]),
+                        false => (ends[id.0].0, flow[id.0]),
+                    };
+                    if residual > 0 && v != source && parent[v].is_none() {
+                        parent[v] = Some((id, forwards));
+                        queue.push_back(v);
+                    }
+                }
+            }
+
+            if parent[sink].is_none() {
+                break;
+            }
+
+            let mut path = Vec::new();
+            let mut v = sink;
+            while let Some((id, forwards)) = parent[v] {
+                path.push((id, forwards));
+                v = if forwards { ends[id.0].0 } else { ends[id.0].1 };
+            }
+
+            let bottleneck = path
+                .iter()
+                .map(|&(id, forwards)| match forwards {
+                    true => capacity[id.0] - flow[id.0],
+                    false => flow[id.0],
+                })
+                .min()
+                .unwrap_or(0);
+
+            for (id, forwards) in path {
+                flow[id.0] += if forwards { bottleneck } else { -bottleneck };
+            }
+            value += bottleneck;
+        }
+
+        Some(MaxFlow {
+            value,
+            flows: flow
+                .into_iter()
+                .enumerate()
+                .filter(|&(_, f)| f > 0)
+                .map(|(id, f)| (EdgeId(id), f))
+                .collect(),
+        })
+    }
+}
+
+impl<N: NodeBounds> Default for MultiGraph<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher> From<&Graph<N, S>> for MultiGraph<N> {
+    /// Converts a graph into a multigraph, giving each of its edges an id
+    fn from(graph: &Graph<N, S>) -> Self {
+        let mut multigraph = Self::new();
+
+        for u in graph.nodes() {
+            multigraph.add_node(u.clone());
+        }
+        for (u, edge) in graph.edges() {
+            multigraph.add_edge(u.clone(), edge.clone());
+        }
+
+        multigraph
+    }
+}
+
+impl<N: NodeBounds> From<&MultiGraph<N>> for Graph<N> {
+    fn from(graph: &MultiGraph<N>) -> Self {
+        graph.to_graph()
+    }
+}
+
+/// A maximum flow through a [`MultiGraph`], found by [`MultiGraph::max_flow`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaxFlow {
+    /// The total flow from the source to the sink
+    pub value: i64,
+    /// The flow along each edge carrying any
+    flows: HashMap<EdgeId, i64>,
+}
+
+impl MaxFlow {
+    /// Returns the flow along an edge
+    pub fn flow(&self, id: EdgeId) -> i64 {
+        self.flows.get(&id).copied().unwrap_or(0)
+    }
+
+    /// Returns the edges carrying any flow and how much they carry, in id order
+    pub fn flows(&self) -> Vec<(EdgeId, i64)> {
+        let mut flows: Vec<(EdgeId, i64)> = self.flows.iter().map(|(&id, &f)| (id, f)).collect();
+        flows.sort_unstable();
+        flows
+    }
+}
+
+impl<'a, N: NodeBounds + 'a> GraphRef<'a> for &'a MultiGraph<N> {
+    type Node = N;
+
+    fn contains_node(self, u: &N) -> bool {
+        MultiGraph::contains_node(self, u)
+    }
+}
+
+impl<'a, N: NodeBounds + 'a> IntoNodes<'a> for &'a MultiGraph<N> {
+    type Nodes = hash_map::Keys<'a, N, Vec<EdgeId>>;
+
+    fn nodes(self) -> Self::Nodes {
+        MultiGraph::nodes(self)
+    }
+
+    fn node_count(self) -> usize {
+        self.len()
+    }
+}
+
+impl<'a, N: NodeBounds + 'a> IntoNeighbors<'a> for &'a MultiGraph<N> {
+    type Neighbors = MultiNeighbors<'a, N>;
+
+    fn neighbors(self, u: &N) -> Self::Neighbors {
+        MultiNeighbors {
+            inner: self.edges_from(u),
+        }
+    }
+}
+
+impl<'a, N: NodeBounds + 'a> EdgeWeighted<'a> for &'a MultiGraph<N> {
+    type NeighborsWeighted = MultiNeighborsWeighted<'a, N>;
+
+    fn neighbors_weighted(self, u: &N) -> Self::NeighborsWeighted {
+        MultiNeighborsWeighted {
+            inner: self.edges_from(u),
+        }
+    }
+}
+
+/// An iterator over the edges leaving a node in a [`MultiGraph`] and their ids
+#[derive(Debug, Clone)]
+pub struct MultiEdgesFrom<'a, N: NodeBounds> {
+    ids: slice::Iter<'a, EdgeId>,
+    edges: &'a [Slot<N>],
+}
+
+impl<'a, N: NodeBounds> Iterator for MultiEdgesFrom<'a, N> {
+    type Item = (EdgeId, &'a Edge<N>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = *self.ids.next()?;
+        let (_, edge) = self.edges[id.0]
+            .as_ref()
+            .expect("edge ids of a node are never removed edges");
+
+        Some((id, edge))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ids.size_hint()
+    }
+}
+
+impl<'a, N: NodeBounds> ExactSizeIterator for MultiEdgesFrom<'a, N> {}
+
+/// An iterator over every edge in a [`MultiGraph`] along with its id and source
+#[derive(Debug, Clone)]
+pub struct MultiEdges<'a, N: NodeBounds> {
+    inner: std::iter::Enumerate<slice::Iter<'a, Slot<N>>>,
+}
+
+impl<'a, N: NodeBounds> Iterator for MultiEdges<'a, N> {
+    type Item = (EdgeId, &'a N, &'a Edge<N>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .by_ref()
+            .find_map(|(id, entry)| entry.as_ref().map(|(u, edge)| (EdgeId(id), u, edge)))
+    }
+}
+
+/// An iterator over the successors of a node in a [`MultiGraph`]
+#[derive(Debug, Clone)]
+pub struct MultiNeighbors<'a, N: NodeBounds> {
+    inner: MultiEdgesFrom<'a, N>,
+}
+
+impl<'a, N: NodeBounds> Iterator for MultiNeighbors<'a, N> {
+    type Item = &'a N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, edge)| edge.destination())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, N: NodeBounds> ExactSizeIterator for MultiNeighbors<'a, N> {}
+
+/// An iterator over the successors of a node in a [`MultiGraph`] along with the edge weights
+#[derive(Debug, Clone)]
+pub struct MultiNeighborsWeighted<'a, N: NodeBounds> {
+    inner: MultiEdgesFrom<'a, N>,
+}
+
+impl<'a, N: NodeBounds> Iterator for MultiNeighborsWeighted<'a, N> {
+    type Item = (&'a N, Option<EdgeWeight>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|(_, edge)| (edge.destination(), edge.weight()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, N: NodeBounds> ExactSizeIterator for MultiNeighborsWeighted<'a, N> {}
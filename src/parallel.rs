@@ -0,0 +1,372 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Parallel implementations of some of the algorithms, built on rayon.
+//!
+//! These give the same results as their sequential counterparts,
+//! but spread the work of each round over every core.
+
+use crate::{
+    analysis::{self, Triangles},
+    util::index_nodes,
+    visit::{EdgeWeighted, IntoNeighbors, IntoNodes},
+    Edge, EdgeWeight, PredMap,
+};
+use rayon::prelude::*;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+/// Returns the predecessor map of a level synchronous breadth first search from `s`
+///
+/// Each level of the search is expanded in parallel,
+/// giving the same paths as [`crate::algos::bfs_shortest_paths`] in terms of length.
+/// ```
+/// use graph_algos::{graph, Graph, Path};
+/// use graph_algos::parallel::par_bfs_shortest_paths;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2, 3],
+///     2 => [4],
+///     3 => [4],
+///     4 => [5],
+/// };
+///
+/// let pred_map = par_bfs_shortest_paths(&graph, &1);
+/// let path = Path::new_path_to(&pred_map, &5).unwrap();
+///
+/// assert_eq!(path.edges().len(), 3);
+/// ```
+pub fn par_bfs_shortest_paths<'a, G>(graph: G, s: &'a G::Node) -> PredMap<'a, G::Node>
+where
+    G: IntoNeighbors<'a> + Sync,
+    G::Node: Sync,
+{
+    let mut pred_map: PredMap<'a, G::Node> = HashMap::new();
+    pred_map.insert(s, Edge::new(s));
+
+    let mut seen: HashSet<&G::Node> = [s].iter().cloned().collect();
+    let mut frontier: Vec<&G::Node> = vec![s];
+
+    while !frontier.is_empty() {
+        // expand every node in the frontier in parallel
+        let discovered: Vec<(&G::Node, &G::Node)> = frontier
+            .par_iter()
+            .flat_map_iter(|u| graph.neighbors(u).map(move |v| (*u, v)))
+            .collect();
+
+        // then claim the newly discovered nodes in order
+        frontier = Vec::new();
+        for (u, v) in discovered {
+            if seen.insert(v) {
+                pred_map.insert(v, Edge::new(u));
+                frontier.push(v);
+            }
+        }
+    }
+
+    pred_map
+}
+
+/// Finds the weakly connected components of the graph using parallel label propagation
+///
+/// Edges are treated as undirected.
+/// Returns a map from each node to the index of its component,
+/// where the components are numbered from 0.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::parallel::par_connected_components;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2],
+///     3 => [2],
+///     4 => [5],
+/// };
+///
+/// let components = par_connected_components(&graph);
+///
+/// assert_eq!(components[&1], components[&3]);
+/// assert_ne!(components[&1], components[&4]);
+/// assert_eq!(components[&4], components[&5]);
+/// ```
+pub fn par_connected_components<'a, G>(graph: G) -> HashMap<&'a G::Node, usize>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a> + Sync,
+    G::Node: Sync,
+{
+    let (nodes, indices) = index_nodes(graph);
+
+    let edges: Vec<(usize, usize)> = nodes
+        .par_iter()
+        .enumerate()
+        .flat_map_iter(|(u, node)| {
+            let indices = &indices;
+            graph.neighbors(node).map(move |v| (u, indices[v]))
+        })
+        .collect();
+
+    // every node starts with its own label, and takes the smallest label of its neighbours
+    let labels: Vec<AtomicUsize> = (0..nodes.len()).map(AtomicUsize::new).collect();
+
+    loop {
+        let changed = AtomicBool::new(false);
+
+        edges.par_iter().for_each(|&(u, v)| {
+            let lu = labels[u].load(Ordering::Relaxed);
+            let lv = labels[v].load(Ordering::Relaxed);
+
+            if lu < lv {
+                labels[v].fetch_min(lu, Ordering::Relaxed);
+                changed.store(true, Ordering::Relaxed);
+            } else if lv < lu {
+                labels[u].fetch_min(lv, Ordering::Relaxed);
+                changed.store(true, Ordering::Relaxed);
+            }
+        });
+
+        if !changed.load(Ordering::Relaxed) {
+            break;
+        }
+    }
+
+    // renumber the labels so the components are numbered from 0
+    let mut components: HashMap<usize, usize> = HashMap::new();
+    nodes
+        .iter()
+        .zip(labels)
+        .map(|(node, label)| {
+            let next = components.len();
+            (*node, *components.entry(label.into_inner()).or_insert(next))
+        })
+        .collect()
+}
+
+/// Computes the PageRank of every node, with the updates for each iteration made in parallel
+///
+/// Stops after `max_iterations`, or once the total change in rank in an iteration is below `tolerance`.
+/// The mass of nodes with no successors is spread evenly over every node.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::parallel::par_pagerank;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [3],
+///     2 => [3],
+///     3 => [1],
+/// };
+///
+/// let ranks = par_pagerank(&graph, 0.85, 100, 1e-9);
+///
+/// assert!(ranks[&3] > ranks[&1]);
+/// assert!(ranks[&1] > ranks[&2]);
+/// assert!((ranks.values().sum::<f64>() - 1.0).abs() < 1e-6);
+/// ```
+pub fn par_pagerank<'a, G>(
+    graph: G,
+    damping: f64,
+    max_iterations: usize,
+    tolerance: f64,
+) -> HashMap<&'a G::Node, f64>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a> + Sync,
+    G::Node: Sync,
+{
+    let (nodes, indices) = index_nodes(graph);
+    let n = nodes.len();
+
+    if n == 0 {
+        return HashMap::new();
+    }
+
+    // the rank flows backwards along the edges, so store the predecessors of each node
+    let out_degree: Vec<usize> = nodes
+        .par_iter()
+        .map(|node| graph.neighbors(node).count())
+        .collect();
+    let mut preds: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (u, node) in nodes.iter().enumerate() {
+        for v in graph.neighbors(node) {
+            preds[indices[v]].push(u);
+        }
+    }
+
+    let mut ranks = vec![1.0 / n as f64; n];
+
+    for _ in 0..max_iterations {
+        let dangling: f64 = (0..n)
+            .into_par_iter()
+            .filter(|&u| out_degree[u] == 0)
+            .map(|u| ranks[u])
+            .sum();
+        let base = (1.0 - damping) / n as f64 + damping * dangling / n as f64;
+
+        let new_ranks: Vec<f64> = preds
+            .par_iter()
+            .map(|preds| {
+                base + damping
+                    * preds
+                        .iter()
+                        .map(|&u| ranks[u] / out_degree[u] as f64)
+                        .sum::<f64>()
+            })
+            .collect();
+
+        let change: f64 = new_ranks
+            .par_iter()
+            .zip(&ranks)
+            .map(|(new, old)| (new - old).abs())
+            .sum();
+
+        ranks = new_ranks;
+
+        if change < tolerance {
+            break;
+        }
+    }
+
+    nodes.into_iter().zip(ranks).collect()
+}
+
+/// The predecessor and distance maps found by [`par_bellman_ford`]
+type ShortestPaths<'a, N> = (PredMap<'a, N>, HashMap<&'a N, EdgeWeight>);
+
+/// Finds the shortest paths from `s` to every node using Bellman-Ford,
+/// where each round relaxes the edges into every node in parallel
+///
+/// Like [`crate::algos::bellman_ford`], returns `None` if there is a negative cycle reachable from `s`.
+/// Panics if the graph contains an edge without a weight.
+/// ```
+/// use graph_algos::{graph, Graph, EdgeWeight};
+/// use graph_algos::parallel::par_bellman_ford;
+///
+/// let graph: Graph<&str> = graph! {
+///     "a" => ["b" => 4, "c" => 2],
+///     "b" => ["d" => 1],
+///     "c" => ["b" => -3],
+/// };
+///
+/// let (pred_map, dist_map) = par_bellman_ford(&graph, &"a").unwrap();
+///
+/// assert_eq!(dist_map[&"d"], 0.into());
+/// assert_eq!(pred_map[&"b"].destination(), &&"c");
+/// ```
+pub fn par_bellman_ford<'a, G>(graph: G, s: &'a G::Node) -> Option<ShortestPaths<'a, G::Node>>
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a> + Sync,
+    G::Node: Sync,
+{
+    let (nodes, indices) = index_nodes(graph);
+    let n = nodes.len();
+
+    // store the weighted edges into each node so each node can be relaxed independently
+    let mut preds: Vec<Vec<(usize, EdgeWeight)>> = vec![Vec::new(); n];
+    for (u, node) in nodes.iter().enumerate() {
+        for (v, weight) in graph.neighbors_weighted(node) {
+            let w = weight.expect("No weight for this edge, panicking");
+            preds[indices[v]].push((u, w));
+        }
+    }
+
+    let mut dists = vec![EdgeWeight::infinity(); n];
+    let mut pred_of: Vec<Option<(usize, EdgeWeight)>> = vec![None; n];
+
+    if let Some(&s_idx) = indices.get(s) {
+        dists[s_idx] = 0.into();
+    }
+
+    // without negative cycles every shortest path has fewer edges than there are nodes,
+    // so a change in the round after that means there is one
+    let mut converged = false;
+    for _ in 0..n {
+        let relaxed: Vec<Option<(EdgeWeight, usize, EdgeWeight)>> = preds
+            .par_iter()
+            .enumerate()
+            .map(|(v, preds)| {
+                preds
+                    .iter()
+                    .filter(|(u, _)| dists[*u] != EdgeWeight::infinity())
+                    .map(|&(u, w)| (dists[u] + w, u, w))
+                    .filter(|(dist, _, _)| *dist < dists[v])
+                    .min_by_key(|(dist, _, _)| *dist)
+            })
+            .collect();
+
+        let mut changed = false;
+        for (v, relaxation) in relaxed.into_iter().enumerate() {
+            if let Some((dist, u, w)) = relaxation {
+                dists[v] = dist;
+                pred_of[v] = Some((u, w));
+                changed = true;
+            }
+        }
+
+        // break out if nothing happened this round
+        if !changed {
+            converged = true;
+            break;
+        }
+    }
+
+    if !converged {
+        return None;
+    }
+
+    let mut pred_map: PredMap<'a, G::Node> = HashMap::new();
+    pred_map.insert(s, Edge::new_with_weight(s, 0));
+    for (v, pred) in pred_of.into_iter().enumerate() {
+        if let Some((u, w)) = pred {
+            pred_map.insert(nodes[v], Edge::new_with_weight(nodes[u], w));
+        }
+    }
+
+    let dist_map = nodes.into_iter().zip(dists).collect();
+
+    Some((pred_map, dist_map))
+}
+
+/// Counts the triangles in the graph and the local clustering coefficient of each node,
+/// counting the triangles through each node in parallel
+///
+/// Gives the same result as [`crate::analysis::triangles`].
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::analysis::triangles;
+/// use graph_algos::parallel::par_triangles;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2, 3, 4],
+///     2 => [3, 4],
+///     3 => [4],
+/// };
+///
+/// let parallel = par_triangles(&graph);
+/// assert_eq!(parallel.count, 4);
+/// assert_eq!(parallel, triangles(&graph));
+/// ```
+pub fn par_triangles<'a, G>(graph: G) -> Triangles<'a, G::Node>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let (nodes, adjacency) = analysis::undirected_adjacency(graph);
+    let counts = (0..nodes.len())
+        .into_par_iter()
+        .map(|u| analysis::node_triangles(&adjacency, u))
+        .collect();
+
+    analysis::collect_triangles(nodes, &adjacency, counts)
+}
@@ -0,0 +1,779 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{
+    error::{GraphParseError, LineError, ParseEdgeError},
+    property::{EdgeAttributes, Properties, PropertyValue},
+    Edge, EdgeWeight, Graph, NodeBounds,
+};
+use std::{fmt::Debug, hash::BuildHasher, str::FromStr};
+
+/// What to do when the same edge appears more than once while parsing a graph
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum DuplicateEdges {
+    /// Store every copy of the edge as a parallel edge
+    #[default]
+    KeepAll,
+
+    /// Fail to parse with [`GraphParseError::DuplicateEdge`] if a copy of the edge has a different weight,
+    /// keeping a single edge if all the copies are identical
+    Error,
+
+    /// Keep the first copy of the edge and ignore the rest
+    KeepFirst,
+
+    /// Keep a single edge with the smallest of the weights
+    KeepMin,
+
+    /// Keep a single edge whose weight is the sum of the weights
+    Sum,
+}
+
+/// Options controlling how a graph is parsed from a string
+/// ```
+/// use graph_algos::{Graph, ParseOptions, DuplicateEdges};
+///
+/// let options = ParseOptions::new().duplicate_edges(DuplicateEdges::KeepMin);
+///
+/// let graph: Graph<u32> = Graph::parse_with_options("1:2,5 2,3", &options).unwrap();
+/// assert_eq!(graph.succs(&1).unwrap().len(), 1);
+/// assert_eq!(graph.succs(&1).unwrap()[0].weight(), Some(3.into()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    duplicate_edges: DuplicateEdges,
+}
+
+impl ParseOptions {
+    /// Creates the default options, which are the ones used by `str::parse`
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets how edges which appear more than once are handled
+    pub fn duplicate_edges(mut self, policy: DuplicateEdges) -> Self {
+        self.duplicate_edges = policy;
+        self
+    }
+}
+
+/// Combines the weights of two copies of the same edge,
+/// an unweighted copy contributes nothing
+pub(crate) fn merge_weights(
+    a: Option<EdgeWeight>,
+    b: Option<EdgeWeight>,
+    merge: impl Fn(EdgeWeight, EdgeWeight) -> EdgeWeight,
+) -> Option<EdgeWeight> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(merge(a, b)),
+        (a, b) => a.or(b),
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher + Default> Graph<N, S>
+where
+    N: FromStr,
+    <N as FromStr>::Err: Debug,
+{
+    /// Parses a graph from a string using the given options
+    ///
+    /// The format is the same as the one accepted by `str::parse`.
+    /// ```
+    /// use graph_algos::{Graph, ParseOptions, DuplicateEdges};
+    ///
+    /// let input = "a:b,2 c,1\na:b,4";
+    ///
+    /// // by default both copies are stored
+    /// let graph: Graph<String> = input.parse().unwrap();
+    /// assert_eq!(graph.succs(&"a".into()).unwrap().len(), 3);
+    ///
+    /// let sum = ParseOptions::new().duplicate_edges(DuplicateEdges::Sum);
+    /// let graph: Graph<String> = Graph::parse_with_options(input, &sum).unwrap();
+    /// assert_eq!(graph.succs(&"a".into()).unwrap()[0].weight(), Some(6.into()));
+    ///
+    /// let error = ParseOptions::new().duplicate_edges(DuplicateEdges::Error);
+    /// assert!(Graph::<String>::parse_with_options(input, &error).is_err());
+    ///
+    /// // identical copies aren't an error
+    /// let graph: Graph<String> = Graph::parse_with_options("a:b,2 c\na:b,2 c", &error).unwrap();
+    /// assert_eq!(graph.succs(&"a".into()).unwrap().len(), 2);
+    /// ```
+    pub fn parse_with_options(s: &str, options: &ParseOptions) -> Result<Self, GraphParseError> {
+        // fill an unweighted directed graph from a string
+        // each line is node:edges seperated by spaces
+
+        // new empty graph
+        let mut graph: Graph<N, S> = Graph::default();
+
+        for (line_no, line) in s.lines().enumerate() {
+            let (u, edges) = parse_line(line, false, str::parse)?;
+
+            for edge in edges {
+                insert_edge(
+                    &mut graph,
+                    &u,
+                    edge.map_err(GraphParseError::Edge)?,
+                    options.duplicate_edges,
+                    line_no + 1,
+                )?;
+            }
+        }
+
+        Ok(graph)
+    }
+
+    /// Parses a graph from a string in the format accepted by `str::parse`,
+    /// skipping over anything which can't be parsed instead of failing
+    ///
+    /// Lines which aren't of the form `node:edges` or whose source node can't be parsed are skipped,
+    /// as are any edges on a line which can't be parsed, while the rest of the line is kept.
+    /// The source node of a line is added even if none of its edges can be parsed.
+    /// When the whole string parses, the graph is the same as the one from `str::parse`.
+    /// Use [`Graph::parse_report`] to find out what was skipped.
+    /// ```
+    /// use graph_algos::Graph;
+    ///
+    /// let input = "1:2,5 3\nnot a line\nx:1\n2:3,? 4\n4:";
+    /// assert!(input.parse::<Graph<u32>>().is_err());
+    ///
+    /// let graph: Graph<u32> = Graph::parse_lenient(input);
+    /// assert_eq!(graph.edges().count(), 3);
+    /// assert!(graph.is_edge(&1, &3));
+    /// assert!(graph.is_edge(&2, &4));
+    /// assert!(!graph.is_edge(&2, &3));
+    /// ```
+    pub fn parse_lenient(s: &str) -> Self {
+        Self::parse_report(s).0
+    }
+
+    /// Parses a graph from a string in the same way as [`Graph::parse_lenient`],
+    /// and also returns every problem it skipped over, in the order they appear
+    ///
+    /// A line which isn't of the form `node:edges` or whose source node can't be parsed
+    /// gives a single error, while a line with several bad edges gives an error for each of them.
+    /// The errors are the ones `str::parse` would have failed with,
+    /// so the string parses exactly when there are no errors.
+    /// ```
+    /// use graph_algos::Graph;
+    /// use graph_algos::error::GraphParseError;
+    ///
+    /// let input = "1:2,5 3\nnot a line\nx:1\n2:3,? 4,!";
+    ///
+    /// let (graph, errors) = Graph::<u32>::parse_report(input);
+    /// assert_eq!(graph.edges().count(), 2);
+    ///
+    /// let lines: Vec<usize> = errors.iter().map(|err| err.line()).collect();
+    /// assert_eq!(lines, vec![2, 3, 4, 4]);
+    /// assert!(matches!(errors[0].error(), GraphParseError::Format));
+    /// assert!(matches!(errors[1].error(), GraphParseError::Node(_)));
+    /// assert!(matches!(errors[2].error(), GraphParseError::Edge(_)));
+    /// assert_eq!(
+    ///     errors[3].to_string(),
+    ///     "Line 4: Failed to parse outbound edge: Failed to parse edge weight: invalid digit found in string",
+    /// );
+    /// ```
+    pub fn parse_report(s: &str) -> (Self, Vec<LineError>) {
+        let mut graph: Graph<N, S> = Graph::default();
+        let mut errors = Vec::new();
+
+        for (line_no, line) in s.lines().enumerate() {
+            let line_no = line_no + 1;
+
+            let (u, edges): (N, _) = match parse_line(line, false, str::parse) {
+                Ok(line) => line,
+                Err(err) => {
+                    errors.push(LineError::new(line_no, err));
+                    continue;
+                }
+            };
+
+            graph.add_node(u.clone());
+            for edge in edges {
+                match edge {
+                    Ok(edge) => graph.add_edge(u.clone(), edge),
+                    Err(err) => errors.push(LineError::new(line_no, GraphParseError::Edge(err))),
+                }
+            }
+        }
+
+        (graph, errors)
+    }
+
+    /// Parses a graph whose edges carry named attributes, such as `a:b,w=3,cap=10`,
+    /// returning the graph and the attributes of its edges
+    ///
+    /// Each edge is its destination followed by comma separated fields.
+    /// A `key=value` field sets an attribute, apart from the keys `w` and `weight`, which set the weight of the edge,
+    /// and a plain number straight after the destination is the weight as in the format accepted by `str::parse`.
+    /// The values are parsed as [`PropertyValue`](crate::property::PropertyValue)s,
+    /// and the attributes of parallel edges are merged, with later values replacing earlier ones.
+    /// A value in double quotes is always a string, and can contain spaces, commas and the escapes
+    /// `\"`, `\\`, `\n`, `\r` and `\t`.
+    /// Only edges with at least one attribute have an entry in the attribute map.
+    /// Fails with [`ParseEdgeError::Attribute`] if a field is neither a weight nor `key=value`,
+    /// or a quoted value isn't closed or has an unknown escape.
+    /// ```
+    /// use graph_algos::{Graph, FormatStyle};
+    /// use graph_algos::property::PropertyValue;
+    ///
+    /// let input = "s:a,w=3,cap=10 b,cap=4\na:t,2,cap=10,kind=road";
+    /// let (graph, attributes) = Graph::<String>::parse_with_attributes(input).unwrap();
+    ///
+    /// let edge = |u: &str, v: &str| (u.to_string(), v.to_string());
+    /// assert_eq!(graph.succs(&"s".into()).unwrap()[0].weight(), Some(3.into()));
+    /// assert_eq!(graph.succs(&"s".into()).unwrap()[1].weight(), None);
+    /// assert_eq!(attributes[&edge("s", "b")]["cap"], PropertyValue::Int(4));
+    /// assert_eq!(attributes[&edge("a", "t")]["kind"], PropertyValue::from("road"));
+    ///
+    /// // the attributes can be written back out alongside the graph
+    /// let output = graph
+    ///     .formatter()
+    ///     .style(FormatStyle::AdjacencyList)
+    ///     .edge_attributes(&attributes)
+    ///     .to_string();
+    /// assert_eq!(output, "a:t,2,cap=10,kind=road\ns:a,3,cap=10 b,cap=4\n");
+    /// assert_eq!(Graph::parse_with_attributes(&output).unwrap(), (graph, attributes));
+    ///
+    /// // string values that wouldn't read back as themselves are quoted
+    /// let mut attributes = graph_algos::property::EdgeAttributes::new();
+    /// let properties = attributes.entry(edge("a", "b")).or_default();
+    /// properties.insert("name".into(), "two words".into());
+    /// properties.insert("expr".into(), "a,b=c".into());
+    /// properties.insert("code".into(), "3".into());
+    /// properties.insert("note".into(), "say \"hi\"\n".into());
+    /// let graph: Graph<String> = "a:b".parse().unwrap();
+    ///
+    /// let output = graph
+    ///     .formatter()
+    ///     .style(FormatStyle::AdjacencyList)
+    ///     .edge_attributes(&attributes)
+    ///     .to_string();
+    /// assert_eq!(
+    ///     output.trim_end(),
+    ///     r#"a:b,code="3",expr="a,b=c",name="two words",note="say \"hi\"\n""#
+    /// );
+    /// assert_eq!(Graph::parse_with_attributes(&output).unwrap(), (graph, attributes));
+    /// ```
+    pub fn parse_with_attributes(s: &str) -> Result<(Self, EdgeAttributes<N>), GraphParseError> {
+        let mut graph: Graph<N, S> = Graph::default();
+        let mut attributes = EdgeAttributes::new();
+
+        for line in s.lines() {
+            let (u, edges): (N, _) = parse_line(line, true, parse_attributed_edge::<N>)?;
+
+            for edge in edges {
+                let (edge, properties) = edge?;
+
+                if !properties.is_empty() {
+                    attributes
+                        .entry((u.clone(), edge.destination().clone()))
+                        .or_insert_with(Properties::new)
+                        .extend(properties);
+                }
+                graph.add_edge(u.clone(), edge);
+            }
+        }
+
+        Ok((graph, attributes))
+    }
+}
+
+/// Parses the source node of a line of the form `node:edges`,
+/// and returns it with the result of parsing each of the space separated edges with `parse_edge`
+///
+/// With `quoted_values` a space inside a quoted attribute value doesn't separate edges.
+fn parse_line<'s, N, E, F>(
+    line: &'s str,
+    quoted_values: bool,
+    parse_edge: F,
+) -> Result<(N, impl Iterator<Item = Result<E, ParseEdgeError>> + 's), GraphParseError>
+where
+    N: FromStr,
+    <N as FromStr>::Err: Debug,
+    F: Fn(&str) -> Result<E, ParseEdgeError> + 's,
+{
+    let (u, edges) = line.split_once(':').ok_or(GraphParseError::Format)?;
+
+    let u: N = u
+        .parse()
+        .map_err(|err| GraphParseError::Node(format!("{:?}", err)))?;
+
+    Ok((u, split_fields(edges, ' ', quoted_values).map(parse_edge)))
+}
+
+/// Splits `s` at each `separator`, skipping those inside quoted values written as `key="..."`
+/// when `quoted_values` is set
+fn split_fields(s: &str, separator: char, quoted_values: bool) -> impl Iterator<Item = &str> {
+    let (mut prev, mut quoted, mut escaped) = (None, false, false);
+    s.split(move |c: char| {
+        let split = if quoted {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                quoted = false;
+            }
+            false
+        } else if quoted_values && c == '"' && prev == Some('=') {
+            quoted = true;
+            false
+        } else {
+            c == separator
+        };
+        prev = Some(c);
+        split
+    })
+}
+
+/// Reads back a string value quoted and escaped by the formatter, returning `None` if it isn't well formed
+fn unquote(value: &str) -> Option<String> {
+    let inner = value.strip_prefix('"')?.strip_suffix('"')?;
+    let mut unquoted = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        unquoted.push(match c {
+            '\\' => match chars.next()? {
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                c @ ('\\' | '"') => c,
+                _ => return None,
+            },
+            '"' => return None,
+            c => c,
+        });
+    }
+
+    Some(unquoted)
+}
+
+/// Parses an edge of the form `destination[,weight][,key=value]...`
+fn parse_attributed_edge<N>(s: &str) -> Result<(Edge<N>, Properties), ParseEdgeError>
+where
+    N: NodeBounds + FromStr,
+    <N as FromStr>::Err: Debug,
+{
+    let mut fields = split_fields(s, ',', true);
+    let destination: N = fields
+        .next()
+        .unwrap_or_default()
+        .parse()
+        .map_err(|err| ParseEdgeError::Node(format!("{:?}", err)))?;
+
+    let mut weight = None;
+    let mut properties = Properties::new();
+    for (i, field) in fields.enumerate() {
+        match field.split_once('=') {
+            Some(("w", value)) | Some(("weight", value)) => {
+                weight = Some(value.parse::<i64>().map_err(ParseEdgeError::Weight)?);
+            }
+            Some((key, value)) if !key.is_empty() => {
+                let value: PropertyValue = if value.starts_with('"') {
+                    let value =
+                        unquote(value).ok_or_else(|| ParseEdgeError::Attribute(field.into()))?;
+                    PropertyValue::Str(value)
+                } else {
+                    match value.parse() {
+                        Ok(value) => value,
+                        Err(never) => match never {},
+                    }
+                };
+                properties.insert(key.into(), value);
+            }
+            None if i == 0 => weight = Some(field.parse().map_err(ParseEdgeError::Weight)?),
+            _ => return Err(ParseEdgeError::Attribute(field.into())),
+        }
+    }
+
+    let edge = match weight {
+        Some(weight) => Edge::new_with_weight(destination, weight),
+        None => Edge::new(destination),
+    };
+
+    Ok((edge, properties))
+}
+
+/// Adds an edge parsed from line `line` to the graph, merging it with any existing copy
+fn insert_edge<N: NodeBounds, S: BuildHasher>(
+    graph: &mut Graph<N, S>,
+    u: &N,
+    e: Edge<N>,
+    policy: DuplicateEdges,
+    line: usize,
+) -> Result<(), GraphParseError> {
+    let existing = match policy {
+        DuplicateEdges::KeepAll => None,
+        _ => graph.succs(u).and_then(|succs| {
+            succs
+                .iter()
+                .position(|succ| succ.destination() == e.destination())
+        }),
+    };
+
+    if let Some(pos) = existing {
+        let succs = graph.succs_mut(u).unwrap();
+        let old = succs[pos].weight();
+
+        let weight = match policy {
+            DuplicateEdges::KeepAll | DuplicateEdges::KeepFirst => old,
+            DuplicateEdges::Error if old == e.weight() => old,
+            DuplicateEdges::Error => {
+                return Err(GraphParseError::DuplicateEdge {
+                    line,
+                    edge: format!("{:?} -> {:?}", u, e.destination()),
+                });
+            }
+            DuplicateEdges::KeepMin => merge_weights(old, e.weight(), Ord::min),
+            DuplicateEdges::Sum => merge_weights(old, e.weight(), |a, b| a + b),
+        };
+
+        succs[pos] = match weight {
+            Some(w) => Edge::new_with_weight(e.destination().clone(), w),
+            None => Edge::new(e.destination().clone()),
+        };
+    } else {
+        graph.add_edge(u.clone(), e);
+    }
+
+    Ok(())
+}
+
+/// How the edges are laid out on each line of a text format
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum TextLayout {
+    /// A source node, the node separator and then every outbound edge of the node,
+    /// such as `1:2,5 3`
+    #[default]
+    AdjacencyList,
+
+    /// A single edge per line of the form `source destination [weight]`, such as `1 2 5`
+    EdgeList,
+}
+
+/// Describes a dialect of plain text graph file, for use with [`Graph::parse_with`]
+///
+/// The default dialect is the format accepted by `str::parse`,
+/// except that blank lines are skipped and a source node may have no edges.
+/// ```
+/// use graph_algos::{Graph, GraphTextFormat};
+/// use graph_algos::error::GraphParseError;
+///
+/// let input = "\
+/// % a small weighted graph
+/// p sp 4 3
+/// 1 2 7
+/// 2 3 1
+/// 1 3 9
+/// ";
+///
+/// let format = GraphTextFormat::edge_list()
+///     .comment_prefix("%")
+///     .header()
+///     .one_indexed();
+///
+/// let graph: Graph<usize> = Graph::parse_with(input, &format).unwrap();
+///
+/// // the header adds the isolated node, and the nodes are renumbered from 0
+/// assert_eq!(graph.len(), 4);
+/// assert!(graph.succs(&3).unwrap().is_empty());
+/// assert_eq!(graph.succs(&0).unwrap()[0].weight(), Some(7.into()));
+///
+/// let truncated = &input[..input.len() - 6];
+/// assert!(matches!(
+///     Graph::<usize>::parse_with(truncated, &format),
+///     Err(GraphParseError::EdgeCount { expected: 3, found: 2 }),
+/// ));
+/// ```
+#[derive(Debug, Clone)]
+pub struct GraphTextFormat {
+    layout: TextLayout,
+    node_separator: char,
+    separator: Option<char>,
+    weight_separator: char,
+    comment_prefix: Option<String>,
+    header: bool,
+    index_base: Option<usize>,
+    duplicate_edges: DuplicateEdges,
+}
+
+impl Default for GraphTextFormat {
+    fn default() -> Self {
+        Self {
+            layout: TextLayout::AdjacencyList,
+            node_separator: ':',
+            separator: Some(' '),
+            weight_separator: ',',
+            comment_prefix: None,
+            header: false,
+            index_base: None,
+            duplicate_edges: DuplicateEdges::KeepAll,
+        }
+    }
+}
+
+impl GraphTextFormat {
+    /// Creates the default adjacency list dialect, `node:dest,weight dest ...`
+    pub fn adjacency_list() -> Self {
+        Default::default()
+    }
+
+    /// Creates an edge list dialect of whitespace separated `source destination [weight]` lines
+    pub fn edge_list() -> Self {
+        Self {
+            layout: TextLayout::EdgeList,
+            separator: None,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the character between a source node and its edges in an adjacency list
+    pub fn node_separator(mut self, separator: char) -> Self {
+        self.node_separator = separator;
+        self
+    }
+
+    /// Sets the character between the edges of an adjacency list,
+    /// or between the fields of an edge list
+    pub fn separator(mut self, separator: char) -> Self {
+        self.separator = Some(separator);
+        self
+    }
+
+    /// Separates the edges of an adjacency list, or the fields of an edge list, by any whitespace
+    pub fn whitespace_separated(mut self) -> Self {
+        self.separator = None;
+        self
+    }
+
+    /// Sets the character between the destination and the weight of an edge in an adjacency list
+    pub fn weight_separator(mut self, separator: char) -> Self {
+        self.weight_separator = separator;
+        self
+    }
+
+    /// Skips any line starting with `prefix`, such as `#`
+    pub fn comment_prefix(mut self, prefix: &str) -> Self {
+        self.comment_prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// Expects the first line to end with the number of nodes and edges, such as `p sp 4 3`
+    ///
+    /// The number of edges must match the number found,
+    /// and with indexed nodes every node up to the node count is added to the graph.
+    pub fn header(mut self) -> Self {
+        self.header = true;
+        self
+    }
+
+    /// Treats the nodes as indices starting from 0
+    pub fn zero_indexed(mut self) -> Self {
+        self.index_base = Some(0);
+        self
+    }
+
+    /// Treats the nodes as indices starting from 1, which are renumbered to start from 0
+    pub fn one_indexed(mut self) -> Self {
+        self.index_base = Some(1);
+        self
+    }
+
+    /// Sets how edges which appear more than once are handled
+    pub fn duplicate_edges(mut self, policy: DuplicateEdges) -> Self {
+        self.duplicate_edges = policy;
+        self
+    }
+
+    /// Splits a list of edges or fields on the separator, dropping any empty pieces
+    fn split<'s>(&self, s: &'s str) -> Vec<&'s str> {
+        match self.separator {
+            Some(separator) => s
+                .split(separator)
+                .map(str::trim)
+                .filter(|field| !field.is_empty())
+                .collect(),
+            None => s.split_whitespace().collect(),
+        }
+    }
+
+    /// Parses a node, converting indices to start from 0 and checking them against the node count
+    fn parse_node<N>(&self, s: &str, nodes: Option<usize>) -> Result<N, String>
+    where
+        N: FromStr,
+        <N as FromStr>::Err: Debug,
+    {
+        match self.index_base {
+            Some(base) => {
+                let index = s
+                    .parse::<usize>()
+                    .map_err(|err| format!("{:?}", err))?
+                    .checked_sub(base)
+                    .filter(|&index| !matches!(nodes, Some(nodes) if index >= nodes))
+                    .ok_or_else(|| format!("node index {} out of range", s))?;
+                node_from_index(index)
+            }
+            None => s.parse().map_err(|err| format!("{:?}", err)),
+        }
+    }
+
+    /// Parses an edge from the fields after its source node
+    fn parse_edge<N>(
+        &self,
+        destination: &str,
+        weight: Option<&str>,
+        nodes: Option<usize>,
+    ) -> Result<Edge<N>, ParseEdgeError>
+    where
+        N: NodeBounds + FromStr,
+        <N as FromStr>::Err: Debug,
+    {
+        let destination = self
+            .parse_node(destination, nodes)
+            .map_err(ParseEdgeError::Node)?;
+
+        match weight {
+            Some(w) => {
+                let weight: i64 = w.parse().map_err(ParseEdgeError::Weight)?;
+                Ok(Edge::new_with_weight(destination, weight))
+            }
+            None => Ok(Edge::new(destination)),
+        }
+    }
+}
+
+/// Creates a node from its index by parsing the index's decimal representation
+fn node_from_index<N>(index: usize) -> Result<N, String>
+where
+    N: FromStr,
+    <N as FromStr>::Err: Debug,
+{
+    index
+        .to_string()
+        .parse()
+        .map_err(|err| format!("{:?}", err))
+}
+
+/// Reads the node and edge counts from the end of a header line
+fn parse_header(line: &str) -> Result<(usize, usize), GraphParseError> {
+    let mut fields = line.split_whitespace().rev();
+    let edges = fields.next().and_then(|m| m.parse().ok());
+    let nodes = fields.next().and_then(|n| n.parse().ok());
+
+    nodes.zip(edges).ok_or(GraphParseError::Header)
+}
+
+impl<N: NodeBounds, S: BuildHasher + Default> Graph<N, S>
+where
+    N: FromStr,
+    <N as FromStr>::Err: Debug,
+{
+    /// Parses a graph from a string written in the given dialect
+    /// ```
+    /// use graph_algos::{Graph, GraphTextFormat};
+    ///
+    /// let input = "\
+    /// // leaves have no edges
+    /// a = b:2; c:1
+    /// b =
+    /// c =
+    /// ";
+    ///
+    /// let format = GraphTextFormat::adjacency_list()
+    ///     .comment_prefix("//")
+    ///     .node_separator('=')
+    ///     .separator(';')
+    ///     .weight_separator(':');
+    ///
+    /// let graph: Graph<String> = Graph::parse_with(input, &format).unwrap();
+    ///
+    /// assert_eq!(graph.len(), 3);
+    /// assert_eq!(graph.succs(&"a".into()).unwrap()[1].weight(), Some(1.into()));
+    /// ```
+    pub fn parse_with(s: &str, format: &GraphTextFormat) -> Result<Self, GraphParseError> {
+        let mut graph: Graph<N, S> = Graph::default();
+
+        let mut lines = s.lines().enumerate().filter(|(_, line)| {
+            let line = line.trim();
+            !line.is_empty()
+                && !format
+                    .comment_prefix
+                    .as_ref()
+                    .is_some_and(|prefix| line.starts_with(prefix.as_str()))
+        });
+
+        let counts = if format.header {
+            let (_, line) = lines.next().ok_or(GraphParseError::Header)?;
+            Some(parse_header(line)?)
+        } else {
+            None
+        };
+        let node_count = counts.map(|(nodes, _)| nodes);
+
+        // with indexed nodes the header tells us about nodes which have no edges
+        if let (Some(_), Some(nodes)) = (format.index_base, node_count) {
+            for index in 0..nodes {
+                graph.add_node(node_from_index(index).map_err(GraphParseError::Node)?);
+            }
+        }
+
+        let mut edge_count = 0;
+        for (line_no, line) in lines {
+            let (u, edges) = match format.layout {
+                TextLayout::AdjacencyList => {
+                    let (u, rest) = line
+                        .split_once(format.node_separator)
+                        .ok_or(GraphParseError::Format)?;
+
+                    let edges = format
+                        .split(rest)
+                        .into_iter()
+                        .map(|edge| match edge.split_once(format.weight_separator) {
+                            Some((v, w)) => format.parse_edge(v, Some(w), node_count),
+                            None => format.parse_edge(edge, None, node_count),
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    (u.trim(), edges)
+                }
+                TextLayout::EdgeList => match format.split(line).as_slice() {
+                    [u, v] => (*u, vec![format.parse_edge(v, None, node_count)?]),
+                    [u, v, w] => (*u, vec![format.parse_edge(v, Some(w), node_count)?]),
+                    _ => return Err(GraphParseError::Format),
+                },
+            };
+
+            let u: N = format
+                .parse_node(u, node_count)
+                .map_err(GraphParseError::Node)?;
+            graph.add_node(u.clone());
+
+            for edge in edges {
+                edge_count += 1;
+                insert_edge(&mut graph, &u, edge, format.duplicate_edges, line_no + 1)?;
+            }
+        }
+
+        match counts {
+            Some((_, expected)) if expected != edge_count => Err(GraphParseError::EdgeCount {
+                expected,
+                found: edge_count,
+            }),
+            _ => Ok(graph),
+        }
+    }
+}
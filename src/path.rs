@@ -15,8 +15,8 @@
  *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use crate::{Edge, EdgeWeight, NodeBounds};
-use std::fmt;
+use crate::{error::PathError, Edge, EdgeWeight, Graph, NodeBounds};
+use std::{fmt, hash::BuildHasher};
 
 /// Represents the predecessor map generated by various graph algorithms
 pub type PredMap<'a, N> = ::std::collections::HashMap<&'a N, Edge<&'a N>>;
@@ -76,6 +76,11 @@ impl<'a, N: NodeBounds> Path<'a, N> {
         &self.edges
     }
 
+    /// constructs a path directly from its head node and edges
+    pub(crate) fn from_parts(head: &'a N, edges: Vec<Edge<&'a N>>) -> Self {
+        Self { head, edges }
+    }
+
     /// extracts a path from the predecessor map and an end node
     /// ```
     /// use graph_algos::{Path, PredMap, Edge, EdgeWeight};
@@ -157,3 +162,90 @@ impl<'a, N: NodeBounds> fmt::Display for Path<'a, N> {
         Ok(())
     }
 }
+
+impl<N: NodeBounds, S: BuildHasher> Graph<N, S> {
+    /// Returns the total weight of the path visiting `nodes` in order
+    ///
+    /// Each consecutive pair of nodes must be joined by a weighted edge,
+    /// where there are parallel edges the lightest one is used.
+    /// A path of a single node has weight zero,
+    /// and a path with edges of both infinite weights fails with [`PathError::UndefinedWeight`].
+    /// ```
+    /// use graph_algos::{error::PathError, graph, EdgeWeight, Graph};
+    ///
+    /// let graph: Graph<&str> = graph! {
+    ///     "a" => ["b" => 2, "c" => 7],
+    ///     "b" => ["c" => 3, "c" => 1],
+    ///     "c" => ["d"],
+    /// };
+    ///
+    /// assert_eq!(graph.path_weight(&[&"a", &"b", &"c"]), Ok(3.into()));
+    /// assert_eq!(graph.path_weight(&[&"a"]), Ok(0.into()));
+    /// assert_eq!(
+    ///     graph.path_weight(&[&"a", &"d"]),
+    ///     Err(PathError::MissingEdge { source: "a", destination: "d" }),
+    /// );
+    /// assert_eq!(
+    ///     graph.path_weight(&[&"b", &"c", &"d"]),
+    ///     Err(PathError::MissingWeight { source: "c", destination: "d" }),
+    /// );
+    /// assert_eq!(graph.path_weight(&[&"e"]), Err(PathError::MissingNode("e")));
+    ///
+    /// let infinite: Graph<&str> = graph! {
+    ///     "a" => ["b" => EdgeWeight::infinity()],
+    ///     "b" => ["c" => EdgeWeight::neg_infinity()],
+    /// };
+    /// assert_eq!(
+    ///     infinite.path_weight(&[&"a", &"b", &"c"]),
+    ///     Err(PathError::UndefinedWeight { source: "b", destination: "c" }),
+    /// );
+    /// ```
+    pub fn path_weight(&self, nodes: &[&N]) -> Result<EdgeWeight, PathError<N>> {
+        let (&first, _) = nodes.split_first().ok_or(PathError::Empty)?;
+        if self.succs(first).is_none() {
+            return Err(PathError::MissingNode(first.clone()));
+        }
+
+        let mut total = EdgeWeight::default();
+        for hop in nodes.windows(2) {
+            let (u, v) = (hop[0], hop[1]);
+            if self.succs(v).is_none() {
+                return Err(PathError::MissingNode(v.clone()));
+            }
+
+            let mut edges = self
+                .succs_or_empty(u)
+                .iter()
+                .filter(|edge| edge.destination() == v)
+                .peekable();
+            if edges.peek().is_none() {
+                return Err(PathError::MissingEdge {
+                    source: u.clone(),
+                    destination: v.clone(),
+                });
+            }
+
+            let weight =
+                edges
+                    .filter_map(Edge::weight)
+                    .min()
+                    .ok_or_else(|| PathError::MissingWeight {
+                        source: u.clone(),
+                        destination: v.clone(),
+                    })?;
+
+            // the opposite infinities have no sum
+            if let (EdgeWeight::PosInfinity, EdgeWeight::NegInfinity)
+            | (EdgeWeight::NegInfinity, EdgeWeight::PosInfinity) = (total, weight)
+            {
+                return Err(PathError::UndefinedWeight {
+                    source: u.clone(),
+                    destination: v.clone(),
+                });
+            }
+            total = total + weight;
+        }
+
+        Ok(total)
+    }
+}
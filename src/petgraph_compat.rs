@@ -0,0 +1,117 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Conversions between [`Graph`] and [`petgraph::Graph`].
+//!
+//! Edges without a weight become edges with [`EdgeWeight::default`] in petgraph,
+//! since every petgraph edge carries a weight.
+//!
+//! ```
+//! use graph_algos::{graph, Graph, EdgeWeight};
+//!
+//! let graph: Graph<&str> = graph! {
+//!     "a" => ["b" => 4, "c" => 1],
+//!     "c" => ["b" => 2],
+//! };
+//!
+//! let pet: petgraph::Graph<&str, EdgeWeight> = (&graph).into();
+//! assert_eq!(pet.node_count(), 3);
+//! assert_eq!(pet.edge_count(), 3);
+//!
+//! let back: Graph<&str> = pet.into();
+//! assert_eq!(back, graph);
+//! ```
+
+use crate::{Edge, EdgeWeight, Graph, NodeBounds};
+use petgraph::{
+    graph::{IndexType, NodeIndex},
+    visit::EdgeRef,
+    Directed, EdgeType,
+};
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    hash::BuildHasher,
+};
+
+impl<N: NodeBounds, S: BuildHasher> From<&Graph<N, S>>
+    for petgraph::Graph<N, EdgeWeight, Directed>
+{
+    fn from(graph: &Graph<N, S>) -> Self {
+        let mut pet = petgraph::Graph::with_capacity(graph.len(), graph.edges().count());
+
+        let indices: HashMap<&N, NodeIndex> = graph
+            .nodes()
+            .map(|node| (node, pet.add_node(node.clone())))
+            .collect();
+
+        for (u, edge) in graph.edges() {
+            pet.add_edge(
+                indices[u],
+                indices[edge.destination()],
+                edge.weight().unwrap_or_default(),
+            );
+        }
+
+        pet
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher> From<Graph<N, S>> for petgraph::Graph<N, EdgeWeight, Directed> {
+    fn from(graph: Graph<N, S>) -> Self {
+        Self::from(&graph)
+    }
+}
+
+/// Undirected petgraph edges become a pair of edges, one in each direction,
+/// and every edge in the result is weighted.
+impl<N, Ty, Ix> From<&petgraph::Graph<N, EdgeWeight, Ty, Ix>> for Graph<N, RandomState>
+where
+    N: NodeBounds,
+    Ty: EdgeType,
+    Ix: IndexType,
+{
+    fn from(pet: &petgraph::Graph<N, EdgeWeight, Ty, Ix>) -> Self {
+        let mut graph = Graph::with_capacity(pet.node_count());
+
+        for node in pet.node_weights() {
+            graph.add_node(node.clone());
+        }
+
+        for edge in pet.edge_references() {
+            let u = &pet[edge.source()];
+            let v = &pet[edge.target()];
+
+            graph.add_edge(u.clone(), Edge::new_with_weight(v.clone(), *edge.weight()));
+            if !pet.is_directed() && u != v {
+                graph.add_edge(v.clone(), Edge::new_with_weight(u.clone(), *edge.weight()));
+            }
+        }
+
+        graph
+    }
+}
+
+impl<N, Ty, Ix> From<petgraph::Graph<N, EdgeWeight, Ty, Ix>> for Graph<N, RandomState>
+where
+    N: NodeBounds,
+    Ty: EdgeType,
+    Ix: IndexType,
+{
+    fn from(pet: petgraph::Graph<N, EdgeWeight, Ty, Ix>) -> Self {
+        Self::from(&pet)
+    }
+}
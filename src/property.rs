@@ -0,0 +1,490 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! A property graph layer on top of [`Graph`].
+//!
+//! Nodes and edges carry a label and a set of string-keyed properties,
+//! which allows heterogeneous data to be modelled in a single graph.
+//!
+//! ```
+//! use graph_algos::property::{PropertyGraph, PropertyValue};
+//! use graph_algos::algos::bfs_shortest_paths;
+//!
+//! let mut graph: PropertyGraph<&str, &str> = PropertyGraph::new();
+//!
+//! graph.add_node("alice", "user");
+//! graph.add_node("bob", "user");
+//! graph.add_node("carol", "user");
+//! graph.add_node("cats", "post");
+//!
+//! graph.add_edge("alice", "bob", "FOLLOWS", None);
+//! graph.add_edge("bob", "carol", "FOLLOWS", None);
+//! graph.add_edge("alice", "cats", "LIKES", None);
+//!
+//! graph.set_node_property(&"cats", "title", "Cats are great");
+//! assert_eq!(
+//!     graph.node_property(&"cats", "title"),
+//!     Some(&PropertyValue::from("Cats are great")),
+//! );
+//!
+//! // only follow the FOLLOWS edges
+//! let follows = graph.label_view(&"FOLLOWS");
+//! let pred_map = bfs_shortest_paths(follows, &"alice");
+//!
+//! assert!(pred_map.contains_key(&"carol"));
+//! assert!(!pred_map.contains_key(&"cats"));
+//! ```
+
+use crate::{
+    visit::{EdgeWeighted, GraphRef, IntoNeighbors, IntoNodes},
+    Edge, EdgeWeight, Graph, NodeBounds,
+};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    fmt::{self, Debug},
+    hash::Hash,
+    slice,
+    str::FromStr,
+};
+
+/// The value of a property on a node or an edge
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyValue {
+    /// A boolean value
+    Bool(bool),
+
+    /// An integer value
+    Int(i64),
+
+    /// A floating point value
+    Float(f64),
+
+    /// A string value
+    Str(String),
+}
+
+impl From<bool> for PropertyValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i64> for PropertyValue {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<f64> for PropertyValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<&str> for PropertyValue {
+    fn from(value: &str) -> Self {
+        Self::Str(value.into())
+    }
+}
+
+impl From<String> for PropertyValue {
+    fn from(value: String) -> Self {
+        Self::Str(value)
+    }
+}
+
+impl FromStr for PropertyValue {
+    type Err = Infallible;
+
+    /// Parses a value written by its `Display` implementation,
+    /// trying a boolean, then an integer, then a float, and falling back to a string
+    /// ```
+    /// use graph_algos::property::PropertyValue;
+    ///
+    /// assert_eq!("true".parse(), Ok(PropertyValue::Bool(true)));
+    /// assert_eq!("-3".parse(), Ok(PropertyValue::Int(-3)));
+    /// assert_eq!("2.5".parse(), Ok(PropertyValue::Float(2.5)));
+    /// assert_eq!("road".parse(), Ok(PropertyValue::from("road")));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "true" => Self::Bool(true),
+            "false" => Self::Bool(false),
+            _ => match (s.parse(), s.parse()) {
+                (Ok(int), _) => Self::Int(int),
+                (_, Ok(float)) => Self::Float(float),
+                _ => Self::Str(s.into()),
+            },
+        })
+    }
+}
+
+impl fmt::Display for PropertyValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(value) => write!(f, "{}", value),
+            Self::Int(value) => write!(f, "{}", value),
+            // always write a decimal point so the value parses back as a float
+            Self::Float(value) => write!(f, "{:?}", value),
+            Self::Str(value) => f.write_str(value),
+        }
+    }
+}
+
+/// The properties of a node or an edge
+pub type Properties = HashMap<String, PropertyValue>;
+
+/// The named attributes of the edges of a graph, keyed by their source and destination,
+/// created by [`Graph::parse_with_attributes`] and written by [`crate::GraphFormatter::edge_attributes`]
+///
+/// Parallel edges between the same pair of nodes share a single set of attributes.
+pub type EdgeAttributes<N> = HashMap<(N, N), Properties>;
+
+/// A trait to represent all of the bounds that a label must provide
+pub trait LabelBounds: Hash + Debug + Eq + Clone {}
+impl<T: Hash + Debug + Eq + Clone> LabelBounds for T {}
+
+/// The label and properties of a node
+#[derive(Debug, Clone, PartialEq)]
+struct NodeData<L> {
+    label: L,
+    properties: Properties,
+}
+
+/// The label and properties of an edge
+#[derive(Debug, Clone, PartialEq)]
+struct EdgeData<L> {
+    label: L,
+    properties: Properties,
+}
+
+/// A graph where every node and edge has a label and a set of properties
+///
+/// The structure of the graph is held in a [`Graph`],
+/// so any of the algorithms can be run over [`PropertyGraph::graph`]
+/// or over a single edge label using [`PropertyGraph::label_view`].
+#[derive(Debug, Clone)]
+pub struct PropertyGraph<N: NodeBounds, L: LabelBounds = String> {
+    graph: Graph<N>,
+
+    /// the labels and properties of the nodes
+    nodes: HashMap<N, NodeData<L>>,
+
+    /// the labels and properties of the edges, in the same order as the successors in `graph`
+    edges: HashMap<N, Vec<EdgeData<L>>>,
+}
+
+impl<N: NodeBounds, L: LabelBounds> Default for PropertyGraph<N, L> {
+    fn default() -> Self {
+        Self {
+            graph: Graph::empty(),
+            nodes: HashMap::new(),
+            edges: HashMap::new(),
+        }
+    }
+}
+
+impl<N: NodeBounds, L: LabelBounds> PropertyGraph<N, L> {
+    /// Creates a new empty property graph
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the underlying graph structure
+    pub fn graph(&self) -> &Graph<N> {
+        &self.graph
+    }
+
+    /// Adds a node with the given label, replacing the label if the node already exists
+    ///
+    /// The properties of an existing node are kept.
+    pub fn add_node(&mut self, node: N, label: L) {
+        self.graph.add_node(node.clone());
+
+        self.nodes
+            .entry(node)
+            .and_modify(|data| data.label = label.clone())
+            .or_insert_with(|| NodeData {
+                label,
+                properties: Properties::new(),
+            });
+    }
+
+    /// Returns the label of a node
+    ///
+    /// Nodes which were only added as the end of an edge have no label.
+    pub fn node_label(&self, node: &N) -> Option<&L> {
+        self.nodes.get(node).map(|data| &data.label)
+    }
+
+    /// Returns the properties of a node
+    pub fn node_properties(&self, node: &N) -> Option<&Properties> {
+        self.nodes.get(node).map(|data| &data.properties)
+    }
+
+    /// Returns a property of a node
+    pub fn node_property(&self, node: &N, key: &str) -> Option<&PropertyValue> {
+        self.node_properties(node)?.get(key)
+    }
+
+    /// Sets a property of a labelled node, returning false if the node has no label
+    pub fn set_node_property(
+        &mut self,
+        node: &N,
+        key: impl Into<String>,
+        value: impl Into<PropertyValue>,
+    ) -> bool {
+        if let Some(data) = self.nodes.get_mut(node) {
+            data.properties.insert(key.into(), value.into());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns an iterator over the nodes with a given label
+    /// ```
+    /// use graph_algos::property::PropertyGraph;
+    ///
+    /// let mut graph: PropertyGraph<u32, &str> = PropertyGraph::new();
+    /// graph.add_node(1, "even");
+    /// graph.add_node(2, "odd");
+    /// graph.add_node(3, "even");
+    ///
+    /// let mut evens: Vec<&u32> = graph.nodes_with_label(&"even").collect();
+    /// evens.sort();
+    /// assert_eq!(evens, vec![&1, &3]);
+    /// ```
+    pub fn nodes_with_label<'a>(&'a self, label: &'a L) -> impl Iterator<Item = &'a N> + 'a {
+        self.nodes
+            .iter()
+            .filter(move |(_, data)| &data.label == label)
+            .map(|(node, _)| node)
+    }
+
+    /// Adds a labelled edge from `u` to `v`
+    ///
+    /// Edges with different labels between the same pair of nodes are kept separately.
+    pub fn add_edge(&mut self, u: N, v: N, label: L, weight: Option<EdgeWeight>) {
+        self.edges.entry(u.clone()).or_default().push(EdgeData {
+            label,
+            properties: Properties::new(),
+        });
+
+        let edge = match weight {
+            Some(w) => Edge::new_with_weight(v, w),
+            None => Edge::new(v),
+        };
+        self.graph.add_edge(u, edge);
+    }
+
+    /// Returns the position of the first edge from `u` to `v` with the given label
+    fn edge_position(&self, u: &N, v: &N, label: &L) -> Option<usize> {
+        let succs = self.graph.succs(u)?;
+        let data = self.edges.get(u)?;
+
+        succs
+            .iter()
+            .zip(data)
+            .position(|(edge, data)| edge.destination() == v && &data.label == label)
+    }
+
+    /// Removes the first edge from `u` to `v` with the given label,
+    /// returning whether there was an edge to remove
+    /// ```
+    /// use graph_algos::property::PropertyGraph;
+    ///
+    /// let mut graph: PropertyGraph<&str, &str> = PropertyGraph::new();
+    /// graph.add_edge("alice", "bob", "FOLLOWS", None);
+    /// graph.add_edge("alice", "bob", "BLOCKS", None);
+    ///
+    /// assert!(graph.remove_edge(&"alice", &"bob", &"BLOCKS"));
+    /// assert!(!graph.remove_edge(&"alice", &"bob", &"BLOCKS"));
+    ///
+    /// assert!(graph.has_edge(&"alice", &"bob", &"FOLLOWS"));
+    /// ```
+    pub fn remove_edge(&mut self, u: &N, v: &N, label: &L) -> bool {
+        if let Some(pos) = self.edge_position(u, v, label) {
+            self.graph.succs_mut(u).unwrap().remove(pos);
+            self.edges.get_mut(u).unwrap().remove(pos);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns whether there is an edge from `u` to `v` with the given label
+    pub fn has_edge(&self, u: &N, v: &N, label: &L) -> bool {
+        self.edge_position(u, v, label).is_some()
+    }
+
+    /// Returns the properties of the first edge from `u` to `v` with the given label
+    pub fn edge_properties(&self, u: &N, v: &N, label: &L) -> Option<&Properties> {
+        let pos = self.edge_position(u, v, label)?;
+        Some(&self.edges[u][pos].properties)
+    }
+
+    /// Returns a property of the first edge from `u` to `v` with the given label
+    pub fn edge_property(&self, u: &N, v: &N, label: &L, key: &str) -> Option<&PropertyValue> {
+        self.edge_properties(u, v, label)?.get(key)
+    }
+
+    /// Sets a property of the first edge from `u` to `v` with the given label,
+    /// returning false if there is no such edge
+    /// ```
+    /// use graph_algos::property::{PropertyGraph, PropertyValue};
+    ///
+    /// let mut graph: PropertyGraph<&str, &str> = PropertyGraph::new();
+    /// graph.add_edge("alice", "bob", "FOLLOWS", None);
+    ///
+    /// assert!(graph.set_edge_property(&"alice", &"bob", &"FOLLOWS", "since", 2019));
+    /// assert!(!graph.set_edge_property(&"bob", &"alice", &"FOLLOWS", "since", 2020));
+    ///
+    /// assert_eq!(
+    ///     graph.edge_property(&"alice", &"bob", &"FOLLOWS", "since"),
+    ///     Some(&PropertyValue::Int(2019)),
+    /// );
+    /// ```
+    pub fn set_edge_property(
+        &mut self,
+        u: &N,
+        v: &N,
+        label: &L,
+        key: impl Into<String>,
+        value: impl Into<PropertyValue>,
+    ) -> bool {
+        if let Some(pos) = self.edge_position(u, v, label) {
+            let data = &mut self.edges.get_mut(u).unwrap()[pos];
+            data.properties.insert(key.into(), value.into());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns an iterator over the successors of `u` along edges with the given label
+    pub fn neighbors_by_label<'a>(&'a self, u: &N, label: &'a L) -> LabelNeighbors<'a, N, L> {
+        self.label_view(label).neighbors(u)
+    }
+
+    /// Returns a view of the graph which only contains the edges with the given label
+    ///
+    /// The view contains every node, and can be passed to any of the algorithms.
+    pub fn label_view<'a>(&'a self, label: &'a L) -> LabelView<'a, N, L> {
+        LabelView { graph: self, label }
+    }
+}
+
+/// A view of a [`PropertyGraph`] containing only the edges with a single label
+#[derive(Debug)]
+pub struct LabelView<'a, N: NodeBounds, L: LabelBounds> {
+    graph: &'a PropertyGraph<N, L>,
+    label: &'a L,
+}
+
+impl<N: NodeBounds, L: LabelBounds> Clone for LabelView<'_, N, L> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<N: NodeBounds, L: LabelBounds> Copy for LabelView<'_, N, L> {}
+
+impl<'a, N: NodeBounds + 'a, L: LabelBounds> GraphRef<'a> for LabelView<'a, N, L> {
+    type Node = N;
+
+    fn contains_node(self, u: &N) -> bool {
+        self.graph.graph.succs(u).is_some()
+    }
+}
+
+impl<'a, N: NodeBounds + 'a, L: LabelBounds> IntoNodes<'a> for LabelView<'a, N, L> {
+    type Nodes = crate::graph::Nodes<'a, N>;
+
+    fn nodes(self) -> Self::Nodes {
+        self.graph.graph.nodes()
+    }
+
+    fn node_count(self) -> usize {
+        self.graph.graph.len()
+    }
+}
+
+impl<'a, N: NodeBounds + 'a, L: LabelBounds> IntoNeighbors<'a> for LabelView<'a, N, L> {
+    type Neighbors = LabelNeighbors<'a, N, L>;
+
+    fn neighbors(self, u: &N) -> Self::Neighbors {
+        LabelNeighbors {
+            inner: self.neighbors_weighted(u),
+        }
+    }
+}
+
+impl<'a, N: NodeBounds + 'a, L: LabelBounds> EdgeWeighted<'a> for LabelView<'a, N, L> {
+    type NeighborsWeighted = LabelNeighborsWeighted<'a, N, L>;
+
+    fn neighbors_weighted(self, u: &N) -> Self::NeighborsWeighted {
+        LabelNeighborsWeighted {
+            succs: self.graph.graph.succs_or_empty(u).iter(),
+            data: self
+                .graph
+                .edges
+                .get(u)
+                .map_or(&[][..], Vec::as_slice)
+                .iter(),
+            label: self.label,
+        }
+    }
+}
+
+/// An iterator over the successors of a node along edges with a given label
+#[derive(Debug, Clone)]
+pub struct LabelNeighbors<'a, N: NodeBounds, L: LabelBounds> {
+    inner: LabelNeighborsWeighted<'a, N, L>,
+}
+
+impl<'a, N: NodeBounds, L: LabelBounds> Iterator for LabelNeighbors<'a, N, L> {
+    type Item = &'a N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(v, _)| v)
+    }
+}
+
+/// An iterator over the successors of a node and the edge weights along edges with a given label
+#[derive(Debug, Clone)]
+pub struct LabelNeighborsWeighted<'a, N: NodeBounds, L: LabelBounds> {
+    succs: slice::Iter<'a, Edge<N>>,
+    data: slice::Iter<'a, EdgeData<L>>,
+    label: &'a L,
+}
+
+impl<'a, N: NodeBounds, L: LabelBounds> Iterator for LabelNeighborsWeighted<'a, N, L> {
+    type Item = (&'a N, Option<EdgeWeight>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let edge = self.succs.next()?;
+            let data = self.data.next()?;
+
+            if &data.label == self.label {
+                break Some((edge.destination(), edge.weight()));
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{algos::dijkstra, Edge, EdgeWeight, Graph, NodeBounds, Path};
+use std::{
+    collections::{
+        hash_map::{Entry, RandomState},
+        HashMap, VecDeque,
+    },
+    hash::BuildHasher,
+    sync::Arc,
+};
+
+/// A cheaply cloneable handle for querying a graph shared between threads
+///
+/// Every query returns owned results, so nothing borrows from the graph and the results can be
+/// sent anywhere or kept around after the reader is dropped.
+/// ```
+/// use graph_algos::{graph, Graph, GraphReader};
+/// use std::thread;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2 => 1, 3 => 4],
+///     2 => [3 => 1],
+///     3 => [4 => 1],
+/// };
+/// let reader = GraphReader::new(graph);
+///
+/// let workers: Vec<_> = (1..=3)
+///     .map(|start| {
+///         let reader = reader.clone();
+///         thread::spawn(move || reader.distances(&start)[&4])
+///     })
+///     .collect();
+///
+/// let distances: Vec<_> = workers.into_iter().map(|w| w.join().unwrap()).collect();
+/// assert_eq!(distances, vec![3.into(), 2.into(), 1.into()]);
+/// ```
+#[derive(Debug)]
+pub struct GraphReader<N: NodeBounds, S = RandomState> {
+    graph: Arc<Graph<N, S>>,
+}
+
+impl<N: NodeBounds, S> Clone for GraphReader<N, S> {
+    fn clone(&self) -> Self {
+        Self {
+            graph: Arc::clone(&self.graph),
+        }
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher> GraphReader<N, S> {
+    /// Creates a reader for a graph, which can be given as an owned graph or an [`Arc`]
+    pub fn new(graph: impl Into<Arc<Graph<N, S>>>) -> Self {
+        Self {
+            graph: graph.into(),
+        }
+    }
+
+    /// Returns the shared graph
+    pub fn graph(&self) -> &Arc<Graph<N, S>> {
+        &self.graph
+    }
+
+    /// Returns the number of nodes in the graph
+    pub fn len(&self) -> usize {
+        self.graph.len()
+    }
+
+    /// Returns whether the graph has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.graph.is_empty()
+    }
+
+    /// Returns whether `u` is in the graph
+    pub fn contains_node(&self, u: &N) -> bool {
+        self.graph.succs(u).is_some()
+    }
+
+    /// Returns the edges out of `u`, which is empty if `u` isn't in the graph
+    /// ```
+    /// use graph_algos::{graph, Edge, Graph, GraphReader};
+    ///
+    /// let graph: Graph<u32> = graph! { 1 => [2 => 3] };
+    /// let reader = GraphReader::new(graph);
+    ///
+    /// assert_eq!(reader.successors(&1), vec![Edge::new_with_weight(2, 3)]);
+    /// assert!(reader.successors(&5).is_empty());
+    /// ```
+    pub fn successors(&self, u: &N) -> Vec<Edge<N>> {
+        self.graph.succs_or_empty(u).to_vec()
+    }
+
+    /// Returns the nodes reachable from `start`, each with the number of edges on a
+    /// shortest path to it
+    /// ```
+    /// use graph_algos::{graph, Graph, GraphReader};
+    ///
+    /// let graph: Graph<u32> = graph! { 1 => [2], 2 => [3], 4 => [1] };
+    /// let reader = GraphReader::new(graph);
+    ///
+    /// let hops = reader.hops(&1);
+    /// assert_eq!(hops.len(), 3);
+    /// assert_eq!(hops[&3], 2);
+    /// ```
+    pub fn hops(&self, start: &N) -> HashMap<N, usize> {
+        let start = match self.graph.node_ref(start) {
+            Some(start) => start,
+            None => return HashMap::new(),
+        };
+
+        let mut hops: HashMap<&N, usize> = HashMap::new();
+        let mut queue = VecDeque::from(vec![start]);
+        hops.insert(start, 0);
+        while let Some(u) = queue.pop_front() {
+            let next = hops[u] + 1;
+            for v in self.graph.neighbors(u) {
+                if let Entry::Vacant(e) = hops.entry(v) {
+                    e.insert(next);
+                    queue.push_back(v);
+                }
+            }
+        }
+
+        hops.into_iter()
+            .map(|(node, n)| (node.clone(), n))
+            .collect()
+    }
+
+    /// Returns the distance from `start` to every node reachable from it
+    ///
+    /// Panics if an edge without a weight is reached, like [`dijkstra`].
+    pub fn distances(&self, start: &N) -> HashMap<N, EdgeWeight> {
+        let start = match self.graph.node_ref(start) {
+            Some(start) => start,
+            None => return HashMap::new(),
+        };
+
+        let (_, dist_map) = dijkstra(&*self.graph, start);
+        dist_map
+            .into_iter()
+            .map(|(node, dist)| (node.clone(), dist))
+            .collect()
+    }
+
+    /// Returns the nodes along a shortest path from `start` to `end` and its total weight,
+    /// or `None` if `end` can't be reached
+    ///
+    /// Panics if an edge without a weight is reached, like [`dijkstra`].
+    /// ```
+    /// use graph_algos::{graph, Graph, GraphReader};
+    ///
+    /// let graph: Graph<&str> = graph! {
+    ///     "a" => ["b" => 1, "c" => 5],
+    ///     "b" => ["c" => 1],
+    /// };
+    /// let reader = GraphReader::new(graph);
+    ///
+    /// assert_eq!(reader.shortest_path(&"a", &"c"), Some((vec!["a", "b", "c"], 2.into())));
+    /// assert_eq!(reader.shortest_path(&"c", &"a"), None);
+    /// ```
+    pub fn shortest_path(&self, start: &N, end: &N) -> Option<(Vec<N>, EdgeWeight)> {
+        let start = self.graph.node_ref(start)?;
+        let end = self.graph.node_ref(end)?;
+
+        let (pred_map, dist_map) = dijkstra(&*self.graph, start);
+        let weight = *dist_map.get(end)?;
+        let path = Path::new_path_to(&pred_map, end).ok()?;
+
+        let nodes = std::iter::once(path.head())
+            .chain(path.edges().iter().map(|edge| *edge.destination()))
+            .cloned()
+            .collect();
+        Some((nodes, weight))
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher> From<Graph<N, S>> for GraphReader<N, S> {
+    fn from(graph: Graph<N, S>) -> Self {
+        Self::new(graph)
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher> From<Arc<Graph<N, S>>> for GraphReader<N, S> {
+    fn from(graph: Arc<Graph<N, S>>) -> Self {
+        Self::new(graph)
+    }
+}
+
+/// Checks at compile time that readers can be sent to other threads whenever their graph can
+#[allow(dead_code)]
+fn assert_send_sync<N: NodeBounds + Send + Sync, S: Send + Sync>() {
+    fn is_send_sync<T: Send + Sync>() {}
+    is_send_sync::<GraphReader<N, S>>();
+}
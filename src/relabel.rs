@@ -0,0 +1,140 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::{error::RelabelError, parse::merge_weights, DuplicateEdges, Edge, Graph, NodeBounds};
+use std::{collections::HashMap, hash::BuildHasher};
+
+impl<N: NodeBounds, S: BuildHasher + Clone> Graph<N, S> {
+    /// Returns a copy of the graph with the nodes renamed by `map`,
+    /// nodes which aren't in the map keep their names
+    ///
+    /// Fails with a [`RelabelError`] listing the clashes if two nodes would end up with the same name,
+    /// including a renamed node taking the name of a node which isn't renamed.
+    /// To merge clashing nodes instead use [`Graph::relabel_with`].
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    /// use std::collections::HashMap;
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2 => 5],
+    ///     2 => [3 => 1],
+    /// };
+    ///
+    /// // swapping names is fine as no two nodes end up with the same name
+    /// let swap: HashMap<u32, u32> = vec![(1, 2), (2, 1)].into_iter().collect();
+    /// let swapped: Graph<u32> = graph! {
+    ///     2 => [1 => 5],
+    ///     1 => [3 => 1],
+    /// };
+    /// assert_eq!(graph.relabel(&swap).unwrap(), swapped);
+    ///
+    /// // 3 already has the name 1 is being given
+    /// let clash: HashMap<u32, u32> = vec![(1, 3)].into_iter().collect();
+    /// assert!(graph.relabel(&clash).is_err());
+    /// ```
+    pub fn relabel(&self, map: &HashMap<N, N>) -> Result<Graph<N, S>, RelabelError<N>> {
+        self.relabel_with(map, DuplicateEdges::Error)
+    }
+
+    /// Returns a copy of the graph with the nodes renamed by `map`,
+    /// merging any nodes which end up with the same name
+    ///
+    /// The merged node has the edges of every node merged into it,
+    /// and `duplicates` decides what happens to any edges which join the same pair of nodes
+    /// in the relabelled graph, with [`DuplicateEdges::KeepFirst`] keeping an arbitrary one of them.
+    /// [`DuplicateEdges::Error`] doesn't merge nodes at all, and fails like [`Graph::relabel`],
+    /// while [`DuplicateEdges::KeepAll`] merges them keeping every edge.
+    /// ```
+    /// use graph_algos::{graph, DuplicateEdges, Graph};
+    /// use std::collections::HashMap;
+    ///
+    /// let graph: Graph<&str> = graph! {
+    ///     "London" => ["Paris" => 340],
+    ///     "london" => ["Paris" => 345, "Rome" => 1430],
+    /// };
+    ///
+    /// let fix_case: HashMap<&str, &str> = vec![("london", "London")].into_iter().collect();
+    ///
+    /// let merged: Graph<&str> = graph! {
+    ///     "London" => ["Paris" => 340, "Rome" => 1430],
+    /// };
+    /// assert_eq!(graph.relabel_with(&fix_case, DuplicateEdges::KeepMin).unwrap(), merged);
+    /// ```
+    pub fn relabel_with(
+        &self,
+        map: &HashMap<N, N>,
+        duplicates: DuplicateEdges,
+    ) -> Result<Graph<N, S>, RelabelError<N>> {
+        let rename = |u: &N| map.get(u).unwrap_or(u).clone();
+
+        if duplicates == DuplicateEdges::Error {
+            let mut names: HashMap<N, Vec<N>> = HashMap::with_capacity(self.len());
+            for u in self.nodes() {
+                names.entry(rename(u)).or_default().push(u.clone());
+            }
+
+            let collisions: Vec<(N, Vec<N>)> = names
+                .into_iter()
+                .filter(|(_, nodes)| nodes.len() > 1)
+                .collect();
+            if !collisions.is_empty() {
+                return Err(RelabelError::new(collisions));
+            }
+        }
+
+        let mut graph = Graph::with_capacity_and_hasher(self.len(), self.hasher().clone());
+        for u in self.nodes() {
+            graph.add_node(rename(u));
+        }
+
+        for (u, edge) in self.edges() {
+            let (u, v) = (rename(u), rename(edge.destination()));
+            let existing = match duplicates {
+                DuplicateEdges::KeepAll | DuplicateEdges::Error => None,
+                _ => graph
+                    .succs(&u)
+                    .and_then(|succs| succs.iter().position(|succ| succ.destination() == &v)),
+            };
+
+            match existing {
+                Some(pos) => {
+                    let succ = &mut graph.succs_mut(&u).unwrap()[pos];
+                    let weight = match duplicates {
+                        DuplicateEdges::KeepMin => {
+                            merge_weights(succ.weight(), edge.weight(), Ord::min)
+                        }
+                        DuplicateEdges::Sum => {
+                            merge_weights(succ.weight(), edge.weight(), |a, b| a + b)
+                        }
+                        _ => succ.weight(),
+                    };
+                    *succ = new_edge(v, weight);
+                }
+                None => graph.add_edge(u, new_edge(v, edge.weight())),
+            }
+        }
+
+        Ok(graph)
+    }
+}
+
+/// Creates an edge to `v` with an optional weight
+fn new_edge<N: NodeBounds>(v: N, weight: Option<crate::EdgeWeight>) -> Edge<N> {
+    match weight {
+        Some(w) => Edge::new_with_weight(v, w),
+        None => Edge::new(v),
+    }
+}
@@ -0,0 +1,219 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use super::{ordered_nodes, Layout, Point};
+use crate::visit::{IntoNeighbors, IntoNodes};
+use rand::Rng;
+use std::collections::HashMap;
+
+/// The Fruchterman–Reingold force-directed layout engine
+///
+/// Nodes push each other apart while edges pull their ends together,
+/// and the nodes settle down as the simulation cools over a number of iterations.
+/// The direction of the edges is ignored, and the nodes are kept inside a `width` by `height` frame.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::render::ForceDirected;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2, 3],
+///     2 => [3],
+///     3 => [4],
+/// };
+///
+/// let engine = ForceDirected::new().size(400.0, 300.0).iterations(200);
+/// let layout = engine.layout(&graph, &mut StdRng::seed_from_u64(7));
+///
+/// for (_, p) in layout.positions() {
+///     assert!((0.0..=400.0).contains(&p.x) && (0.0..=300.0).contains(&p.y));
+/// }
+///
+/// // the same seed gives the same layout
+/// let again = engine.layout(&graph, &mut StdRng::seed_from_u64(7));
+/// assert_eq!(layout.position(&4), again.position(&4));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ForceDirected {
+    width: f64,
+    height: f64,
+    iterations: usize,
+}
+
+impl Default for ForceDirected {
+    fn default() -> Self {
+        Self {
+            width: 800.0,
+            height: 600.0,
+            iterations: 300,
+        }
+    }
+}
+
+impl ForceDirected {
+    /// Creates an engine with an 800 by 600 frame which runs for 300 iterations
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the size of the frame the nodes are placed in
+    pub fn size(mut self, width: f64, height: f64) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Sets the number of steps of the simulation to run
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Lays out a graph, starting from random positions drawn from `rng`
+    pub fn layout<'a, G, R>(&self, graph: G, rng: &mut R) -> Layout<'a, G::Node>
+    where
+        G: IntoNodes<'a> + IntoNeighbors<'a>,
+        R: Rng + ?Sized,
+    {
+        self.simulate(graph, None, rng)
+    }
+
+    /// Lays out a graph, starting from the positions in `start`,
+    /// clamped to the frame, and from random positions drawn from `rng` for any nodes it doesn't have
+    ///
+    /// Starting from a [`Layout::from_embedding`] of the
+    /// [`spectral_embedding`](crate::analysis::spectral_embedding) usually untangles the graph
+    /// in fewer iterations than starting from random positions.
+    /// ```
+    /// use graph_algos::{ungraph, Graph};
+    /// use graph_algos::analysis::spectral_embedding;
+    /// use graph_algos::render::{ForceDirected, Layout};
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let graph: Graph<u32> = ungraph! {
+    ///     1 => [2, 3],
+    ///     2 => [3],
+    ///     3 => [4],
+    /// };
+    ///
+    /// let start = Layout::from_embedding(&spectral_embedding(&graph, 2), 400.0, 300.0);
+    /// let engine = ForceDirected::new().size(400.0, 300.0).iterations(50);
+    /// let layout = engine.layout_from(&graph, &start, &mut StdRng::seed_from_u64(7));
+    ///
+    /// assert_eq!(layout.len(), 4);
+    /// ```
+    pub fn layout_from<'a, G, R>(
+        &self,
+        graph: G,
+        start: &Layout<'a, G::Node>,
+        rng: &mut R,
+    ) -> Layout<'a, G::Node>
+    where
+        G: IntoNodes<'a> + IntoNeighbors<'a>,
+        R: Rng + ?Sized,
+    {
+        self.simulate(graph, Some(start), rng)
+    }
+
+    /// Runs the simulation from `start`, or from random positions if there isn't one
+    fn simulate<'a, G, R>(
+        &self,
+        graph: G,
+        start: Option<&Layout<'a, G::Node>>,
+        rng: &mut R,
+    ) -> Layout<'a, G::Node>
+    where
+        G: IntoNodes<'a> + IntoNeighbors<'a>,
+        R: Rng + ?Sized,
+    {
+        let nodes = ordered_nodes(graph);
+        let n = nodes.len();
+        let (width, height) = (self.width.max(1.0), self.height.max(1.0));
+
+        let indices: HashMap<&G::Node, usize> =
+            nodes.iter().enumerate().map(|(i, &u)| (u, i)).collect();
+        let edges: Vec<(usize, usize)> = nodes
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &u)| graph.neighbors(u).map(move |v| (i, v)))
+            .filter_map(|(i, v)| indices.get(v).map(|&j| (i, j)))
+            .filter(|(i, j)| i != j)
+            .collect();
+
+        let mut positions: Vec<Point> = nodes
+            .iter()
+            .map(|&u| match start.and_then(|start| start.position(u)) {
+                Some(p) => Point::new(p.x.clamp(0.0, width), p.y.clamp(0.0, height)),
+                None => Point::new(rng.gen_range(0.0..=width), rng.gen_range(0.0..=height)),
+            })
+            .collect();
+
+        // the ideal distance between nodes
+        let k = (width * height / n.max(1) as f64).sqrt();
+        let start_temperature = width.max(height) / 10.0;
+
+        for iteration in 0..self.iterations {
+            let temperature = start_temperature * (1.0 - iteration as f64 / self.iterations as f64);
+            let mut displacement = vec![Point::default(); n];
+
+            for i in 0..n {
+                for j in i + 1..n {
+                    let (dx, dy, distance) = separation(positions[i], positions[j], rng);
+                    let force = k * k / distance;
+                    displacement[i].x += dx / distance * force;
+                    displacement[i].y += dy / distance * force;
+                    displacement[j].x -= dx / distance * force;
+                    displacement[j].y -= dy / distance * force;
+                }
+            }
+
+            for &(i, j) in &edges {
+                let (dx, dy, distance) = separation(positions[i], positions[j], rng);
+                let force = distance * distance / k;
+                displacement[i].x -= dx / distance * force;
+                displacement[i].y -= dy / distance * force;
+                displacement[j].x += dx / distance * force;
+                displacement[j].y += dy / distance * force;
+            }
+
+            for (p, d) in positions.iter_mut().zip(&displacement) {
+                let length = d.x.hypot(d.y);
+                if length > 0.0 {
+                    let step = length.min(temperature);
+                    p.x = (p.x + d.x / length * step).clamp(0.0, width);
+                    p.y = (p.y + d.y / length * step).clamp(0.0, height);
+                }
+            }
+        }
+
+        Layout {
+            positions: nodes.into_iter().zip(positions).collect(),
+        }
+    }
+}
+
+/// Returns the offset from `q` to `p` and its length,
+/// nudging the points apart at random if they are on top of each other
+fn separation<R: Rng + ?Sized>(p: Point, q: Point, rng: &mut R) -> (f64, f64, f64) {
+    let (mut dx, mut dy) = (p.x - q.x, p.y - q.y);
+
+    if dx.hypot(dy) < 1e-9 {
+        dx = rng.gen_range(-0.5..0.5);
+        dy = rng.gen_range(-0.5..0.5);
+    }
+
+    (dx, dy, dx.hypot(dy).max(1e-9))
+}
@@ -0,0 +1,191 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use super::{ordered_nodes, Layout, Point};
+use crate::{
+    algos::{acyclic_edges, longest_path_layers},
+    visit::{IntoNeighbors, IntoNodes},
+};
+use std::collections::HashMap;
+
+/// A simplified Sugiyama layered layout engine
+///
+/// The nodes are placed in horizontal layers so that edges point downwards:
+/// cycles are broken by reversing the edges which close them, each node goes in the layer
+/// below its lowest predecessor, and the nodes of each layer are ordered by the barycenter
+/// heuristic to reduce the number of crossing edges.
+/// Unlike a full Sugiyama layout no dummy nodes are added for edges spanning several layers,
+/// [`crate::algos::layering`] provides the layering and dummy nodes for building a full one.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::render::Layered;
+///
+/// let graph: Graph<&str> = graph! {
+///     "shirt" => ["tie", "belt"],
+///     "tie" => ["jacket"],
+///     "trousers" => ["belt", "shoes"],
+///     "belt" => ["jacket"],
+/// };
+///
+/// let layout = Layered::new().layer_spacing(80.0).layout(&graph);
+/// let y = |node| layout.position(&node).unwrap().y;
+///
+/// assert_eq!(y("shirt"), 0.0);
+/// assert_eq!(y("trousers"), 0.0);
+/// assert_eq!(y("belt"), 80.0);
+/// assert_eq!(y("jacket"), 160.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Layered {
+    layer_spacing: f64,
+    node_spacing: f64,
+    sweeps: usize,
+}
+
+impl Default for Layered {
+    fn default() -> Self {
+        Self {
+            layer_spacing: 100.0,
+            node_spacing: 80.0,
+            sweeps: 4,
+        }
+    }
+}
+
+impl Layered {
+    /// Creates an engine with layers 100 apart and nodes 80 apart within a layer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the vertical distance between layers
+    pub fn layer_spacing(mut self, spacing: f64) -> Self {
+        self.layer_spacing = spacing;
+        self
+    }
+
+    /// Sets the horizontal distance between neighbouring nodes in a layer
+    pub fn node_spacing(mut self, spacing: f64) -> Self {
+        self.node_spacing = spacing;
+        self
+    }
+
+    /// Sets how many times to sweep down and back up the layers when reducing crossings
+    pub fn sweeps(mut self, sweeps: usize) -> Self {
+        self.sweeps = sweeps;
+        self
+    }
+
+    /// Lays out a graph
+    ///
+    /// The layout only depends on the graph, so laying out the same graph twice gives the same positions.
+    pub fn layout<'a, G>(&self, graph: G) -> Layout<'a, G::Node>
+    where
+        G: IntoNodes<'a> + IntoNeighbors<'a>,
+    {
+        let nodes = ordered_nodes(graph);
+        let indices: HashMap<&G::Node, usize> =
+            nodes.iter().enumerate().map(|(i, &u)| (u, i)).collect();
+        let succs: Vec<Vec<usize>> = nodes
+            .iter()
+            .map(|&u| {
+                graph
+                    .neighbors(u)
+                    .filter_map(|v| indices.get(v).copied())
+                    .collect()
+            })
+            .collect();
+
+        let edges: Vec<(usize, usize)> = acyclic_edges(&succs)
+            .into_iter()
+            .map(|(u, v, _)| (u, v))
+            .collect();
+        let layer_of = longest_path_layers(nodes.len(), &edges);
+        let layers = self.order_layers(&layer_of, &edges);
+
+        let widest = layers.iter().map(Vec::len).max().unwrap_or(0);
+        let mut positions = vec![Point::default(); nodes.len()];
+        for (depth, layer) in layers.iter().enumerate() {
+            // centre each layer under the widest one
+            let offset = (widest - layer.len()) as f64 / 2.0;
+            for (i, &u) in layer.iter().enumerate() {
+                positions[u] = Point::new(
+                    (offset + i as f64) * self.node_spacing,
+                    depth as f64 * self.layer_spacing,
+                );
+            }
+        }
+
+        Layout {
+            positions: nodes.into_iter().zip(positions).collect(),
+        }
+    }
+
+    /// Groups the nodes into layers, ordering each layer with the barycenter heuristic
+    fn order_layers(&self, layer_of: &[usize], edges: &[(usize, usize)]) -> Vec<Vec<usize>> {
+        let depth = layer_of.iter().map(|&l| l + 1).max().unwrap_or(0);
+        let mut layers = vec![Vec::new(); depth];
+        for (u, &layer) in layer_of.iter().enumerate() {
+            layers[layer].push(u);
+        }
+
+        let mut preds = vec![Vec::new(); layer_of.len()];
+        let mut succs = vec![Vec::new(); layer_of.len()];
+        for &(u, v) in edges {
+            succs[u].push(v);
+            preds[v].push(u);
+        }
+
+        let mut order = vec![0.0; layer_of.len()];
+        for layer in &layers {
+            for (i, &u) in layer.iter().enumerate() {
+                order[u] = i as f64;
+            }
+        }
+
+        for _ in 0..self.sweeps {
+            for layer in layers.iter_mut().skip(1) {
+                reorder(layer, &preds, &mut order);
+            }
+            for layer in layers.iter_mut().rev().skip(1) {
+                reorder(layer, &succs, &mut order);
+            }
+        }
+
+        layers
+    }
+}
+
+/// Sorts a layer by the average position of each node's neighbours in the adjacent layers,
+/// leaving nodes without neighbours where they are
+fn reorder(layer: &mut [usize], neighbors: &[Vec<usize>], order: &mut [f64]) {
+    let barycenter = |u: usize| {
+        let adjacent = &neighbors[u];
+        if adjacent.is_empty() {
+            order[u]
+        } else {
+            adjacent.iter().map(|&v| order[v]).sum::<f64>() / adjacent.len() as f64
+        }
+    };
+
+    let mut keyed: Vec<(f64, usize)> = layer.iter().map(|&u| (barycenter(u), u)).collect();
+    keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    for (i, (slot, (_, u))) in layer.iter_mut().zip(keyed).enumerate() {
+        *slot = u;
+        order[u] = i as f64;
+    }
+}
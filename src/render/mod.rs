@@ -0,0 +1,199 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Laying graphs out in the plane and drawing them as SVG, without any external tools.
+//!
+//! A layout engine assigns every node a position, which [`write_svg`] then uses to draw
+//! the nodes, the directed edges between them and their weights.
+//! [`ForceDirected`] suits general graphs, while [`Layered`] draws mostly acyclic graphs
+//! as a hierarchy with the edges pointing downwards.
+//!
+//! ```
+//! use graph_algos::{graph, Graph};
+//! use graph_algos::render::{self, Layered};
+//!
+//! let graph: Graph<&str> = graph! {
+//!     "a" => ["b" => 3, "c" => 1],
+//!     "b" => ["d" => 2],
+//!     "c" => ["d" => 5],
+//! };
+//!
+//! let layout = Layered::new().layout(&graph);
+//! assert!(layout.position(&"a").unwrap().y < layout.position(&"d").unwrap().y);
+//!
+//! let svg = render::to_svg(&graph, &layout);
+//! assert!(svg.starts_with("<svg"));
+//! ```
+
+mod force;
+pub use force::ForceDirected;
+
+mod layered;
+pub use layered::Layered;
+
+mod svg;
+pub use svg::{to_svg, write_svg};
+
+use crate::{visit::IntoNodes, NodeBounds};
+use std::{collections::HashMap, hash::BuildHasher};
+
+/// A position in the plane, with `y` increasing downwards as in SVG
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Point {
+    /// The horizontal coordinate
+    pub x: f64,
+    /// The vertical coordinate
+    pub y: f64,
+}
+
+impl Point {
+    /// Creates a point from its coordinates
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    /// Returns the distance to another point
+    /// ```
+    /// use graph_algos::render::Point;
+    ///
+    /// assert_eq!(Point::new(0.0, 0.0).distance(Point::new(3.0, 4.0)), 5.0);
+    /// ```
+    pub fn distance(self, other: Point) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+}
+
+/// The positions of the nodes of a graph, produced by one of the layout engines
+#[derive(Debug, Clone)]
+pub struct Layout<'a, N: NodeBounds> {
+    positions: HashMap<&'a N, Point>,
+}
+
+impl<'a, N: NodeBounds> Layout<'a, N> {
+    /// Creates a layout from the first two coordinates of an embedding,
+    /// such as the one from [`spectral_embedding`](crate::analysis::spectral_embedding),
+    /// scaled to fill a `width` by `height` frame
+    ///
+    /// Missing coordinates count as 0, and a coordinate which is the same for every node
+    /// puts the nodes in the middle of the frame.
+    /// The layout can be used as it is, or as the starting point for [`ForceDirected::layout_from`].
+    /// ```
+    /// use graph_algos::{ungraph, Graph};
+    /// use graph_algos::analysis::spectral_embedding;
+    /// use graph_algos::render::Layout;
+    ///
+    /// let graph: Graph<u32> = ungraph! {
+    ///     1 => [2],
+    ///     2 => [3],
+    ///     3 => [4],
+    /// };
+    ///
+    /// let embedding = spectral_embedding(&graph, 2);
+    /// let layout = Layout::from_embedding(&embedding, 400.0, 300.0);
+    ///
+    /// let (min, max) = layout.bounds().unwrap();
+    /// assert_eq!((min.x, max.x), (0.0, 400.0));
+    /// // the ends of the path are on opposite sides
+    /// assert_eq!(layout.position(&1).unwrap().x.max(layout.position(&4).unwrap().x), 400.0);
+    /// ```
+    pub fn from_embedding<S: BuildHasher>(
+        embedding: &HashMap<&'a N, Vec<f64>, S>,
+        width: f64,
+        height: f64,
+    ) -> Self {
+        let coordinate = |coords: &[f64], i: usize| coords.get(i).copied().unwrap_or(0.0);
+        let range = |i: usize| {
+            embedding
+                .values()
+                .map(|coords| coordinate(coords, i))
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), a| {
+                    (min.min(a), max.max(a))
+                })
+        };
+        let scale = |a: f64, (min, max): (f64, f64), size: f64| {
+            if max - min > 1e-12 {
+                (a - min) / (max - min) * size
+            } else {
+                size / 2.0
+            }
+        };
+
+        let (xs, ys) = (range(0), range(1));
+        Layout {
+            positions: embedding
+                .iter()
+                .map(|(&u, coords)| {
+                    let x = scale(coordinate(coords, 0), xs, width);
+                    let y = scale(coordinate(coords, 1), ys, height);
+                    (u, Point::new(x, y))
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the position of a node, or `None` if it isn't part of the layout
+    pub fn position(&self, u: &N) -> Option<Point> {
+        self.positions.get(u).copied()
+    }
+
+    /// Returns an iterator over the nodes and their positions, in an arbitrary order
+    pub fn positions(&self) -> impl Iterator<Item = (&'a N, Point)> + '_ {
+        self.positions.iter().map(|(&u, &p)| (u, p))
+    }
+
+    /// Returns the number of nodes in the layout
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns whether the layout has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Returns the top left and bottom right corners of the smallest box holding every node,
+    /// or `None` if the layout is empty
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    /// use graph_algos::render::{Layered, Point};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2, 3],
+    /// };
+    ///
+    /// let layout = Layered::new().layer_spacing(100.0).node_spacing(50.0).layout(&graph);
+    /// assert_eq!(layout.bounds(), Some((Point::new(0.0, 0.0), Point::new(50.0, 100.0))));
+    /// ```
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        let mut points = self.positions.values();
+        let first = *points.next()?;
+
+        Some(points.fold((first, first), |(min, max), p| {
+            (
+                Point::new(min.x.min(p.x), min.y.min(p.y)),
+                Point::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        }))
+    }
+}
+
+/// Returns the nodes of a graph ordered by their `Debug` output,
+/// so that the layouts are the same every time the program runs
+fn ordered_nodes<'a, G: IntoNodes<'a>>(graph: G) -> Vec<&'a G::Node> {
+    let mut nodes: Vec<&G::Node> = graph.nodes().collect();
+    nodes.sort_by_cached_key(|u| format!("{:?}", u));
+    nodes
+}
@@ -0,0 +1,299 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use super::{ordered_nodes, Layout, Point};
+use crate::visit::{EdgeWeighted, IntoNodes};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    io::{self, Write},
+};
+
+/// The radius of the circle drawn for each node
+const NODE_RADIUS: f64 = 18.0;
+
+/// The space left around the outermost nodes
+const PADDING: f64 = 40.0;
+
+/// How far apart the middles of the curves between the same pair of nodes are drawn
+const EDGE_SPACING: f64 = 14.0;
+
+/// Draws a laid out graph as an SVG image
+///
+/// See [`write_svg`] for how the graph is drawn.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::render::{self, Layered};
+///
+/// let graph: Graph<&str> = graph! {
+///     "a" => ["b" => 7],
+/// };
+///
+/// let svg = render::to_svg(&graph, &Layered::new().layout(&graph));
+/// assert!(svg.contains(">a</text>"));
+/// assert!(svg.contains(">7</text>"));
+/// ```
+pub fn to_svg<'a, G>(graph: G, layout: &Layout<'a, G::Node>) -> String
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a>,
+    G::Node: Display,
+{
+    let mut svg = Vec::new();
+    write_svg(graph, layout, &mut svg).expect("writing to a Vec can't fail");
+    String::from_utf8(svg).expect("the SVG is built from valid UTF-8")
+}
+
+/// Writes a laid out graph as an SVG image to `writer`
+///
+/// Each node is drawn as a circle labelled with its `Display` output,
+/// and each edge as an arrow labelled with its weight.
+/// Edges are routed so that they don't hide each other on dense graphs:
+/// a single edge between a pair of nodes is a straight line, while parallel edges
+/// and edges in both directions between the same pair of nodes fan out into curves,
+/// and self loops are drawn on the side of their node facing away from its neighbours,
+/// each loop on a node larger than the last.
+/// The image is translated so the nodes sit just inside its edges,
+/// and nodes or edges missing from the layout are left out.
+/// ```
+/// use graph_algos::{graph, Edge, Graph};
+/// use graph_algos::render::{self, Layered};
+///
+/// let mut graph: Graph<&str> = graph! {
+///     "a" => ["b" => 1, "b" => 2, "c"],
+///     "b" => ["a" => 3, "a" => 4, "b"],
+/// };
+/// graph.add_edge("b", Edge::new("b"));
+///
+/// let svg = render::to_svg(&graph, &Layered::new().layout(&graph));
+///
+/// // the lone edge is straight, the four between "a" and "b" are curved
+/// assert_eq!(svg.matches("<line").count(), 1);
+/// assert_eq!(svg.matches(" Q ").count(), 4);
+/// // and the two self loops have different sizes
+/// assert_eq!(svg.matches(" C ").count(), 2);
+/// ```
+pub fn write_svg<'a, G, W>(graph: G, layout: &Layout<'a, G::Node>, mut writer: W) -> io::Result<()>
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a>,
+    G::Node: Display,
+    W: Write,
+{
+    let (min, max) = layout
+        .bounds()
+        .unwrap_or((Point::default(), Point::default()));
+    let (width, height) = (max.x - min.x + 2.0 * PADDING, max.y - min.y + 2.0 * PADDING);
+    let position = |u: &G::Node| {
+        layout
+            .position(u)
+            .map(|p| Point::new(p.x - min.x + PADDING, p.y - min.y + PADDING))
+    };
+
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="12">"#,
+        w = width,
+        h = height,
+    )?;
+    writeln!(
+        writer,
+        r#"  <defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="8" markerHeight="8" orient="auto-start-reverse"><path d="M 0 0 L 10 5 L 0 10 z"/></marker></defs>"#
+    )?;
+
+    let nodes = ordered_nodes(graph);
+    let drawn: Vec<(usize, Point)> = nodes
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &u)| Some((i, position(u)?)))
+        .collect();
+    let indices: HashMap<&G::Node, (usize, Point)> =
+        drawn.iter().map(|&(i, p)| (nodes[i], (i, p))).collect();
+
+    // count the edges between each pair of nodes, in either direction,
+    // and find the direction pointing away from the neighbours of each node
+    let mut pair_edges: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut pull = vec![Point::default(); nodes.len()];
+    for &(i, p) in &drawn {
+        for v in graph.neighbors(nodes[i]) {
+            let (j, q) = match indices.get(v) {
+                Some(&found) => found,
+                None => continue,
+            };
+
+            *pair_edges.entry((i.min(j), i.max(j))).or_default() += 1;
+            if i != j {
+                let length = p.distance(q).max(1e-9);
+                let (dx, dy) = ((q.x - p.x) / length, (q.y - p.y) / length);
+                pull[i] = Point::new(pull[i].x + dx, pull[i].y + dy);
+                pull[j] = Point::new(pull[j].x - dx, pull[j].y - dy);
+            }
+        }
+    }
+
+    let mut lanes: HashMap<(usize, usize), usize> = HashMap::new();
+    for &(i, p) in &drawn {
+        for (v, weight) in graph.neighbors_weighted(nodes[i]) {
+            let (j, q) = match indices.get(v) {
+                Some(&found) => found,
+                None => continue,
+            };
+
+            let pair = (i.min(j), i.max(j));
+            let lane = lanes.entry(pair).or_default();
+            let label = if i == j {
+                let away = Point::new(-pull[i].x, -pull[i].y);
+                let label = write_self_loop(&mut writer, p, away, *lane)?;
+                *lane += 1;
+                label
+            } else {
+                // spread the edges of the pair evenly either side of the straight line between them,
+                // measuring the bend from the node which comes first so opposite edges bend apart
+                let centre = (pair_edges[&pair] - 1) as f64 / 2.0;
+                let bend = (*lane as f64 - centre) * EDGE_SPACING;
+                *lane += 1;
+                write_edge(&mut writer, p, q, if i < j { bend } else { -bend })?
+            };
+
+            if let Some(w) = weight {
+                writeln!(
+                    writer,
+                    r#"  <text x="{:.1}" y="{:.1}" text-anchor="middle" fill="dimgray">{}</text>"#,
+                    label.x, label.y, w
+                )?;
+            }
+        }
+    }
+
+    for &u in &nodes {
+        if let Some(p) = position(u) {
+            writeln!(
+                writer,
+                r#"  <circle cx="{:.1}" cy="{:.1}" r="{}" fill="white" stroke="black"/>"#,
+                p.x, p.y, NODE_RADIUS
+            )?;
+            writeln!(
+                writer,
+                r#"  <text x="{:.1}" y="{:.1}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                p.x,
+                p.y,
+                escape(&u.to_string())
+            )?;
+        }
+    }
+
+    writeln!(writer, "</svg>")
+}
+
+/// Draws an arrow from the edge of one node's circle to the next, returning where its label should go
+///
+/// The arrow is a straight line if `bend` is 0, and otherwise a curve whose middle is
+/// `bend` to the left of the straight line.
+fn write_edge<W: Write>(writer: &mut W, p: Point, q: Point, bend: f64) -> io::Result<Point> {
+    let length = p.distance(q).max(1e-9);
+    let (dx, dy) = ((q.x - p.x) / length, (q.y - p.y) / length);
+    // the normal to the left of the direction of travel
+    let (nx, ny) = (dy, -dx);
+
+    if bend == 0.0 {
+        let start = Point::new(p.x + dx * NODE_RADIUS, p.y + dy * NODE_RADIUS);
+        let end = Point::new(q.x - dx * NODE_RADIUS, q.y - dy * NODE_RADIUS);
+
+        writeln!(
+            writer,
+            r#"  <line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="black" marker-end="url(#arrow)"/>"#,
+            start.x, start.y, end.x, end.y
+        )?;
+
+        return Ok(Point::new(
+            (start.x + end.x) / 2.0 + nx * 10.0,
+            (start.y + end.y) / 2.0 + ny * 10.0,
+        ));
+    }
+
+    // the middle of a quadratic curve is half way from the chord to its control point
+    let control = Point::new(
+        (p.x + q.x) / 2.0 + nx * 2.0 * bend,
+        (p.y + q.y) / 2.0 + ny * 2.0 * bend,
+    );
+    let start = towards(p, control, NODE_RADIUS);
+    let end = towards(q, control, NODE_RADIUS);
+
+    writeln!(
+        writer,
+        r#"  <path d="M {:.1} {:.1} Q {:.1} {:.1} {:.1} {:.1}" fill="none" stroke="black" marker-end="url(#arrow)"/>"#,
+        start.x, start.y, control.x, control.y, end.x, end.y
+    )?;
+
+    let side = 10.0 * bend.signum();
+    Ok(Point::new(
+        0.25 * start.x + 0.5 * control.x + 0.25 * end.x + nx * side,
+        0.25 * start.y + 0.5 * control.y + 0.25 * end.y + ny * side,
+    ))
+}
+
+/// Returns the point `distance` from `p` in the direction of `q`
+fn towards(p: Point, q: Point, distance: f64) -> Point {
+    let length = p.distance(q).max(1e-9);
+    Point::new(
+        p.x + (q.x - p.x) / length * distance,
+        p.y + (q.y - p.y) / length * distance,
+    )
+}
+
+/// Draws a loop on a node for an edge from the node to itself, returning where its label should go
+///
+/// The loop points in the direction of `away`, or upwards if `away` is too short to have a direction,
+/// and is larger for each earlier loop on the same node, given by `lane`.
+fn write_self_loop<W: Write>(
+    writer: &mut W,
+    p: Point,
+    away: Point,
+    lane: usize,
+) -> io::Result<Point> {
+    let length = away.x.hypot(away.y);
+    let (ax, ay) = if length > 1e-6 {
+        (away.x / length, away.y / length)
+    } else {
+        (0.0, -1.0)
+    };
+    // the direction to the right when looking away from the node
+    let (sx, sy) = (-ay, ax);
+    let at = |side: f64, out: f64| {
+        Point::new(
+            p.x + (sx * side + ax * out) * NODE_RADIUS,
+            p.y + (sy * side + ay * out) * NODE_RADIUS,
+        )
+    };
+
+    let scale = 1.0 + 0.6 * lane as f64;
+    let (start, end) = (at(-0.6, 0.8), at(0.6, 0.8));
+    let (first, second) = (at(-1.5 * scale, 3.2 * scale), at(1.5 * scale, 3.2 * scale));
+
+    writeln!(
+        writer,
+        r#"  <path d="M {:.1} {:.1} C {:.1} {:.1} {:.1} {:.1} {:.1} {:.1}" fill="none" stroke="black" marker-end="url(#arrow)"/>"#,
+        start.x, start.y, first.x, first.y, second.x, second.y, end.x, end.y,
+    )?;
+
+    // the furthest point of the loop from the node
+    Ok(at(0.0, 0.2 + 2.4 * scale))
+}
+
+/// Escapes text for use in an SVG document
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,560 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Sampling manageable subgraphs out of large graphs.
+//!
+//! Every sampler picks a set of nodes or edges at random and returns the subgraph they make up,
+//! with the same hasher as the original graph.
+//! [`node2vec_walks`] instead returns random walks over the graph, as training data for node embeddings.
+//! The samplers take the random number generator to use, so seeding it makes the samples repeatable
+//! for a graph built in the same way.
+//!
+//! ```
+//! use graph_algos::{generators, sampling};
+//! use rand::{rngs::StdRng, SeedableRng};
+//!
+//! let mut rng = StdRng::seed_from_u64(42);
+//! let graph = generators::gnm_random(10_000, 50_000, 10, &mut rng);
+//!
+//! let sample = sampling::uniform_nodes(&graph, 100, &mut rng);
+//! assert_eq!(sample.len(), 100);
+//! assert!(sample.edges().all(|(u, edge)| graph.is_edge(u, edge.destination())));
+//! ```
+
+use crate::{walk::step_weight, Edge, Graph, NodeBounds};
+use rand::{seq::SliceRandom, Rng};
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashSet, VecDeque},
+    fmt,
+    hash::BuildHasher,
+};
+
+/// Samples `size` nodes uniformly at random and returns the subgraph they induce
+///
+/// The sample has every edge of the graph between two sampled nodes,
+/// and is the whole graph if it has no more than `size` nodes.
+pub fn uniform_nodes<N, S, R>(graph: &Graph<N, S>, size: usize, rng: &mut R) -> Graph<N, S>
+where
+    N: NodeBounds,
+    S: BuildHasher + Clone,
+    R: Rng + ?Sized,
+{
+    let nodes: Vec<&N> = graph.nodes().collect();
+    let sampled = nodes.choose_multiple(rng, size).copied().collect();
+
+    induced(graph, &sampled)
+}
+
+/// Samples `size` edges uniformly at random and returns the subgraph made up of them and their endpoints
+///
+/// The sample is every edge of the graph with its endpoints if it has no more than `size` edges,
+/// so nodes without any edges never appear in it.
+/// ```
+/// use graph_algos::{graph, Graph, sampling};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2 => 1, 3 => 2],
+///     2 => [3 => 4],
+///     3 => [4 => 8],
+/// };
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// let sample = sampling::uniform_edges(&graph, 2, &mut rng);
+///
+/// assert_eq!(sample.edges().count(), 2);
+/// assert!(sample.edges().all(|(u, edge)| graph.succs(u).unwrap().contains(edge)));
+/// ```
+pub fn uniform_edges<N, S, R>(graph: &Graph<N, S>, size: usize, rng: &mut R) -> Graph<N, S>
+where
+    N: NodeBounds,
+    S: BuildHasher + Clone,
+    R: Rng + ?Sized,
+{
+    let edges: Vec<_> = graph.edges().collect();
+
+    let mut sample = Graph::with_capacity_and_hasher(size.min(edges.len()), graph.hasher().clone());
+    for &(u, edge) in edges.choose_multiple(rng, size) {
+        sample.add_edge(u.clone(), edge.clone());
+    }
+
+    sample
+}
+
+/// Samples `size` nodes by a breadth first search from `start` and returns the subgraph they induce
+///
+/// The successors of each node are visited in a random order,
+/// so when the search reaches more nodes than it needs the ones it keeps are chosen at random.
+/// If everything reachable from `start` has been sampled before there are `size` nodes,
+/// the search carries on from a node picked at random from the rest of the graph.
+/// Returns `None` if `start` isn't in the graph.
+/// ```
+/// use graph_algos::{generators, sampling};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// let graph = generators::gnm_random(1000, 5000, 10, &mut rng);
+///
+/// let sample = sampling::snowball(&graph, &0, 50, &mut rng).unwrap();
+///
+/// assert_eq!(sample.len(), 50);
+/// assert!(sample.nodes().any(|u| *u == 0));
+/// ```
+pub fn snowball<N, S, R>(
+    graph: &Graph<N, S>,
+    start: &N,
+    size: usize,
+    rng: &mut R,
+) -> Option<Graph<N, S>>
+where
+    N: NodeBounds,
+    S: BuildHasher + Clone,
+    R: Rng + ?Sized,
+{
+    let start = graph.node_ref(start)?;
+    let size = size.min(graph.len());
+    let mut restarts = Restarts::new(graph);
+
+    let mut sampled = HashSet::with_capacity(size);
+    let mut queue = VecDeque::new();
+    if size > 0 {
+        sampled.insert(start);
+        queue.push_back(start);
+    }
+
+    while sampled.len() < size {
+        let u = match queue.pop_front() {
+            Some(u) => u,
+            None => {
+                let u = restarts.next(&sampled, rng);
+                sampled.insert(u);
+                u
+            }
+        };
+
+        let mut succs: Vec<&N> = graph.neighbors(u).collect();
+        succs.shuffle(rng);
+        for v in succs {
+            if sampled.len() == size {
+                break;
+            }
+            if sampled.insert(v) {
+                queue.push_back(v);
+            }
+        }
+    }
+
+    Some(induced(graph, &sampled))
+}
+
+/// Samples `size` nodes by a random walk from `start` and returns the subgraph they induce
+///
+/// At each step the walk jumps back to `start` with probability `restart`,
+/// and otherwise follows one of the outbound edges of the current node, picked uniformly at random.
+/// It also jumps back from nodes with no outbound edges.
+/// If the walk goes `100 * size` steps without finding a new node,
+/// it carries on from a node picked at random from the rest of the graph,
+/// so a sample is always found even if less than `size` nodes are reachable from `start`.
+/// Returns `None` if `start` isn't in the graph.
+/// ```
+/// use graph_algos::{graph, Graph, sampling};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [3],
+///     3 => [1],
+///     4 => [5],
+/// };
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// let sample = sampling::random_walk_with_restart(&graph, &1, 3, 0.15, &mut rng).unwrap();
+///
+/// // only the cycle is reachable from 1, and the walk has time to find all of it
+/// let cycle: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [3],
+///     3 => [1],
+/// };
+/// assert_eq!(sample, cycle);
+/// ```
+pub fn random_walk_with_restart<N, S, R>(
+    graph: &Graph<N, S>,
+    start: &N,
+    size: usize,
+    restart: f64,
+    rng: &mut R,
+) -> Option<Graph<N, S>>
+where
+    N: NodeBounds,
+    S: BuildHasher + Clone,
+    R: Rng + ?Sized,
+{
+    let mut start = graph.node_ref(start)?;
+    let size = size.min(graph.len());
+    let restart = restart.clamp(0.0, 1.0);
+    let patience = size.saturating_mul(100);
+    let mut restarts = Restarts::new(graph);
+
+    let mut sampled = HashSet::with_capacity(size);
+    if size > 0 {
+        sampled.insert(start);
+    }
+
+    let mut current = start;
+    let mut stale = 0;
+    while sampled.len() < size {
+        if stale == patience {
+            start = restarts.next(&sampled, rng);
+            sampled.insert(start);
+            current = start;
+            stale = 0;
+            continue;
+        }
+
+        let succs = graph.succs_or_empty(current);
+        current = if succs.is_empty() || rng.gen_bool(restart) {
+            start
+        } else {
+            succs[rng.gen_range(0..succs.len())].destination()
+        };
+
+        if sampled.insert(current) {
+            stale = 0;
+        } else {
+            stale += 1;
+        }
+    }
+
+    Some(induced(graph, &sampled))
+}
+
+/// Generates `walks_per_node` biased random walks from every node of the graph,
+/// the training corpus for node2vec style node embeddings
+///
+/// Each walk is a list of up to `walk_length` nodes, starting with the node it was generated from,
+/// and ends early if it reaches a node with no outbound edges it can take.
+/// The walk picks each edge with probability proportional to its weight times a bias
+/// which depends on the node `t` it came from:
+/// `1 / p` for the edge back to `t`, `1` for an edge to a node which `t` has an edge to,
+/// and `1 / q` for any other edge.
+/// So a low `p` keeps the walk close to where it started, while a low `q` sends it further out,
+/// and `p = q = 1` is an ordinary weighted random walk.
+/// Unweighted edges count as having a weight of 1,
+/// and edges with a non-positive or infinite weight are never taken.
+///
+/// The walks are grouped into `walks_per_node` rounds,
+/// each with one walk from every node in a random order.
+/// Panics if `p` or `q` isn't positive and finite.
+/// ```
+/// use graph_algos::{ungraph, Graph, sampling};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let graph: Graph<u32> = ungraph! {
+///     1 => [2, 3],
+///     2 => [3],
+///     3 => [4],
+/// };
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// let walks = sampling::node2vec_walks(&graph, 10, 5, 1.0, 0.5, &mut rng);
+///
+/// assert_eq!(walks.len(), 40);
+/// assert!(walks.iter().all(|walk| walk.len() == 5));
+/// assert!(walks
+///     .iter()
+///     .all(|walk| walk.windows(2).all(|step| graph.is_edge(step[0], step[1]))));
+///
+/// // a very low return parameter makes the walks bounce back and forth
+/// let walks = sampling::node2vec_walks(&graph, 1, 5, 1e-9, 1.0, &mut rng);
+/// assert!(walks
+///     .iter()
+///     .all(|walk| walk[0] == walk[2] && walk[1] == walk[3] && walk[2] == walk[4]));
+/// ```
+pub fn node2vec_walks<'a, N, S, R>(
+    graph: &'a Graph<N, S>,
+    walks_per_node: usize,
+    walk_length: usize,
+    p: f64,
+    q: f64,
+    rng: &mut R,
+) -> Vec<Vec<&'a N>>
+where
+    N: NodeBounds,
+    S: BuildHasher,
+    R: Rng + ?Sized,
+{
+    assert!(
+        p > 0.0 && p.is_finite() && q > 0.0 && q.is_finite(),
+        "node2vec_walks needs positive finite p and q, found p = {} and q = {}",
+        p,
+        q
+    );
+
+    if walk_length == 0 {
+        return Vec::new();
+    }
+
+    let mut nodes: Vec<&N> = graph.nodes().collect();
+
+    let mut walks = Vec::with_capacity(nodes.len().saturating_mul(walks_per_node));
+    let mut biases = Vec::new();
+    for _ in 0..walks_per_node {
+        nodes.shuffle(rng);
+
+        for &start in &nodes {
+            let mut walk = Vec::with_capacity(walk_length);
+            walk.push(start);
+
+            while walk.len() < walk_length {
+                let current = walk[walk.len() - 1];
+                let previous = walk.len().checked_sub(2).map(|i| walk[i]);
+                let succs = graph.succs_or_empty(current);
+
+                biases.clear();
+                biases.extend(succs.iter().map(|edge| {
+                    let weight = step_weight(edge.weight()) as f64;
+                    let next = edge.destination();
+                    match previous {
+                        None => weight,
+                        Some(t) if t == next => weight / p,
+                        Some(t) if graph.is_edge(t, next) => weight,
+                        Some(_) => weight / q,
+                    }
+                }));
+
+                let total: f64 = biases.iter().sum();
+                if total <= 0.0 {
+                    break;
+                }
+
+                let mut target = rng.gen_range(0.0..total);
+                let chosen = biases
+                    .iter()
+                    .position(|&bias| {
+                        if target < bias {
+                            true
+                        } else {
+                            target -= bias;
+                            false
+                        }
+                    })
+                    // rounding can leave the target just past the last edge
+                    .or_else(|| biases.iter().rposition(|&bias| bias > 0.0))
+                    .expect("the total bias is positive");
+                walk.push(succs[chosen].destination());
+            }
+
+            walks.push(walk);
+        }
+    }
+
+    walks
+}
+
+impl<N: NodeBounds, S: BuildHasher> Graph<N, S> {
+    /// Samples `k` distinct edges, picking each with probability proportional to its weight
+    ///
+    /// Unweighted edges count as having a weight of 1,
+    /// edges with a non-positive or infinite weight are never picked.
+    /// Every edge which can be picked is returned if there are no more than `k` of them.
+    /// This makes a single pass over the edges with a [`WeightedReservoir`].
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2 => 1_000_000, 3 => 1, 4 => 0],
+    ///     2 => [3 => 1_000_000],
+    /// };
+    ///
+    /// let mut rng = StdRng::seed_from_u64(42);
+    /// let mut sample = graph.sample_edges_weighted(2, &mut rng);
+    /// sample.sort();
+    ///
+    /// // the heavy edges are far more likely to be picked
+    /// let heavy: Vec<(u32, u32)> = sample.iter().map(|(u, edge)| (**u, *edge.destination())).collect();
+    /// assert_eq!(heavy, vec![(1, 2), (2, 3)]);
+    /// ```
+    pub fn sample_edges_weighted<R: Rng + ?Sized>(
+        &self,
+        k: usize,
+        rng: &mut R,
+    ) -> Vec<(&N, &Edge<N>)> {
+        let mut reservoir = WeightedReservoir::new(k);
+        for (u, edge) in self.edges() {
+            reservoir.push((u, edge), step_weight(edge.weight()) as f64, rng);
+        }
+
+        reservoir.into_vec()
+    }
+}
+
+/// A weighted random sample of up to `k` items from a stream,
+/// found in a single pass without knowing the length of the stream
+///
+/// Each item is kept with probability proportional to its weight,
+/// using the A-Res algorithm of Efraimidis and Spirakis,
+/// so only the `k` items in the sample are held in memory.
+/// Items with a non-positive, infinite or NaN weight are never kept.
+/// ```
+/// use graph_algos::sampling::WeightedReservoir;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// let mut reservoir = WeightedReservoir::new(3);
+///
+/// for i in 0..1000 {
+///     let weight = if i % 100 == 0 { 1e9 } else { 1.0 };
+///     reservoir.push(i, weight, &mut rng);
+/// }
+///
+/// assert_eq!(reservoir.len(), 3);
+/// assert!(reservoir.into_vec().iter().all(|i| i % 100 == 0));
+/// ```
+pub struct WeightedReservoir<T> {
+    k: usize,
+    heap: BinaryHeap<Keyed<T>>,
+}
+
+impl<T> WeightedReservoir<T> {
+    /// Creates an empty reservoir which keeps a sample of up to `k` items
+    pub fn new(k: usize) -> Self {
+        Self {
+            k,
+            heap: BinaryHeap::with_capacity(k),
+        }
+    }
+
+    /// Offers an item to the sample with the given weight
+    pub fn push<R: Rng + ?Sized>(&mut self, item: T, weight: f64, rng: &mut R) {
+        if self.k == 0 || !(weight > 0.0 && weight.is_finite()) {
+            return;
+        }
+
+        // the k items with the largest u^(1/w) form the sample, comparing logs avoids underflow
+        let key = (1.0 - rng.gen::<f64>()).ln() / weight;
+        if self.heap.len() < self.k {
+            self.heap.push(Keyed { key, item });
+        } else if matches!(self.heap.peek(), Some(min) if key > min.key) {
+            self.heap.pop();
+            self.heap.push(Keyed { key, item });
+        }
+    }
+
+    /// Returns the number of items in the sample
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns whether the sample is empty
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Returns an iterator over the items in the sample, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.heap.iter().map(|keyed| &keyed.item)
+    }
+
+    /// Returns the items in the sample, in no particular order
+    pub fn into_vec(self) -> Vec<T> {
+        self.heap.into_iter().map(|keyed| keyed.item).collect()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for WeightedReservoir<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeightedReservoir")
+            .field("k", &self.k)
+            .field("sample", &self.iter().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// An item in a [`WeightedReservoir`] with its random key,
+/// ordered so the smallest key is at the top of the heap
+struct Keyed<T> {
+    key: f64,
+    item: T,
+}
+
+impl<T> PartialEq for Keyed<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Keyed<T> {}
+
+impl<T> PartialOrd for Keyed<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Keyed<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.key.total_cmp(&self.key)
+    }
+}
+
+/// Picks the nodes a sampler carries on from once it has run out of new nodes
+struct Restarts<'a, N> {
+    nodes: Vec<&'a N>,
+}
+
+impl<'a, N: NodeBounds> Restarts<'a, N> {
+    fn new<S: BuildHasher>(graph: &'a Graph<N, S>) -> Self {
+        Self {
+            nodes: graph.nodes().collect(),
+        }
+    }
+
+    /// Returns a random node which hasn't been sampled,
+    /// there must be at least one left
+    fn next<R: Rng + ?Sized>(&mut self, sampled: &HashSet<&'a N>, rng: &mut R) -> &'a N {
+        // nodes are removed as they're found to be sampled, so this is amortised linear overall
+        loop {
+            let i = rng.gen_range(0..self.nodes.len());
+            let u = self.nodes.swap_remove(i);
+            if !sampled.contains(u) {
+                return u;
+            }
+        }
+    }
+}
+
+/// Returns the subgraph induced by `nodes`, with every edge of the graph between them
+fn induced<N, S>(graph: &Graph<N, S>, nodes: &HashSet<&N>) -> Graph<N, S>
+where
+    N: NodeBounds,
+    S: BuildHasher + Clone,
+{
+    let mut subgraph = Graph::with_capacity_and_hasher(nodes.len(), graph.hasher().clone());
+
+    for &u in nodes {
+        subgraph.add_node(u.clone());
+        for edge in graph.succs_or_empty(u) {
+            if nodes.contains(edge.destination()) {
+                subgraph.add_edge(u.clone(), edge.clone());
+            }
+        }
+    }
+
+    subgraph
+}
@@ -0,0 +1,230 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Scheduling the tasks of a project laid out as a DAG, with the critical path method.
+//!
+//! Every node is a task with a duration, and an edge `u -> v` means `v` can't start until `u` has finished,
+//! optionally with a lag given by the weight of the edge.
+//! [`schedule`] works out when every task can start and finish,
+//! how far each one can slip without delaying the project, and which tasks can't slip at all.
+//!
+//! ```
+//! use graph_algos::{graph, Graph};
+//! use graph_algos::schedule::schedule;
+//! use std::collections::HashMap;
+//!
+//! let project: Graph<&str> = graph! {
+//!     "design" => ["build", "docs"],
+//!     "build" => ["test"],
+//!     "docs" => ["release"],
+//!     "test" => ["release"],
+//! };
+//! let durations: HashMap<&str, i64> =
+//!     [("design", 3), ("build", 5), ("docs", 2), ("test", 2), ("release", 1)].into();
+//!
+//! let plan = schedule(&project, |task| durations[task]).unwrap();
+//!
+//! assert_eq!(plan.makespan, 11);
+//! assert_eq!(plan.critical_path, vec![&"design", &"build", &"test", &"release"]);
+//! assert_eq!(plan.tasks[&"docs"].earliest_start, 3);
+//! assert_eq!(plan.tasks[&"docs"].slack, 5);
+//! ```
+
+use crate::{
+    algos::topo_iter,
+    visit::{EdgeWeighted, IntoNodes},
+    EdgeWeight, NodeBounds,
+};
+use std::collections::HashMap;
+
+/// The timings of a single task in a [`Schedule`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TaskTimes {
+    /// How long the task takes
+    pub duration: i64,
+    /// The earliest time the task can start
+    pub earliest_start: i64,
+    /// The earliest time the task can finish
+    pub earliest_finish: i64,
+    /// The latest time the task can start without delaying the project
+    pub latest_start: i64,
+    /// The latest time the task can finish without delaying the project
+    pub latest_finish: i64,
+    /// How long the task can be delayed by without delaying the project,
+    /// the difference between its latest and earliest start
+    pub slack: i64,
+}
+
+impl TaskTimes {
+    /// Returns whether the task is critical, so delaying it would delay the whole project
+    pub fn is_critical(&self) -> bool {
+        self.slack == 0
+    }
+}
+
+/// The start and finish times of every task in a project, created by [`schedule`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule<'a, N: NodeBounds> {
+    /// The time the whole project takes, when every task starts as early as it can
+    pub makespan: i64,
+    /// The timings of each task
+    pub tasks: HashMap<&'a N, TaskTimes>,
+    /// A chain of critical tasks running from the start of the project to its end,
+    /// each of which starts as soon as the one before it finishes
+    pub critical_path: Vec<&'a N>,
+    /// The tasks in an order in which they can be carried out one after another
+    pub order: Vec<&'a N>,
+}
+
+impl<'a, N: NodeBounds> Schedule<'a, N> {
+    /// Returns an iterator over every critical task, in the order of [`Schedule::order`]
+    ///
+    /// There can be critical tasks which aren't on the [`Schedule::critical_path`]
+    /// when there is more than one critical path.
+    pub fn critical_tasks(&self) -> impl Iterator<Item = &'a N> + '_ {
+        self.order
+            .iter()
+            .copied()
+            .filter(move |u| self.tasks[u].is_critical())
+    }
+}
+
+/// Schedules the tasks of a project with the critical path method
+///
+/// `duration` gives the time each task takes.
+/// An edge `u -> v` means `v` can start once `u` has finished,
+/// and its weight is an extra lag between the end of `u` and the start of `v`,
+/// with unweighted edges having no lag.
+/// The project starts at time 0, and tasks with nothing before them can start straight away.
+/// Returns `None` if the graph has a cycle,
+/// and panics if any duration or lag is negative or infinite.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::schedule::schedule;
+///
+/// // the concrete has to cure for 2 days after it's poured before the walls go up
+/// let project: Graph<&str> = graph! {
+///     "pour" => ["walls" => 2, "plumbing"],
+///     "plumbing" => ["walls"],
+/// };
+///
+/// let plan = schedule(&project, |task| match *task {
+///     "pour" => 1,
+///     "plumbing" => 1,
+///     _ => 3,
+/// })
+/// .unwrap();
+///
+/// assert_eq!(plan.tasks[&"walls"].earliest_start, 3);
+/// assert_eq!(plan.tasks[&"plumbing"].slack, 1);
+/// assert_eq!(plan.makespan, 6);
+/// assert_eq!(plan.critical_tasks().collect::<Vec<_>>(), vec![&"pour", &"walls"]);
+///
+/// let cyclic: Graph<&str> = graph! {
+///     "a" => ["b"],
+///     "b" => ["a"],
+/// };
+/// assert!(schedule(&cyclic, |_| 1).is_none());
+/// ```
+pub fn schedule<'a, G, F>(graph: G, mut duration: F) -> Option<Schedule<'a, G::Node>>
+where
+    G: IntoNodes<'a> + EdgeWeighted<'a>,
+    F: FnMut(&G::Node) -> i64,
+{
+    let order: Vec<&G::Node> = topo_iter(graph).collect();
+    if order.len() < graph.nodes().count() {
+        return None;
+    }
+
+    let durations: HashMap<&G::Node, i64> = order
+        .iter()
+        .map(|&u| {
+            let d = duration(u);
+            assert!(d >= 0, "schedule needs non-negative durations, found {}", d);
+            (u, d)
+        })
+        .collect();
+    let lag = |weight: Option<EdgeWeight>| match weight {
+        None => 0,
+        Some(EdgeWeight::Weight(w)) if w >= 0 => w,
+        Some(w) => panic!("schedule needs non-negative finite lags, found {}", w),
+    };
+
+    let mut earliest_start: HashMap<&G::Node, i64> = order.iter().map(|&u| (u, 0)).collect();
+    for &u in &order {
+        let finish = earliest_start[u] + durations[u];
+        for (v, weight) in graph.neighbors_weighted(u) {
+            if let Some(start) = earliest_start.get_mut(v) {
+                *start = (*start).max(finish + lag(weight));
+            }
+        }
+    }
+
+    let makespan = order
+        .iter()
+        .map(|u| earliest_start[u] + durations[u])
+        .max()
+        .unwrap_or(0);
+
+    let mut latest_start: HashMap<&G::Node, i64> = HashMap::with_capacity(order.len());
+    for &u in order.iter().rev() {
+        let finish = graph
+            .neighbors_weighted(u)
+            .filter_map(|(v, weight)| Some(latest_start.get(v)? - lag(weight)))
+            .fold(makespan, i64::min);
+        latest_start.insert(u, finish - durations[u]);
+    }
+
+    let tasks: HashMap<&G::Node, TaskTimes> = order
+        .iter()
+        .map(|&u| {
+            let (duration, earliest_start, latest_start) =
+                (durations[u], earliest_start[u], latest_start[u]);
+            let times = TaskTimes {
+                duration,
+                earliest_start,
+                earliest_finish: earliest_start + duration,
+                latest_start,
+                latest_finish: latest_start + duration,
+                slack: latest_start - earliest_start,
+            };
+            (u, times)
+        })
+        .collect();
+
+    // every critical task finishing before the end has a critical successor which starts
+    // as soon as it can, so following those from the start of the project reaches its end
+    let mut critical_path = Vec::new();
+    let mut current = order
+        .iter()
+        .copied()
+        .find(|u| tasks[u].is_critical() && tasks[u].earliest_start == 0);
+    while let Some(u) = current {
+        critical_path.push(u);
+        let finish = tasks[u].earliest_finish;
+        current = graph.neighbors_weighted(u).find_map(|(v, weight)| {
+            let times = tasks.get(v)?;
+            (times.is_critical() && times.earliest_start == finish + lag(weight)).then_some(v)
+        });
+    }
+
+    Some(Schedule {
+        makespan,
+        tasks,
+        critical_path,
+        order,
+    })
+}
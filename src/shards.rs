@@ -0,0 +1,151 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::{
+    error::{ReadGraphError, ShardError},
+    Graph, GraphTextFormat, NodeBounds,
+};
+use std::{
+    fmt::Debug,
+    fs,
+    hash::BuildHasher,
+    path::Path,
+    str::FromStr,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    thread,
+};
+
+impl<N, S> Graph<N, S>
+where
+    N: NodeBounds + FromStr + Send + Sync,
+    <N as FromStr>::Err: Debug,
+    S: BuildHasher + Default + Send,
+{
+    /// Loads a graph split across several edge list files, parsing them on `threads` threads
+    ///
+    /// Each shard holds whitespace separated `source destination [weight]` lines,
+    /// and the shards are merged into a single graph.
+    /// Passing 0 threads uses the parallelism reported by the operating system.
+    /// If shards fail to load, the error for the first of them in `paths` is returned.
+    /// ```
+    /// use graph_algos::Graph;
+    /// use std::fs;
+    ///
+    /// let dir = std::env::temp_dir().join("graph_algos_from_shards_doctest");
+    /// fs::create_dir_all(&dir).unwrap();
+    ///
+    /// let paths = vec![dir.join("part-0.edges"), dir.join("part-1.edges")];
+    /// fs::write(&paths[0], "1 2 5\n2 3 1\n").unwrap();
+    /// fs::write(&paths[1], "3 1 2\n4 1 7\n").unwrap();
+    ///
+    /// let graph: Graph<u32> = Graph::from_shards(&paths, 2).unwrap();
+    /// assert_eq!(graph.len(), 4);
+    /// assert_eq!(graph.edges().len(), 4);
+    ///
+    /// let missing = [dir.join("missing.edges")];
+    /// let err = Graph::<u32>::from_shards(&missing, 2).unwrap_err();
+    /// assert_eq!(err.path(), missing[0].as_path());
+    /// # fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    pub fn from_shards<P>(paths: &[P], threads: usize) -> Result<Self, ShardError>
+    where
+        P: AsRef<Path> + Sync,
+    {
+        Self::from_shards_with(paths, threads, &GraphTextFormat::edge_list())
+    }
+
+    /// Loads a graph split across several files written in the given dialect,
+    /// parsing them on `threads` threads
+    ///
+    /// See [`Graph::from_shards`] for the details.
+    pub fn from_shards_with<P>(
+        paths: &[P],
+        threads: usize,
+        format: &GraphTextFormat,
+    ) -> Result<Self, ShardError>
+    where
+        P: AsRef<Path> + Sync,
+    {
+        let threads = match threads {
+            0 => thread::available_parallelism().map_or(1, usize::from),
+            n => n,
+        }
+        .min(paths.len())
+        .max(1);
+
+        // the workers take the next shard until they run out or one of them fails
+        let next = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+
+        let work = || {
+            let mut graph = Graph::default();
+            let mut errors = Vec::new();
+
+            while !failed.load(Ordering::Relaxed) {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let path = match paths.get(index) {
+                    Some(path) => path.as_ref(),
+                    None => break,
+                };
+
+                match load_shard(path, format) {
+                    Ok(shard) => graph.absorb(shard),
+                    Err(err) => {
+                        failed.store(true, Ordering::Relaxed);
+                        errors.push((index, ShardError::new(path.to_path_buf(), err)));
+                    }
+                }
+            }
+
+            (graph, errors)
+        };
+
+        let results: Vec<(Self, Vec<(usize, ShardError)>)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads).map(|_| scope.spawn(work)).collect();
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("shard worker panicked"))
+                .collect()
+        });
+
+        let mut graph = Graph::default();
+        let mut first_error: Option<(usize, ShardError)> = None;
+        for (shard, errors) in results {
+            graph.absorb(shard);
+            for (index, err) in errors {
+                if !matches!(first_error, Some((first, _)) if first < index) {
+                    first_error = Some((index, err));
+                }
+            }
+        }
+
+        match first_error {
+            Some((_, err)) => Err(err),
+            None => Ok(graph),
+        }
+    }
+}
+
+/// Reads and parses a single shard
+fn load_shard<N, S>(path: &Path, format: &GraphTextFormat) -> Result<Graph<N, S>, ReadGraphError>
+where
+    N: NodeBounds + FromStr,
+    <N as FromStr>::Err: Debug,
+    S: BuildHasher + Default,
+{
+    let text = fs::read_to_string(path)?;
+    Ok(Graph::parse_with(&text, format)?)
+}
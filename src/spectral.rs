@@ -0,0 +1,201 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::{util::index_nodes, EdgeWeight, Graph, NodeBounds};
+use std::{collections::HashMap, hash::BuildHasher};
+
+/// A dense square matrix with a row and column for each node of a graph,
+/// created by [`Graph::adjacency_matrix`] and [`Graph::laplacian_matrix`]
+///
+/// The rows and columns are in the order of [`NodeMatrix::nodes`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeMatrix<'a, N: NodeBounds> {
+    nodes: Vec<&'a N>,
+    indices: HashMap<&'a N, usize>,
+    rows: Vec<Vec<f64>>,
+}
+
+impl<'a, N: NodeBounds> NodeMatrix<'a, N> {
+    /// Returns the number of nodes in the matrix
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns whether the matrix has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the nodes in the order of the rows and columns of the matrix
+    pub fn nodes(&self) -> &[&'a N] {
+        &self.nodes
+    }
+
+    /// Returns the row and column of `u` in the matrix
+    pub fn index_of(&self, u: &N) -> Option<usize> {
+        self.indices.get(u).copied()
+    }
+
+    /// Returns the entry in the row of `u` and the column of `v`,
+    /// or `None` if either isn't in the matrix
+    pub fn get(&self, u: &N, v: &N) -> Option<f64> {
+        Some(self.rows[self.index_of(u)?][self.index_of(v)?])
+    }
+
+    /// Returns the rows of the matrix
+    pub fn rows(&self) -> &[Vec<f64>] {
+        &self.rows
+    }
+
+    /// Returns the rows of the matrix, dropping the node mapping
+    pub fn into_rows(self) -> Vec<Vec<f64>> {
+        self.rows
+    }
+
+    /// Returns the matrix with its rows and columns in ascending order of the nodes
+    pub fn sorted(self) -> Self
+    where
+        N: Ord,
+    {
+        let mut order: Vec<usize> = (0..self.len()).collect();
+        order.sort_by_key(|&i| self.nodes[i]);
+
+        let nodes: Vec<&'a N> = order.iter().map(|&i| self.nodes[i]).collect();
+        let indices = nodes.iter().enumerate().map(|(i, &u)| (u, i)).collect();
+        let rows = order
+            .iter()
+            .map(|&i| order.iter().map(|&j| self.rows[i][j]).collect())
+            .collect();
+
+        Self {
+            nodes,
+            indices,
+            rows,
+        }
+    }
+
+    /// Converts the matrix into an `ndarray` array
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2 => 4],
+    /// };
+    ///
+    /// let array = graph.adjacency_matrix().sorted().to_array();
+    ///
+    /// assert_eq!(array.shape(), &[2, 2]);
+    /// assert_eq!(array[[0, 1]], 4.0);
+    /// ```
+    #[cfg(feature = "ndarray")]
+    pub fn to_array(&self) -> ndarray::Array2<f64> {
+        ndarray::Array2::from_shape_fn((self.len(), self.len()), |(i, j)| self.rows[i][j])
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher> Graph<N, S> {
+    /// Returns the weighted adjacency matrix of the graph,
+    /// where the entry for `u` and `v` is the total weight of the edges from `u` to `v`
+    ///
+    /// Unweighted edges count as having a weight of 1, and infinite weights become infinite entries.
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2 => 3, 3 => 1],
+    ///     2 => [3 => 2],
+    /// };
+    ///
+    /// let matrix = graph.adjacency_matrix().sorted();
+    ///
+    /// assert_eq!(matrix.nodes(), &[&1, &2, &3]);
+    /// assert_eq!(
+    ///     matrix.rows(),
+    ///     &[
+    ///         vec![0.0, 3.0, 1.0],
+    ///         vec![0.0, 0.0, 2.0],
+    ///         vec![0.0, 0.0, 0.0],
+    ///     ]
+    /// );
+    /// assert_eq!(matrix.get(&2, &3), Some(2.0));
+    /// ```
+    pub fn adjacency_matrix(&self) -> NodeMatrix<'_, N> {
+        let (nodes, indices) = index_nodes(self);
+
+        let mut rows = vec![vec![0.0; nodes.len()]; nodes.len()];
+        for (u, edge) in self.edges() {
+            let v = match indices.get(edge.destination()) {
+                Some(&v) => v,
+                None => continue,
+            };
+
+            rows[indices[u]][v] += match edge.weight() {
+                None => 1.0,
+                Some(EdgeWeight::Weight(w)) => w as f64,
+                Some(EdgeWeight::PosInfinity) => f64::INFINITY,
+                Some(EdgeWeight::NegInfinity) => f64::NEG_INFINITY,
+            };
+        }
+
+        NodeMatrix {
+            nodes,
+            indices,
+            rows,
+        }
+    }
+
+    /// Returns the Laplacian matrix of the graph, the diagonal matrix of weighted out degrees
+    /// minus the [adjacency matrix](Graph::adjacency_matrix)
+    ///
+    /// When every edge has a matching edge of the same weight in the other direction,
+    /// as in an undirected graph, this is the usual symmetric graph Laplacian,
+    /// whose eigenvalues give the algebraic connectivity and spectral clusterings of the graph.
+    /// Self loops cancel out on the diagonal, so every row sums to 0.
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    ///
+    /// // an undirected path
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2],
+    ///     2 => [1, 3],
+    ///     3 => [2],
+    /// };
+    ///
+    /// let laplacian = graph.laplacian_matrix().sorted();
+    ///
+    /// assert_eq!(
+    ///     laplacian.into_rows(),
+    ///     vec![
+    ///         vec![1.0, -1.0, 0.0],
+    ///         vec![-1.0, 2.0, -1.0],
+    ///         vec![0.0, -1.0, 1.0],
+    ///     ]
+    /// );
+    /// ```
+    pub fn laplacian_matrix(&self) -> NodeMatrix<'_, N> {
+        let mut matrix = self.adjacency_matrix();
+
+        for (i, row) in matrix.rows.iter_mut().enumerate() {
+            let degree: f64 = row.iter().sum();
+            for entry in row.iter_mut() {
+                *entry = -*entry;
+            }
+            row[i] += degree;
+        }
+
+        matrix
+    }
+}
@@ -0,0 +1,402 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Random graph generators for property testing with proptest or quickcheck, and for fuzzing.
+//!
+//! Enable the `proptest` feature for the strategies and the `proptest` `Arbitrary` impl,
+//! the `quickcheck` feature for the `quickcheck` `Arbitrary` impls,
+//! or the `arbitrary` feature for the `arbitrary` impl used by cargo-fuzz targets.
+//! The graphs shrink towards fewer nodes and fewer edges,
+//! and the DAG and connected generators keep their shape while shrinking.
+//!
+//! The generated graphs over `u32` have the nodes `0..n` and every edge has a weight between 1 and 100,
+//! so they can be passed straight to any of the algorithms.
+
+#[cfg(any(feature = "quickcheck", feature = "arbitrary"))]
+use crate::NodeBounds;
+use crate::{Edge, Graph};
+
+/// The largest weight given to a generated edge
+const MAX_WEIGHT: i64 = 100;
+
+/// An edge between two node indices, which are reduced modulo the number of nodes
+#[cfg(any(feature = "proptest", feature = "quickcheck"))]
+type EdgeSpec = (u32, u32, i64);
+
+/// clamps a generated weight into the range of weights used by the generators
+fn clamp_weight(weight: i64) -> i64 {
+    weight.rem_euclid(MAX_WEIGHT) + 1
+}
+
+/// builds a graph over the nodes `0..n` with the given edges
+#[cfg(any(feature = "proptest", feature = "quickcheck"))]
+fn random_graph(n: u32, edges: &[EdgeSpec]) -> Graph<u32> {
+    let mut graph = Graph::with_capacity(n as usize);
+    (0..n).for_each(|u| graph.add_node(u));
+
+    if n > 0 {
+        for &(u, v, weight) in edges {
+            graph.add_edge(u % n, Edge::new_with_weight(v % n, clamp_weight(weight)));
+        }
+    }
+
+    graph
+}
+
+/// builds a graph from a list of nodes and a list of edges
+#[cfg(any(feature = "quickcheck", feature = "arbitrary"))]
+fn from_parts<N: NodeBounds>(nodes: Vec<N>, edges: Vec<(N, N, i64)>) -> Graph<N> {
+    let mut graph = Graph::empty();
+    nodes.into_iter().for_each(|u| graph.add_node(u));
+    for (u, v, weight) in edges {
+        graph.add_edge(u, Edge::new_with_weight(v, clamp_weight(weight)));
+    }
+    graph
+}
+
+/// builds a DAG over the nodes `0..n`, every edge points from a smaller node to a larger one
+#[cfg(any(feature = "proptest", feature = "quickcheck"))]
+fn dag(n: u32, edges: &[EdgeSpec]) -> Graph<u32> {
+    let mut graph = Graph::with_capacity(n as usize);
+    (0..n).for_each(|u| graph.add_node(u));
+
+    if n > 0 {
+        for &(u, v, weight) in edges {
+            let (u, v) = (u % n, v % n);
+            if u != v {
+                graph.add_edge(
+                    u.min(v),
+                    Edge::new_with_weight(u.max(v), clamp_weight(weight)),
+                );
+            }
+        }
+    }
+
+    graph
+}
+
+/// builds a graph over the nodes `0..=parents.len()` where every node is reachable from 0,
+/// node `i` has an edge from its parent `parents[i - 1] % i`
+#[cfg(any(feature = "proptest", feature = "quickcheck"))]
+fn connected(parents: &[u32], edges: &[EdgeSpec]) -> Graph<u32> {
+    let mut graph = random_graph(parents.len() as u32 + 1, edges);
+
+    for (i, &parent) in (1..).zip(parents) {
+        graph.add_edge(
+            parent % i,
+            Edge::new_with_weight(i, clamp_weight(parent.into())),
+        );
+    }
+
+    graph
+}
+
+#[cfg(feature = "proptest")]
+pub use self::proptest_impls::{connected_strategy, dag_strategy, graph_strategy};
+
+#[cfg(feature = "proptest")]
+mod proptest_impls {
+    use super::{connected, dag, random_graph, EdgeSpec};
+    use crate::{Edge, Graph, NodeBounds};
+    use proptest::{
+        arbitrary::{any, Arbitrary},
+        collection::vec,
+        strategy::{BoxedStrategy, Strategy},
+    };
+
+    /// the strategy for a list of up to `max_edges` edges between node indices
+    fn edges(max_edges: usize) -> impl Strategy<Value = Vec<EdgeSpec>> {
+        vec((any::<u32>(), any::<u32>(), any::<i64>()), 0..=max_edges)
+    }
+
+    /// A strategy for graphs with up to `max_nodes` nodes and `max_edges` edges,
+    /// which may include self loops and parallel edges
+    /// ```
+    /// use graph_algos::testing::graph_strategy;
+    /// use proptest::{prelude::*, test_runner::TestRunner};
+    ///
+    /// TestRunner::default()
+    ///     .run(&graph_strategy(10, 30), |graph| {
+    ///         prop_assert!(graph.len() <= 10);
+    ///         prop_assert!(graph.edges().count() <= 30);
+    ///         Ok(())
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn graph_strategy(max_nodes: u32, max_edges: usize) -> impl Strategy<Value = Graph<u32>> {
+        (0..=max_nodes, edges(max_edges)).prop_map(|(n, edges)| random_graph(n, &edges))
+    }
+
+    /// A strategy for directed acyclic graphs with up to `max_nodes` nodes and `max_edges` edges
+    /// ```
+    /// use graph_algos::algos::topological_sort;
+    /// use graph_algos::testing::dag_strategy;
+    /// use proptest::{prelude::*, test_runner::TestRunner};
+    ///
+    /// TestRunner::default()
+    ///     .run(&dag_strategy(10, 30), |graph| {
+    ///         prop_assert!(graph.nodes().all(|u| topological_sort(&graph, u).is_some()));
+    ///         Ok(())
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn dag_strategy(max_nodes: u32, max_edges: usize) -> impl Strategy<Value = Graph<u32>> {
+        (0..=max_nodes, edges(max_edges)).prop_map(|(n, edges)| dag(n, &edges))
+    }
+
+    /// A strategy for graphs with between 1 and `max_nodes` nodes where every node is reachable from node 0,
+    /// with up to `max_edges` edges on top of those needed to reach every node
+    /// ```
+    /// use graph_algos::algos::bfs_shortest_paths;
+    /// use graph_algos::testing::connected_strategy;
+    /// use proptest::{prelude::*, test_runner::TestRunner};
+    ///
+    /// TestRunner::default()
+    ///     .run(&connected_strategy(10, 5), |graph| {
+    ///         prop_assert_eq!(bfs_shortest_paths(&graph, &0).len(), graph.len());
+    ///         Ok(())
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn connected_strategy(
+        max_nodes: u32,
+        max_edges: usize,
+    ) -> impl Strategy<Value = Graph<u32>> {
+        let max_parents = max_nodes.saturating_sub(1) as usize;
+        (vec(any::<u32>(), 0..=max_parents), edges(max_edges))
+            .prop_map(|(parents, edges)| connected(&parents, &edges))
+    }
+
+    /// Generates graphs with arbitrary nodes and weighted edges between them
+    impl<N: NodeBounds + Arbitrary + 'static> Arbitrary for Graph<N> {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            (
+                vec(any::<N>(), 0..16),
+                vec((any::<N>(), any::<N>(), 1..=super::MAX_WEIGHT), 0..32),
+            )
+                .prop_map(|(nodes, edges)| {
+                    let mut graph = Graph::empty();
+                    nodes.into_iter().for_each(|u| graph.add_node(u));
+                    for (u, v, weight) in edges {
+                        graph.add_edge(u, Edge::new_with_weight(v, weight));
+                    }
+                    graph
+                })
+                .boxed()
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+pub use self::quickcheck_impls::{Connected, Dag};
+
+#[cfg(feature = "quickcheck")]
+mod quickcheck_impls {
+    use super::{connected, dag, from_parts, EdgeSpec};
+    use crate::{EdgeWeight, Graph, NodeBounds};
+    use quickcheck::{Arbitrary, Gen};
+
+    /// Generates graphs with arbitrary nodes and weighted edges between them
+    /// ```
+    /// use graph_algos::Graph;
+    /// use quickcheck::{quickcheck, TestResult};
+    ///
+    /// fn complement_has_no_common_edges(graph: Graph<u8>) -> TestResult {
+    ///     let complement = graph.complement();
+    ///     TestResult::from_bool(graph.edges().all(|(u, e)| !complement.is_edge(u, e.destination())))
+    /// }
+    ///
+    /// quickcheck(complement_has_no_common_edges as fn(Graph<u8>) -> TestResult);
+    /// ```
+    impl<N: NodeBounds + Arbitrary> Arbitrary for Graph<N> {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let nodes: Vec<N> = Arbitrary::arbitrary(g);
+            let edges: Vec<(N, N, i64)> = Arbitrary::arbitrary(g);
+            from_parts(nodes, edges)
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            let nodes: Vec<N> = self.nodes().cloned().collect();
+            let edges: Vec<(N, N, i64)> = self
+                .edges()
+                .map(|(u, edge)| {
+                    let weight = match edge.weight() {
+                        Some(EdgeWeight::Weight(w)) => w,
+                        _ => 1,
+                    };
+                    (u.clone(), edge.destination().clone(), weight)
+                })
+                .collect();
+
+            Box::new(
+                (nodes, edges)
+                    .shrink()
+                    .map(|(nodes, edges)| from_parts(nodes, edges)),
+            )
+        }
+    }
+
+    /// A directed acyclic graph over the nodes `0..n`,
+    /// which stays acyclic when shrunk
+    /// ```
+    /// use graph_algos::algos::topological_sort;
+    /// use graph_algos::testing::Dag;
+    /// use quickcheck::quickcheck;
+    ///
+    /// fn sortable(dag: Dag) -> bool {
+    ///     let graph = dag.graph();
+    ///     graph.nodes().all(|u| topological_sort(graph, u).is_some())
+    /// }
+    ///
+    /// quickcheck(sortable as fn(Dag) -> bool);
+    /// ```
+    #[derive(Debug, Clone)]
+    pub struct Dag {
+        graph: Graph<u32>,
+        nodes: u32,
+        edges: Vec<EdgeSpec>,
+    }
+
+    impl Dag {
+        /// Returns the generated graph
+        pub fn graph(&self) -> &Graph<u32> {
+            &self.graph
+        }
+
+        /// Returns the generated graph by value
+        pub fn into_graph(self) -> Graph<u32> {
+            self.graph
+        }
+
+        /// builds the graph from its description
+        fn new(nodes: u32, edges: Vec<EdgeSpec>) -> Self {
+            Self {
+                graph: dag(nodes, &edges),
+                nodes,
+                edges,
+            }
+        }
+    }
+
+    impl Arbitrary for Dag {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let nodes = u32::arbitrary(g) % (g.size() as u32 + 1);
+            Self::new(nodes, Arbitrary::arbitrary(g))
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            Box::new(
+                (self.nodes, self.edges.clone())
+                    .shrink()
+                    .map(|(nodes, edges)| Self::new(nodes, edges)),
+            )
+        }
+    }
+
+    /// A graph over the nodes `0..n` where every node is reachable from node 0,
+    /// which stays connected when shrunk
+    /// ```
+    /// use graph_algos::algos::bfs_shortest_paths;
+    /// use graph_algos::testing::Connected;
+    /// use quickcheck::quickcheck;
+    ///
+    /// fn all_reachable(connected: Connected) -> bool {
+    ///     let graph = connected.graph();
+    ///     bfs_shortest_paths(graph, &0).len() == graph.len()
+    /// }
+    ///
+    /// quickcheck(all_reachable as fn(Connected) -> bool);
+    /// ```
+    #[derive(Debug, Clone)]
+    pub struct Connected {
+        graph: Graph<u32>,
+        parents: Vec<u32>,
+        edges: Vec<EdgeSpec>,
+    }
+
+    impl Connected {
+        /// Returns the generated graph
+        pub fn graph(&self) -> &Graph<u32> {
+            &self.graph
+        }
+
+        /// Returns the generated graph by value
+        pub fn into_graph(self) -> Graph<u32> {
+            self.graph
+        }
+
+        /// builds the graph from its description
+        fn new(parents: Vec<u32>, edges: Vec<EdgeSpec>) -> Self {
+            Self {
+                graph: connected(&parents, &edges),
+                parents,
+                edges,
+            }
+        }
+    }
+
+    impl Arbitrary for Connected {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Self::new(Arbitrary::arbitrary(g), Arbitrary::arbitrary(g))
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            Box::new(
+                (self.parents.clone(), self.edges.clone())
+                    .shrink()
+                    .map(|(parents, edges)| Self::new(parents, edges)),
+            )
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls {
+    use super::from_parts;
+    use crate::{Graph, NodeBounds};
+    use arbitrary::{Arbitrary, Result, Unstructured};
+
+    /// Builds graphs with arbitrary nodes and weighted edges between them out of raw fuzzer input
+    ///
+    /// As with the other generators, every edge has a weight between 1 and 100.
+    /// The input is read as a list of nodes followed by a list of edges,
+    /// so small changes to it make small changes to the graph,
+    /// which keeps fuzzing corpora compact and lets the fuzzer minimise failing inputs.
+    /// ```
+    /// use graph_algos::Graph;
+    /// use arbitrary::{Arbitrary, Unstructured};
+    ///
+    /// let bytes: Vec<u8> = (0..=255).collect();
+    /// let graph = Graph::<u8>::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+    ///
+    /// assert!(graph.edges().all(|(_, edge)| graph.succs(edge.destination()).is_some()));
+    ///
+    /// // running out of input gives an empty graph rather than an error
+    /// let empty = Graph::<u8>::arbitrary(&mut Unstructured::new(&[])).unwrap();
+    /// assert!(empty.is_empty());
+    /// ```
+    impl<'a, N: NodeBounds + Arbitrary<'a>> Arbitrary<'a> for Graph<N> {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            let nodes: Vec<N> = u.arbitrary()?;
+            let edges: Vec<(N, N, i64)> = u.arbitrary()?;
+            Ok(from_parts(nodes, edges))
+        }
+    }
+}
@@ -0,0 +1,171 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{Graph, NodeBounds};
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::BuildHasher,
+};
+
+/// A spanning tree or forest found by a traversal,
+/// created by [`Graph::bfs_tree`] and [`Graph::dfs_forest`]
+///
+/// The timestamps come from a single clock which ticks once each time
+/// a node is discovered or finished, so for any two nodes their
+/// `[discovered, finished]` intervals are either nested or disjoint in a depth first forest.
+#[derive(Debug, Clone)]
+pub struct SpanningForest<'a, N: NodeBounds, S> {
+    /// The tree edges of the traversal, over every node it reached
+    pub tree: Graph<N, S>,
+    /// The time at which each node was first reached
+    pub discovered: HashMap<&'a N, usize>,
+    /// The time at which all of the outbound edges of each node had been explored
+    pub finished: HashMap<&'a N, usize>,
+}
+
+impl<N: NodeBounds, S: BuildHasher + Clone> Graph<N, S> {
+    /// Returns the breadth first search tree of the nodes reachable from `start`,
+    /// or `None` if `start` isn't in the graph
+    ///
+    /// The path from `start` to any node in the tree has the fewest possible edges.
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2 => 1, 3 => 4],
+    ///     2 => [3 => 1, 4 => 2],
+    ///     3 => [4 => 1],
+    ///     5 => [1 => 1],
+    /// };
+    ///
+    /// let bfs = graph.bfs_tree(&1).unwrap();
+    ///
+    /// let tree: Graph<u32> = graph! {
+    ///     1 => [2 => 1, 3 => 4],
+    ///     2 => [4 => 2],
+    /// };
+    ///
+    /// assert_eq!(bfs.tree, tree);
+    /// assert_eq!(bfs.discovered[&1], 0);
+    /// assert!(!bfs.discovered.contains_key(&5));
+    /// ```
+    pub fn bfs_tree(&self, start: &N) -> Option<SpanningForest<'_, N, S>> {
+        let start = self.node_ref(start)?;
+        let mut forest = SpanningForest::new(self);
+        let mut clock = 0;
+
+        forest.discover(start, &mut clock);
+        let mut queue: VecDeque<&N> = vec![start].into();
+
+        while let Some(u) = queue.pop_front() {
+            for edge in self.succs_or_empty(u) {
+                let v = edge.destination();
+                if !forest.discovered.contains_key(v) {
+                    forest.discover(v, &mut clock);
+                    forest.tree.add_edge(u.clone(), edge.clone());
+                    queue.push_back(v);
+                }
+            }
+
+            forest.finished.insert(u, clock);
+            clock += 1;
+        }
+
+        Some(forest)
+    }
+
+    /// Returns a depth first search forest covering every node in the graph
+    ///
+    /// The roots of the trees are chosen in the same order as [`Graph::nodes`].
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2],
+    ///     2 => [3],
+    ///     3 => [1],
+    /// };
+    ///
+    /// let dfs = graph.dfs_forest();
+    ///
+    /// // a cycle always loses exactly one edge
+    /// assert_eq!(dfs.tree.len(), 3);
+    /// assert_eq!(dfs.tree.edges().count(), 2);
+    ///
+    /// // the edge left out goes back to an ancestor which is still being explored
+    /// let back_edge = graph
+    ///     .edges()
+    ///     .find(|(u, edge)| !dfs.tree.is_edge(u, edge.destination()))
+    ///     .unwrap();
+    /// let (u, v) = (back_edge.0, back_edge.1.destination());
+    /// assert!(dfs.discovered[v] < dfs.discovered[u]);
+    /// assert!(dfs.finished[u] < dfs.finished[v]);
+    /// ```
+    pub fn dfs_forest(&self) -> SpanningForest<'_, N, S> {
+        let mut forest = SpanningForest::new(self);
+        let mut clock = 0;
+
+        for root in self.nodes() {
+            if forest.discovered.contains_key(root) {
+                continue;
+            }
+
+            forest.discover(root, &mut clock);
+
+            // the recursion is kept on an explicit stack so deep graphs can't overflow it
+            let mut stack = vec![(root, self.succs_or_empty(root).iter())];
+            while let Some((u, succs)) = stack.last_mut() {
+                let u = *u;
+                match succs.next() {
+                    Some(edge) => {
+                        let v = edge.destination();
+                        if !forest.discovered.contains_key(v) {
+                            forest.discover(v, &mut clock);
+                            forest.tree.add_edge(u.clone(), edge.clone());
+                            stack.push((v, self.succs_or_empty(v).iter()));
+                        }
+                    }
+                    None => {
+                        forest.finished.insert(u, clock);
+                        clock += 1;
+                        stack.pop();
+                    }
+                }
+            }
+        }
+
+        forest
+    }
+}
+
+impl<'a, N: NodeBounds, S: BuildHasher + Clone> SpanningForest<'a, N, S> {
+    /// creates an empty forest using the same hasher as `graph`
+    fn new(graph: &'a Graph<N, S>) -> Self {
+        Self {
+            tree: Graph::with_hasher(graph.hasher().clone()),
+            discovered: HashMap::new(),
+            finished: HashMap::new(),
+        }
+    }
+
+    /// records the discovery of `u`, adding it to the tree
+    fn discover(&mut self, u: &'a N, clock: &mut usize) {
+        self.tree.add_node(u.clone());
+        self.discovered.insert(u, *clock);
+        *clock += 1;
+    }
+}
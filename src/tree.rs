@@ -0,0 +1,334 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+//! Utilities for graphs which are really hierarchies.
+//!
+//! A graph is a forest when no node has more than one inbound edge and there are no cycles,
+//! so every node can be reached from exactly one root along exactly one path.
+//! A tree is a forest with a single root.
+//!
+//! ```
+//! use graph_algos::{graph, Graph};
+//! use graph_algos::tree;
+//!
+//! let graph: Graph<&str> = graph! {
+//!     "root" => ["a", "b"],
+//!     "a" => ["c", "d"],
+//! };
+//!
+//! assert!(tree::is_tree(&graph));
+//! assert_eq!(tree::find_roots(&graph), vec![&"root"]);
+//!
+//! let rooted = graph.rooted_at(&"root").unwrap();
+//! assert_eq!(rooted.parent(&"d"), Some(&"a"));
+//! assert_eq!(rooted.depth(&"d"), Some(2));
+//! ```
+
+use crate::{
+    visit::{IntoNeighbors, IntoNodes},
+    Graph, NodeBounds,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::BuildHasher,
+};
+
+/// Returns the nodes of the graph which have no inbound edges
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::tree::find_roots;
+///
+/// let mut graph: Graph<u32> = graph! {
+///     1 => [2, 3],
+///     4 => [3],
+/// };
+/// graph.add_node(5);
+///
+/// let mut roots = find_roots(&graph);
+/// roots.sort();
+/// assert_eq!(roots, vec![&1, &4, &5]);
+/// ```
+pub fn find_roots<'a, G>(graph: G) -> Vec<&'a G::Node>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let in_degrees = in_degrees(graph);
+    graph
+        .nodes()
+        .filter(|u| in_degrees.get(u).copied().unwrap_or(0) == 0)
+        .collect()
+}
+
+/// Returns whether the graph is a forest,
+/// i.e. it has no cycles and no node has more than one inbound edge
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::tree::is_forest;
+///
+/// let forest: Graph<u32> = graph! {
+///     1 => [2, 3],
+///     4 => [5],
+/// };
+/// assert!(is_forest(&forest));
+///
+/// let diamond: Graph<u32> = graph! {
+///     1 => [2, 3],
+///     2 => [4],
+///     3 => [4],
+/// };
+/// assert!(!is_forest(&diamond));
+///
+/// let cycle: Graph<u32> = graph! {
+///     1 => [2],
+///     3 => [4],
+///     4 => [3],
+/// };
+/// assert!(!is_forest(&cycle));
+/// ```
+pub fn is_forest<'a, G>(graph: G) -> bool
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    if in_degrees(graph).values().any(|&d| d > 1) {
+        return false;
+    }
+
+    // with every in-degree at most one the only way to hide a node from the roots is a cycle
+    let mut stack = find_roots(graph);
+    let mut reached = 0;
+    while let Some(u) = stack.pop() {
+        reached += 1;
+        stack.extend(graph.neighbors(u));
+    }
+
+    reached == graph.node_count()
+}
+
+/// Returns whether the graph is a tree, i.e. a forest with exactly one root
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::tree::is_tree;
+///
+/// let tree: Graph<u32> = graph! {
+///     1 => [2, 3],
+///     3 => [4],
+/// };
+/// assert!(is_tree(&tree));
+///
+/// let forest: Graph<u32> = graph! {
+///     1 => [2],
+///     3 => [4],
+/// };
+/// assert!(!is_tree(&forest));
+///
+/// assert!(!is_tree(&Graph::<u32>::empty()));
+/// ```
+pub fn is_tree<'a, G>(graph: G) -> bool
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    find_roots(graph).len() == 1 && is_forest(graph)
+}
+
+/// Returns a longest path in a tree, ignoring the direction of the edges,
+/// or `None` if the graph isn't a tree
+///
+/// The path is found using two breadth first searches,
+/// so its length is measured in edges and any edge weights are ignored.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::tree::diameter;
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2, 3],
+///     2 => [4],
+///     3 => [5],
+///     5 => [6],
+/// };
+///
+/// let mut path = diameter(&graph).unwrap();
+/// if path[0] != &4 {
+///     path.reverse();
+/// }
+/// assert_eq!(path, vec![&4, &2, &1, &3, &5, &6]);
+/// ```
+pub fn diameter<'a, G>(graph: G) -> Option<Vec<&'a G::Node>>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    if !is_tree(graph) {
+        return None;
+    }
+
+    let mut adjacent: HashMap<&G::Node, Vec<&G::Node>> = HashMap::new();
+    for u in graph.nodes() {
+        for v in graph.neighbors(u) {
+            adjacent.entry(u).or_default().push(v);
+            adjacent.entry(v).or_default().push(u);
+        }
+    }
+
+    // the furthest node from anywhere is one end of a diameter
+    let start = graph.nodes().next()?;
+    let (end, _) = furthest(&adjacent, start);
+    let (other_end, parents) = furthest(&adjacent, end);
+
+    let mut path = vec![other_end];
+    while let Some(&parent) = parents.get(path.last()?) {
+        path.push(parent);
+    }
+
+    Some(path)
+}
+
+/// A tree stored as a pointer from each node to its parent, created by [`Graph::rooted_at`]
+#[derive(Debug, Clone)]
+pub struct RootedTree<'a, N: NodeBounds> {
+    root: &'a N,
+    parents: HashMap<&'a N, &'a N>,
+    depths: HashMap<&'a N, usize>,
+}
+
+impl<'a, N: NodeBounds> RootedTree<'a, N> {
+    /// Returns the root of the tree
+    pub fn root(&self) -> &'a N {
+        self.root
+    }
+
+    /// Returns whether `u` is in the tree
+    pub fn contains(&self, u: &N) -> bool {
+        self.depths.contains_key(u)
+    }
+
+    /// Returns the parent of `u`, or `None` if `u` is the root or isn't in the tree
+    pub fn parent(&self, u: &N) -> Option<&'a N> {
+        self.parents.get(u).copied()
+    }
+
+    /// Returns the number of edges between the root and `u`
+    pub fn depth(&self, u: &N) -> Option<usize> {
+        self.depths.get(u).copied()
+    }
+
+    /// Returns an iterator over the ancestors of `u`, starting with its parent and ending at the root
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2],
+    ///     2 => [3],
+    /// };
+    ///
+    /// let tree = graph.rooted_at(&1).unwrap();
+    /// assert_eq!(tree.ancestors(&3).collect::<Vec<_>>(), vec![&2, &1]);
+    /// assert_eq!(tree.ancestors(&1).count(), 0);
+    /// ```
+    pub fn ancestors<'t>(&'t self, u: &N) -> impl Iterator<Item = &'a N> + 't {
+        let mut current = self.parent(u);
+        std::iter::from_fn(move || {
+            let node = current?;
+            current = self.parent(node);
+            Some(node)
+        })
+    }
+
+    /// Returns the parent of every node other than the root
+    pub fn parents(&self) -> &HashMap<&'a N, &'a N> {
+        &self.parents
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher> Graph<N, S> {
+    /// Returns the tree of nodes reachable from `root`,
+    /// or `None` if `root` isn't in the graph or the nodes reachable from it don't form a tree
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2, 3],
+    ///     3 => [4],
+    ///     5 => [3],
+    /// };
+    ///
+    /// let tree = graph.rooted_at(&1).unwrap();
+    /// assert_eq!(tree.parent(&4), Some(&3));
+    /// assert_eq!(tree.parent(&1), None);
+    /// assert!(!tree.contains(&5));
+    ///
+    /// // 3 can be reached from 1 and 5, but only once from each of them
+    /// assert!(graph.rooted_at(&5).is_some());
+    /// ```
+    pub fn rooted_at(&self, root: &N) -> Option<RootedTree<'_, N>> {
+        let root = self.node_ref(root)?;
+        let mut tree = RootedTree {
+            root,
+            parents: HashMap::new(),
+            depths: HashMap::new(),
+        };
+        tree.depths.insert(root, 0);
+
+        let mut queue: VecDeque<&N> = vec![root].into();
+        while let Some(u) = queue.pop_front() {
+            let depth = tree.depths[u] + 1;
+            for v in self.neighbors(u) {
+                // reaching a node twice means it has two parents or sits on a cycle
+                if tree.depths.insert(v, depth).is_some() {
+                    return None;
+                }
+                tree.parents.insert(v, u);
+                queue.push_back(v);
+            }
+        }
+
+        Some(tree)
+    }
+}
+
+/// Counts the inbound edges of every node with at least one
+fn in_degrees<'a, G>(graph: G) -> HashMap<&'a G::Node, usize>
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let mut in_degrees = HashMap::new();
+    for u in graph.nodes() {
+        for v in graph.neighbors(u) {
+            *in_degrees.entry(v).or_insert(0) += 1;
+        }
+    }
+    in_degrees
+}
+
+/// Returns the node furthest from `start` and the breadth first search tree used to find it
+fn furthest<'a, N: NodeBounds>(
+    adjacent: &HashMap<&'a N, Vec<&'a N>>,
+    start: &'a N,
+) -> (&'a N, HashMap<&'a N, &'a N>) {
+    let mut parents: HashMap<&N, &N> = HashMap::new();
+    let mut queue: VecDeque<&N> = vec![start].into();
+    let mut last = start;
+
+    while let Some(u) = queue.pop_front() {
+        last = u;
+        for &v in adjacent.get(u).into_iter().flatten() {
+            if v != start && !parents.contains_key(v) {
+                parents.insert(v, u);
+                queue.push_back(v);
+            }
+        }
+    }
+
+    (last, parents)
+}
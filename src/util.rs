@@ -0,0 +1,522 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Data structures used to implement the algorithms, which are useful on their own.
+
+use crate::{
+    visit::{IntoNeighbors, IntoNodes},
+    Graph, NodeBounds,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{BuildHasher, Hash},
+};
+
+/// A disjoint set forest over the indices `0..n`, with union by rank and path compression
+/// ```
+/// use graph_algos::util::UnionFind;
+///
+/// let mut sets = UnionFind::new(4);
+/// assert_eq!(sets.count(), 4);
+///
+/// assert!(sets.union(0, 1));
+/// assert!(sets.union(2, 3));
+/// assert!(!sets.union(1, 0));
+///
+/// assert!(sets.same_set(0, 1));
+/// assert!(!sets.same_set(1, 2));
+/// assert_eq!(sets.count(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    count: usize,
+}
+
+impl UnionFind {
+    /// Creates `n` sets, each containing a single index
+    pub fn new(n: usize) -> Self {
+        Self {
+            parents: (0..n).collect(),
+            ranks: vec![0; n],
+            count: n,
+        }
+    }
+
+    /// Returns the number of indices
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Returns whether there are no indices
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// Returns the number of disjoint sets
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Adds a new set containing only the next index, and returns that index
+    pub fn push(&mut self) -> usize {
+        let index = self.parents.len();
+        self.parents.push(index);
+        self.ranks.push(0);
+        self.count += 1;
+        index
+    }
+
+    /// Returns the representative of the set containing `u`
+    pub fn find(&mut self, u: usize) -> usize {
+        let mut root = u;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        // point everything on the path directly at the root
+        let mut node = u;
+        while self.parents[node] != root {
+            let next = self.parents[node];
+            self.parents[node] = root;
+            node = next;
+        }
+
+        root
+    }
+
+    /// Returns whether `u` and `v` are in the same set
+    pub fn same_set(&mut self, u: usize, v: usize) -> bool {
+        self.find(u) == self.find(v)
+    }
+
+    /// Merges the sets containing `u` and `v`, returns false if they were already the same set
+    pub fn union(&mut self, u: usize, v: usize) -> bool {
+        let (u, v) = (self.find(u), self.find(v));
+        if u == v {
+            return false;
+        }
+
+        let (child, parent) = if self.ranks[u] < self.ranks[v] {
+            (u, v)
+        } else {
+            (v, u)
+        };
+
+        self.parents[child] = parent;
+        if self.ranks[child] == self.ranks[parent] {
+            self.ranks[parent] += 1;
+        }
+        self.count -= 1;
+
+        true
+    }
+}
+
+/// Tracks which nodes of an undirected graph are connected as edges are added,
+/// with support for removing edges at a cost
+///
+/// Adding an edge is a near constant time union-find operation.
+/// The structure also keeps a spanning forest of the edges,
+/// so removing an edge which isn't part of the forest is just as cheap,
+/// but a union-find can't be split, so removing a forest edge rebuilds it from the remaining edges
+/// in `O(m α(n))` time.
+/// This suits graphs which mostly gain edges, it isn't a fully dynamic structure
+/// with polylogarithmic deletions.
+/// ```
+/// use graph_algos::util::IncrementalConnectivity;
+///
+/// let mut network = IncrementalConnectivity::new();
+/// network.add_edge("a", "b");
+/// network.add_edge("b", "c");
+/// network.add_edge("a", "c");
+/// network.add_node("d");
+///
+/// assert!(network.connected(&"a", &"c"));
+/// assert!(!network.connected(&"a", &"d"));
+/// assert_eq!(network.component_count(), 2);
+///
+/// // the triangle stays connected after losing any one edge
+/// assert!(network.remove_edge(&"a", &"b"));
+/// assert!(network.connected(&"a", &"b"));
+///
+/// assert!(network.remove_edge(&"c", &"b"));
+/// assert!(!network.connected(&"a", &"b"));
+/// assert_eq!(network.component_count(), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct IncrementalConnectivity<N: NodeBounds> {
+    indices: HashMap<N, usize>,
+    sets: UnionFind,
+    /// the number of copies of each edge, keyed with the smaller index first
+    edges: HashMap<(usize, usize), usize>,
+    /// the edges making up a spanning forest of the current edges
+    forest: HashSet<(usize, usize)>,
+}
+
+impl<N: NodeBounds> IncrementalConnectivity<N> {
+    /// Creates a structure with no nodes
+    pub fn new() -> Self {
+        Self {
+            indices: HashMap::new(),
+            sets: UnionFind::new(0),
+            edges: HashMap::new(),
+            forest: HashSet::new(),
+        }
+    }
+
+    /// Returns the number of nodes
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Returns whether there are no nodes
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Returns the number of connected components
+    pub fn component_count(&self) -> usize {
+        self.sets.count()
+    }
+
+    /// Adds a node with no edges, returns false if it was already present
+    pub fn add_node(&mut self, u: N) -> bool {
+        if self.indices.contains_key(&u) {
+            return false;
+        }
+
+        let index = self.sets.push();
+        self.indices.insert(u, index);
+        true
+    }
+
+    /// Adds an undirected edge between `u` and `v`, adding the nodes if they are missing
+    ///
+    /// Adding the same edge more than once keeps it until every copy has been removed.
+    pub fn add_edge(&mut self, u: N, v: N) {
+        self.add_node(u.clone());
+        self.add_node(v.clone());
+
+        let key = edge_key(self.indices[&u], self.indices[&v]);
+        *self.edges.entry(key).or_insert(0) += 1;
+
+        if self.sets.union(key.0, key.1) {
+            self.forest.insert(key);
+        }
+    }
+
+    /// Removes one copy of the edge between `u` and `v`, returns false if there was no such edge
+    ///
+    /// Takes time linear in the number of edges if the edge was part of the spanning forest.
+    pub fn remove_edge(&mut self, u: &N, v: &N) -> bool {
+        let key = match (self.indices.get(u), self.indices.get(v)) {
+            (Some(&u), Some(&v)) => edge_key(u, v),
+            _ => return false,
+        };
+
+        match self.edges.get_mut(&key) {
+            None => return false,
+            Some(copies) if *copies > 1 => *copies -= 1,
+            Some(_) => {
+                self.edges.remove(&key);
+                // only losing a forest edge can disconnect anything
+                if self.forest.remove(&key) {
+                    self.rebuild();
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Returns whether there is a path between `u` and `v`,
+    /// which is false if either of them is missing
+    pub fn connected(&mut self, u: &N, v: &N) -> bool {
+        match (self.indices.get(u), self.indices.get(v)) {
+            (Some(&u), Some(&v)) => self.sets.same_set(u, v),
+            _ => false,
+        }
+    }
+
+    /// Recomputes the components and the spanning forest from the current edges
+    fn rebuild(&mut self) {
+        self.sets = UnionFind::new(self.indices.len());
+        self.forest.clear();
+
+        for &(u, v) in self.edges.keys() {
+            if self.sets.union(u, v) {
+                self.forest.insert((u, v));
+            }
+        }
+    }
+}
+
+impl<N: NodeBounds> Default for IncrementalConnectivity<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher> From<&Graph<N, S>> for IncrementalConnectivity<N> {
+    /// Starts tracking the connectivity of a graph, ignoring the direction of its edges
+    fn from(graph: &Graph<N, S>) -> Self {
+        let mut connectivity = Self::new();
+
+        for u in graph.nodes() {
+            connectivity.add_node(u.clone());
+        }
+        for (u, edge) in graph.edges() {
+            connectivity.add_edge(u.clone(), edge.destination().clone());
+        }
+
+        connectivity
+    }
+}
+
+/// Orders the ends of an undirected edge so both directions share a key
+fn edge_key(u: usize, v: usize) -> (usize, usize) {
+    (u.min(v), u.max(v))
+}
+
+/// The number of children of each node in an [`IndexedHeap`]
+const HEAP_ARITY: usize = 4;
+
+/// A min-heap of keys ordered by priority, where the priority of a key already in the heap can be changed
+///
+/// Each key appears at most once, so algorithms like Dijkstra's
+/// can lower a node's priority instead of pushing a duplicate entry.
+/// It is stored as a 4-ary heap, which is shallower and more cache friendly than a binary heap.
+/// ```
+/// use graph_algos::util::IndexedHeap;
+///
+/// let mut heap = IndexedHeap::new();
+/// heap.push("a", 5);
+/// heap.push("b", 3);
+/// heap.push("c", 4);
+///
+/// assert!(heap.decrease_key(&"a", 1));
+/// assert!(!heap.decrease_key(&"c", 9));
+/// assert_eq!(heap.len(), 3);
+///
+/// assert_eq!(heap.pop(), Some(("a", 1)));
+/// assert_eq!(heap.pop(), Some(("b", 3)));
+/// assert_eq!(heap.pop(), Some(("c", 4)));
+/// assert_eq!(heap.pop(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct IndexedHeap<K, P> {
+    heap: Vec<(K, P)>,
+    positions: HashMap<K, usize>,
+}
+
+impl<K: Hash + Eq + Clone, P: Ord> IndexedHeap<K, P> {
+    /// Creates an empty heap
+    pub fn new() -> Self {
+        Self {
+            heap: Vec::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Creates an empty heap with space for at least `capacity` keys
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            heap: Vec::with_capacity(capacity),
+            positions: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the number of keys in the heap
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns whether the heap is empty
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Returns whether `key` is in the heap
+    pub fn contains(&self, key: &K) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    /// Returns the priority of `key`, if it is in the heap
+    pub fn priority(&self, key: &K) -> Option<&P> {
+        self.positions.get(key).map(|&i| &self.heap[i].1)
+    }
+
+    /// Returns the key with the smallest priority without removing it
+    pub fn peek(&self) -> Option<(&K, &P)> {
+        self.heap.first().map(|(key, priority)| (key, priority))
+    }
+
+    /// Adds `key` with the given priority, or changes its priority if it is already in the heap,
+    /// returning the old priority
+    pub fn push(&mut self, key: K, priority: P) -> Option<P> {
+        match self.positions.get(&key) {
+            Some(&i) => {
+                let old = std::mem::replace(&mut self.heap[i].1, priority);
+                if self.heap[i].1 < old {
+                    self.sift_up(i);
+                } else {
+                    self.sift_down(i);
+                }
+                Some(old)
+            }
+            None => {
+                let i = self.heap.len();
+                self.positions.insert(key.clone(), i);
+                self.heap.push((key, priority));
+                self.sift_up(i);
+                None
+            }
+        }
+    }
+
+    /// Lowers the priority of `key`,
+    /// returns false if it isn't in the heap or `priority` isn't lower than its current priority
+    pub fn decrease_key(&mut self, key: &K, priority: P) -> bool {
+        match self.positions.get(key) {
+            Some(&i) if priority < self.heap[i].1 => {
+                self.heap[i].1 = priority;
+                self.sift_up(i);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes and returns the key with the smallest priority
+    pub fn pop(&mut self) -> Option<(K, P)> {
+        self.remove_at(0)
+    }
+
+    /// Removes `key` from the heap, returning its priority
+    pub fn remove(&mut self, key: &K) -> Option<P> {
+        let i = *self.positions.get(key)?;
+        self.remove_at(i).map(|(_, priority)| priority)
+    }
+
+    /// Removes the entry at position `i` of the heap
+    fn remove_at(&mut self, i: usize) -> Option<(K, P)> {
+        if i >= self.heap.len() {
+            return None;
+        }
+
+        let last = self.heap.len() - 1;
+        self.swap(i, last);
+        let (key, priority) = self.heap.pop()?;
+        self.positions.remove(&key);
+
+        if i < self.heap.len() {
+            self.sift_down(i);
+            self.sift_up(i);
+        }
+
+        Some((key, priority))
+    }
+
+    /// Moves the entry at position `i` up until its parent is no larger
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / HEAP_ARITY;
+            if self.heap[i].1 >= self.heap[parent].1 {
+                break;
+            }
+            self.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    /// Moves the entry at position `i` down until none of its children are smaller
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let first = i * HEAP_ARITY + 1;
+            let last = (first + HEAP_ARITY).min(self.heap.len());
+            let smallest = (first..last).min_by(|&a, &b| self.heap[a].1.cmp(&self.heap[b].1));
+
+            match smallest {
+                Some(child) if self.heap[child].1 < self.heap[i].1 => {
+                    self.swap(i, child);
+                    i = child;
+                }
+                _ => break,
+            }
+        }
+    }
+
+    /// Swaps two entries, keeping their positions up to date
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        for i in [a, b] {
+            if let Some(position) = self.positions.get_mut(&self.heap[i].0) {
+                *position = i;
+            }
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, P: Ord> Default for IndexedHeap<K, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the nodes of a graph in the order it lists them, along with the position of each node in that list
+pub(crate) fn index_nodes<'a, G: IntoNodes<'a>>(
+    graph: G,
+) -> (Vec<&'a G::Node>, HashMap<&'a G::Node, usize>) {
+    let nodes: Vec<&G::Node> = graph.nodes().collect();
+    let indices = nodes.iter().enumerate().map(|(i, &u)| (u, i)).collect();
+
+    (nodes, indices)
+}
+
+/// Returns the nodes of a graph and the positions of the successors of each of them,
+/// leaving out edges to nodes which the graph doesn't list
+pub(crate) fn index_graph<'a, G>(graph: G) -> (Vec<&'a G::Node>, Vec<Vec<usize>>)
+where
+    G: IntoNodes<'a> + IntoNeighbors<'a>,
+{
+    let (nodes, indices) = index_nodes(graph);
+    let succs = nodes
+        .iter()
+        .map(|&u| {
+            graph
+                .neighbors(u)
+                .filter_map(|v| indices.get(v).copied())
+                .collect()
+        })
+        .collect();
+
+    (nodes, succs)
+}
+
+/// Returns the predecessors of each node given the successors of each node, both by position
+pub(crate) fn reverse_adjacency(succs: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut preds = vec![Vec::new(); succs.len()];
+    for (u, succs) in succs.iter().enumerate() {
+        for &v in succs {
+            preds[v].push(u);
+        }
+    }
+
+    preds
+}
@@ -0,0 +1,92 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::{error::GraphViolation, EdgeWeight, Graph, NodeBounds};
+use std::{collections::HashSet, hash::BuildHasher};
+
+impl<N: NodeBounds, S: BuildHasher> Graph<N, S> {
+    /// Checks the graph for common data problems, returning every one found in no particular order
+    ///
+    /// Missing weights are only reported when at least one edge in the graph has a weight.
+    /// ```
+    /// use graph_algos::{Graph, Edge};
+    /// use graph_algos::error::GraphViolation;
+    ///
+    /// let mut graph: Graph<u32> = Graph::empty();
+    /// graph.add_edge(1, Edge::new_with_weight(2, 3));
+    /// assert!(graph.validate().is_empty());
+    ///
+    /// graph.add_edge(2, Edge::new(2));
+    /// let mut violations = graph.validate();
+    /// violations.sort_by_key(|violation| violation.to_string());
+    ///
+    /// assert_eq!(
+    ///     violations,
+    ///     vec![
+    ///         GraphViolation::MissingWeight { source: 2, destination: 2 },
+    ///         GraphViolation::SelfLoop(2),
+    ///     ],
+    /// );
+    /// ```
+    pub fn validate(&self) -> Vec<GraphViolation<N>> {
+        let mut violations = Vec::new();
+        let weighted = self.edges().any(|(_, edge)| edge.weight().is_some());
+
+        for u in self.nodes() {
+            let mut seen: HashSet<&N> = HashSet::new();
+            let mut reported: HashSet<&N> = HashSet::new();
+
+            for edge in self.succs_or_empty(u) {
+                let v = edge.destination();
+
+                if self.succs(v).is_none() {
+                    violations.push(GraphViolation::DanglingEdge {
+                        source: u.clone(),
+                        destination: v.clone(),
+                    });
+                }
+
+                if !seen.insert(v) && reported.insert(v) {
+                    violations.push(GraphViolation::ParallelEdge {
+                        source: u.clone(),
+                        destination: v.clone(),
+                    });
+                }
+
+                if u == v {
+                    violations.push(GraphViolation::SelfLoop(u.clone()));
+                }
+
+                match edge.weight() {
+                    None if weighted => violations.push(GraphViolation::MissingWeight {
+                        source: u.clone(),
+                        destination: v.clone(),
+                    }),
+                    Some(weight) if weight < EdgeWeight::new(0) => {
+                        violations.push(GraphViolation::NegativeWeight {
+                            source: u.clone(),
+                            destination: v.clone(),
+                            weight,
+                        })
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        violations
+    }
+}
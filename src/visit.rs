@@ -0,0 +1,644 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Traits describing the operations the graph algorithms need from a graph.
+//!
+//! The algorithms in [`crate::algos`] are written against these traits rather than
+//! against [`Graph`] directly, so they work equally well over a `&Graph<N>` or over
+//! one of the views in this module: [`NodeFiltered`] and [`EdgeFiltered`] hide parts of a graph
+//! without copying it, and [`Reversed`] turns every edge around.
+//!
+//! ```
+//! use graph_algos::{graph, Graph};
+//! use graph_algos::visit::{IntoNeighbors, NodeFiltered};
+//!
+//! let graph: Graph<u32> = graph! {
+//!     1 => [2, 3],
+//!     2 => [4],
+//!     3 => [4],
+//! };
+//!
+//! // hide node 2 without copying the graph
+//! let filter = |node: &u32| *node != 2;
+//! let view = NodeFiltered::new(&graph, &filter);
+//!
+//! let succs: Vec<&u32> = view.neighbors(&1).collect();
+//! assert_eq!(succs, vec![&3]);
+//! ```
+
+use crate::{Edge, EdgeWeight, Graph, NodeBounds};
+use std::{collections::HashMap, fmt, hash::BuildHasher, slice};
+
+/// A cheaply copyable reference to a graph whose nodes live for `'a`
+pub trait GraphRef<'a>: Copy {
+    /// The type of the nodes in the graph
+    type Node: NodeBounds + 'a;
+
+    /// Returns whether the node is present in the graph
+    fn contains_node(self, u: &Self::Node) -> bool;
+}
+
+/// A graph which can iterate over all of its nodes
+pub trait IntoNodes<'a>: GraphRef<'a> {
+    /// The iterator over the nodes of the graph
+    type Nodes: Iterator<Item = &'a Self::Node>;
+
+    /// Returns an iterator over the nodes in the graph
+    fn nodes(self) -> Self::Nodes;
+
+    /// Returns the number of nodes in the graph
+    fn node_count(self) -> usize {
+        self.nodes().count()
+    }
+}
+
+/// A graph which can iterate over the successors of a node
+pub trait IntoNeighbors<'a>: GraphRef<'a> {
+    /// The iterator over the successors of a node
+    type Neighbors: Iterator<Item = &'a Self::Node>;
+
+    /// Returns an iterator over the successors of `u`
+    ///
+    /// Nodes which are not in the graph have no successors.
+    fn neighbors(self, u: &Self::Node) -> Self::Neighbors;
+}
+
+/// A graph which can iterate over the successors of a node along with the edge weights
+pub trait EdgeWeighted<'a>: IntoNeighbors<'a> {
+    /// The iterator over the successors of a node and the weights of the edges to them
+    type NeighborsWeighted: Iterator<Item = (&'a Self::Node, Option<EdgeWeight>)>;
+
+    /// Returns an iterator over the successors of `u` and the weight of the edge to each
+    ///
+    /// Nodes which are not in the graph have no successors.
+    fn neighbors_weighted(self, u: &Self::Node) -> Self::NeighborsWeighted;
+}
+
+impl<'a, N: NodeBounds + 'a, S: BuildHasher> GraphRef<'a> for &'a Graph<N, S> {
+    type Node = N;
+
+    fn contains_node(self, u: &N) -> bool {
+        self.succs(u).is_some()
+    }
+}
+
+impl<'a, N: NodeBounds + 'a, S: BuildHasher> IntoNodes<'a> for &'a Graph<N, S> {
+    type Nodes = crate::graph::Nodes<'a, N>;
+
+    fn nodes(self) -> Self::Nodes {
+        Graph::nodes(self)
+    }
+
+    fn node_count(self) -> usize {
+        self.len()
+    }
+}
+
+impl<'a, N: NodeBounds + 'a, S: BuildHasher> IntoNeighbors<'a> for &'a Graph<N, S> {
+    type Neighbors = Neighbors<'a, N>;
+
+    fn neighbors(self, u: &N) -> Self::Neighbors {
+        Graph::neighbors(self, u)
+    }
+}
+
+impl<'a, N: NodeBounds + 'a, S: BuildHasher> EdgeWeighted<'a> for &'a Graph<N, S> {
+    type NeighborsWeighted = NeighborsWeighted<'a, N>;
+
+    fn neighbors_weighted(self, u: &N) -> Self::NeighborsWeighted {
+        Graph::neighbors_weighted(self, u)
+    }
+}
+
+/// An iterator over the successors of a node
+#[derive(Debug, Clone)]
+pub struct Neighbors<'a, N: NodeBounds> {
+    inner: slice::Iter<'a, Edge<N>>,
+}
+
+impl<'a, N: NodeBounds> Neighbors<'a, N> {
+    pub(crate) fn new(edges: &'a [Edge<N>]) -> Self {
+        Self {
+            inner: edges.iter(),
+        }
+    }
+}
+
+impl<'a, N: NodeBounds> Iterator for Neighbors<'a, N> {
+    type Item = &'a N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(Edge::destination)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, N: NodeBounds> ExactSizeIterator for Neighbors<'a, N> {}
+
+/// An iterator over the successors of a node along with the weights of the edges
+#[derive(Debug, Clone)]
+pub struct NeighborsWeighted<'a, N: NodeBounds> {
+    inner: slice::Iter<'a, Edge<N>>,
+}
+
+impl<'a, N: NodeBounds> NeighborsWeighted<'a, N> {
+    pub(crate) fn new(edges: &'a [Edge<N>]) -> Self {
+        Self {
+            inner: edges.iter(),
+        }
+    }
+}
+
+impl<'a, N: NodeBounds> Iterator for NeighborsWeighted<'a, N> {
+    type Item = (&'a N, Option<EdgeWeight>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|edge| (edge.destination(), edge.weight()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, N: NodeBounds> ExactSizeIterator for NeighborsWeighted<'a, N> {}
+
+/// A view of a graph which only contains the nodes matching a predicate
+///
+/// Edges to or from a hidden node are hidden as well.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::visit::{IntoNodes, NodeFiltered};
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2, 3],
+///     3 => [4],
+/// };
+///
+/// let filter = |node: &u32| node % 2 == 1;
+/// let view = NodeFiltered::new(&graph, &filter);
+///
+/// let mut nodes: Vec<&u32> = view.nodes().collect();
+/// nodes.sort();
+/// assert_eq!(nodes, vec![&1, &3]);
+/// ```
+pub struct NodeFiltered<'f, G, F> {
+    graph: G,
+    filter: &'f F,
+}
+
+impl<'f, G, F> NodeFiltered<'f, G, F> {
+    /// Creates a new view of `graph` hiding every node for which `filter` returns false
+    pub fn new(graph: G, filter: &'f F) -> Self {
+        Self { graph, filter }
+    }
+}
+
+impl<G: Copy, F> Clone for NodeFiltered<'_, G, F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<G: Copy, F> Copy for NodeFiltered<'_, G, F> {}
+
+impl<G: fmt::Debug, F> fmt::Debug for NodeFiltered<'_, G, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NodeFiltered")
+            .field("graph", &self.graph)
+            .finish()
+    }
+}
+
+impl<'f, 'a, G, F> GraphRef<'a> for NodeFiltered<'f, G, F>
+where
+    G: GraphRef<'a>,
+    F: Fn(&G::Node) -> bool,
+{
+    type Node = G::Node;
+
+    fn contains_node(self, u: &Self::Node) -> bool {
+        (self.filter)(u) && self.graph.contains_node(u)
+    }
+}
+
+impl<'f, 'a, G, F> IntoNodes<'a> for NodeFiltered<'f, G, F>
+where
+    G: IntoNodes<'a>,
+    F: Fn(&G::Node) -> bool,
+{
+    type Nodes = FilteredNodes<'f, G::Nodes, F>;
+
+    fn nodes(self) -> Self::Nodes {
+        FilteredNodes {
+            inner: Some(self.graph.nodes()),
+            filter: self.filter,
+        }
+    }
+}
+
+impl<'f, 'a, G, F> IntoNeighbors<'a> for NodeFiltered<'f, G, F>
+where
+    G: IntoNeighbors<'a>,
+    F: Fn(&G::Node) -> bool,
+{
+    type Neighbors = FilteredNodes<'f, G::Neighbors, F>;
+
+    fn neighbors(self, u: &Self::Node) -> Self::Neighbors {
+        FilteredNodes {
+            inner: if (self.filter)(u) {
+                Some(self.graph.neighbors(u))
+            } else {
+                None
+            },
+            filter: self.filter,
+        }
+    }
+}
+
+impl<'f, 'a, G, F> EdgeWeighted<'a> for NodeFiltered<'f, G, F>
+where
+    G: EdgeWeighted<'a>,
+    F: Fn(&G::Node) -> bool,
+{
+    type NeighborsWeighted = FilteredNeighborsWeighted<'f, G::NeighborsWeighted, F>;
+
+    fn neighbors_weighted(self, u: &Self::Node) -> Self::NeighborsWeighted {
+        FilteredNeighborsWeighted {
+            inner: if (self.filter)(u) {
+                Some(self.graph.neighbors_weighted(u))
+            } else {
+                None
+            },
+            filter: self.filter,
+        }
+    }
+}
+
+/// An iterator over the nodes of a [`NodeFiltered`] view
+pub struct FilteredNodes<'f, I, F> {
+    inner: Option<I>,
+    filter: &'f F,
+}
+
+impl<I: fmt::Debug, F> fmt::Debug for FilteredNodes<'_, I, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FilteredNodes")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<'a, N: 'a, I, F> Iterator for FilteredNodes<'_, I, F>
+where
+    I: Iterator<Item = &'a N>,
+    F: Fn(&N) -> bool,
+{
+    type Item = &'a N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let filter = self.filter;
+        self.inner.as_mut()?.find(|node| filter(node))
+    }
+}
+
+/// An iterator over the weighted successors of a node in a [`NodeFiltered`] view
+pub struct FilteredNeighborsWeighted<'f, I, F> {
+    inner: Option<I>,
+    filter: &'f F,
+}
+
+impl<I: fmt::Debug, F> fmt::Debug for FilteredNeighborsWeighted<'_, I, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FilteredNeighborsWeighted")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<'a, N: 'a, I, F> Iterator for FilteredNeighborsWeighted<'_, I, F>
+where
+    I: Iterator<Item = (&'a N, Option<EdgeWeight>)>,
+    F: Fn(&N) -> bool,
+{
+    type Item = (&'a N, Option<EdgeWeight>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let filter = self.filter;
+        self.inner.as_mut()?.find(|(node, _)| filter(node))
+    }
+}
+
+/// A view of a graph which only contains the edges matching a predicate
+///
+/// The predicate is given the source, the destination and the weight of each edge.
+/// ```
+/// use graph_algos::{graph, Graph, EdgeWeight};
+/// use graph_algos::visit::{EdgeFiltered, IntoNeighbors};
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2 => 5, 3 => 50],
+/// };
+///
+/// // only keep the cheap edges
+/// let filter = |_: &u32, _: &u32, w: Option<EdgeWeight>| w < Some(10.into());
+/// let view = EdgeFiltered::new(&graph, &filter);
+///
+/// let succs: Vec<&u32> = view.neighbors(&1).collect();
+/// assert_eq!(succs, vec![&2]);
+/// ```
+pub struct EdgeFiltered<'f, G, F> {
+    graph: G,
+    filter: &'f F,
+}
+
+impl<'f, G, F> EdgeFiltered<'f, G, F> {
+    /// Creates a new view of `graph` hiding every edge for which `filter` returns false
+    pub fn new(graph: G, filter: &'f F) -> Self {
+        Self { graph, filter }
+    }
+}
+
+impl<G: Copy, F> Clone for EdgeFiltered<'_, G, F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<G: Copy, F> Copy for EdgeFiltered<'_, G, F> {}
+
+impl<G: fmt::Debug, F> fmt::Debug for EdgeFiltered<'_, G, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EdgeFiltered")
+            .field("graph", &self.graph)
+            .finish()
+    }
+}
+
+impl<'f, 'a, G, F> GraphRef<'a> for EdgeFiltered<'f, G, F>
+where
+    G: GraphRef<'a>,
+    F: Fn(&G::Node, &G::Node, Option<EdgeWeight>) -> bool,
+{
+    type Node = G::Node;
+
+    fn contains_node(self, u: &Self::Node) -> bool {
+        self.graph.contains_node(u)
+    }
+}
+
+impl<'f, 'a, G, F> IntoNodes<'a> for EdgeFiltered<'f, G, F>
+where
+    G: IntoNodes<'a>,
+    F: Fn(&G::Node, &G::Node, Option<EdgeWeight>) -> bool,
+{
+    type Nodes = G::Nodes;
+
+    fn nodes(self) -> Self::Nodes {
+        self.graph.nodes()
+    }
+
+    fn node_count(self) -> usize {
+        self.graph.node_count()
+    }
+}
+
+impl<'f, 'a, G, F> IntoNeighbors<'a> for EdgeFiltered<'f, G, F>
+where
+    G: EdgeWeighted<'a>,
+    F: Fn(&G::Node, &G::Node, Option<EdgeWeight>) -> bool,
+{
+    type Neighbors = FilteredEdgeNeighbors<'f, G::Node, G::NeighborsWeighted, F>;
+
+    fn neighbors(self, u: &Self::Node) -> Self::Neighbors {
+        FilteredEdgeNeighbors {
+            inner: self.neighbors_weighted(u),
+        }
+    }
+}
+
+impl<'f, 'a, G, F> EdgeWeighted<'a> for EdgeFiltered<'f, G, F>
+where
+    G: EdgeWeighted<'a>,
+    F: Fn(&G::Node, &G::Node, Option<EdgeWeight>) -> bool,
+{
+    type NeighborsWeighted = FilteredEdges<'f, G::Node, G::NeighborsWeighted, F>;
+
+    fn neighbors_weighted(self, u: &Self::Node) -> Self::NeighborsWeighted {
+        FilteredEdges {
+            source: u.clone(),
+            inner: self.graph.neighbors_weighted(u),
+            filter: self.filter,
+        }
+    }
+}
+
+/// An iterator over the weighted successors of a node in an [`EdgeFiltered`] view
+pub struct FilteredEdges<'f, N, I, F> {
+    source: N,
+    inner: I,
+    filter: &'f F,
+}
+
+impl<N: fmt::Debug, I: fmt::Debug, F> fmt::Debug for FilteredEdges<'_, N, I, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FilteredEdges")
+            .field("source", &self.source)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<'a, N: 'a, I, F> Iterator for FilteredEdges<'_, N, I, F>
+where
+    I: Iterator<Item = (&'a N, Option<EdgeWeight>)>,
+    F: Fn(&N, &N, Option<EdgeWeight>) -> bool,
+{
+    type Item = (&'a N, Option<EdgeWeight>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (source, filter) = (&self.source, self.filter);
+        self.inner.find(|(dest, w)| filter(source, dest, *w))
+    }
+}
+
+/// An iterator over the successors of a node in an [`EdgeFiltered`] view
+pub struct FilteredEdgeNeighbors<'f, N, I, F> {
+    inner: FilteredEdges<'f, N, I, F>,
+}
+
+impl<N: fmt::Debug, I: fmt::Debug, F> fmt::Debug for FilteredEdgeNeighbors<'_, N, I, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FilteredEdgeNeighbors")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<'a, N: 'a, I, F> Iterator for FilteredEdgeNeighbors<'_, N, I, F>
+where
+    I: Iterator<Item = (&'a N, Option<EdgeWeight>)>,
+    F: Fn(&N, &N, Option<EdgeWeight>) -> bool,
+{
+    type Item = &'a N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(dest, _)| dest)
+    }
+}
+
+/// A view of a graph with the direction of every edge reversed
+///
+/// Graphs only store the edges leaving each node, so creating the view indexes the edges
+/// into every node, taking time and memory linear in the size of the graph.
+/// The algorithms are run over a reference to the view, and the nodes they return borrow from
+/// the original graph rather than the view.
+/// ```
+/// use graph_algos::{graph, Graph};
+/// use graph_algos::algos::dijkstra;
+/// use graph_algos::visit::{IntoNeighbors, Reversed};
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2 => 4, 3 => 1],
+///     3 => [2 => 1],
+/// };
+///
+/// let reversed = Reversed::new(&graph);
+/// let mut preds: Vec<&u32> = (&reversed).neighbors(&2).collect();
+/// preds.sort();
+/// assert_eq!(preds, vec![&1, &3]);
+///
+/// // the distance from every node to 2
+/// let (_, distances) = dijkstra(&reversed, &2);
+/// assert_eq!(distances[&1], 2.into());
+/// ```
+pub struct Reversed<'a, G: GraphRef<'a>> {
+    graph: G,
+    preds: HashMap<&'a G::Node, Vec<WeightedPred<'a, G::Node>>>,
+}
+
+/// A predecessor of a node and the weight of the edge from it
+type WeightedPred<'a, N> = (&'a N, Option<EdgeWeight>);
+
+impl<'a, G: IntoNodes<'a> + EdgeWeighted<'a>> Reversed<'a, G> {
+    /// Creates a view of `graph` with every edge reversed
+    pub fn new(graph: G) -> Self {
+        let mut preds: HashMap<&'a G::Node, Vec<_>> = HashMap::new();
+        for u in graph.nodes() {
+            for (v, weight) in graph.neighbors_weighted(u) {
+                preds.entry(v).or_default().push((u, weight));
+            }
+        }
+
+        Self { graph, preds }
+    }
+}
+
+impl<'a, G: GraphRef<'a> + fmt::Debug> fmt::Debug for Reversed<'a, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reversed")
+            .field("graph", &self.graph)
+            .finish()
+    }
+}
+
+impl<'a, G: GraphRef<'a>> GraphRef<'a> for &Reversed<'a, G> {
+    type Node = G::Node;
+
+    fn contains_node(self, u: &Self::Node) -> bool {
+        self.graph.contains_node(u)
+    }
+}
+
+impl<'a, G: IntoNodes<'a>> IntoNodes<'a> for &Reversed<'a, G> {
+    type Nodes = G::Nodes;
+
+    fn nodes(self) -> Self::Nodes {
+        self.graph.nodes()
+    }
+
+    fn node_count(self) -> usize {
+        self.graph.node_count()
+    }
+}
+
+impl<'r, 'a, G: GraphRef<'a>> IntoNeighbors<'a> for &'r Reversed<'a, G> {
+    type Neighbors = ReversedNeighbors<'r, 'a, G::Node>;
+
+    fn neighbors(self, u: &Self::Node) -> Self::Neighbors {
+        ReversedNeighbors {
+            inner: self.neighbors_weighted(u),
+        }
+    }
+}
+
+impl<'r, 'a, G: GraphRef<'a>> EdgeWeighted<'a> for &'r Reversed<'a, G> {
+    type NeighborsWeighted = ReversedEdges<'r, 'a, G::Node>;
+
+    fn neighbors_weighted(self, u: &Self::Node) -> Self::NeighborsWeighted {
+        let preds = self.preds.get(u).map_or(&[][..], Vec::as_slice);
+        ReversedEdges {
+            inner: preds.iter(),
+        }
+    }
+}
+
+/// An iterator over the predecessors of a node in the original graph of a [`Reversed`] view,
+/// along with the weights of the edges from them
+#[derive(Debug, Clone)]
+pub struct ReversedEdges<'r, 'a, N> {
+    inner: slice::Iter<'r, WeightedPred<'a, N>>,
+}
+
+impl<'r, 'a, N> Iterator for ReversedEdges<'r, 'a, N> {
+    type Item = (&'a N, Option<EdgeWeight>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().copied()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<N> ExactSizeIterator for ReversedEdges<'_, '_, N> {}
+
+/// An iterator over the predecessors of a node in the original graph of a [`Reversed`] view
+#[derive(Debug, Clone)]
+pub struct ReversedNeighbors<'r, 'a, N> {
+    inner: ReversedEdges<'r, 'a, N>,
+}
+
+impl<'r, 'a, N> Iterator for ReversedNeighbors<'r, 'a, N> {
+    type Item = &'a N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(u, _)| u)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<N> ExactSizeIterator for ReversedNeighbors<'_, '_, N> {}
@@ -0,0 +1,280 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{Edge, EdgeWeight, Graph, NodeBounds, Path};
+use rand::Rng;
+use std::{fmt, hash::BuildHasher};
+
+impl<N: NodeBounds, S: BuildHasher> Graph<N, S> {
+    /// takes a random walk of up to `steps` edges from `start`,
+    /// picking each outbound edge with equal probability
+    ///
+    /// The walk stops early if it reaches a node with no outbound edges,
+    /// returns `None` if `start` isn't in the graph.
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2, 3],
+    ///     2 => [1, 3],
+    ///     3 => [1, 2],
+    /// };
+    ///
+    /// let mut rng = StdRng::seed_from_u64(42);
+    /// let walk = graph.random_walk(&1, 10, &mut rng).unwrap();
+    ///
+    /// assert_eq!(walk.head(), &1);
+    /// assert_eq!(walk.edges().len(), 10);
+    /// ```
+    pub fn random_walk<R: Rng + ?Sized>(
+        &self,
+        start: &N,
+        steps: usize,
+        rng: &mut R,
+    ) -> Option<Path<'_, N>> {
+        self.walk(start, steps, |succs| uniform_step(succs, rng))
+    }
+
+    /// takes a random walk of up to `steps` edges from `start`,
+    /// picking each outbound edge with probability proportional to its weight
+    ///
+    /// Unweighted edges count as having a weight of 1,
+    /// edges with a non-positive or infinite weight are never taken.
+    /// The walk stops early if it reaches a node with no edges it can take,
+    /// returns `None` if `start` isn't in the graph.
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2 => 1, 3 => 0],
+    ///     2 => [1 => 1],
+    /// };
+    ///
+    /// let mut rng = StdRng::seed_from_u64(42);
+    /// let walk = graph.weighted_random_walk(&1, 10, &mut rng).unwrap();
+    ///
+    /// // the edge to 3 has no weight so the walk bounces between 1 and 2
+    /// assert_eq!(walk.edges().len(), 10);
+    /// assert!(walk.edges().iter().all(|edge| edge.destination() != &&3));
+    /// ```
+    pub fn weighted_random_walk<R: Rng + ?Sized>(
+        &self,
+        start: &N,
+        steps: usize,
+        rng: &mut R,
+    ) -> Option<Path<'_, N>> {
+        self.walk(start, steps, |succs| weighted_step(succs, rng))
+    }
+
+    /// walks from `start` using `step` to pick the next edge
+    fn walk<'a, F>(&'a self, start: &N, steps: usize, mut step: F) -> Option<Path<'a, N>>
+    where
+        F: FnMut(&'a [Edge<N>]) -> Option<&'a Edge<N>>,
+    {
+        let head = self.node_ref(start)?;
+
+        let mut edges = Vec::with_capacity(steps);
+        let mut current = head;
+        for _ in 0..steps {
+            let edge = match self.succs(current).and_then(&mut step) {
+                Some(edge) => edge,
+                None => break,
+            };
+
+            current = edge.destination();
+            edges.push(match edge.weight() {
+                Some(weight) => Edge::new_with_weight(current, weight),
+                None => Edge::new(current),
+            });
+        }
+
+        Some(Path::from_parts(head, edges))
+    }
+}
+
+/// picks an edge uniformly at random
+fn uniform_step<'a, N: NodeBounds, R: Rng + ?Sized>(
+    succs: &'a [Edge<N>],
+    rng: &mut R,
+) -> Option<&'a Edge<N>> {
+    if succs.is_empty() {
+        None
+    } else {
+        Some(&succs[rng.gen_range(0..succs.len())])
+    }
+}
+
+/// the relative probability of taking an edge in a weighted walk
+pub(crate) fn step_weight(weight: Option<EdgeWeight>) -> u64 {
+    match weight {
+        None => 1,
+        Some(EdgeWeight::Weight(w)) if w > 0 => w as u64,
+        Some(_) => 0,
+    }
+}
+
+/// picks an edge with probability proportional to its weight
+fn weighted_step<'a, N: NodeBounds, R: Rng + ?Sized>(
+    succs: &'a [Edge<N>],
+    rng: &mut R,
+) -> Option<&'a Edge<N>> {
+    let total: u64 = succs
+        .iter()
+        .map(|edge| step_weight(edge.weight()))
+        .fold(0, u64::saturating_add);
+
+    if total == 0 {
+        return None;
+    }
+
+    let mut target = rng.gen_range(0..total);
+    succs.iter().find(|edge| {
+        let weight = step_weight(edge.weight());
+        if target < weight {
+            true
+        } else {
+            target -= weight;
+            false
+        }
+    })
+}
+
+/// An endless random walk over a graph which jumps back to its start node
+/// with a fixed probability at each step, or whenever it gets stuck
+///
+/// Yields the start node first, then each node the walk visits.
+/// ```
+/// use graph_algos::{graph, Graph, RandomWalker};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let graph: Graph<u32> = graph! {
+///     1 => [2],
+///     2 => [3],
+///     3 => [4],
+/// };
+///
+/// let walker = RandomWalker::new(&graph, &1, StdRng::seed_from_u64(42));
+/// let visited: Vec<&u32> = walker.take(8).collect();
+///
+/// // 4 is a dead end, so the walk restarts from 1
+/// assert_eq!(visited, vec![&1, &2, &3, &4, &1, &2, &3, &4]);
+/// ```
+pub struct RandomWalker<'a, N: NodeBounds, S, R> {
+    graph: &'a Graph<N, S>,
+    start: Option<&'a N>,
+    current: Option<&'a N>,
+    restart_probability: f64,
+    weighted: bool,
+    rng: R,
+}
+
+impl<'a, N: NodeBounds, S: BuildHasher, R: Rng> RandomWalker<'a, N, S, R> {
+    /// creates a new walker starting at `start`, which never restarts except at dead ends
+    ///
+    /// The walker yields nothing if `start` isn't in the graph.
+    pub fn new(graph: &'a Graph<N, S>, start: &N, rng: R) -> Self {
+        let start = graph.node_ref(start);
+
+        Self {
+            graph,
+            start,
+            current: None,
+            restart_probability: 0.0,
+            weighted: false,
+            rng,
+        }
+    }
+
+    /// sets the probability of jumping back to the start node at each step
+    /// ```
+    /// use graph_algos::{graph, Graph, RandomWalker};
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2],
+    ///     2 => [1, 3],
+    ///     3 => [2],
+    /// };
+    ///
+    /// let walker = RandomWalker::new(&graph, &1, StdRng::seed_from_u64(42))
+    ///     .restart_probability(1.0);
+    ///
+    /// assert!(walker.take(10).all(|node| node == &1));
+    /// ```
+    pub fn restart_probability(mut self, probability: f64) -> Self {
+        self.restart_probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// sets whether edges are chosen with probability proportional to their weight,
+    /// as in [`Graph::weighted_random_walk`]
+    pub fn weighted(mut self, weighted: bool) -> Self {
+        self.weighted = weighted;
+        self
+    }
+
+    /// sends the walk back to the start node, which is yielded next
+    pub fn restart(&mut self) {
+        self.current = None;
+    }
+}
+
+impl<'a, N: NodeBounds, S: BuildHasher, R: Rng> Iterator for RandomWalker<'a, N, S, R> {
+    type Item = &'a N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.start?;
+
+        let next = match self.current {
+            Some(current) if !self.rng.gen_bool(self.restart_probability) => {
+                let graph = self.graph;
+                let succs = graph.succs_or_empty(current);
+                let edge = if self.weighted {
+                    weighted_step(succs, &mut self.rng)
+                } else {
+                    uniform_step(succs, &mut self.rng)
+                };
+
+                edge.map_or(start, Edge::destination)
+            }
+            _ => start,
+        };
+
+        self.current = Some(next);
+        Some(next)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.start {
+            Some(_) => (usize::MAX, None),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<'a, N: NodeBounds, S, R> fmt::Debug for RandomWalker<'a, N, S, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RandomWalker")
+            .field("start", &self.start)
+            .field("current", &self.current)
+            .field("restart_probability", &self.restart_probability)
+            .field("weighted", &self.weighted)
+            .finish()
+    }
+}
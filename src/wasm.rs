@@ -0,0 +1,112 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! A small JavaScript facing API built with wasm-bindgen.
+//!
+//! Nodes are named by strings and edge weights are 32 bit integers,
+//! so every value can cross the boundary as a plain JavaScript string or number.
+//!
+//! ```
+//! use graph_algos::wasm::WasmGraph;
+//!
+//! let mut graph = WasmGraph::new();
+//! graph.add_edge("home", "shop", 4);
+//! graph.add_edge("home", "park", 1);
+//! graph.add_edge("park", "shop", 2);
+//!
+//! assert_eq!(graph.shortest_path("home", "shop"), Some(vec!["home".into(), "park".into(), "shop".into()]));
+//! assert_eq!(graph.distance("home", "shop"), Some(3.0));
+//! ```
+
+use crate::{algos, Edge, EdgeWeight, Graph, Path};
+use wasm_bindgen::prelude::*;
+
+/// A weighted directed graph with string nodes, exported to JavaScript as `Graph`
+#[wasm_bindgen(js_name = Graph)]
+#[derive(Debug, Clone, Default)]
+pub struct WasmGraph {
+    graph: Graph<String>,
+}
+
+#[wasm_bindgen(js_class = Graph)]
+impl WasmGraph {
+    /// Creates an empty graph
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            graph: Graph::empty(),
+        }
+    }
+
+    /// adds a node with no edges, if it isn't already in the graph
+    #[wasm_bindgen(js_name = addNode)]
+    pub fn add_node(&mut self, node: &str) {
+        self.graph.add_node(node.to_owned());
+    }
+
+    /// adds a weighted edge to the graph
+    #[wasm_bindgen(js_name = addEdge)]
+    pub fn add_edge(&mut self, source: &str, destination: &str, weight: i32) {
+        self.graph.add_edge(
+            source.to_owned(),
+            Edge::new_with_weight(destination.to_owned(), i64::from(weight)),
+        );
+    }
+
+    /// Returns the number of nodes in the graph
+    #[wasm_bindgen(js_name = nodeCount)]
+    pub fn node_count(&self) -> usize {
+        self.graph.len()
+    }
+
+    /// Returns the number of edges in the graph
+    #[wasm_bindgen(js_name = edgeCount)]
+    pub fn edge_count(&self) -> usize {
+        self.graph.edges().count()
+    }
+
+    /// Returns the nodes on a shortest path from `source` to `destination` found with Dijkstra's algorithm,
+    /// or `undefined` if there is no such path
+    #[wasm_bindgen(js_name = shortestPath)]
+    pub fn shortest_path(&self, source: &str, destination: &str) -> Option<Vec<String>> {
+        let source = self.graph.node_ref(&source.to_owned())?;
+        let destination = self.graph.node_ref(&destination.to_owned())?;
+
+        let (pred_map, _) = algos::dijkstra(&self.graph, source);
+        let path = Path::new_path_to(&pred_map, destination).ok()?;
+
+        Some(
+            std::iter::once(path.head())
+                .chain(path.edges().iter().map(|edge| *edge.destination()))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Returns the length of a shortest path from `source` to `destination`,
+    /// or `undefined` if there is no such path
+    pub fn distance(&self, source: &str, destination: &str) -> Option<f64> {
+        let source = self.graph.node_ref(&source.to_owned())?;
+
+        let (_, dist_map) = algos::dijkstra(&self.graph, source);
+        match dist_map.get(&destination.to_owned())? {
+            EdgeWeight::Weight(w) => Some(*w as f64),
+            EdgeWeight::PosInfinity => Some(f64::INFINITY),
+            EdgeWeight::NegInfinity => Some(f64::NEG_INFINITY),
+        }
+    }
+}
@@ -0,0 +1,282 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{Edge, Graph, NodeBounds};
+use std::{
+    collections::{hash_map::RandomState, HashSet},
+    fmt,
+    hash::BuildHasher,
+};
+
+/// A change made to a [`WatchedGraph`] or recorded by a [`JournaledGraph`](crate::JournaledGraph)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GraphEvent<N: NodeBounds> {
+    /// A node was added to the graph
+    NodeAdded(N),
+
+    /// A node was removed from the graph, after each of its edges were reported as removed
+    NodeRemoved(N),
+
+    /// An edge was added to the graph, after any new nodes it introduced were reported as added
+    EdgeAdded {
+        /// the source node of the edge
+        source: N,
+        /// the edge which was added
+        edge: Edge<N>,
+    },
+
+    /// An edge was removed from the graph
+    EdgeRemoved {
+        /// the source node of the edge
+        source: N,
+        /// the edge which was removed
+        edge: Edge<N>,
+    },
+}
+
+type Observer<N> = Box<dyn FnMut(&GraphEvent<N>)>;
+
+/// A graph which reports every change made to it to a set of observers
+///
+/// This makes it possible to keep indexes built from the graph,
+/// such as reverse adjacency lists or shortest path trees, up to date incrementally
+/// instead of rebuilding them after each change.
+/// ```
+/// use graph_algos::{Edge, GraphEvent, WatchedGraph};
+/// use std::{cell::RefCell, collections::HashMap, rc::Rc};
+///
+/// // maintain the in-degree of every node as the graph changes
+/// let in_degrees: Rc<RefCell<HashMap<u32, usize>>> = Rc::default();
+///
+/// let mut graph: WatchedGraph<u32> = WatchedGraph::new();
+/// let degrees = Rc::clone(&in_degrees);
+/// graph.on_mutation(move |event| {
+///     let mut degrees = degrees.borrow_mut();
+///     match event {
+///         GraphEvent::NodeAdded(node) => {
+///             degrees.insert(*node, 0);
+///         }
+///         GraphEvent::NodeRemoved(node) => {
+///             degrees.remove(node);
+///         }
+///         GraphEvent::EdgeAdded { edge, .. } => *degrees.get_mut(edge.destination()).unwrap() += 1,
+///         GraphEvent::EdgeRemoved { edge, .. } => *degrees.get_mut(edge.destination()).unwrap() -= 1,
+///     }
+/// });
+///
+/// graph.add_edge(1, Edge::new(2));
+/// graph.add_edge(3, Edge::new(2));
+/// graph.add_edge(2, Edge::new(3));
+/// assert_eq!(in_degrees.borrow()[&2], 2);
+///
+/// graph.retain_nodes(|&node| node != 1);
+/// assert_eq!(in_degrees.borrow()[&2], 1);
+/// assert!(!in_degrees.borrow().contains_key(&1));
+///
+/// graph.remove_edge(&2, &3);
+/// assert_eq!(in_degrees.borrow()[&3], 0);
+/// ```
+pub struct WatchedGraph<N: NodeBounds, S = RandomState> {
+    graph: Graph<N, S>,
+    observers: Vec<Observer<N>>,
+}
+
+impl<N: NodeBounds, S: BuildHasher + Default> WatchedGraph<N, S> {
+    /// Creates an empty graph with no observers
+    pub fn new() -> Self {
+        Self::from(Graph::default())
+    }
+}
+
+impl<N: NodeBounds, S: BuildHasher> WatchedGraph<N, S> {
+    /// Registers a callback which is called with every change made to the graph from now on,
+    /// in the order the changes are made
+    pub fn on_mutation<F: FnMut(&GraphEvent<N>) + 'static>(&mut self, callback: F) {
+        self.observers.push(Box::new(callback));
+    }
+
+    /// Returns the graph being watched
+    pub fn graph(&self) -> &Graph<N, S> {
+        &self.graph
+    }
+
+    /// Stops watching the graph and returns it
+    pub fn into_inner(self) -> Graph<N, S> {
+        self.graph
+    }
+
+    /// Adds a node with no edges to the graph, if it isn't already in the graph
+    pub fn add_node(&mut self, u: N) {
+        let events = add_node(&mut self.graph, u);
+        self.notify(events);
+    }
+
+    /// Adds an edge to the graph, along with either of its nodes which aren't in the graph yet
+    pub fn add_edge(&mut self, u: N, e: Edge<N>) {
+        let events = add_edge(&mut self.graph, u, e);
+        self.notify(events);
+    }
+
+    /// Removes an edge from `u` to `v`, if there is one
+    pub fn remove_edge(&mut self, u: &N, v: &N) {
+        let events = remove_edge(&mut self.graph, u, v);
+        self.notify(events);
+    }
+
+    /// Removes every node for which `keep` returns false, along with all edges into and out of it
+    pub fn retain_nodes<F: FnMut(&N) -> bool>(&mut self, keep: F) {
+        let events = retain_nodes(&mut self.graph, keep);
+        self.notify(events);
+    }
+
+    /// Removes every edge for which `keep` returns false, given the source node and the edge
+    pub fn retain_edges<F: FnMut(&N, &Edge<N>) -> bool>(&mut self, keep: F) {
+        let events = retain_edges(&mut self.graph, keep);
+        self.notify(events);
+    }
+
+    /// Passes each event to every observer, in order
+    fn notify(&mut self, events: Vec<GraphEvent<N>>) {
+        for event in events {
+            for observer in &mut self.observers {
+                observer(&event);
+            }
+        }
+    }
+}
+
+// The mutations shared by `WatchedGraph` and `JournaledGraph`,
+// each of which changes the graph and returns the events describing the change in order
+
+/// Adds a node with no edges to the graph, if it isn't already in the graph
+pub(crate) fn add_node<N: NodeBounds, S: BuildHasher>(
+    graph: &mut Graph<N, S>,
+    u: N,
+) -> Vec<GraphEvent<N>> {
+    if graph.succs(&u).is_some() {
+        return Vec::new();
+    }
+
+    graph.add_node(u.clone());
+    vec![GraphEvent::NodeAdded(u)]
+}
+
+/// Adds an edge to the graph, along with either of its nodes which aren't in the graph yet
+pub(crate) fn add_edge<N: NodeBounds, S: BuildHasher>(
+    graph: &mut Graph<N, S>,
+    u: N,
+    e: Edge<N>,
+) -> Vec<GraphEvent<N>> {
+    let mut events = add_node(graph, u.clone());
+    events.extend(add_node(graph, e.destination().clone()));
+
+    graph.add_edge(u.clone(), e.clone());
+    events.push(GraphEvent::EdgeAdded { source: u, edge: e });
+    events
+}
+
+/// Removes an edge from `u` to `v`, if there is one
+pub(crate) fn remove_edge<N: NodeBounds, S: BuildHasher>(
+    graph: &mut Graph<N, S>,
+    u: &N,
+    v: &N,
+) -> Vec<GraphEvent<N>> {
+    let removed = graph
+        .succs_or_empty(u)
+        .iter()
+        .find(|edge| edge.destination() == v)
+        .cloned();
+
+    match removed {
+        Some(edge) => {
+            graph.remove_edge(u, v);
+            vec![GraphEvent::EdgeRemoved {
+                source: u.clone(),
+                edge,
+            }]
+        }
+        None => Vec::new(),
+    }
+}
+
+/// Removes every node for which `keep` returns false, along with all edges into and out of it
+pub(crate) fn retain_nodes<N: NodeBounds, S: BuildHasher, F: FnMut(&N) -> bool>(
+    graph: &mut Graph<N, S>,
+    mut keep: F,
+) -> Vec<GraphEvent<N>> {
+    let removed: HashSet<N> = graph.nodes().filter(|node| !keep(node)).cloned().collect();
+    if removed.is_empty() {
+        return Vec::new();
+    }
+
+    let mut events: Vec<GraphEvent<N>> = graph
+        .edges()
+        .filter(|(u, edge)| removed.contains(*u) || removed.contains(edge.destination()))
+        .map(|(u, edge)| GraphEvent::EdgeRemoved {
+            source: u.clone(),
+            edge: edge.clone(),
+        })
+        .collect();
+    graph.retain_nodes(|node| !removed.contains(node));
+
+    events.extend(removed.into_iter().map(GraphEvent::NodeRemoved));
+    events
+}
+
+/// Removes every edge for which `keep` returns false, given the source node and the edge
+pub(crate) fn retain_edges<N: NodeBounds, S: BuildHasher, F: FnMut(&N, &Edge<N>) -> bool>(
+    graph: &mut Graph<N, S>,
+    mut keep: F,
+) -> Vec<GraphEvent<N>> {
+    let mut events = Vec::new();
+    graph.retain_edges(|u, edge| {
+        keep(u, edge) || {
+            events.push(GraphEvent::EdgeRemoved {
+                source: u.clone(),
+                edge: edge.clone(),
+            });
+            false
+        }
+    });
+
+    events
+}
+
+impl<N: NodeBounds, S: BuildHasher + Default> Default for WatchedGraph<N, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: NodeBounds, S> From<Graph<N, S>> for WatchedGraph<N, S> {
+    /// Starts watching an existing graph, only changes made from now on are reported
+    fn from(graph: Graph<N, S>) -> Self {
+        Self {
+            graph,
+            observers: Vec::new(),
+        }
+    }
+}
+
+impl<N: NodeBounds, S: fmt::Debug> fmt::Debug for WatchedGraph<N, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WatchedGraph")
+            .field("graph", &self.graph)
+            .field("observers", &self.observers.len())
+            .finish()
+    }
+}
@@ -0,0 +1,226 @@
+/*
+ *  Copyright (C) 2021  Sam Leonard
+ *
+ *  This program is free software: you can redistribute it and/or modify
+ *  it under the terms of the GNU Affero General Public License as published
+ *  by the Free Software Foundation, either version 3 of the License, or
+ *  (at your option) any later version.
+ *
+ *  This program is distributed in the hope that it will be useful,
+ *  but WITHOUT ANY WARRANTY; without even the implied warranty of
+ *  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ *  GNU Affero General Public License for more details.
+ *
+ *  You should have received a copy of the GNU Affero General Public License
+ *  along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{Edge, EdgeWeight, Graph, NodeBounds};
+use std::{hash::BuildHasher, ops::RangeInclusive};
+
+impl<N: NodeBounds, S: BuildHasher + Clone> Graph<N, S> {
+    /// Returns the weights of all of the weighted edges in the graph in ascending order
+    /// ```
+    /// use graph_algos::{graph, Graph, EdgeWeight};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2 => 5, 3 => 1],
+    ///     2 => [3 => 3],
+    /// };
+    ///
+    /// let weights: Vec<EdgeWeight> = vec![1.into(), 3.into(), 5.into()];
+    /// assert_eq!(graph.sorted_weights(), weights);
+    /// ```
+    pub fn sorted_weights(&self) -> Vec<EdgeWeight> {
+        let mut weights: Vec<EdgeWeight> =
+            self.edges().filter_map(|(_, edge)| edge.weight()).collect();
+
+        weights.sort();
+
+        weights
+    }
+
+    /// Returns the `q` quantile of the edge weights using the nearest rank method,
+    /// where `q` is between 0 and 1
+    ///
+    /// Unweighted edges are ignored, returns `None` if there are no weighted edges.
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2 => 10, 3 => 20, 4 => 30, 5 => 40],
+    /// };
+    ///
+    /// assert_eq!(graph.weight_quantile(0.0), Some(10.into()));
+    /// assert_eq!(graph.weight_quantile(0.5), Some(20.into()));
+    /// assert_eq!(graph.weight_quantile(0.75), Some(30.into()));
+    /// assert_eq!(graph.weight_quantile(1.0), Some(40.into()));
+    /// ```
+    pub fn weight_quantile(&self, q: f64) -> Option<EdgeWeight> {
+        let weights = self.sorted_weights();
+        quantile_of_sorted(&weights, q)
+    }
+
+    /// Returns the median edge weight, the 0.5 quantile
+    pub fn weight_median(&self) -> Option<EdgeWeight> {
+        self.weight_quantile(0.5)
+    }
+
+    /// Returns a subgraph keeping only the edges with a weight of at least `threshold`
+    ///
+    /// Every node is kept, even if all of its edges are removed.
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2 => 10, 3 => 20],
+    /// };
+    ///
+    /// let strong = graph.weight_threshold_filter(15.into());
+    /// assert!(strong.is_edge(&1, &3));
+    /// assert!(!strong.is_edge(&1, &2));
+    /// assert_eq!(strong.len(), 3);
+    /// ```
+    pub fn weight_threshold_filter(&self, threshold: EdgeWeight) -> Graph<N, S> {
+        self.filter_by_weight(|w| w >= threshold)
+    }
+
+    /// Returns a subgraph with every node, keeping the weighted edges matching `keep`
+    fn filter_by_weight(&self, keep: impl Fn(EdgeWeight) -> bool) -> Graph<N, S> {
+        let mut graph = Graph::with_capacity_and_hasher(self.len(), self.hasher().clone());
+
+        for node in self.nodes() {
+            graph.add_node(node.clone());
+        }
+
+        for (u, edge) in self.edges() {
+            if matches!(edge.weight(), Some(w) if keep(w)) {
+                graph.add_edge(u.clone(), edge.clone());
+            }
+        }
+
+        graph
+    }
+
+    /// Returns a subgraph keeping only the strongest `p` percent of the weighted edges,
+    /// where `p` is between 0 and 100
+    ///
+    /// Edges with the same weight as the weakest kept edge are also kept,
+    /// so ties can result in slightly more than `p` percent of the edges being kept.
+    /// Unweighted edges are removed, and every node is kept.
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2 => 1, 3 => 2, 4 => 3, 5 => 4],
+    /// };
+    ///
+    /// let top_half = graph.weight_percentile_filter(50.0);
+    /// assert!(top_half.is_edge(&1, &4));
+    /// assert!(top_half.is_edge(&1, &5));
+    /// assert!(!top_half.is_edge(&1, &3));
+    /// assert!(!top_half.is_edge(&1, &2));
+    /// ```
+    pub fn weight_percentile_filter(&self, p: f64) -> Graph<N, S> {
+        let weights = self.sorted_weights();
+        let keep = ((p.clamp(0.0, 100.0) / 100.0) * weights.len() as f64).ceil() as usize;
+
+        if keep == 0 {
+            self.filter_by_weight(|_| false)
+        } else {
+            self.weight_threshold_filter(weights[weights.len() - keep])
+        }
+    }
+
+    /// Returns a copy of the graph with the finite edge weights linearly rescaled onto `range`,
+    /// so the lightest edge gets the start of the range and the heaviest edge the end
+    ///
+    /// Scaled weights are rounded to the nearest integer.
+    /// If every finite weight is the same they all become the start of the range.
+    /// Unweighted edges and infinite weights are left alone.
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    ///
+    /// let graph: Graph<u32> = graph! {
+    ///     1 => [2 => 10, 3 => 20, 4 => 50],
+    /// };
+    ///
+    /// let normalized: Graph<u32> = graph! {
+    ///     1 => [2 => 0, 3 => 25, 4 => 100],
+    /// };
+    /// assert_eq!(graph.normalize_weights(0..=100), normalized);
+    /// ```
+    pub fn normalize_weights(&self, range: RangeInclusive<i64>) -> Graph<N, S> {
+        let finite = self.edges().filter_map(|(_, edge)| match edge.weight() {
+            Some(EdgeWeight::Weight(w)) => Some(w),
+            _ => None,
+        });
+        let (min, max) = finite.fold((i64::MAX, i64::MIN), |(min, max), w| {
+            (min.min(w), max.max(w))
+        });
+
+        let (start, end) = range.into_inner();
+        let scale = if max > min {
+            (end as f64 - start as f64) / (max as f64 - min as f64)
+        } else {
+            0.0
+        };
+
+        self.map_weights(|weight| match weight {
+            EdgeWeight::Weight(w) => {
+                EdgeWeight::Weight(start + ((w as f64 - min as f64) * scale).round() as i64)
+            }
+            infinite => infinite,
+        })
+    }
+
+    /// Returns a copy of the graph with every finite edge weight `w` replaced by `max - w`,
+    /// for example to turn similarities into distances
+    ///
+    /// Infinite weights swap sign, and unweighted edges are left alone.
+    /// ```
+    /// use graph_algos::{graph, Graph};
+    ///
+    /// let similarities: Graph<&str> = graph! {
+    ///     "a" => ["b" => 9, "c" => 2],
+    /// };
+    ///
+    /// let distances: Graph<&str> = graph! {
+    ///     "a" => ["b" => 1, "c" => 8],
+    /// };
+    /// assert_eq!(similarities.invert_weights(10), distances);
+    /// ```
+    pub fn invert_weights(&self, max: i64) -> Graph<N, S> {
+        self.map_weights(|weight| EdgeWeight::new(max) - weight)
+    }
+
+    /// Returns a copy of the graph with `f` applied to the weight of every weighted edge
+    fn map_weights(&self, f: impl Fn(EdgeWeight) -> EdgeWeight) -> Graph<N, S> {
+        let mut graph = Graph::with_capacity_and_hasher(self.len(), self.hasher().clone());
+
+        for node in self.nodes() {
+            graph.add_node(node.clone());
+        }
+
+        for (u, edge) in self.edges() {
+            let edge = match edge.weight() {
+                Some(w) => Edge::new_with_weight(edge.destination().clone(), f(w)),
+                None => edge.clone(),
+            };
+            graph.add_edge(u.clone(), edge);
+        }
+
+        graph
+    }
+}
+
+/// Returns the `q` quantile of some sorted weights using the nearest rank method
+pub(crate) fn quantile_of_sorted(weights: &[EdgeWeight], q: f64) -> Option<EdgeWeight> {
+    if weights.is_empty() {
+        return None;
+    }
+
+    let rank = (q.clamp(0.0, 1.0) * weights.len() as f64).ceil() as usize;
+
+    Some(weights[rank.saturating_sub(1)])
+}